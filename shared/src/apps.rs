@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+/// An application to close before suspending
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuitAppRule {
+    /// Process name (or app name on macOS), e.g. "steam" or "Minecraft"
+    pub name: String,
+    pub enabled: bool,
+    /// Kill the process if it is still running after the grace period
+    pub force_kill: bool,
+}
+
+impl Default for QuitAppRule {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            enabled: true,
+            force_kill: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppQuitSettings {
+    pub enabled: bool,
+    /// How long to wait for an app to quit politely
+    pub grace_secs: u32,
+    pub apps: Vec<QuitAppRule>,
+}
+
+impl Default for AppQuitSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            grace_secs: 10,
+            apps: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuitOutcome {
+    NotRunning,
+    Closed,
+    ForceKilled,
+    StillRunning,
+    Failed(String),
+}
+
+/// What happened to one app when quitting before suspend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuitResult {
+    pub name: String,
+    pub outcome: QuitOutcome,
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+/// What to do when the system is busy at the end of a session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BusyBehaviour {
+    /// Silently push the suspend back by `postpone_minutes`
+    Postpone,
+    /// Ask the user whether to suspend anyway
+    Ask,
+}
+
+/// Checks run right before suspending so an in-progress backup or download
+/// isn't interrupted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BusyGuardSettings {
+    pub enabled: bool,
+    pub behaviour: BusyBehaviour,
+    pub postpone_minutes: u32,
+    /// Give up postponing after this many attempts and suspend anyway
+    pub max_postpones: u32,
+    /// Global CPU usage (percent) considered busy, 0 disables the check
+    pub cpu_threshold_percent: u32,
+    /// Combined network throughput (KiB/s) considered busy, 0 disables the check
+    pub network_threshold_kbps: u32,
+    /// Process names that block suspending while running (case-insensitive)
+    pub processes: Vec<String>,
+}
+
+impl Default for BusyGuardSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            behaviour: BusyBehaviour::Ask,
+            postpone_minutes: 10,
+            max_postpones: 6,
+            cpu_threshold_percent: 60,
+            network_threshold_kbps: 512,
+            processes: Vec::new(),
+        }
+    }
+}
+
+/// Result of probing the system before suspending
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BusyReport {
+    pub busy: bool,
+    pub reasons: Vec<BusyReason>,
+}
+
+/// Why the system looks busy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BusyReason {
+    /// CPU load at or over the threshold, in percent
+    Cpu(u32),
+    /// Network throughput at or over the threshold, in KB/s
+    Network(u32),
+    /// A watched process is running, by the name it was given
+    Process(String),
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+/// Sound of the last-call chime
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChimeTone {
+    #[default]
+    Bell,
+    /// Low, long singing bowl
+    Bowl,
+    /// Two short soft notes
+    Marimba,
+}
+
+impl ChimeTone {
+    pub const ALL: [ChimeTone; 3] = [ChimeTone::Bell, ChimeTone::Bowl, ChimeTone::Marimba];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChimeTone::Bell => "bell",
+            ChimeTone::Bowl => "bowl",
+            ChimeTone::Marimba => "marimba",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|tone| tone.as_str() == value)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ChimeTone::Bell => "BELL",
+            ChimeTone::Bowl => "SINGING BOWL",
+            ChimeTone::Marimba => "MARIMBA",
+        }
+    }
+
+    /// Notes as (start in seconds, frequency in Hz, decay in seconds)
+    pub fn notes(self) -> &'static [(f64, f32, f64)] {
+        match self {
+            ChimeTone::Bell => &[(0.0, 880.0, 2.5)],
+            ChimeTone::Bowl => &[(0.0, 220.0, 6.0)],
+            ChimeTone::Marimba => &[(0.0, 523.25, 0.8), (0.35, 392.0, 1.2)],
+        }
+    }
+}
+
+/// Soft chime shortly before the end action, on its own audio path so it
+/// plays whatever the video does
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChimeSettings {
+    pub enabled: bool,
+    pub tone: ChimeTone,
+    /// Percent
+    pub volume: u32,
+    pub seconds_before: u32,
+}
+
+impl Default for ChimeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tone: ChimeTone::default(),
+            volume: 40,
+            seconds_before: 60,
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// Offer to load a YouTube link found on the clipboard
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipboardSettings {
+    /// Only read when the window gains focus, and only YouTube links leave
+    /// the backend
+    pub enabled: bool,
+}
+
+impl Default for ClipboardSettings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
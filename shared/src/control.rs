@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+use crate::power::EndAction;
+
+/// Minutes added when an extend request doesn't say how many
+pub const DEFAULT_EXTEND_MINUTES: u32 = 15;
+
+/// Instructions the backend sends to the frontend (which owns the timer) on
+/// the "control" event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlCommand {
+    Start(StartRequest),
+    /// Load a YouTube URL or video ID without starting the timer
+    LoadVideo(String),
+    /// Add minutes to a running timer
+    Extend {
+        minutes: u32,
+    },
+    Cancel,
+    /// Play or pause the video, e.g. from a media key
+    TogglePlay,
+    Pause,
+    /// Seek the video by this many seconds, back when negative
+    Seek {
+        seconds: i32,
+    },
+    /// It's the morning alarm, show the sunrise
+    WakeAlarm,
+}
+
+impl ControlCommand {
+    /// Whether strict mode holds it back while it locks a session: anything
+    /// that keeps the end from coming when it should
+    pub fn needs_unlock(&self) -> bool {
+        matches!(self, ControlCommand::Extend { .. } | ControlCommand::Cancel)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StartRequest {
+    /// Falls back to the currently selected duration
+    pub minutes: Option<u32>,
+    /// YouTube URL or video ID to load before starting
+    pub video: Option<String>,
+    /// Overrides the configured end action for this session
+    pub action: Option<EndAction>,
+    /// Fade the video volume at the end; defaults to on
+    pub fade: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_cancelling_and_extending_need_unlocking() {
+        assert!(ControlCommand::Cancel.needs_unlock());
+        assert!(ControlCommand::Extend { minutes: 15 }.needs_unlock());
+        for command in [
+            ControlCommand::Start(StartRequest::default()),
+            ControlCommand::LoadVideo("dQw4w9WgXcQ".to_string()),
+            ControlCommand::TogglePlay,
+            ControlCommand::Pause,
+            ControlCommand::Seek { seconds: -10 },
+            ControlCommand::WakeAlarm,
+        ] {
+            assert!(!command.needs_unlock(), "{command:?}");
+        }
+    }
+}
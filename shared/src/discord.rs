@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// Discord Rich Presence while a session is running
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiscordSettings {
+    pub enabled: bool,
+    /// Discord application the presence is shown as
+    pub application_id: String,
+    /// Include the video title; off by default for privacy
+    pub show_title: bool,
+}
@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::Locale;
+
+/// Cadence of the breathing guide
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreathingPattern {
+    /// In for 4, hold for 7, out for 8
+    #[default]
+    FourSevenEight,
+    /// In, hold, out, hold, 4 seconds each
+    Box,
+}
+
+/// One step of a breathing pattern
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Breath {
+    In,
+    Hold,
+    Out,
+}
+
+impl BreathingPattern {
+    pub const ALL: [BreathingPattern; 2] =
+        [BreathingPattern::FourSevenEight, BreathingPattern::Box];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BreathingPattern::FourSevenEight => "4_7_8",
+            BreathingPattern::Box => "box",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|pattern| pattern.as_str() == value)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BreathingPattern::FourSevenEight => "4-7-8",
+            BreathingPattern::Box => "BOX (4-4-4-4)",
+        }
+    }
+
+    /// Steps and their length in seconds, repeated for as long as the guide runs
+    pub fn steps(self) -> &'static [(Breath, u32)] {
+        match self {
+            BreathingPattern::FourSevenEight => {
+                &[(Breath::In, 4), (Breath::Hold, 7), (Breath::Out, 8)]
+            }
+            BreathingPattern::Box => &[
+                (Breath::In, 4),
+                (Breath::Hold, 4),
+                (Breath::Out, 4),
+                (Breath::Hold, 4),
+            ],
+        }
+    }
+
+    /// Step at `elapsed` seconds into the guide, and how many seconds into it
+    pub fn step_at(self, elapsed: u32) -> (Breath, u32, u32) {
+        let steps = self.steps();
+        let cycle: u32 = steps.iter().map(|(_, seconds)| seconds).sum();
+        let mut offset = elapsed % cycle;
+        for &(breath, seconds) in steps {
+            if offset < seconds {
+                return (breath, offset, seconds);
+            }
+            offset -= seconds;
+        }
+        (Breath::Hold, 0, 1)
+    }
+}
+
+/// Sent with the name of the desktop dimming the screen, or `None` when the
+/// window's own overlay has to
+pub const SYSTEM_DIM_EVENT: &str = "display://system-dim";
+
+/// Range of the UI scale setting, in percent
+pub const MIN_SCALE: u32 = 75;
+pub const MAX_SCALE: u32 = 250;
+
+/// Palette the UI is drawn in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+    /// Pure black, so OLED screens switch their pixels off
+    Oled,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 4] = [Theme::Dark, Theme::Light, Theme::HighContrast, Theme::Oled];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+            Theme::HighContrast => "high_contrast",
+            Theme::Oled => "oled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|theme| theme.as_str() == value)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Theme::Dark => "DEEP SEA",
+            Theme::Light => "LIGHT",
+            Theme::HighContrast => "HIGH CONTRAST",
+            Theme::Oled => "OLED BLACK",
+        }
+    }
+
+    /// Body class that switches the stylesheet's palette
+    pub fn class(self) -> &'static str {
+        match self {
+            Theme::Dark => "theme-dark",
+            Theme::Light => "theme-light",
+            Theme::HighContrast => "theme-high-contrast",
+            Theme::Oled => "theme-oled",
+        }
+    }
+}
+
+/// What the screen shows as the session winds down
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplaySettings {
+    /// Replace the UI with a big dim clock once the screen is dim enough
+    pub zen_clock: bool,
+    /// Dim level, in percent, the zen clock takes over at. The dim tops out
+    /// at 90.
+    pub zen_dim: u32,
+    /// Show a breathing guide at the start of each session
+    pub breathing: bool,
+    pub breathing_pattern: BreathingPattern,
+    /// How long the breathing guide runs
+    pub breathing_minutes: u32,
+    /// Show times of day as 11:42 PM rather than 23:42
+    pub clock_12h: bool,
+    /// UI language; `None` follows the system
+    pub language: Option<Locale>,
+    pub theme: Theme,
+    /// Accent color as `#rrggbb`; `None` keeps the theme's own
+    pub accent: Option<String>,
+    /// Zoom of the whole UI in percent; `None` follows the system's text size
+    pub scale: Option<u32>,
+    /// Blow the countdown up to be read from across the room
+    pub bedside: bool,
+    /// Big controls and focus rings for a TV, driven by a controller or
+    /// remote from the couch
+    pub leanback: bool,
+    /// Name of the monitor theater mode and the countdown widget go to;
+    /// `None` keeps them on the one the window is on
+    pub monitor: Option<String>,
+    /// Dim the whole screen through the system instead of only the window,
+    /// where the desktop allows it
+    pub system_dim: bool,
+    /// Warm the screen's colour along with the system dim
+    pub night_light: bool,
+    /// Cover the other monitors with black windows that dim along with the
+    /// main one, for screens the system can't dim
+    pub dim_all_monitors: bool,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            zen_clock: false,
+            zen_dim: 60,
+            breathing: false,
+            breathing_pattern: BreathingPattern::default(),
+            breathing_minutes: 5,
+            clock_12h: false,
+            language: None,
+            theme: Theme::default(),
+            accent: None,
+            scale: None,
+            bedside: false,
+            leanback: false,
+            monitor: None,
+            system_dim: false,
+            night_light: false,
+            dim_all_monitors: false,
+        }
+    }
+}
+
+/// Time of day as 23:42, or 11:42 PM on a 12 hour clock
+pub fn format_clock(hours: u32, minutes: u32, twelve_hour: bool) -> String {
+    if !twelve_hour {
+        return format!("{:02}:{:02}", hours, minutes);
+    }
+    let suffix = if hours < 12 { "AM" } else { "PM" };
+    let hours = match hours % 12 {
+        0 => 12,
+        h => h,
+    };
+    format!("{}:{:02} {}", hours, minutes, suffix)
+}
+
+/// The user's own stylesheet, loaded over the built-in one
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CustomCss {
+    /// Where eepy looks for it
+    pub path: String,
+    /// `None` if there's no file there
+    pub css: Option<String>,
+}
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+/// Centre frequencies of the bands in Hz. The lowest is a shelf under it
+/// and the highest a shelf over it.
+pub const BANDS_HZ: [f32; 5] = [60.0, 250.0, 1000.0, 4000.0, 12000.0];
+/// Furthest a band goes up or down, in dB
+pub const MAX_GAIN_DB: i32 = 12;
+
+/// Starting points for the bands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EqPreset {
+    Flat,
+    ReduceTreble,
+    BassHeavyRain,
+    SoftVoices,
+}
+
+impl EqPreset {
+    pub const ALL: [EqPreset; 4] = [
+        EqPreset::Flat,
+        EqPreset::ReduceTreble,
+        EqPreset::BassHeavyRain,
+        EqPreset::SoftVoices,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EqPreset::Flat => "flat",
+            EqPreset::ReduceTreble => "reduce_treble",
+            EqPreset::BassHeavyRain => "bass_heavy_rain",
+            EqPreset::SoftVoices => "soft_voices",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.as_str() == value)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EqPreset::Flat => "FLAT",
+            EqPreset::ReduceTreble => "REDUCE TREBLE",
+            EqPreset::BassHeavyRain => "BASS HEAVY RAIN",
+            EqPreset::SoftVoices => "SOFT VOICES",
+        }
+    }
+
+    /// Gain of each band in dB
+    pub fn gains(self) -> [i32; 5] {
+        match self {
+            EqPreset::Flat => [0, 0, 0, 0, 0],
+            EqPreset::ReduceTreble => [0, 0, 0, -6, -12],
+            EqPreset::BassHeavyRain => [8, 4, 0, -3, -6],
+            // Speech sits in the middle bands
+            EqPreset::SoftVoices => [0, -2, -6, -4, 0],
+        }
+    }
+
+    /// The preset these gains are, if any
+    pub fn matching(gains: &[i32; 5]) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| &preset.gains() == gains)
+    }
+}
+
+/// Equalizer on the audio stream
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EqSettings {
+    pub enabled: bool,
+    /// Gain of each of [`BANDS_HZ`] in dB
+    pub gains: [i32; 5],
+}
@@ -0,0 +1,112 @@
+use std::fmt;
+use std::io::ErrorKind;
+
+use serde::{Deserialize, Serialize};
+
+/// Why a backend command failed, so the frontend can say what to do about it
+/// and offer a retry where one can help
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum EepyError {
+    /// This platform has no way to do it
+    UnsupportedPlatform(String),
+    /// The system refused, e.g. suspending without the right polkit rule
+    PermissionDenied(String),
+    /// A program eepy runs isn't installed; holds the program
+    CommandNotFound(String),
+    /// A program eepy runs failed or took too long
+    CommandFailed(String),
+    /// Reading or writing eepy's files failed
+    Io(String),
+    /// A service couldn't be reached or answered with an error
+    Network(String),
+    /// Strict mode or the parental profile doesn't allow it
+    Locked(String),
+    /// Input the command can't use, e.g. a wrong PIN
+    Invalid(String),
+    Other(String),
+}
+
+impl EepyError {
+    /// Failed to spawn `program`
+    pub fn spawn(program: &str, e: std::io::Error) -> Self {
+        match e.kind() {
+            ErrorKind::NotFound => EepyError::CommandNotFound(program.to_string()),
+            ErrorKind::PermissionDenied => {
+                EepyError::PermissionDenied(format!("Not allowed to run {}", program))
+            }
+            _ => EepyError::CommandFailed(format!("Failed to run {}: {}", program, e)),
+        }
+    }
+
+    /// Failed to `what` with a file
+    pub fn io(what: &str, e: std::io::Error) -> Self {
+        let message = format!("Failed to {}: {}", what, e);
+        match e.kind() {
+            ErrorKind::PermissionDenied => EepyError::PermissionDenied(message),
+            _ => EepyError::Io(message),
+        }
+    }
+
+    /// Whether trying again can work, possibly after following the hint
+    pub fn retryable(&self) -> bool {
+        !matches!(
+            self,
+            EepyError::UnsupportedPlatform(_) | EepyError::Locked(_) | EepyError::Invalid(_)
+        )
+    }
+
+    /// What the user can do about it
+    pub fn hint(&self) -> Option<String> {
+        match self {
+            EepyError::UnsupportedPlatform(_) => {
+                Some("Pick something else in settings".to_string())
+            }
+            EepyError::PermissionDenied(_) => Some(
+                "Allow eepy in your system's power or privacy settings, then try again".to_string(),
+            ),
+            EepyError::CommandNotFound(program) => {
+                Some(format!("Install {} or add it to your PATH", program))
+            }
+            EepyError::Io(_) => Some("Check that there is free disk space".to_string()),
+            EepyError::Network(_) => Some("Check your connection and try again".to_string()),
+            EepyError::CommandFailed(_)
+            | EepyError::Locked(_)
+            | EepyError::Invalid(_)
+            | EepyError::Other(_) => None,
+        }
+    }
+
+    /// The message followed by the hint, for showing to the user
+    pub fn explained(&self) -> String {
+        match self.hint() {
+            Some(hint) => format!("{}. {}", self, hint),
+            None => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for EepyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EepyError::CommandNotFound(program) => write!(f, "{} was not found", program),
+            EepyError::UnsupportedPlatform(message)
+            | EepyError::PermissionDenied(message)
+            | EepyError::CommandFailed(message)
+            | EepyError::Io(message)
+            | EepyError::Network(message)
+            | EepyError::Locked(message)
+            | EepyError::Invalid(message)
+            | EepyError::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for EepyError {}
+
+/// Internal helpers still fail with plain messages
+impl From<String> for EepyError {
+    fn from(message: String) -> Self {
+        EepyError::Other(message)
+    }
+}
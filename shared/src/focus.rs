@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// Silence OS notifications while a session runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DndSettings {
+    pub enabled: bool,
+    /// macOS has no public Focus API, so we run user-created Shortcuts
+    pub mac_shortcut_on: String,
+    pub mac_shortcut_off: String,
+}
+
+impl Default for DndSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mac_shortcut_on: "eepy Focus On".to_string(),
+            mac_shortcut_off: "eepy Focus Off".to_string(),
+        }
+    }
+}
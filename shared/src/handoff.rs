@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+/// What happens when a browser extension sends a tab to eepy
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HandoffSettings {
+    /// Start a session right away with the last-used duration
+    pub auto_start: bool,
+}
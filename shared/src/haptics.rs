@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+/// Kind of buzz, from lightest to most insistent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Haptic {
+    /// Picking a preset
+    Selection,
+    /// Starting or cancelling the timer
+    Impact,
+    /// A milestone coming up
+    Warning,
+}
+
+impl Haptic {
+    /// Milliseconds of vibration and pause in turn, starting with vibration
+    pub fn pattern(self) -> &'static [u64] {
+        match self {
+            Haptic::Selection => &[10],
+            Haptic::Impact => &[25],
+            Haptic::Warning => &[40, 80, 40],
+        }
+    }
+}
+
+/// Short buzzes on phones as presets are picked, the timer starts or is
+/// cancelled, and milestones come up
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HapticSettings {
+    pub enabled: bool,
+}
+
+impl Default for HapticSettings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
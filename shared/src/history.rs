@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+use crate::power::EndAction;
+
+/// Sessions kept in the history, oldest dropped first
+pub const HISTORY_LIMIT: usize = 5000;
+/// How long after a session ends waking up from it can be noted, in ms
+pub const WAKE_NOTE_WINDOW_MS: u64 = 16 * 60 * 60 * 1000;
+
+/// How a session came to an end
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionOutcome {
+    /// The countdown ran out and the end action ran
+    Finished,
+    Cancelled,
+}
+
+impl SessionOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionOutcome::Finished => "finished",
+            SessionOutcome::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// A past session. Times are Unix milliseconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub started_at: u64,
+    pub ended_at: u64,
+    /// Length the session was started with
+    pub planned_seconds: u32,
+    pub video_id: Option<String>,
+    pub action: EndAction,
+    pub outcome: SessionOutcome,
+    /// How they felt going to bed
+    #[serde(default)]
+    pub bedtime_note: Option<String>,
+    /// How they slept, noted after waking up
+    #[serde(default)]
+    pub wake_note: Option<String>,
+    /// When eepy first came up again after the session ran out, taken as
+    /// when they woke up
+    #[serde(default)]
+    pub woke_at: Option<u64>,
+}
+
+impl SessionRecord {
+    /// Whether waking up from it is still to be noted: the session ran out
+    /// not long before `now` and nothing has been said yet
+    pub fn awaits_wake(&self, now: u64) -> bool {
+        self.outcome == SessionOutcome::Finished
+            && self.woke_at.is_none()
+            && self.wake_note.is_none()
+            && now.saturating_sub(self.ended_at) < WAKE_NOTE_WINDOW_MS
+    }
+
+    /// From going to bed to waking up, for sessions where that's known
+    pub fn sleep(&self) -> Option<(u64, u64)> {
+        match (self.outcome, self.woke_at) {
+            (SessionOutcome::Finished, Some(woke_at)) => Some((self.started_at, woke_at)),
+            _ => None,
+        }
+    }
+}
+
+/// One-line notes on how they feel, kept with each session
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JournalSettings {
+    /// Ask how they're feeling once the timer starts
+    pub at_start: bool,
+    /// Ask how they slept the next time eepy is opened
+    pub on_wake: bool,
+}
+
+/// File formats the history can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryFormat {
+    Csv,
+    Json,
+    /// Nights in the columns of Fitbit's sleep export, which sleep trackers
+    /// and health apps import
+    SleepCsv,
+    /// Nights as Open mHealth `sleep-episode` data points
+    OpenMHealth,
+}
+
+impl HistoryFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            HistoryFormat::Csv | HistoryFormat::SleepCsv => "csv",
+            HistoryFormat::Json | HistoryFormat::OpenMHealth => "json",
+        }
+    }
+
+    /// Name the save dialog suggests, without the extension
+    pub fn file_stem(&self) -> &'static str {
+        match self {
+            HistoryFormat::Csv | HistoryFormat::Json => "eepy-history",
+            HistoryFormat::SleepCsv => "eepy-sleep",
+            HistoryFormat::OpenMHealth => "eepy-sleep-omh",
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+/// Languages the UI is translated into
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+    Es,
+    Ja,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 4] = [Locale::En, Locale::De, Locale::Es, Locale::Ja];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::De => "de",
+            Locale::Es => "es",
+            Locale::Ja => "ja",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|locale| locale.as_str() == value)
+    }
+
+    /// The language's own name for itself
+    pub fn label(self) -> &'static str {
+        match self {
+            Locale::En => "ENGLISH",
+            Locale::De => "DEUTSCH",
+            Locale::Es => "ESPAÑOL",
+            Locale::Ja => "日本語",
+        }
+    }
+
+    /// The closest match for a language tag like "de-AT", falling back to
+    /// English
+    pub fn from_tag(tag: &str) -> Self {
+        let language = tag.split(['-', '_']).next().unwrap_or_default();
+        Self::parse(&language.to_lowercase()).unwrap_or_default()
+    }
+}
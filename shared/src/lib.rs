@@ -0,0 +1,46 @@
+//! Types that cross the IPC boundary between the frontend and the backend.
+
+pub mod apps;
+pub mod busy;
+pub mod chime;
+pub mod clipboard;
+pub mod control;
+pub mod discord;
+pub mod display;
+pub mod equalizer;
+pub mod error;
+pub mod focus;
+pub mod handoff;
+pub mod haptics;
+pub mod history;
+pub mod i18n;
+pub mod library;
+pub mod lights;
+pub mod mascot;
+pub mod media;
+pub mod milestones;
+pub mod mqtt;
+pub mod offline;
+pub mod parental;
+pub mod pipeline;
+pub mod playback;
+pub mod plugins;
+pub mod power;
+pub mod profiles;
+pub mod push;
+pub mod remote;
+pub mod schedule;
+pub mod search;
+pub mod session;
+pub mod settings;
+pub mod sfx;
+pub mod sonos;
+pub mod sponsorblock;
+pub mod spotify;
+pub mod streaks;
+pub mod strict;
+pub mod templates;
+pub mod timer;
+pub mod webhooks;
+pub mod widget;
+pub mod windows_integration;
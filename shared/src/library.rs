@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+/// Non-favorite videos kept in the recently played list
+pub const RECENT_LIMIT: usize = 12;
+
+/// A recently played or favorited video
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibraryEntry {
+    pub video_id: String,
+    /// Looked up when the video is first played; missing when offline
+    pub title: Option<String>,
+    pub favorite: bool,
+    /// Volume in percent it was last set to while playing; the fades scale
+    /// down from it
+    #[serde(default)]
+    pub volume: Option<u32>,
+}
+
+/// What the library shows of a video, cached by the backend
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VideoInfo {
+    pub video_id: String,
+    pub title: Option<String>,
+    pub duration_secs: Option<u32>,
+    /// The thumbnail inlined as a data URL, missing until it's downloaded
+    pub thumbnail: Option<String>,
+}
+
+impl LibraryEntry {
+    pub fn thumbnail_url(&self) -> String {
+        thumbnail_url(&self.video_id)
+    }
+}
+
+/// Medium size YouTube thumbnail of a video
+pub fn thumbnail_url(video_id: &str) -> String {
+    format!("https://i.ytimg.com/vi/{}/mqdefault.jpg", video_id)
+}
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+use crate::timer::CURVE_SECONDS;
+
+/// Smart lights that dim along with the screen and switch off at the end
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LightSettings {
+    pub enabled: bool,
+    pub hue: HueSettings,
+    pub lifx: LifxSettings,
+    /// Brightness the fade starts from, in percent
+    pub start_brightness: u8,
+    /// Colour temperature at the start and at the end of the fade
+    pub start_kelvin: u16,
+    pub end_kelvin: u16,
+    pub off_at_end: bool,
+}
+
+impl Default for LightSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hue: HueSettings::default(),
+            lifx: LifxSettings::default(),
+            start_brightness: 80,
+            start_kelvin: 2700,
+            end_kelvin: 2000,
+            off_at_end: true,
+        }
+    }
+}
+
+impl LightSettings {
+    pub fn has_lights(&self) -> bool {
+        !self.hue.lights.is_empty() || !self.lifx.lights.is_empty()
+    }
+
+    /// Brightness (percent) and colour temperature for a dim level from
+    /// [`dim_level`]
+    pub fn at(&self, dim: f64) -> (u8, u16) {
+        let brightness = (self.start_brightness as f64 * (1.0 - dim))
+            .round()
+            .max(1.0);
+        let (start, end) = (self.start_kelvin as f64, self.end_kelvin as f64);
+        let kelvin = start + (end - start) * dim;
+        (brightness as u8, kelvin.round() as u16)
+    }
+}
+
+/// Philips Hue bridge on the local network
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HueSettings {
+    pub bridge_ip: String,
+    /// API user created by pressing the bridge's link button
+    pub username: String,
+    /// Bridge light IDs taking part in the fade
+    pub lights: Vec<String>,
+}
+
+/// LIFX bulbs, addressed directly over the LAN protocol
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifxSettings {
+    /// IP addresses of the bulbs taking part in the fade
+    pub lights: Vec<String>,
+}
+
+/// A light found on the network, for picking which ones take part
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FoundLight {
+    pub id: String,
+    pub name: String,
+}
+
+/// Where [`dim_level`] ends up
+pub const MAX_DIM: f64 = 0.9;
+
+/// How far the screen dim has progressed, from 0.0 at the start of a
+/// session to [`MAX_DIM`] at the end. Lights follow the same curve. Sessions longer
+/// than [`CURVE_SECONDS`] reach full dim that far in and stay there.
+pub fn dim_level(total_seconds: u32, remaining_seconds: u32) -> f64 {
+    let span = total_seconds.min(CURVE_SECONDS);
+    if span == 0 {
+        return 0.0;
+    }
+    let elapsed = total_seconds.saturating_sub(remaining_seconds).min(span);
+    elapsed as f64 / span as f64 * MAX_DIM
+}
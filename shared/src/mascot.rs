@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Largest sprite that's loaded, so a photo picked by mistake doesn't stall
+/// the window
+pub const MAX_SPRITE_BYTES: u64 = 1024 * 1024;
+
+/// Who keeps you company in the header and by the streak
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mascot {
+    #[default]
+    Whale,
+    Cat,
+    Moon,
+    /// An image of their own, from `sprite_path`
+    Custom,
+}
+
+impl Mascot {
+    pub const ALL: [Mascot; 4] = [Mascot::Whale, Mascot::Cat, Mascot::Moon, Mascot::Custom];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Mascot::Whale => "whale",
+            Mascot::Cat => "cat",
+            Mascot::Moon => "moon",
+            Mascot::Custom => "custom",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|mascot| mascot.as_str() == value)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Mascot::Whale => "WHALE",
+            Mascot::Cat => "CAT",
+            Mascot::Moon => "MOON",
+            Mascot::Custom => "MY OWN IMAGE",
+        }
+    }
+
+    /// Drawn as text; a custom sprite shows the whale until it's loaded
+    pub fn emoji(self) -> &'static str {
+        match self {
+            Mascot::Whale | Mascot::Custom => "🐳",
+            Mascot::Cat => "🐱",
+            Mascot::Moon => "🌙",
+        }
+    }
+}
+
+/// Status lines that can be reworded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusMessage {
+    Ready,
+    Running,
+    Paused,
+    Cancelled,
+    GettingSleepy,
+    AlmostThere,
+    ClosingApps,
+    SweetDreams,
+}
+
+impl StatusMessage {
+    pub const ALL: [StatusMessage; 8] = [
+        StatusMessage::Ready,
+        StatusMessage::Running,
+        StatusMessage::Paused,
+        StatusMessage::Cancelled,
+        StatusMessage::GettingSleepy,
+        StatusMessage::AlmostThere,
+        StatusMessage::ClosingApps,
+        StatusMessage::SweetDreams,
+    ];
+
+    /// Key in `MascotSettings::messages`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StatusMessage::Ready => "ready",
+            StatusMessage::Running => "running",
+            StatusMessage::Paused => "paused",
+            StatusMessage::Cancelled => "cancelled",
+            StatusMessage::GettingSleepy => "getting_sleepy",
+            StatusMessage::AlmostThere => "almost_there",
+            StatusMessage::ClosingApps => "closing_apps",
+            StatusMessage::SweetDreams => "sweet_dreams",
+        }
+    }
+
+    /// When it shows, for the settings
+    pub fn label(self) -> &'static str {
+        match self {
+            StatusMessage::Ready => "READY",
+            StatusMessage::Running => "RUNNING",
+            StatusMessage::Paused => "PAUSED",
+            StatusMessage::Cancelled => "CANCELLED",
+            StatusMessage::GettingSleepy => "HALF WAY",
+            StatusMessage::AlmostThere => "LAST STRETCH",
+            StatusMessage::ClosingApps => "CLOSING APPS",
+            StatusMessage::SweetDreams => "AT THE END",
+        }
+    }
+}
+
+/// The mascot and reworded status lines
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MascotSettings {
+    pub mascot: Mascot,
+    /// PNG, GIF, WebP, SVG or JPEG for `Mascot::Custom`; relative paths are
+    /// in the config dir
+    pub sprite_path: String,
+    /// Replacement text by `StatusMessage::as_str`, in place of every
+    /// language's
+    pub messages: BTreeMap<String, String>,
+}
+
+impl MascotSettings {
+    /// The replacement for a status line, if there's one
+    pub fn message(&self, which: StatusMessage) -> Option<&str> {
+        self.messages
+            .get(which.as_str())
+            .map(|text| text.trim())
+            .filter(|text| !text.is_empty())
+    }
+}
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+use crate::timer::fade_window;
+
+/// Points during a session that integrations (notifications etc.) react to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Milestone {
+    MinutesLeft(u32),
+    FadeStarted,
+    SuspendSoon { seconds: u32 },
+}
+
+/// Which milestones fire during a session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MilestoneSettings {
+    /// Announce when this many minutes remain
+    pub minutes_left: Vec<u32>,
+    /// Announce when the volume fade begins
+    pub fade_started: bool,
+    /// Warn this many seconds before suspending, 0 disables the warning
+    pub suspend_warning_secs: u32,
+    /// Read milestones aloud, as quiet as the volume fade has got
+    pub speak: bool,
+}
+
+impl Default for MilestoneSettings {
+    fn default() -> Self {
+        Self {
+            minutes_left: vec![30],
+            fade_started: true,
+            suspend_warning_secs: 60,
+            speak: false,
+        }
+    }
+}
+
+/// Milestones reached on the tick where `remaining` seconds are left.
+/// The volume fade covers the [`fade_window`] at the end of the session.
+pub fn due_milestones(settings: &MilestoneSettings, total: u32, remaining: u32) -> Vec<Milestone> {
+    let mut due = Vec::new();
+    if total == 0 || remaining == 0 {
+        return due;
+    }
+
+    for &minutes in &settings.minutes_left {
+        let at = minutes * 60;
+        if at > 0 && at < total && remaining == at {
+            due.push(Milestone::MinutesLeft(minutes));
+        }
+    }
+
+    let fade = fade_window(total);
+    if settings.fade_started && fade > 0 && remaining == fade {
+        due.push(Milestone::FadeStarted);
+    }
+
+    let warning = settings.suspend_warning_secs;
+    if warning > 0 && warning < total && remaining == warning {
+        due.push(Milestone::SuspendSoon { seconds: warning });
+    }
+
+    due
+}
+
+/// Native OS notifications for milestones
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+}
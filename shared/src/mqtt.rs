@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_PORT: u16 = 1883;
+
+/// Optional MQTT publishing of the session, for home-automation setups
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttSettings {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    /// Leave empty for brokers without authentication
+    pub username: String,
+    pub password: String,
+    pub client_id: String,
+    /// Retained "running", "idle" or "offline"
+    pub state_topic: String,
+    /// Retained seconds left in the session
+    pub remaining_topic: String,
+    /// Milestones and session end, as JSON session events
+    pub event_topic: String,
+}
+
+impl Default for MqttSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "localhost".to_string(),
+            port: DEFAULT_PORT,
+            username: String::new(),
+            password: String::new(),
+            client_id: "eepy".to_string(),
+            state_topic: "eepy/state".to_string(),
+            remaining_topic: "eepy/remaining".to_string(),
+            event_topic: "eepy/events".to_string(),
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+/// URI scheme the player loads downloaded audio from, as `<scheme>://localhost/<video id>`
+pub const OFFLINE_AUDIO_SCHEME: &str = "offline-audio";
+
+/// Keeping the audio of favorites on disk, for when the connection drops
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OfflineSettings {
+    /// Download a video's audio as soon as it's made a favorite
+    pub enabled: bool,
+    /// Most the cache may take up; the longest unplayed go first past it
+    pub max_mb: u32,
+}
+
+impl Default for OfflineSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_mb: 2000,
+        }
+    }
+}
+
+/// A downloaded video's audio
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedAudio {
+    pub video_id: String,
+    pub bytes: u64,
+}
+
+/// What's in the cache, longest unplayed last
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OfflineCache {
+    pub videos: Vec<CachedAudio>,
+    /// Still being downloaded
+    pub downloading: Vec<String>,
+}
+
+impl OfflineCache {
+    pub fn total_bytes(&self) -> u64 {
+        self.videos.iter().map(|video| video.bytes).sum()
+    }
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+use crate::power::EndAction;
+
+/// Who is using the app. With a parental profile set up the app runs as the
+/// child until the parent signs in with their password.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Profile {
+    #[default]
+    Parent,
+    Child,
+}
+
+/// Limits the parent sets for the child profile
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParentalSettings {
+    pub enabled: bool,
+    /// Salted PBKDF2 of the parent's password. Only the backend sets it.
+    pub password_hash: String,
+    /// Session length the child gets
+    pub minutes: u32,
+    pub action: EndAction,
+    /// YouTube video IDs the child can pick from
+    pub videos: Vec<String>,
+}
+
+impl Default for ParentalSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            password_hash: String::new(),
+            minutes: 30,
+            action: EndAction::default(),
+            videos: Vec::new(),
+        }
+    }
+}
+
+impl ParentalSettings {
+    pub fn allows(&self, video_id: &str) -> bool {
+        self.videos.iter().any(|video| video == video_id)
+    }
+}
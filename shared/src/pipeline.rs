@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::EepyError;
+
+/// How long the fade-audio stage takes to bring the player down to silence
+pub const FADE_OUT_SECONDS: u32 = 3;
+
+/// A step of what happens once the countdown runs out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    /// Bring the in-app player down to silence
+    FadeAudio,
+    /// Pause the in-app player, media servers, Spotify and Sonos
+    PauseMedia,
+    /// Call the webhooks set up for the end of a session
+    RunHook,
+    /// Take the smart lights down, or off if they're set to switch off
+    DimLights,
+    Lock,
+    /// Call `stage` in every plugin script that has one
+    Plugins,
+    /// Whatever the session ends with: sleep, shut down, ...
+    EndAction,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 7] = [
+        Stage::FadeAudio,
+        Stage::PauseMedia,
+        Stage::RunHook,
+        Stage::DimLights,
+        Stage::Lock,
+        Stage::Plugins,
+        Stage::EndAction,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Stage::FadeAudio => "FADE AUDIO",
+            Stage::PauseMedia => "PAUSE MEDIA",
+            Stage::RunHook => "RUN WEBHOOKS",
+            Stage::DimLights => "DIM LIGHTS",
+            Stage::Lock => "LOCK THE SCREEN",
+            Stage::Plugins => "RUN PLUGINS",
+            Stage::EndAction => "END ACTION",
+        }
+    }
+}
+
+/// A stage in the pipeline, whether it runs and how long to wait before it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PipelineStage {
+    pub stage: Stage,
+    pub enabled: bool,
+    pub delay_seconds: u32,
+}
+
+/// The stages run at the end of a session, in order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PipelineSettings {
+    pub stages: Vec<PipelineStage>,
+}
+
+impl Default for PipelineSettings {
+    fn default() -> Self {
+        Self {
+            stages: Stage::ALL
+                .into_iter()
+                .map(|stage| PipelineStage {
+                    stage,
+                    // Locking first would only get in the way of sleeping
+                    enabled: stage != Stage::Lock,
+                    delay_seconds: 0,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl PipelineSettings {
+    /// Add stages newer than the saved list, switched off, before the end
+    /// action
+    pub fn fill_missing(&mut self) {
+        for stage in Stage::ALL {
+            if self.stages.iter().any(|step| step.stage == stage) {
+                continue;
+            }
+            let at = self
+                .stages
+                .iter()
+                .position(|step| step.stage == Stage::EndAction)
+                .unwrap_or(self.stages.len());
+            self.stages.insert(
+                at,
+                PipelineStage {
+                    stage,
+                    enabled: false,
+                    delay_seconds: 0,
+                },
+            );
+        }
+    }
+
+    /// Swap the stage at `index` with the one before it
+    pub fn move_up(&mut self, index: usize) {
+        if index > 0 && index < self.stages.len() {
+            self.stages.swap(index - 1, index);
+        }
+    }
+}
+
+/// How a stage went
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", content = "error", rename_all = "snake_case")]
+pub enum StageOutcome {
+    Started,
+    Done,
+    Failed(EepyError),
+}
+
+/// Progress of the pipeline, sent as each stage starts and finishes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageReport {
+    pub stage: Stage,
+    pub outcome: StageOutcome,
+}
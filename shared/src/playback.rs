@@ -0,0 +1,243 @@
+use serde::{Deserialize, Serialize};
+
+use crate::equalizer::EqSettings;
+
+/// Where the embedded player is loaded from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbedHost {
+    #[default]
+    YouTube,
+    Invidious,
+    Piped,
+}
+
+impl EmbedHost {
+    pub const ALL: [EmbedHost; 3] = [EmbedHost::YouTube, EmbedHost::Invidious, EmbedHost::Piped];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EmbedHost::YouTube => "youtube",
+            EmbedHost::Invidious => "invidious",
+            EmbedHost::Piped => "piped",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|host| host.as_str() == value)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EmbedHost::YouTube => "YOUTUBE",
+            EmbedHost::Invidious => "INVIDIOUS",
+            EmbedHost::Piped => "PIPED",
+        }
+    }
+}
+
+/// What stopping the player means at the end of a session
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopStyle {
+    /// Fade to silence, then pause
+    #[default]
+    FadeThenPause,
+    /// Fade to silence and leave it playing silently until the computer
+    /// sleeps, for players that pop when paused
+    FadeToSilence,
+    /// Pause straight away, without the fade
+    HardPause,
+}
+
+impl StopStyle {
+    pub const ALL: [StopStyle; 3] = [
+        StopStyle::FadeThenPause,
+        StopStyle::FadeToSilence,
+        StopStyle::HardPause,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StopStyle::FadeThenPause => "fade_then_pause",
+            StopStyle::FadeToSilence => "fade_to_silence",
+            StopStyle::HardPause => "hard_pause",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|style| style.as_str() == value)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StopStyle::FadeThenPause => "FADE, THEN PAUSE",
+            StopStyle::FadeToSilence => "FADE AND KEEP PLAYING SILENTLY",
+            StopStyle::HardPause => "PAUSE RIGHT AWAY",
+        }
+    }
+
+    /// Whether the fade-audio stage fades the player
+    pub fn fades(self) -> bool {
+        self != StopStyle::HardPause
+    }
+
+    /// Whether the pause-media stage pauses the player, rather than only
+    /// making sure it's silent
+    pub fn pauses(self) -> bool {
+        self != StopStyle::FadeToSilence
+    }
+}
+
+/// How the video is shown while it plays
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlaybackSettings {
+    /// Shrink the player out of sight and keep only the sound. YouTube picks
+    /// the stream quality from the player size, so this also drops the
+    /// video to its lowest resolution.
+    pub audio_only: bool,
+    /// Resolve videos into a direct audio stream with yt-dlp and play that
+    /// instead of the embed, falling back to the embed when it fails
+    pub extract_audio: bool,
+    /// yt-dlp executable; empty looks it up on the PATH
+    pub ytdlp_path: String,
+    pub embed_host: EmbedHost,
+    /// Base URL of the Invidious or Piped instance, e.g. https://yewtu.be
+    pub instance_url: String,
+    /// Close a low-pass filter along with the volume fade, so the sound goes
+    /// soft and muffled instead of just quieter. Only the native player for
+    /// yt-dlp streams can be filtered.
+    pub muffle: bool,
+    /// Even out loudness between videos, using the loudness YouTube reports
+    pub normalize: bool,
+    /// Turn sudden loud bursts down, like mid-roll ads and loud chapters.
+    /// The audio stream is measured; the embed drops its volume during ads.
+    pub duck_spikes: bool,
+    /// Take the keyboard's media keys for the player, even in the background
+    pub media_keys: bool,
+    /// How the fade-audio and pause-media stages stop the player
+    pub stop: StopStyle,
+    pub eq: EqSettings,
+    /// Play the lowest quality YouTube has and the smallest yt-dlp stream
+    pub low_data: bool,
+    /// Save data like `low_data` whenever the connection is metered
+    pub low_data_when_metered: bool,
+}
+
+/// Loudness, in dB against YouTube's reference, that normalized videos are
+/// brought to. Below the reference, so quiet videos have room to come up.
+pub const NORMALIZED_DB: f64 = -6.0;
+
+/// Volume in percent that brings a video of this loudness to
+/// `NORMALIZED_DB`. Unknown loudness is taken to be at the reference, so
+/// other sources don't jump out either. Volume can't go past 100, so very
+/// quiet videos stay a little quieter.
+pub fn normalized_level(loudness_db: Option<f64>) -> u32 {
+    let gain = 10f64.powf((NORMALIZED_DB - loudness_db.unwrap_or(0.0)) / 20.0);
+    (gain.clamp(0.0, 1.0) * 100.0).round() as u32
+}
+
+/// How far, in dB, a burst has to jump over the recent loudness to be
+/// turned down
+pub const SPIKE_DB: f64 = 10.0;
+/// The most a burst is turned down by, in dB
+pub const MAX_DUCK_DB: f64 = 18.0;
+/// Volume in percent the embed keeps while it plays an ad
+pub const AD_BREAK_LEVEL: u32 = 30;
+
+/// Gain, 1 for untouched, that brings a burst at `level_db` back down to
+/// `baseline_db`. Anything less than `SPIKE_DB` over it is left alone.
+pub fn spike_gain(level_db: f64, baseline_db: f64) -> f64 {
+    let over = level_db - baseline_db;
+    if over < SPIKE_DB {
+        return 1.0;
+    }
+    10f64.powf(-over.min(MAX_DUCK_DB) / 20.0)
+}
+
+impl PlaybackSettings {
+    /// Whether the audio stream is routed through the filters, which needs
+    /// its server to allow it
+    pub fn filters_stream(&self) -> bool {
+        self.muffle || self.duck_spikes || self.eq.enabled
+    }
+
+    /// Whether to save data on a connection that is `metered` or not
+    pub fn saves_data(&self, metered: bool) -> bool {
+        self.low_data || (self.low_data_when_metered && metered)
+    }
+
+    /// Player URL for a video. Falls back to youtube.com until an instance
+    /// is configured.
+    pub fn embed_url(&self, video_id: &str) -> String {
+        let instance = self.instance_url.trim().trim_end_matches('/');
+        if self.embed_host == EmbedHost::YouTube || instance.is_empty() {
+            format!(
+                "https://www.youtube.com/embed/{}?autoplay=1&enablejsapi=1",
+                video_id
+            )
+        } else {
+            format!("{}/embed/{}?autoplay=1", instance, video_id)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hosted(embed_host: EmbedHost, instance_url: &str) -> PlaybackSettings {
+        PlaybackSettings {
+            embed_host,
+            instance_url: instance_url.to_string(),
+            ..PlaybackSettings::default()
+        }
+    }
+
+    #[test]
+    fn youtube_embeds_take_api_commands() {
+        assert_eq!(
+            PlaybackSettings::default().embed_url("dQw4w9WgXcQ"),
+            "https://www.youtube.com/embed/dQw4w9WgXcQ?autoplay=1&enablejsapi=1"
+        );
+    }
+
+    #[test]
+    fn invidious_and_piped_embed_from_their_instance() {
+        assert_eq!(
+            hosted(EmbedHost::Invidious, "https://yewtu.be").embed_url("dQw4w9WgXcQ"),
+            "https://yewtu.be/embed/dQw4w9WgXcQ?autoplay=1"
+        );
+        assert_eq!(
+            hosted(EmbedHost::Piped, " https://piped.video/ ").embed_url("dQw4w9WgXcQ"),
+            "https://piped.video/embed/dQw4w9WgXcQ?autoplay=1"
+        );
+    }
+
+    #[test]
+    fn instance_hosts_fall_back_to_youtube_until_an_instance_is_set() {
+        for host in [EmbedHost::Invidious, EmbedHost::Piped] {
+            assert_eq!(
+                hosted(host, "  ").embed_url("dQw4w9WgXcQ"),
+                PlaybackSettings::default().embed_url("dQw4w9WgXcQ")
+            );
+        }
+    }
+
+    #[test]
+    fn youtube_ignores_a_leftover_instance() {
+        assert_eq!(
+            hosted(EmbedHost::YouTube, "https://yewtu.be").embed_url("dQw4w9WgXcQ"),
+            PlaybackSettings::default().embed_url("dQw4w9WgXcQ")
+        );
+    }
+
+    #[test]
+    fn embed_hosts_round_trip_through_their_names() {
+        for host in EmbedHost::ALL {
+            assert_eq!(EmbedHost::parse(host.as_str()), Some(host));
+        }
+        assert_eq!(EmbedHost::parse("vimeo"), None);
+    }
+}
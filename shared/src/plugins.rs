@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// Rhai scripts from the plugins folder, run on session start, at the end and
+/// as a stage of the end-of-session pipeline
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PluginSettings {
+    pub enabled: bool,
+    /// Let scripts start programs with `run`
+    pub allow_commands: bool,
+}
+
+/// The scripts found in the plugins folder
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PluginList {
+    pub dir: String,
+    /// File names without the `.rhai` extension
+    pub names: Vec<String>,
+}
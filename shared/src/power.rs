@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+
+/// What happens to the computer when the timer runs out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EndAction {
+    #[default]
+    Suspend,
+    Hibernate,
+    Shutdown,
+    /// Lock the screen and leave the computer on
+    Lock,
+    /// Turn the screen off and leave the computer on
+    ScreenOff,
+    /// Only pause playback
+    Nothing,
+}
+
+impl EndAction {
+    pub const ALL: [EndAction; 6] = [
+        EndAction::Suspend,
+        EndAction::Hibernate,
+        EndAction::Shutdown,
+        EndAction::Lock,
+        EndAction::ScreenOff,
+        EndAction::Nothing,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EndAction::Suspend => "suspend",
+            EndAction::Hibernate => "hibernate",
+            EndAction::Shutdown => "shutdown",
+            EndAction::Lock => "lock",
+            EndAction::ScreenOff => "screen_off",
+            EndAction::Nothing => "nothing",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "suspend" | "sleep" => Some(EndAction::Suspend),
+            "hibernate" => Some(EndAction::Hibernate),
+            "shutdown" | "poweroff" => Some(EndAction::Shutdown),
+            "lock" => Some(EndAction::Lock),
+            "screen_off" | "display_off" => Some(EndAction::ScreenOff),
+            "nothing" | "none" => Some(EndAction::Nothing),
+            _ => None,
+        }
+    }
+
+    /// What the computer does at the end, as in "SUSPENDS AT 23:42"
+    pub fn verb(&self) -> &'static str {
+        match self {
+            EndAction::Suspend => "SUSPENDS",
+            EndAction::Hibernate => "HIBERNATES",
+            EndAction::Shutdown => "SHUTS DOWN",
+            EndAction::Lock => "LOCKS",
+            EndAction::ScreenOff => "GOES DARK",
+            EndAction::Nothing => "PAUSES",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EndAction::Suspend => "SLEEP",
+            EndAction::Hibernate => "HIBERNATE",
+            EndAction::Shutdown => "SHUT DOWN",
+            EndAction::Lock => "LOCK",
+            EndAction::ScreenOff => "TURN THE SCREEN OFF",
+            EndAction::Nothing => "JUST PAUSE",
+        }
+    }
+}
+
+/// What this platform can do at the end of a session
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PowerCapabilities {
+    pub suspend: bool,
+    pub hibernate: bool,
+    pub shutdown: bool,
+    pub lock: bool,
+    pub display_off: bool,
+    /// Waking from sleep at a set time, for the wake-up alarm
+    pub wake: bool,
+}
+
+impl PowerCapabilities {
+    pub fn supports(&self, action: EndAction) -> bool {
+        match action {
+            EndAction::Suspend => self.suspend,
+            EndAction::Hibernate => self.hibernate,
+            EndAction::Shutdown => self.shutdown,
+            EndAction::Lock => self.lock,
+            EndAction::ScreenOff => self.display_off,
+            EndAction::Nothing => true,
+        }
+    }
+}
+
+/// How a troubleshooting check came out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    /// Works, but something around it won't
+    Warn,
+    Fail,
+}
+
+/// One line of the sleep troubleshooting checklist
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PowerCheck {
+    pub label: String,
+    pub status: CheckStatus,
+    /// What was found, and what to do about it if it isn't a pass
+    pub detail: String,
+}
+
+impl PowerCheck {
+    pub fn new(label: &str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            label: label.to_string(),
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Everything the backend found out about putting this computer to sleep
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PowerDiagnostics {
+    /// OS family and architecture, e.g. "linux x86_64"
+    pub platform: String,
+    pub checks: Vec<PowerCheck>,
+}
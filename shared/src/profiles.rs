@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// Profile that exists from the start and can't be deleted
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Someone sharing the app, with their own presets, playback and fade
+/// preferences, favorites and history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserProfile {
+    pub id: String,
+    pub name: String,
+}
+
+/// Every profile and which one is in use
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileList {
+    pub active: String,
+    pub profiles: Vec<UserProfile>,
+}
+
+/// A settings export picked for importing, and the profiles here it would
+/// remove along with their libraries and histories. Asked about before
+/// anything changes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportPreview {
+    pub path: String,
+    /// Names of the profiles missing from the export
+    pub dropped: Vec<String>,
+}
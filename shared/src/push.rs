@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PushService {
+    #[default]
+    Ntfy,
+    Gotify,
+    Telegram,
+}
+
+impl PushService {
+    pub const ALL: [PushService; 3] = [
+        PushService::Ntfy,
+        PushService::Gotify,
+        PushService::Telegram,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PushService::Ntfy => "ntfy",
+            PushService::Gotify => "gotify",
+            PushService::Telegram => "telegram",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|service| service.as_str() == value)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PushService::Ntfy => "NTFY",
+            PushService::Gotify => "GOTIFY",
+            PushService::Telegram => "TELEGRAM",
+        }
+    }
+}
+
+/// Push messages to a phone when a session starts and when it ends
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PushSettings {
+    pub enabled: bool,
+    pub service: PushService,
+    /// ntfy or Gotify server; unused for Telegram
+    pub server: String,
+    /// ntfy topic or Telegram chat ID
+    pub target: String,
+    /// Gotify app token or Telegram bot token
+    pub token: String,
+    pub on_start: bool,
+    pub on_end: bool,
+}
+
+impl Default for PushSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            service: PushService::default(),
+            server: "https://ntfy.sh".to_string(),
+            target: String::new(),
+            token: String::new(),
+            on_start: true,
+            on_end: true,
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_PORT: u16 = 4774;
+
+/// Opt-in HTTP/WebSocket API for controlling the timer from another device
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteSettings {
+    pub enabled: bool,
+    /// Listen on all interfaces instead of localhost only
+    pub bind_lan: bool,
+    pub port: u16,
+    /// Required on every request; generated by the backend when empty
+    pub token: String,
+}
+
+impl Default for RemoteSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_lan: false,
+            port: DEFAULT_PORT,
+            token: String::new(),
+        }
+    }
+}
+
+/// What the settings panel needs to pair a phone with the remote page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pairing {
+    pub url: String,
+    /// QR code of `url` as an SVG document
+    pub qr_svg: String,
+}
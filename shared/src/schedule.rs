@@ -0,0 +1,298 @@
+use serde::{Deserialize, Serialize};
+
+use crate::chime::ChimeTone;
+
+/// Launch eepy at login
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutostartSettings {
+    pub enabled: bool,
+    /// Start hidden in the tray when launched at login
+    pub start_minimized: bool,
+}
+
+impl Default for AutostartSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start_minimized: true,
+        }
+    }
+}
+
+/// Start a session automatically at bedtime on selected days
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BedtimeSchedule {
+    pub enabled: bool,
+    /// Local time as "HH:MM"
+    pub time: String,
+    /// Days the schedule is active, 0 = Monday .. 6 = Sunday
+    pub days: Vec<u32>,
+    pub minutes: u32,
+}
+
+impl Default for BedtimeSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            time: "23:00".to_string(),
+            days: (0..7).collect(),
+            minutes: 60,
+        }
+    }
+}
+
+impl BedtimeSchedule {
+    /// Parse `time` into (hour, minute)
+    pub fn parsed_time(&self) -> Option<(u32, u32)> {
+        parse_time(&self.time)
+    }
+
+    /// Whether bedtime falls on this weekday (0 = Monday) and minute
+    pub fn is_due(&self, weekday: u32, hour: u32, minute: u32) -> bool {
+        self.enabled && self.days.contains(&weekday) && self.parsed_time() == Some((hour, minute))
+    }
+
+    /// Minutes from this weekday (0 = Monday) and time to the next bedtime,
+    /// if there's one within a day. Reminders count down to it whether or
+    /// not the schedule starts the session itself.
+    pub fn minutes_until(&self, weekday: u32, hour: u32, minute: u32) -> Option<u32> {
+        let (h, m) = self.parsed_time()?;
+        let now = hour * 60 + minute;
+        let bedtime = h * 60 + m;
+        if bedtime >= now && self.days.contains(&weekday) {
+            return Some(bedtime - now);
+        }
+        // Today's has gone by, so it's tomorrow's
+        let tomorrow = (weekday + 1) % 7;
+        (bedtime < now && self.days.contains(&tomorrow)).then(|| bedtime + 24 * 60 - now)
+    }
+}
+
+/// Tauri event carrying the minutes left to bedtime, for the banner
+pub const REMINDER_EVENT: &str = "schedule://reminder";
+
+/// Nudges before the scheduled bedtime, as a notification and a banner
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BedtimeReminders {
+    pub enabled: bool,
+    /// Minutes before bedtime to nudge at
+    pub lead_minutes: Vec<u32>,
+}
+
+impl Default for BedtimeReminders {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            lead_minutes: vec![30, 10],
+        }
+    }
+}
+
+/// Lead times above this are a heads-up, the rest a nudge to start
+pub const SOON_MINUTES: u32 = 15;
+
+impl BedtimeReminders {
+    /// Whether to nudge with this many minutes left
+    pub fn is_due(&self, minutes_left: u32) -> bool {
+        self.enabled && self.lead_minutes.contains(&minutes_left)
+    }
+}
+
+/// What the reminder says, firmer the closer bedtime is
+pub fn reminder_message(minutes_left: u32) -> String {
+    match minutes_left {
+        0 => "It's bedtime! Start the timer 🐳".to_string(),
+        m if m <= SOON_MINUTES => format!("Bedtime in {} min, time to wind down 🐳", m),
+        m => format!("Bedtime in {} min 🐳", m),
+    }
+}
+
+/// Parse a local "HH:MM" time into (hour, minute)
+pub fn parse_time(time: &str) -> Option<(u32, u32)> {
+    let (h, m) = time.trim().split_once(':')?;
+    let (h, m) = (h.parse().ok()?, m.parse().ok()?);
+    (h < 24 && m < 60).then_some((h, m))
+}
+
+/// Minutes after the alarm time it still goes off, e.g. when the computer
+/// woke up late or was opened by hand
+pub const WAKE_GRACE_MINUTES: u32 = 30;
+
+/// Wake the computer from suspend in the morning and show a sunrise
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WakeAlarm {
+    pub enabled: bool,
+    /// Local time as "HH:MM"
+    pub time: String,
+    /// Days the alarm is active, 0 = Monday .. 6 = Sunday
+    pub days: Vec<u32>,
+    /// How long the sunrise takes to reach full brightness and volume
+    pub sunrise_minutes: u32,
+    pub snooze_minutes: u32,
+    pub tone: ChimeTone,
+    /// Percent, reached at the end of the sunrise
+    pub volume: u32,
+}
+
+impl Default for WakeAlarm {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            time: "07:00".to_string(),
+            days: (0..5).collect(),
+            sunrise_minutes: 10,
+            snooze_minutes: 9,
+            tone: ChimeTone::Bowl,
+            volume: 80,
+        }
+    }
+}
+
+impl WakeAlarm {
+    /// Parse `time` into (hour, minute)
+    pub fn parsed_time(&self) -> Option<(u32, u32)> {
+        parse_time(&self.time)
+    }
+
+    /// Whether the alarm should go off on this weekday (0 = Monday) and
+    /// minute, allowing for a late wake-up
+    pub fn is_due(&self, weekday: u32, hour: u32, minute: u32) -> bool {
+        let Some((h, m)) = self.parsed_time().filter(|_| self.enabled) else {
+            return false;
+        };
+        let late = (hour * 60 + minute) as i64 - (h * 60 + m) as i64;
+        self.days.contains(&weekday) && (0..WAKE_GRACE_MINUTES as i64).contains(&late)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bedtime(time: &str, days: &[u32]) -> BedtimeSchedule {
+        BedtimeSchedule {
+            enabled: true,
+            time: time.to_string(),
+            days: days.to_vec(),
+            ..BedtimeSchedule::default()
+        }
+    }
+
+    fn alarm(time: &str, days: &[u32]) -> WakeAlarm {
+        WakeAlarm {
+            enabled: true,
+            time: time.to_string(),
+            days: days.to_vec(),
+            ..WakeAlarm::default()
+        }
+    }
+
+    #[test]
+    fn parse_time_reads_hours_and_minutes() {
+        assert_eq!(parse_time("23:05"), Some((23, 5)));
+        assert_eq!(parse_time(" 7:30 "), Some((7, 30)));
+        assert_eq!(parse_time("00:00"), Some((0, 0)));
+    }
+
+    #[test]
+    fn parse_time_rejects_impossible_times() {
+        assert_eq!(parse_time("24:00"), None);
+        assert_eq!(parse_time("12:60"), None);
+        assert_eq!(parse_time("noon"), None);
+        assert_eq!(parse_time("12"), None);
+        assert_eq!(parse_time(""), None);
+    }
+
+    #[test]
+    fn bedtime_is_due_on_its_days_and_minute() {
+        let schedule = bedtime("23:00", &[0, 1, 2]);
+        assert!(schedule.is_due(1, 23, 0));
+        assert!(!schedule.is_due(1, 23, 1));
+        assert!(!schedule.is_due(5, 23, 0));
+        let off = BedtimeSchedule {
+            enabled: false,
+            ..schedule
+        };
+        assert!(!off.is_due(1, 23, 0));
+    }
+
+    #[test]
+    fn minutes_until_later_today() {
+        let schedule = bedtime("23:00", &[0]);
+        assert_eq!(schedule.minutes_until(0, 22, 30), Some(30));
+        assert_eq!(schedule.minutes_until(0, 23, 0), Some(0));
+        // Not one of its days
+        assert_eq!(schedule.minutes_until(3, 22, 30), None);
+    }
+
+    #[test]
+    fn minutes_until_after_midnight() {
+        // A bedtime past midnight falls on the next day
+        let schedule = bedtime("00:30", &[2]);
+        assert_eq!(schedule.minutes_until(1, 23, 45), Some(45));
+        assert_eq!(schedule.minutes_until(2, 0, 10), Some(20));
+        assert_eq!(schedule.minutes_until(0, 23, 45), None);
+    }
+
+    #[test]
+    fn minutes_until_wraps_from_sunday_to_monday() {
+        let schedule = bedtime("01:00", &[0]);
+        assert_eq!(schedule.minutes_until(6, 23, 0), Some(120));
+    }
+
+    #[test]
+    fn minutes_until_counts_without_the_schedule_enabled() {
+        let schedule = BedtimeSchedule {
+            enabled: false,
+            ..bedtime("23:00", &[0])
+        };
+        assert_eq!(schedule.minutes_until(0, 22, 50), Some(10));
+        let broken = bedtime("late", &[0]);
+        assert_eq!(broken.minutes_until(0, 22, 50), None);
+    }
+
+    #[test]
+    fn reminders_are_due_at_their_lead_times() {
+        let reminders = BedtimeReminders {
+            enabled: true,
+            ..BedtimeReminders::default()
+        };
+        assert!(reminders.is_due(30));
+        assert!(reminders.is_due(10));
+        assert!(!reminders.is_due(20));
+        assert!(!BedtimeReminders::default().is_due(30));
+    }
+
+    #[test]
+    fn reminder_message_gets_firmer() {
+        assert_eq!(reminder_message(0), "It's bedtime! Start the timer 🐳");
+        assert_eq!(
+            reminder_message(SOON_MINUTES),
+            "Bedtime in 15 min, time to wind down 🐳"
+        );
+        assert_eq!(reminder_message(30), "Bedtime in 30 min 🐳");
+    }
+
+    #[test]
+    fn alarm_goes_off_within_the_grace_period() {
+        let wake = alarm("07:00", &[0, 1, 2, 3, 4]);
+        assert!(wake.is_due(0, 7, 0));
+        assert!(wake.is_due(0, 7, WAKE_GRACE_MINUTES - 1));
+        assert!(!wake.is_due(0, 7, WAKE_GRACE_MINUTES));
+        assert!(!wake.is_due(0, 6, 59));
+        assert!(!wake.is_due(5, 7, 0));
+    }
+
+    #[test]
+    fn alarm_stays_quiet_when_off() {
+        let wake = WakeAlarm {
+            enabled: false,
+            ..alarm("07:00", &[0])
+        };
+        assert!(!wake.is_due(0, 7, 0));
+    }
+}
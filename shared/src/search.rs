@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// Where in-app search gets its results
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchSettings {
+    /// YouTube Data API v3 key; used when set
+    pub api_key: String,
+    /// Invidious instance searched when there's no API key
+    pub invidious_url: String,
+}
+
+impl SearchSettings {
+    pub fn is_configured(&self) -> bool {
+        !self.api_key.trim().is_empty() || !self.invidious_url.trim().is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub video_id: String,
+    pub title: String,
+    pub channel: String,
+}
+
+impl SearchResult {
+    pub fn thumbnail_url(&self) -> String {
+        format!("https://i.ytimg.com/vi/{}/mqdefault.jpg", self.video_id)
+    }
+}
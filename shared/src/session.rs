@@ -0,0 +1,339 @@
+use serde::{Deserialize, Serialize};
+
+use crate::milestones::Milestone;
+use crate::pipeline::StageReport;
+use crate::power::EndAction;
+
+/// Snapshot of the countdown, reported by the frontend whenever it changes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionStatus {
+    pub running: bool,
+    pub remaining_seconds: u32,
+    pub total_seconds: u32,
+    pub video_id: Option<String>,
+    pub action: EndAction,
+}
+
+/// A running session as written to disk, so it can be picked up again after
+/// a crash or an accidental quit. Times are Unix milliseconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedSession {
+    pub started_at: u64,
+    pub ends_at: u64,
+    pub total_seconds: u32,
+    /// Link the video was loaded from
+    pub video: Option<String>,
+    pub action: EndAction,
+    pub fade: bool,
+}
+
+/// Tauri events the windows get, one per kind of [`SessionEvent`]
+pub const STARTED_EVENT: &str = "session://started";
+pub const TICK_EVENT: &str = "session://tick";
+pub const MILESTONE_EVENT: &str = "session://milestone";
+pub const FINISHED_EVENT: &str = "session://finished";
+pub const CANCELLED_EVENT: &str = "session://cancelled";
+pub const STAGE_EVENT: &str = "session://stage";
+
+/// Everything that happens to a session, as pushed to the windows, remote
+/// clients and integrations
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum SessionEvent {
+    /// The countdown began
+    Started(SessionStatus),
+    Status(SessionStatus),
+    Milestone(Milestone),
+    /// The countdown ran out and the end action is about to run
+    Ended(EndAction),
+    /// The session was stopped before the countdown ran out
+    Cancelled,
+    /// An end-of-session stage started or finished
+    Stage(StageReport),
+}
+
+impl SessionEvent {
+    /// The Tauri event this goes out as
+    pub fn name(&self) -> &'static str {
+        match self {
+            SessionEvent::Started(_) => STARTED_EVENT,
+            SessionEvent::Status(_) => TICK_EVENT,
+            SessionEvent::Milestone(_) => MILESTONE_EVENT,
+            SessionEvent::Ended(_) => FINISHED_EVENT,
+            SessionEvent::Cancelled => CANCELLED_EVENT,
+            SessionEvent::Stage(_) => STAGE_EVENT,
+        }
+    }
+}
+
+/// Where a session is. Times are Unix milliseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum SessionPhase {
+    #[default]
+    Idle,
+    Running {
+        end: f64,
+    },
+    Paused {
+        remaining: u32,
+    },
+    /// The countdown ran out and the busy guard is being asked
+    Finishing,
+    /// The end action ran
+    Done,
+}
+
+/// Everything that moves a session from one phase to the next
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transition {
+    Start {
+        seconds: u32,
+    },
+    Pause,
+    Resume,
+    /// Add time; a finishing session goes back to counting down
+    Extend {
+        seconds: u32,
+    },
+    Shorten {
+        seconds: u32,
+    },
+    /// The countdown reached zero
+    Expire,
+    /// The end action is running, whether the countdown ran out or not
+    Finish,
+    Cancel,
+}
+
+fn millis(seconds: u32) -> f64 {
+    seconds as f64 * 1000.0
+}
+
+impl SessionPhase {
+    /// The phase after `transition` happens at `now`. Transitions that don't
+    /// apply to the current phase leave it as it is.
+    pub fn reduce(self, transition: Transition, now: f64) -> Self {
+        use SessionPhase::*;
+        match (self, transition) {
+            (_, Transition::Cancel) => Idle,
+            (phase, Transition::Start { seconds }) if phase.can_start() => Running {
+                end: now + millis(seconds),
+            },
+            (Running { .. }, Transition::Pause) => Paused {
+                remaining: self.remaining(now),
+            },
+            (Paused { remaining }, Transition::Resume) => Running {
+                end: now + millis(remaining),
+            },
+            (Running { end }, Transition::Extend { seconds }) => Running {
+                end: end.max(now) + millis(seconds),
+            },
+            (Paused { remaining }, Transition::Extend { seconds }) => Paused {
+                remaining: remaining + seconds,
+            },
+            (Finishing, Transition::Extend { seconds }) => Running {
+                end: now + millis(seconds),
+            },
+            (Running { end }, Transition::Shorten { seconds }) => Running {
+                end: end - millis(seconds),
+            },
+            (Paused { remaining }, Transition::Shorten { seconds }) => Paused {
+                remaining: remaining.saturating_sub(seconds),
+            },
+            (Running { .. }, Transition::Expire) => Finishing,
+            (Running { .. } | Paused { .. } | Finishing, Transition::Finish) => Done,
+            (phase, _) => phase,
+        }
+    }
+
+    /// Whether a new session can start. One under way has to be cancelled
+    /// first, or its countdown and the new one's would get mixed up.
+    pub fn can_start(self) -> bool {
+        matches!(self, SessionPhase::Idle | SessionPhase::Done)
+    }
+
+    /// Whether a session is under way, counting down or not
+    pub fn is_active(self) -> bool {
+        matches!(
+            self,
+            SessionPhase::Running { .. } | SessionPhase::Paused { .. } | SessionPhase::Finishing
+        )
+    }
+
+    /// Whole seconds left at `now`
+    pub fn remaining(self, now: f64) -> u32 {
+        match self {
+            SessionPhase::Running { end } => ((end - now) / 1000.0).round().max(0.0) as u32,
+            SessionPhase::Paused { remaining } => remaining,
+            _ => 0,
+        }
+    }
+
+    /// When the session ends if nothing changes, for sessions under way. A
+    /// paused one ends as if it were resumed at `now`.
+    pub fn ends_at(self, now: f64) -> Option<f64> {
+        match self {
+            SessionPhase::Running { end } => Some(end),
+            SessionPhase::Paused { remaining } => Some(now + millis(remaining)),
+            SessionPhase::Finishing => Some(now),
+            SessionPhase::Idle | SessionPhase::Done => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: f64 = 1_000_000.0;
+
+    fn running(seconds: u32) -> SessionPhase {
+        SessionPhase::Idle.reduce(Transition::Start { seconds }, NOW)
+    }
+
+    #[test]
+    fn start_runs_until_the_end() {
+        assert_eq!(
+            running(60),
+            SessionPhase::Running {
+                end: NOW + 60_000.0
+            }
+        );
+        assert_eq!(running(60).remaining(NOW), 60);
+    }
+
+    #[test]
+    fn only_a_session_that_is_not_under_way_starts() {
+        assert!(SessionPhase::Idle.can_start());
+        assert!(SessionPhase::Done.can_start());
+        assert!(!running(60).can_start());
+        assert!(!SessionPhase::Paused { remaining: 40 }.can_start());
+        assert!(!SessionPhase::Finishing.can_start());
+    }
+
+    #[test]
+    fn a_finished_session_starts_again() {
+        let phase = SessionPhase::Done.reduce(Transition::Start { seconds: 30 }, NOW);
+        assert_eq!(
+            phase,
+            SessionPhase::Running {
+                end: NOW + 30_000.0
+            }
+        );
+    }
+
+    #[test]
+    fn extend_adds_to_a_running_session() {
+        let phase = running(60).reduce(Transition::Extend { seconds: 30 }, NOW + 10_000.0);
+        assert_eq!(
+            phase,
+            SessionPhase::Running {
+                end: NOW + 90_000.0
+            }
+        );
+    }
+
+    #[test]
+    fn extend_adds_to_a_paused_session() {
+        let phase =
+            SessionPhase::Paused { remaining: 40 }.reduce(Transition::Extend { seconds: 30 }, NOW);
+        assert_eq!(phase, SessionPhase::Paused { remaining: 70 });
+    }
+
+    #[test]
+    fn extend_counts_a_finishing_session_down_again() {
+        let phase = SessionPhase::Finishing.reduce(Transition::Extend { seconds: 300 }, NOW);
+        assert_eq!(
+            phase,
+            SessionPhase::Running {
+                end: NOW + 300_000.0
+            }
+        );
+    }
+
+    #[test]
+    fn shorten_takes_from_a_running_session() {
+        let phase = running(60).reduce(Transition::Shorten { seconds: 20 }, NOW);
+        assert_eq!(
+            phase,
+            SessionPhase::Running {
+                end: NOW + 40_000.0
+            }
+        );
+    }
+
+    #[test]
+    fn shorten_takes_from_a_paused_session_down_to_zero() {
+        let paused = SessionPhase::Paused { remaining: 40 };
+        assert_eq!(
+            paused.reduce(Transition::Shorten { seconds: 20 }, NOW),
+            SessionPhase::Paused { remaining: 20 }
+        );
+        assert_eq!(
+            paused.reduce(Transition::Shorten { seconds: 60 }, NOW),
+            SessionPhase::Paused { remaining: 0 }
+        );
+    }
+
+    #[test]
+    fn shorten_leaves_a_finishing_session_alone() {
+        let phase = SessionPhase::Finishing.reduce(Transition::Shorten { seconds: 20 }, NOW);
+        assert_eq!(phase, SessionPhase::Finishing);
+    }
+
+    #[test]
+    fn resume_ends_as_far_from_now_as_was_left() {
+        let paused = running(60).reduce(Transition::Pause, NOW + 15_000.0);
+        assert_eq!(paused, SessionPhase::Paused { remaining: 45 });
+        let resumed = paused.reduce(Transition::Resume, NOW + 100_000.0);
+        assert_eq!(
+            resumed,
+            SessionPhase::Running {
+                end: NOW + 145_000.0
+            }
+        );
+    }
+
+    #[test]
+    fn illegal_transitions_change_nothing() {
+        let idle = SessionPhase::Idle;
+        let paused = SessionPhase::Paused { remaining: 40 };
+        let cases = [
+            (idle, Transition::Pause),
+            (idle, Transition::Resume),
+            (idle, Transition::Extend { seconds: 30 }),
+            (idle, Transition::Expire),
+            (idle, Transition::Finish),
+            (running(60), Transition::Start { seconds: 10 }),
+            (running(60), Transition::Resume),
+            (paused, Transition::Start { seconds: 10 }),
+            (paused, Transition::Pause),
+            (paused, Transition::Expire),
+            (SessionPhase::Finishing, Transition::Start { seconds: 10 }),
+            (SessionPhase::Finishing, Transition::Pause),
+            (SessionPhase::Finishing, Transition::Resume),
+            (SessionPhase::Done, Transition::Pause),
+            (SessionPhase::Done, Transition::Extend { seconds: 30 }),
+        ];
+        for (phase, transition) in cases {
+            assert_eq!(
+                phase.reduce(transition, NOW),
+                phase,
+                "{transition:?} from {phase:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn cancel_goes_back_to_idle_from_anywhere() {
+        for phase in [
+            running(60),
+            SessionPhase::Paused { remaining: 40 },
+            SessionPhase::Finishing,
+            SessionPhase::Done,
+        ] {
+            assert_eq!(phase.reduce(Transition::Cancel, NOW), SessionPhase::Idle);
+        }
+    }
+}
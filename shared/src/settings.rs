@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+
+use crate::apps::AppQuitSettings;
+use crate::busy::BusyGuardSettings;
+use crate::chime::ChimeSettings;
+use crate::clipboard::ClipboardSettings;
+use crate::discord::DiscordSettings;
+use crate::display::DisplaySettings;
+use crate::focus::DndSettings;
+use crate::handoff::HandoffSettings;
+use crate::haptics::HapticSettings;
+use crate::history::JournalSettings;
+use crate::lights::LightSettings;
+use crate::mascot::MascotSettings;
+use crate::media::MediaServerSettings;
+use crate::milestones::{MilestoneSettings, NotificationSettings};
+use crate::mqtt::MqttSettings;
+use crate::offline::OfflineSettings;
+use crate::parental::ParentalSettings;
+use crate::pipeline::PipelineSettings;
+use crate::playback::PlaybackSettings;
+use crate::plugins::PluginSettings;
+use crate::power::EndAction;
+use crate::push::PushSettings;
+use crate::remote::RemoteSettings;
+use crate::schedule::{AutostartSettings, BedtimeReminders, BedtimeSchedule, WakeAlarm};
+use crate::search::SearchSettings;
+use crate::sfx::SoundSettings;
+use crate::sonos::SonosSettings;
+use crate::sponsorblock::SponsorBlockSettings;
+use crate::spotify::SpotifySettings;
+use crate::streaks::StreakSettings;
+use crate::strict::StrictSettings;
+use crate::templates::SessionTemplate;
+use crate::timer::TimerSettings;
+use crate::webhooks::WebhookSettings;
+use crate::widget::WidgetSettings;
+use crate::windows_integration::WindowsIntegrationSettings;
+
+/// Persisted user configuration, stored as JSON in the app config directory
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub end_action: EndAction,
+    pub pipeline: PipelineSettings,
+    pub busy_guard: BusyGuardSettings,
+    pub quit_apps: AppQuitSettings,
+    pub dnd: DndSettings,
+    pub windows: WindowsIntegrationSettings,
+    pub milestones: MilestoneSettings,
+    pub notifications: NotificationSettings,
+    pub autostart: AutostartSettings,
+    pub schedule: BedtimeSchedule,
+    pub reminders: BedtimeReminders,
+    pub streaks: StreakSettings,
+    pub wake: WakeAlarm,
+    pub remote: RemoteSettings,
+    pub webhooks: WebhookSettings,
+    pub mqtt: MqttSettings,
+    pub lights: LightSettings,
+    pub discord: DiscordSettings,
+    pub media_servers: MediaServerSettings,
+    pub spotify: SpotifySettings,
+    pub sonos: SonosSettings,
+    pub push: PushSettings,
+    pub handoff: HandoffSettings,
+    pub clipboard: ClipboardSettings,
+    pub templates: Vec<SessionTemplate>,
+    pub playback: PlaybackSettings,
+    pub offline: OfflineSettings,
+    pub sponsorblock: SponsorBlockSettings,
+    pub search: SearchSettings,
+    pub widget: WidgetSettings,
+    pub display: DisplaySettings,
+    pub mascot: MascotSettings,
+    pub timer: TimerSettings,
+    pub strict: StrictSettings,
+    pub parental: ParentalSettings,
+    pub chime: ChimeSettings,
+    pub sounds: SoundSettings,
+    pub haptics: HapticSettings,
+    pub plugins: PluginSettings,
+    pub journal: JournalSettings,
+    /// Whether the first-run wizard was seen. Settings saved before it
+    /// existed count as seen.
+    #[serde(default = "already_onboarded")]
+    pub onboarded: bool,
+}
+
+fn already_onboarded() -> bool {
+    true
+}
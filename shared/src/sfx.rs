@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+/// Percent left of the end-of-session fade below which UI sounds stay
+/// quiet, so a blip can't wake anyone who's nearly asleep
+pub const MUTE_BELOW_FADE: u32 = 50;
+
+/// Retro blips for button presses, the timer starting and milestones
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundSettings {
+    pub enabled: bool,
+    /// Percent, apart from the video's volume
+    pub volume: u32,
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            volume: 40,
+        }
+    }
+}
+
+impl SoundSettings {
+    /// Gain (0.0 to 1.0) to play at where the fade is at `fade_level` percent
+    pub fn gain(&self, fade_level: u32) -> f32 {
+        if !self.enabled || fade_level < MUTE_BELOW_FADE {
+            return 0.0;
+        }
+        self.volume.min(100) as f32 / 100.0
+    }
+}
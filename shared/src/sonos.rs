@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// A Sonos group, addressed through its coordinator speaker
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SonosGroup {
+    /// Coordinator UUID, e.g. `RINCON_000E58A0123401400`
+    pub id: String,
+    /// Room names of the members, e.g. "Bedroom + Bathroom"
+    pub name: String,
+    /// Coordinator IP address
+    pub host: String,
+}
+
+/// Sonos groups to fade alongside the video and stop at the end
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SonosSettings {
+    pub enabled: bool,
+    pub groups: Vec<SonosGroup>,
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+/// SponsorBlock categories that can be skipped, as (API name, label)
+pub const CATEGORIES: [(&str, &str); 6] = [
+    ("sponsor", "SPONSORS"),
+    ("selfpromo", "SELF PROMOTION"),
+    ("interaction", "SUBSCRIBE REMINDERS"),
+    ("intro", "INTROS"),
+    ("outro", "OUTROS"),
+    ("music_offtopic", "TALKING IN MUSIC VIDEOS"),
+];
+
+/// Skip crowd-sourced segments such as ad reads during playback
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SponsorBlockSettings {
+    pub enabled: bool,
+    /// API names from `CATEGORIES`
+    pub categories: Vec<String>,
+}
+
+impl Default for SponsorBlockSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            categories: ["sponsor", "selfpromo", "interaction", "intro"]
+                .map(str::to_string)
+                .to_vec(),
+        }
+    }
+}
+
+/// A stretch of the video to seek past, in seconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkipSegment {
+    pub start: f64,
+    pub end: f64,
+    pub category: String,
+}
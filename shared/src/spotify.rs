@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+/// Pause the active Spotify Connect device when the timer ends
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpotifySettings {
+    pub enabled: bool,
+    /// Client ID of a Spotify developer app with eepy's redirect URI
+    pub client_id: String,
+    /// Fade the device volume down over the same window as the video
+    pub fade: bool,
+}
+
+impl Default for SpotifySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            client_id: String::new(),
+            fade: true,
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+/// Days as chrono's `num_days_from_ce` counts them: day 1 is Monday,
+/// January 1st of the year 1
+pub type Day = i32;
+
+/// Sessions started up to this long either side of bedtime count towards
+/// that night
+const HALF_DAY_MINUTES: i32 = 12 * 60;
+
+/// Weekday of `day`, 0 = Monday .. 6 = Sunday
+fn weekday(day: Day) -> u32 {
+    (day - 1).rem_euclid(7) as u32
+}
+
+/// The night a session started on `day`, `minute` minutes after midnight,
+/// kept, if it was started by `bedtime` (minutes after midnight too).
+/// Nights go by the day bedtime falls on: a start late in the evening before
+/// a bedtime after midnight is the next day's, one after midnight past an
+/// evening bedtime the day before's.
+pub fn kept_night(day: Day, minute: u32, bedtime: u32) -> Option<Day> {
+    let late = minute as i32 - bedtime as i32;
+    let (night, late) = if late > HALF_DAY_MINUTES {
+        (day + 1, late - 2 * HALF_DAY_MINUTES)
+    } else if late <= -HALF_DAY_MINUTES {
+        (day - 1, late + 2 * HALF_DAY_MINUTES)
+    } else {
+        (day, late)
+    };
+    (late <= 0).then_some(night)
+}
+
+/// Keeping count of nights the timer was started by bedtime
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreakSettings {
+    /// Show the streak and the whale on the start screen
+    pub enabled: bool,
+    /// Sum up the week in a notification on Sunday evening
+    pub weekly_summary: bool,
+}
+
+/// Nights in a row, counting only the bedtime schedule's days, that a
+/// session which ran out was started by bedtime
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct StreakReport {
+    pub current: u32,
+    pub best: u32,
+    /// Of the schedule's nights in the last seven days, how many were kept
+    pub week_kept: u32,
+    pub week_nights: u32,
+}
+
+impl StreakReport {
+    /// Streaks of the `kept` nights, counting only the schedule's `days`
+    /// (0 = Monday), up to `today`. Tonight counts once it's kept, and
+    /// doesn't break the streak before then.
+    pub fn from_nights(kept: &BTreeSet<Day>, today: Day, days: &[u32]) -> Self {
+        let Some(&first) = kept.first() else {
+            return Self::default();
+        };
+        let mut report = Self::default();
+        let mut run = 0;
+        for night in (first..=today).filter(|night| days.contains(&weekday(*night))) {
+            let is_kept = kept.contains(&night);
+            if is_kept {
+                run += 1;
+                report.best = report.best.max(run);
+            } else if night < today {
+                run = 0;
+            }
+            if today - night < 7 {
+                report.week_nights += 1;
+                report.week_kept += is_kept as u32;
+            }
+        }
+        report.current = run;
+        report
+    }
+
+    pub fn summary_message(&self) -> String {
+        format!(
+            "This week you made bedtime {} of {} nights. Streak: {} 🐳",
+            self.week_kept, self.week_nights, self.current
+        )
+    }
+}
+
+/// How the whale feels about the streak: sleepier and happier the longer
+/// it runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhaleMood {
+    Restless,
+    Drowsy,
+    Content,
+    Blissful,
+}
+
+impl WhaleMood {
+    pub fn from_streak(nights: u32) -> Self {
+        match nights {
+            0 => WhaleMood::Restless,
+            1..=2 => WhaleMood::Drowsy,
+            3..=6 => WhaleMood::Content,
+            _ => WhaleMood::Blissful,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WhaleMood::Restless => "restless",
+            WhaleMood::Drowsy => "drowsy",
+            WhaleMood::Content => "content",
+            WhaleMood::Blissful => "blissful",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WhaleMood::Restless => "WAITING UP FOR YOU",
+            WhaleMood::Drowsy => "GETTING SLEEPY",
+            WhaleMood::Content => "SNOOZING HAPPILY",
+            WhaleMood::Blissful => "DREAMING OF KRILL",
+        }
+    }
+
+    /// What floats over the whale
+    pub fn bubble(self) -> &'static str {
+        match self {
+            WhaleMood::Restless => "❔",
+            WhaleMood::Drowsy => "💤",
+            WhaleMood::Content => "💤💤",
+            WhaleMood::Blissful => "✨💤✨",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Monday, January 6th 2025
+    const MONDAY: Day = 739_257;
+    const EVERY_DAY: [u32; 7] = [0, 1, 2, 3, 4, 5, 6];
+    const ELEVEN_PM: u32 = 23 * 60;
+    const HALF_PAST_MIDNIGHT: u32 = 30;
+
+    fn nights(days: &[Day]) -> BTreeSet<Day> {
+        days.iter().map(|day| MONDAY + day).collect()
+    }
+
+    #[test]
+    fn days_count_from_a_monday() {
+        assert_eq!(weekday(1), 0);
+        assert_eq!(weekday(MONDAY), 0);
+        assert_eq!(weekday(MONDAY + 6), 6);
+        assert_eq!(weekday(MONDAY + 7), 0);
+    }
+
+    #[test]
+    fn starting_by_bedtime_keeps_that_night() {
+        assert_eq!(kept_night(MONDAY, ELEVEN_PM, ELEVEN_PM), Some(MONDAY));
+        assert_eq!(kept_night(MONDAY, 21 * 60, ELEVEN_PM), Some(MONDAY));
+        assert_eq!(kept_night(MONDAY, ELEVEN_PM + 1, ELEVEN_PM), None);
+    }
+
+    #[test]
+    fn starting_after_midnight_is_late_for_an_evening_bedtime() {
+        // Still Monday night's, just too late for it
+        assert_eq!(kept_night(MONDAY + 1, 0, 22 * 60), None);
+        assert_eq!(kept_night(MONDAY + 1, 10, ELEVEN_PM), None);
+    }
+
+    #[test]
+    fn starting_after_midnight_keeps_a_bedtime_after_midnight() {
+        assert_eq!(
+            kept_night(MONDAY + 1, 10, HALF_PAST_MIDNIGHT),
+            Some(MONDAY + 1)
+        );
+    }
+
+    #[test]
+    fn starting_in_the_evening_before_a_bedtime_after_midnight_keeps_the_next_day() {
+        assert_eq!(
+            kept_night(MONDAY, 23 * 60 + 59, HALF_PAST_MIDNIGHT),
+            Some(MONDAY + 1)
+        );
+        assert_eq!(
+            kept_night(MONDAY, 12 * 60 + 31, HALF_PAST_MIDNIGHT),
+            Some(MONDAY + 1)
+        );
+        // Just past noon is too far from bedtime to be the next night's
+        assert_eq!(kept_night(MONDAY, 12 * 60 + 30, HALF_PAST_MIDNIGHT), None);
+    }
+
+    #[test]
+    fn no_history_is_no_streak() {
+        assert_eq!(
+            StreakReport::from_nights(&BTreeSet::new(), MONDAY, &EVERY_DAY),
+            StreakReport::default()
+        );
+    }
+
+    #[test]
+    fn nights_in_a_row_make_the_streak() {
+        let report = StreakReport::from_nights(&nights(&[0, 1, 2]), MONDAY + 2, &EVERY_DAY);
+        assert_eq!(report.current, 3);
+        assert_eq!(report.best, 3);
+        assert_eq!((report.week_kept, report.week_nights), (3, 3));
+    }
+
+    #[test]
+    fn a_missed_night_breaks_the_streak() {
+        let report = StreakReport::from_nights(&nights(&[0, 1, 2, 4, 5]), MONDAY + 5, &EVERY_DAY);
+        assert_eq!(report.current, 2);
+        assert_eq!(report.best, 3);
+        assert_eq!((report.week_kept, report.week_nights), (5, 6));
+    }
+
+    #[test]
+    fn tonight_does_not_break_the_streak_before_bedtime() {
+        let report = StreakReport::from_nights(&nights(&[0, 1]), MONDAY + 2, &EVERY_DAY);
+        assert_eq!(report.current, 2);
+        assert_eq!((report.week_kept, report.week_nights), (2, 3));
+    }
+
+    #[test]
+    fn a_gap_of_unscheduled_nights_keeps_the_streak() {
+        // Weeknights only: Tuesday to Friday, then Monday again across the
+        // weekend
+        let weeknights = [0, 1, 2, 3, 4];
+        let report = StreakReport::from_nights(&nights(&[1, 2, 3, 4, 7]), MONDAY + 7, &weeknights);
+        assert_eq!(report.current, 5);
+        assert_eq!((report.week_kept, report.week_nights), (5, 5));
+    }
+
+    #[test]
+    fn only_the_last_seven_days_make_the_week() {
+        let report = StreakReport::from_nights(&nights(&[0]), MONDAY + 20, &EVERY_DAY);
+        assert_eq!(report.current, 0);
+        assert_eq!(report.best, 1);
+        assert_eq!((report.week_kept, report.week_nights), (0, 7));
+    }
+}
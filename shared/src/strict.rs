@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+/// Bedtime enforcement: once a session starts, it can't be cancelled or
+/// extended without the PIN, or until a cooldown after asking has passed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StrictSettings {
+    pub enabled: bool,
+    /// Salted PBKDF2 of the PIN. Only the backend sets it; empty when there
+    /// is no PIN and the cooldown is the only way out.
+    pub pin_hash: String,
+    /// Minutes to wait for the lock to lift without the PIN
+    pub cooldown_minutes: u32,
+}
+
+impl Default for StrictSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            pin_hash: String::new(),
+            cooldown_minutes: 15,
+        }
+    }
+}
+
+/// Whether the running session is locked, as far as strict mode goes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StrictStatus {
+    pub locked: bool,
+    /// Seconds until the lock lifts by itself, once the cooldown was asked for
+    pub unlocks_in: Option<u64>,
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use crate::control::StartRequest;
+use crate::power::EndAction;
+
+/// A named nightly setup, e.g. "Rain 1h then hibernate", started in one click
+/// from the main screen or the tray menu
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionTemplate {
+    pub name: String,
+    /// YouTube URL or video ID; empty keeps whatever is loaded
+    pub video: String,
+    pub minutes: u32,
+    /// Fade the video volume over the last 10% of the session
+    pub fade: bool,
+    pub action: EndAction,
+}
+
+impl Default for SessionTemplate {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            video: String::new(),
+            minutes: 60,
+            fade: true,
+            action: EndAction::default(),
+        }
+    }
+}
+
+impl SessionTemplate {
+    pub fn start_request(&self) -> StartRequest {
+        let video = self.video.trim();
+        StartRequest {
+            minutes: Some(self.minutes),
+            video: (!video.is_empty()).then(|| video.to_string()),
+            action: Some(self.action),
+            fade: Some(self.fade),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+/// Longest session allowed out of the box, in minutes
+pub const DEFAULT_MAX_MINUTES: u32 = 480;
+
+/// Highest the longest session can be set to: a full day
+pub const MAX_MINUTES_LIMIT: u32 = 1440;
+
+/// Sessions longer than this dim and fade as if they were this long, so a
+/// full night doesn't leave the volume fading for an hour
+pub const CURVE_SECONDS: u32 = DEFAULT_MAX_MINUTES * 60;
+
+/// What closing the main window does while a timer runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseBehaviour {
+    /// Ask whether to keep running in the tray
+    #[default]
+    Ask,
+    /// Hide the window and keep the session going from the tray
+    Tray,
+    /// Quit and end the session
+    Quit,
+}
+
+impl CloseBehaviour {
+    pub const ALL: [CloseBehaviour; 3] = [
+        CloseBehaviour::Ask,
+        CloseBehaviour::Tray,
+        CloseBehaviour::Quit,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CloseBehaviour::Ask => "ask",
+            CloseBehaviour::Tray => "tray",
+            CloseBehaviour::Quit => "quit",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|behaviour| behaviour.as_str() == value)
+    }
+}
+
+/// Preset buttons and the longest session the timer accepts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimerSettings {
+    /// Minutes, in button order
+    pub presets: Vec<u32>,
+    pub max_minutes: u32,
+    pub on_close: CloseBehaviour,
+    /// Fade the volume out towards the end of a session
+    pub fade: bool,
+    /// Minutes picked when the app starts
+    pub default_minutes: u32,
+}
+
+impl Default for TimerSettings {
+    fn default() -> Self {
+        Self {
+            presets: vec![15, 30, 45, 60, 90, 120, 180, 240],
+            max_minutes: DEFAULT_MAX_MINUTES,
+            on_close: CloseBehaviour::default(),
+            fade: true,
+            default_minutes: 60,
+        }
+    }
+}
+
+impl TimerSettings {
+    /// Longest session in minutes, kept within the supported range
+    pub fn max_minutes(&self) -> u32 {
+        self.max_minutes.clamp(1, MAX_MINUTES_LIMIT)
+    }
+}
+
+/// Seconds at the end of a session over which the volume fades out: the
+/// last 10%, never more than for an 8 hour session
+pub fn fade_window(total_seconds: u32) -> u32 {
+    total_seconds.min(CURVE_SECONDS) / 10
+}
+
+/// Button label for a preset: whole and half hours as hours, the rest as
+/// minutes
+pub fn preset_label(minutes: u32) -> String {
+    if minutes >= 60 && minutes.is_multiple_of(60) {
+        format!("{}h", minutes / 60)
+    } else if minutes >= 60 && minutes.is_multiple_of(30) {
+        format!("{}.5h", minutes / 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+/// Session moments that can trigger a webhook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    Start,
+    Milestone,
+    Suspend,
+}
+
+/// HTTP POSTs fired at session events, e.g. Home Assistant webhook triggers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookSettings {
+    pub enabled: bool,
+    pub urls: Vec<String>,
+    pub on_start: bool,
+    pub on_milestone: bool,
+    pub on_suspend: bool,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            urls: Vec::new(),
+            on_start: true,
+            on_milestone: true,
+            on_suspend: true,
+        }
+    }
+}
+
+impl WebhookSettings {
+    pub fn wants(&self, event: WebhookEvent) -> bool {
+        self.enabled
+            && !self.urls.is_empty()
+            && match event {
+                WebhookEvent::Start => self.on_start,
+                WebhookEvent::Milestone => self.on_milestone,
+                WebhookEvent::Suspend => self.on_suspend,
+            }
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+/// Screen corner the countdown widget sits in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+impl Corner {
+    pub const ALL: [Corner; 4] = [
+        Corner::TopLeft,
+        Corner::TopRight,
+        Corner::BottomLeft,
+        Corner::BottomRight,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Corner::TopLeft => "top_left",
+            Corner::TopRight => "top_right",
+            Corner::BottomLeft => "bottom_left",
+            Corner::BottomRight => "bottom_right",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|corner| corner.as_str() == value)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Corner::TopLeft => "TOP LEFT",
+            Corner::TopRight => "TOP RIGHT",
+            Corner::BottomLeft => "BOTTOM LEFT",
+            Corner::BottomRight => "BOTTOM RIGHT",
+        }
+    }
+}
+
+/// Transparent, click-through countdown floating over every app
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WidgetSettings {
+    pub enabled: bool,
+    pub corner: Corner,
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+/// Windows settings switched for the length of a session and put back after
+/// waking up or cancelling
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowsIntegrationSettings {
+    /// GUID of the power plan to switch to, e.g. Power saver
+    pub power_plan: Option<String>,
+    /// Let only alarms through Focus Assist
+    pub focus_assist: bool,
+}
+
+impl WindowsIntegrationSettings {
+    pub fn enabled(&self) -> bool {
+        self.power_plan.is_some() || self.focus_assist
+    }
+}
+
+/// A power plan as listed by `powercfg /list`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PowerPlan {
+    pub guid: String,
+    pub name: String,
+    pub active: bool,
+}
@@ -1,3 +1,49 @@
 fn main() {
+    #[cfg(target_os = "macos")]
+    if std::env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("ios") {
+        ios::link();
+    }
     tauri_build::build()
 }
+
+/// The Swift half of the haptics plugin is a package in `ios/`, built
+/// against the Swift API that comes with the tauri crate, the way Tauri's
+/// own plugins are
+#[cfg(target_os = "macos")]
+mod ios {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    const PACKAGE: &str = "eepy-haptics";
+    /// Left out of the copied API, as Tauri's plugins do
+    const SKIP: [&str; 3] = [".build", "Package.resolved", "Tests"];
+
+    pub fn link() {
+        let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+        let api = std::env::var("DEP_TAURI_IOS_LIBRARY_PATH")
+            .expect("tauri didn't say where its iOS library is");
+        let target = manifest_dir.join(".tauri").join("tauri-api");
+        let _ = fs::remove_dir_all(&target);
+        copy_dir(Path::new(&api), &target).expect("failed to copy the Tauri iOS API");
+        println!("cargo:rerun-if-changed=ios/Package.swift");
+        println!("cargo:rerun-if-changed=ios/Sources");
+        tauri_utils::build::link_apple_library(PACKAGE, manifest_dir.join("ios"));
+    }
+
+    fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            if SKIP.iter().any(|skip| entry.file_name() == *skip) {
+                continue;
+            }
+            let path = entry.path();
+            if entry.file_type()?.is_dir() {
+                copy_dir(&path, &to.join(entry.file_name()))?;
+            } else {
+                fs::copy(&path, to.join(entry.file_name()))?;
+            }
+        }
+        Ok(())
+    }
+}
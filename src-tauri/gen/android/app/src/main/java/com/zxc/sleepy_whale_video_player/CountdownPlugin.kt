@@ -0,0 +1,86 @@
+package com.zxc.sleepy_whale_video_player
+
+import android.app.Activity
+import android.app.admin.DevicePolicyManager
+import android.content.Context
+import android.content.Intent
+import android.os.Build
+import android.os.VibrationEffect
+import android.os.Vibrator
+import android.os.VibratorManager
+import app.tauri.annotation.Command
+import app.tauri.annotation.InvokeArg
+import app.tauri.annotation.TauriPlugin
+import app.tauri.plugin.Invoke
+import app.tauri.plugin.Plugin
+
+@InvokeArg
+class StartArgs {
+    var endsAt: Long = 0
+    var lock: Boolean = false
+}
+
+@InvokeArg
+class VibrateArgs {
+    var pattern: LongArray = longArrayOf()
+}
+
+/** Called from src/android.rs */
+@TauriPlugin
+class CountdownPlugin(private val activity: Activity) : Plugin(activity) {
+    @Command
+    fun start(invoke: Invoke) {
+        val args = invoke.parseArgs(StartArgs::class.java)
+        CountdownService.start(activity, args.endsAt, args.lock)
+        invoke.resolve()
+    }
+
+    @Command
+    fun stop(invoke: Invoke) {
+        CountdownService.stop(activity)
+        invoke.resolve()
+    }
+
+    @Command
+    fun pauseMedia(invoke: Invoke) {
+        Media.pause(activity)
+        invoke.resolve()
+    }
+
+    @Command
+    fun vibrate(invoke: Invoke) {
+        val args = invoke.parseArgs(VibrateArgs::class.java)
+        val vibrator = if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.S) {
+            val manager = activity.getSystemService(Context.VIBRATOR_MANAGER_SERVICE) as VibratorManager
+            manager.defaultVibrator
+        } else {
+            @Suppress("DEPRECATION")
+            activity.getSystemService(Context.VIBRATOR_SERVICE) as Vibrator
+        }
+        if (!vibrator.hasVibrator() || args.pattern.isEmpty()) {
+            invoke.resolve()
+            return
+        }
+        // Android's patterns start with a pause
+        val timings = longArrayOf(0) + args.pattern
+        vibrator.vibrate(VibrationEffect.createWaveform(timings, -1))
+        invoke.resolve()
+    }
+
+    @Command
+    fun lock(invoke: Invoke) {
+        if (EepyAdmin.lock(activity)) {
+            invoke.resolve()
+            return
+        }
+        // Ask now, so the next session can lock
+        val intent = Intent(DevicePolicyManager.ACTION_ADD_DEVICE_ADMIN)
+            .putExtra(DevicePolicyManager.EXTRA_DEVICE_ADMIN, EepyAdmin.component(activity))
+            .putExtra(
+                DevicePolicyManager.EXTRA_ADD_EXPLANATION,
+                "eepy locks the phone when the sleep timer runs out."
+            )
+        activity.startActivity(intent)
+        invoke.reject("eepy isn't allowed to lock the phone yet")
+    }
+}
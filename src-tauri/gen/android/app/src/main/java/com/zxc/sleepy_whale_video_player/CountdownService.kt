@@ -0,0 +1,137 @@
+package com.zxc.sleepy_whale_video_player
+
+import android.app.AlarmManager
+import android.app.Notification
+import android.app.NotificationChannel
+import android.app.NotificationManager
+import android.app.PendingIntent
+import android.app.Service
+import android.content.BroadcastReceiver
+import android.content.Context
+import android.content.Intent
+import android.content.pm.ServiceInfo
+import android.os.Build
+import android.os.IBinder
+
+/**
+ * Keeps the countdown alive while the screen is off. The notification keeps
+ * the process from being killed, and an exact alarm that fires through Doze
+ * ends the session even when the webview's timers are frozen.
+ */
+class CountdownService : Service() {
+    override fun onBind(intent: Intent?): IBinder? = null
+
+    override fun onStartCommand(intent: Intent?, flags: Int, startId: Int): Int {
+        val endsAt = intent?.getLongExtra(EXTRA_ENDS_AT, 0) ?: 0
+        val lock = intent?.getBooleanExtra(EXTRA_LOCK, false) ?: false
+        if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.Q) {
+            startForeground(
+                NOTIFICATION_ID,
+                notification(endsAt),
+                ServiceInfo.FOREGROUND_SERVICE_TYPE_MEDIA_PLAYBACK
+            )
+        } else {
+            startForeground(NOTIFICATION_ID, notification(endsAt))
+        }
+        schedule(this, endsAt, lock)
+        return START_REDELIVER_INTENT
+    }
+
+    override fun onDestroy() {
+        alarms(this).cancel(endIntent(this, false))
+        super.onDestroy()
+    }
+
+    private fun notification(endsAt: Long): Notification {
+        val manager = getSystemService(NotificationManager::class.java)
+        if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
+            val channel = NotificationChannel(
+                CHANNEL_ID,
+                "Sleep timer",
+                NotificationManager.IMPORTANCE_LOW
+            )
+            manager.createNotificationChannel(channel)
+        }
+        val open = PendingIntent.getActivity(
+            this,
+            0,
+            packageManager.getLaunchIntentForPackage(packageName),
+            PendingIntent.FLAG_IMMUTABLE
+        )
+        val builder = if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
+            Notification.Builder(this, CHANNEL_ID)
+        } else {
+            @Suppress("DEPRECATION")
+            Notification.Builder(this)
+        }
+        return builder
+            .setSmallIcon(applicationInfo.icon)
+            .setContentTitle("getting eepy 🐳")
+            .setContentIntent(open)
+            .setOngoing(true)
+            .setWhen(endsAt)
+            .setUsesChronometer(true)
+            .setChronometerCountDown(true)
+            .build()
+    }
+
+    companion object {
+        private const val CHANNEL_ID = "countdown"
+        private const val NOTIFICATION_ID = 1
+        private const val EXTRA_ENDS_AT = "endsAt"
+        private const val EXTRA_LOCK = "lock"
+
+        fun start(context: Context, endsAt: Long, lock: Boolean) {
+            val intent = Intent(context, CountdownService::class.java)
+                .putExtra(EXTRA_ENDS_AT, endsAt)
+                .putExtra(EXTRA_LOCK, lock)
+            if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
+                context.startForegroundService(intent)
+            } else {
+                context.startService(intent)
+            }
+        }
+
+        fun stop(context: Context) {
+            context.stopService(Intent(context, CountdownService::class.java))
+        }
+
+        private fun alarms(context: Context) = context.getSystemService(AlarmManager::class.java)
+
+        private fun endIntent(context: Context, lock: Boolean): PendingIntent {
+            val intent = Intent(context, SessionEndReceiver::class.java).putExtra(EXTRA_LOCK, lock)
+            return PendingIntent.getBroadcast(
+                context,
+                0,
+                intent,
+                PendingIntent.FLAG_IMMUTABLE or PendingIntent.FLAG_UPDATE_CURRENT
+            )
+        }
+
+        private fun schedule(context: Context, endsAt: Long, lock: Boolean) {
+            val manager = alarms(context)
+            val pending = endIntent(context, lock)
+            // Without the exact alarm permission Android may run it a few minutes late
+            val exact = Build.VERSION.SDK_INT < Build.VERSION_CODES.S ||
+                manager.canScheduleExactAlarms()
+            if (exact) {
+                manager.setExactAndAllowWhileIdle(AlarmManager.RTC_WAKEUP, endsAt, pending)
+            } else {
+                manager.setAndAllowWhileIdle(AlarmManager.RTC_WAKEUP, endsAt, pending)
+            }
+        }
+
+        fun lockRequested(intent: Intent) = intent.getBooleanExtra(EXTRA_LOCK, false)
+    }
+}
+
+/** The end of the session, run natively in case the webview is asleep */
+class SessionEndReceiver : BroadcastReceiver() {
+    override fun onReceive(context: Context, intent: Intent) {
+        Media.pause(context)
+        if (CountdownService.lockRequested(intent)) {
+            EepyAdmin.lock(context)
+        }
+        CountdownService.stop(context)
+    }
+}
@@ -0,0 +1,23 @@
+package com.zxc.sleepy_whale_video_player
+
+import android.app.admin.DeviceAdminReceiver
+import android.app.admin.DevicePolicyManager
+import android.content.ComponentName
+import android.content.Context
+
+/** Device admin with only the force-lock policy, see res/xml/device_admin.xml */
+class EepyAdmin : DeviceAdminReceiver() {
+    companion object {
+        fun component(context: Context) = ComponentName(context, EepyAdmin::class.java)
+
+        /** Lock the phone if the user made eepy a device admin */
+        fun lock(context: Context): Boolean {
+            val policy = context.getSystemService(DevicePolicyManager::class.java)
+            if (!policy.isAdminActive(component(context))) {
+                return false
+            }
+            policy.lockNow()
+            return true
+        }
+    }
+}
@@ -0,0 +1,17 @@
+package com.zxc.sleepy_whale_video_player
+
+import android.content.Context
+import android.media.AudioManager
+import android.os.SystemClock
+import android.view.KeyEvent
+
+object Media {
+    /** Send a pause key press to the active media session, like a headset button */
+    fun pause(context: Context) {
+        val audio = context.getSystemService(AudioManager::class.java)
+        val now = SystemClock.uptimeMillis()
+        for (action in listOf(KeyEvent.ACTION_DOWN, KeyEvent.ACTION_UP)) {
+            audio.dispatchMediaKeyEvent(KeyEvent(now, now, action, KeyEvent.KEYCODE_MEDIA_PAUSE, 0))
+        }
+    }
+}
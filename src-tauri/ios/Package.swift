@@ -0,0 +1,27 @@
+// swift-tools-version:5.3
+
+import PackageDescription
+
+let package = Package(
+  name: "eepy-haptics",
+  platforms: [
+    .iOS(.v13),
+  ],
+  products: [
+    .library(
+      name: "eepy-haptics",
+      type: .static,
+      targets: ["eepy-haptics"])
+  ],
+  dependencies: [
+    .package(name: "Tauri", path: "../.tauri/tauri-api")
+  ],
+  targets: [
+    .target(
+      name: "eepy-haptics",
+      dependencies: [
+        .byName(name: "Tauri")
+      ],
+      path: "Sources")
+  ]
+)
@@ -0,0 +1,44 @@
+import Foundation
+import SwiftRs
+import Tauri
+import UIKit
+import WebKit
+
+struct ImpactArgs: Decodable {
+  let haptic: String
+}
+
+class HapticsPlugin: Plugin {
+  // Taps for each kind of buzz, as (style, seconds after the first), close
+  // to the Android patterns
+  private func taps(_ haptic: String) -> [(UIImpactFeedbackGenerator.FeedbackStyle, Double)] {
+    switch haptic {
+    case "selection":
+      return [(.light, 0)]
+    case "warning":
+      return [(.heavy, 0), (.heavy, 0.12)]
+    default:
+      return [(.medium, 0)]
+    }
+  }
+
+  @objc public func impact(_ invoke: Invoke) throws {
+    let args = try invoke.parseArgs(ImpactArgs.self)
+    let taps = taps(args.haptic)
+    DispatchQueue.main.async {
+      for (style, after) in taps {
+        let generator = UIImpactFeedbackGenerator(style: style)
+        generator.prepare()
+        DispatchQueue.main.asyncAfter(deadline: .now() + after) {
+          generator.impactOccurred()
+        }
+      }
+    }
+    invoke.resolve()
+  }
+}
+
+@_cdecl("init_plugin_haptics")
+public func initPlugin() -> Plugin {
+  return HapticsPlugin()
+}
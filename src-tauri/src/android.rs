@@ -0,0 +1,131 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::power::EndAction;
+use sleepy_video_shared::session::SessionEvent;
+use tauri::plugin::{Builder, PluginHandle, TauriPlugin};
+use tauri::{AppHandle, Manager, Wry};
+use tokio::sync::broadcast;
+
+use crate::session::SessionState;
+
+/// Kotlin half of the plugin, in the app's own package
+const PLUGIN_PACKAGE: &str = "com.zxc.sleepy_whale_video_player";
+const PLUGIN_CLASS: &str = "CountdownPlugin";
+
+/// Let a reported end time drift this much before moving the alarm
+const RESCHEDULE_MILLIS: u64 = 5_000;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StartArgs {
+    ends_at: u64,
+    lock: bool,
+}
+
+#[derive(Serialize)]
+struct VibrateArgs<'a> {
+    pattern: &'a [u64],
+}
+
+/// Android keeps the countdown in a foreground service with an exact alarm
+/// for its end, as Doze freezes the webview and its timers with the screen off
+#[derive(Clone)]
+pub struct Countdown(PluginHandle<Wry>);
+
+impl Countdown {
+    fn call(&self, command: &str, payload: impl Serialize) -> Result<(), EepyError> {
+        self.0
+            .run_mobile_plugin::<serde_json::Value>(command, payload)
+            .map(|_| ())
+            .map_err(|e| EepyError::Other(format!("Android {} failed: {}", command, e)))
+    }
+
+    /// Hold the service until `ends_at`, then pause media and, for `lock`,
+    /// lock the phone without waiting for the webview to wake up
+    fn start(&self, ends_at: u64, lock: bool) -> Result<(), EepyError> {
+        self.call("start", StartArgs { ends_at, lock })
+    }
+
+    fn stop(&self) -> Result<(), EepyError> {
+        self.call("stop", ())
+    }
+
+    /// Needs eepy to be a device admin; asks for it the first time
+    pub fn lock(&self) -> Result<(), EepyError> {
+        self.call("lock", ())
+    }
+
+    /// Milliseconds of vibration and pause in turn
+    pub fn vibrate(&self, pattern: &[u64]) -> Result<(), EepyError> {
+        self.call("vibrate", VibrateArgs { pattern })
+    }
+}
+
+/// Pause whatever holds the active media session, for the pause stage
+pub fn pause_media(app: &AppHandle) -> Result<(), EepyError> {
+    app.state::<Countdown>().call("pauseMedia", ())
+}
+
+pub fn plugin() -> TauriPlugin<Wry> {
+    Builder::new("countdown")
+        .setup(|app, api| {
+            let handle = api.register_android_plugin(PLUGIN_PACKAGE, PLUGIN_CLASS)?;
+            app.manage(Countdown(handle));
+            Ok(())
+        })
+        .build()
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Keep the service and alarm in step with the countdown
+pub fn spawn(app: AppHandle) {
+    let events = app.state::<SessionState>().subscribe();
+    let countdown = app.state::<Countdown>().inner().clone();
+    tauri::async_runtime::spawn(follow_session(countdown, events));
+}
+
+async fn follow_session(countdown: Countdown, mut events: broadcast::Receiver<SessionEvent>) {
+    // End time the alarm is set for
+    let mut scheduled: Option<u64> = None;
+    loop {
+        let status = match events.recv().await {
+            Ok(SessionEvent::Status(status)) => status,
+            Ok(SessionEvent::Ended(_) | SessionEvent::Cancelled) => {
+                if scheduled.take().is_some() {
+                    if let Err(e) = countdown.stop() {
+                        tracing::warn!("{}", e);
+                    }
+                }
+                continue;
+            }
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+        // Paused sessions let go of the service until they carry on
+        if !status.running {
+            if scheduled.take().is_some() {
+                if let Err(e) = countdown.stop() {
+                    tracing::warn!("{}", e);
+                }
+            }
+            continue;
+        }
+        let ends_at = now_millis() + status.remaining_seconds as u64 * 1000;
+        if scheduled.is_some_and(|at| at.abs_diff(ends_at) < RESCHEDULE_MILLIS) {
+            continue;
+        }
+        let lock = matches!(status.action, EndAction::Lock | EndAction::ScreenOff);
+        match countdown.start(ends_at, lock) {
+            Ok(()) => scheduled = Some(ends_at),
+            Err(e) => tracing::warn!("{}", e),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::time::{Duration, Instant};
+
+use sleepy_video_shared::apps::{AppQuitSettings, QuitAppRule, QuitOutcome, QuitResult};
+use sleepy_video_shared::error::EepyError;
+use sysinfo::{ProcessesToUpdate, System};
+use tauri::State;
+
+use crate::busy::{is_process_running, normalize_process_name};
+use crate::settings::SettingsStore;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// How long to wait for the OS to reap a force-killed process
+const KILL_WAIT: Duration = Duration::from_secs(2);
+
+/// Close every enabled app in parallel, waiting up to the grace period each
+pub fn quit_apps(settings: &AppQuitSettings) -> Vec<QuitResult> {
+    if !settings.enabled {
+        return Vec::new();
+    }
+
+    let grace = Duration::from_secs(settings.grace_secs as u64);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = settings
+            .apps
+            .iter()
+            .filter(|rule| rule.enabled && !rule.name.trim().is_empty())
+            .map(|rule| scope.spawn(move || quit_app(rule, grace)))
+            .collect();
+
+        handles
+            .into_iter()
+            .filter_map(|handle| handle.join().ok())
+            .collect()
+    })
+}
+
+fn quit_app(rule: &QuitAppRule, grace: Duration) -> QuitResult {
+    let result = |outcome| QuitResult {
+        name: rule.name.clone(),
+        outcome,
+    };
+
+    if !is_running(&rule.name) {
+        return result(QuitOutcome::NotRunning);
+    }
+
+    if let Err(e) = request_quit(&rule.name) {
+        return result(QuitOutcome::Failed(e));
+    }
+    if wait_for_exit(&rule.name, grace) {
+        return result(QuitOutcome::Closed);
+    }
+
+    if !rule.force_kill {
+        return result(QuitOutcome::StillRunning);
+    }
+
+    force_kill(&rule.name);
+    if wait_for_exit(&rule.name, KILL_WAIT) {
+        result(QuitOutcome::ForceKilled)
+    } else {
+        result(QuitOutcome::StillRunning)
+    }
+}
+
+fn is_running(name: &str) -> bool {
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::All, true);
+    is_process_running(&sys, name)
+}
+
+fn wait_for_exit(name: &str, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if !is_running(name) {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Ask the app to quit the way its platform expects
+fn request_quit(name: &str) -> Result<(), String> {
+    tracing::info!("Asking {} to quit", name);
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("osascript")
+            .args(["-e", &format!("quit app \"{}\"", name.replace('"', ""))])
+            .output()
+            .map_err(|e| format!("Failed to quit {}: {}", name, e))?;
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let image = format!("{}.exe", normalize_process_name(name));
+        std::process::Command::new("taskkill")
+            .args(["/IM", &image])
+            .output()
+            .map_err(|e| format!("Failed to quit {}: {}", name, e))?;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let mut sys = System::new();
+        sys.refresh_processes(ProcessesToUpdate::All, true);
+        let wanted = normalize_process_name(name);
+        for process in sys.processes().values() {
+            if normalize_process_name(&process.name().to_string_lossy()) == wanted {
+                process.kill_with(sysinfo::Signal::Term);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn force_kill(name: &str) {
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::All, true);
+    let wanted = normalize_process_name(name);
+    for process in sys.processes().values() {
+        if normalize_process_name(&process.name().to_string_lossy()) == wanted {
+            process.kill();
+        }
+    }
+}
+
+/// Close the configured apps before suspending and report what happened
+#[tauri::command]
+pub async fn quit_apps_before_suspend(
+    store: State<'_, SettingsStore>,
+) -> Result<Vec<QuitResult>, EepyError> {
+    let settings = store.get().quit_apps;
+    tauri::async_runtime::spawn_blocking(move || quit_apps(&settings))
+        .await
+        .map_err(|e| EepyError::Other(format!("Closing apps failed: {}", e)))
+}
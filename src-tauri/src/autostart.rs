@@ -0,0 +1,36 @@
+use sleepy_video_shared::schedule::AutostartSettings;
+use tauri::AppHandle;
+use tauri_plugin_autostart::ManagerExt;
+
+/// Passed by the OS login item so we can tell a login launch apart
+pub const AUTOSTART_ARG: &str = "--autostart";
+
+pub fn launched_at_login() -> bool {
+    std::env::args().any(|arg| arg == AUTOSTART_ARG)
+}
+
+/// Register or remove the login item to match the settings
+pub fn apply(app: &AppHandle, settings: &AutostartSettings) -> Result<(), String> {
+    // The host's autostart folder is out of the sandbox's reach
+    #[cfg(target_os = "linux")]
+    if crate::portal::sandboxed() {
+        let exe = std::env::current_exe()
+            .map_err(|e| format!("Failed to find eepy's own path: {}", e))?;
+        let commandline = vec![exe.display().to_string(), AUTOSTART_ARG.to_string()];
+        return crate::portal::request_background(settings.enabled, commandline);
+    }
+
+    let launcher = app.autolaunch();
+    let registered = launcher.is_enabled().unwrap_or(false);
+
+    if settings.enabled && !registered {
+        launcher
+            .enable()
+            .map_err(|e| format!("Failed to enable autostart: {}", e))?;
+    } else if !settings.enabled && registered {
+        launcher
+            .disable()
+            .map_err(|e| format!("Failed to disable autostart: {}", e))?;
+    }
+    Ok(())
+}
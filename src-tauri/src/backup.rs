@@ -0,0 +1,274 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::library::LibraryEntry;
+use sleepy_video_shared::parental::Profile;
+use sleepy_video_shared::profiles::ImportPreview;
+use sleepy_video_shared::settings::Settings;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_dialog::DialogExt;
+
+#[cfg(desktop)]
+use crate::autostart;
+use crate::history::History;
+use crate::library::Library;
+use crate::parental::ProfileState;
+use crate::profiles::{Profiles, ProfilesFile};
+use crate::remote;
+use crate::settings::{self, SettingsStore};
+use crate::strict::StrictLock;
+
+/// Layout of the export file written by this build. Bump it, and add a step
+/// to `migrate`, whenever a change needs more than serde defaults to read
+/// older files.
+const BACKUP_VERSION: u64 = 1;
+const DEFAULT_FILE_NAME: &str = "eepy-settings.json";
+
+/// The whole setup: settings with presets, templates and integrations, every
+/// profile, and each profile's favorites and recently played
+#[derive(Debug, Serialize, Deserialize)]
+struct Backup {
+    version: u64,
+    settings: Settings,
+    /// Missing from exports of a bare settings file
+    profiles: Option<ProfilesFile>,
+    /// By profile ID
+    #[serde(default)]
+    libraries: BTreeMap<String, Vec<LibraryEntry>>,
+}
+
+/// Bring an export from any earlier version up to `BACKUP_VERSION`, one
+/// version at a time
+fn migrate(mut raw: Value) -> Result<Backup, EepyError> {
+    let version = match raw.get("version") {
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| EepyError::Invalid("Unknown export version".to_string()))?,
+        // Version 0 is a bare settings.json copied out of the config folder
+        None => 0,
+    };
+    if version > BACKUP_VERSION {
+        return Err(EepyError::Invalid(
+            "This export is from a newer eepy; update first".to_string(),
+        ));
+    }
+
+    if version < 1 {
+        raw = json!({ "version": 1, "settings": raw });
+    }
+
+    let mut backup: Backup = serde_json::from_value(raw)
+        .map_err(|e| EepyError::Invalid(format!("Not an eepy settings export: {}", e)))?;
+    backup.version = BACKUP_VERSION;
+    backup.settings.pipeline.fill_missing();
+    Ok(backup)
+}
+
+/// Write the whole setup to a file the user picks. Returns where it went, or
+/// nothing if they cancelled.
+#[tauri::command]
+pub async fn settings_export(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    profiles: State<'_, Profiles>,
+) -> Result<Option<String>, EepyError> {
+    let Some(picked) = app
+        .dialog()
+        .file()
+        .add_filter("eepy settings", &["json"])
+        .set_file_name(DEFAULT_FILE_NAME)
+        .blocking_save_file()
+    else {
+        return Ok(None);
+    };
+    let path = picked
+        .into_path()
+        .map_err(|e| EepyError::Invalid(format!("Can't save there: {}", e)))?;
+
+    let mut settings = store.get();
+    // Secrets only mean something on this machine
+    settings.parental.password_hash.clear();
+    settings.strict.pin_hash.clear();
+    let (profiles, libraries) = profiles.backup();
+    let backup = Backup {
+        version: BACKUP_VERSION,
+        settings,
+        profiles: Some(profiles),
+        libraries,
+    };
+
+    let raw = serde_json::to_string_pretty(&backup)
+        .map_err(|e| EepyError::Other(format!("Failed to serialize settings: {}", e)))?;
+    fs::write(&path, raw).map_err(|e| EepyError::io("write the export", e))?;
+    Ok(Some(path.to_string_lossy().into_owned()))
+}
+
+/// Read and migrate the export at `path`
+fn read_backup(path: &Path) -> Result<Backup, EepyError> {
+    let raw = fs::read_to_string(path).map_err(|e| EepyError::io("read the export", e))?;
+    let raw: Value = serde_json::from_str(&raw)
+        .map_err(|e| EepyError::Invalid(format!("Not an eepy settings export: {}", e)))?;
+    migrate(raw)
+}
+
+/// Neither the child nor a strict mode session may replace the setup
+fn check_unlocked(app: &AppHandle, settings: &Settings) -> Result<(), EepyError> {
+    if app.state::<ProfileState>().active(settings) == Profile::Child {
+        return Err(EepyError::Locked(
+            "Settings are locked by the parental profile".to_string(),
+        ));
+    }
+    if app.state::<StrictLock>().locked(app) {
+        return Err(EepyError::Locked(
+            "Settings can't be imported while strict mode locks a session".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Pick an export to import and check it, without changing anything yet.
+/// Returns the file and the profiles here importing it would remove, or
+/// nothing if they cancelled.
+#[tauri::command]
+pub async fn settings_import_pick(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    profiles: State<'_, Profiles>,
+) -> Result<Option<ImportPreview>, EepyError> {
+    check_unlocked(&app, &store.get())?;
+    let Some(picked) = app
+        .dialog()
+        .file()
+        .add_filter("eepy settings", &["json"])
+        .blocking_pick_file()
+    else {
+        return Ok(None);
+    };
+    let path = picked
+        .into_path()
+        .map_err(|e| EepyError::Invalid(format!("Can't open that file: {}", e)))?;
+    let backup = read_backup(&path)?;
+    let dropped = match &backup.profiles {
+        Some(imported) => profiles.check_restore(imported)?,
+        None => Vec::new(),
+    };
+    Ok(Some(ImportPreview {
+        path: path.to_string_lossy().into_owned(),
+        dropped,
+    }))
+}
+
+/// Replace the whole setup with the export at `path`, picked by
+/// `settings_import_pick`. The parent password and strict PIN stay as they
+/// are on this machine. Returns the settings now in use.
+#[tauri::command]
+pub async fn settings_import(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    profiles: State<'_, Profiles>,
+    library: State<'_, Library>,
+    history: State<'_, History>,
+    path: String,
+) -> Result<Settings, EepyError> {
+    let previous = store.get();
+    check_unlocked(&app, &previous)?;
+    let backup = read_backup(Path::new(&path))?;
+
+    let mut settings = backup.settings;
+    settings.parental.password_hash = previous.parental.password_hash.clone();
+    settings.parental.enabled &= !settings.parental.password_hash.is_empty();
+    settings.strict.pin_hash = previous.strict.pin_hash.clone();
+    #[cfg(desktop)]
+    autostart::apply(&app, &settings.autostart)?;
+    if settings.remote.enabled {
+        remote::ensure_token(&mut settings.remote);
+    }
+
+    // A bare settings file carries no profiles; keep the ones here
+    if let Some(imported) = backup.profiles {
+        profiles.restore(imported, backup.libraries, &library, &history)?;
+    }
+    store.save(settings.clone()).map_err(EepyError::Io)?;
+    settings::apply(&app, &previous, &settings);
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use sleepy_video_shared::pipeline::{PipelineStage, Stage};
+    use sleepy_video_shared::power::EndAction;
+
+    use super::*;
+
+    #[test]
+    fn a_bare_settings_file_is_version_zero() {
+        let settings = Settings {
+            end_action: EndAction::Hibernate,
+            ..Settings::default()
+        };
+        let backup = migrate(serde_json::to_value(&settings).unwrap()).unwrap();
+        assert_eq!(backup.version, BACKUP_VERSION);
+        assert_eq!(backup.settings.end_action, EndAction::Hibernate);
+        assert!(backup.profiles.is_none());
+        assert!(backup.libraries.is_empty());
+    }
+
+    #[test]
+    fn a_current_export_keeps_its_profiles_and_libraries() {
+        let entry = LibraryEntry {
+            video_id: "dQw4w9WgXcQ".to_string(),
+            title: None,
+            favorite: true,
+            volume: Some(40),
+        };
+        let exported = Backup {
+            version: BACKUP_VERSION,
+            settings: Settings::default(),
+            profiles: Some(ProfilesFile::default()),
+            libraries: BTreeMap::from([("default".to_string(), vec![entry.clone()])]),
+        };
+        let backup = migrate(serde_json::to_value(&exported).unwrap()).unwrap();
+        assert_eq!(backup.version, BACKUP_VERSION);
+        assert!(backup.profiles.is_some());
+        assert_eq!(backup.libraries["default"], [entry]);
+    }
+
+    #[test]
+    fn missing_pipeline_stages_come_back_switched_off() {
+        let end = PipelineStage {
+            stage: Stage::EndAction,
+            enabled: true,
+            delay_seconds: 0,
+        };
+        let raw = json!({
+            "version": 1,
+            "settings": { "pipeline": { "stages": [end] } },
+        });
+        let stages = migrate(raw).unwrap().settings.pipeline.stages;
+        assert_eq!(stages.len(), Stage::ALL.len());
+        assert_eq!(stages.last(), Some(&end));
+        assert!(stages[..stages.len() - 1].iter().all(|step| !step.enabled));
+    }
+
+    #[test]
+    fn a_newer_export_is_refused() {
+        let raw = json!({ "version": BACKUP_VERSION + 1, "settings": {} });
+        assert!(matches!(migrate(raw), Err(EepyError::Invalid(_))));
+    }
+
+    #[test]
+    fn an_unreadable_version_is_refused() {
+        let raw = json!({ "version": "one", "settings": {} });
+        assert!(matches!(migrate(raw), Err(EepyError::Invalid(_))));
+    }
+
+    #[test]
+    fn an_export_without_settings_is_refused() {
+        let raw = json!({ "version": 1 });
+        assert!(matches!(migrate(raw), Err(EepyError::Invalid(_))));
+    }
+}
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use sleepy_video_shared::busy::{BusyGuardSettings, BusyReason, BusyReport};
+use sleepy_video_shared::error::EepyError;
+use sysinfo::{Networks, ProcessesToUpdate, System};
+use tauri::State;
+
+use crate::settings::SettingsStore;
+
+/// How long CPU and network usage are sampled for
+const SAMPLE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Probe CPU load, network throughput and running processes against the
+/// configured thresholds. Blocks for `SAMPLE_WINDOW`.
+pub fn probe(guard: &BusyGuardSettings) -> BusyReport {
+    if !guard.enabled {
+        return BusyReport::default();
+    }
+
+    let mut reasons = Vec::new();
+    let mut sys = System::new();
+    let mut networks = Networks::new_with_refreshed_list();
+
+    sys.refresh_cpu_usage();
+    std::thread::sleep(SAMPLE_WINDOW.max(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL));
+    sys.refresh_cpu_usage();
+    networks.refresh(true);
+
+    if guard.cpu_threshold_percent > 0 {
+        let cpu = sys.global_cpu_usage();
+        if cpu >= guard.cpu_threshold_percent as f32 {
+            reasons.push(BusyReason::Cpu(cpu.round() as u32));
+        }
+    }
+
+    if guard.network_threshold_kbps > 0 {
+        let bytes: u64 = networks
+            .list()
+            .values()
+            .map(|data| data.received() + data.transmitted())
+            .sum();
+        let kbps = bytes as f64 / SAMPLE_WINDOW.as_secs_f64() / 1024.0;
+        if kbps >= guard.network_threshold_kbps as f64 {
+            reasons.push(BusyReason::Network(kbps.round() as u32));
+        }
+    }
+
+    if !guard.processes.is_empty() {
+        sys.refresh_processes(ProcessesToUpdate::All, true);
+        for wanted in &guard.processes {
+            if is_process_running(&sys, wanted) {
+                reasons.push(BusyReason::Process(wanted.clone()));
+            }
+        }
+    }
+
+    BusyReport {
+        busy: !reasons.is_empty(),
+        reasons,
+    }
+}
+
+/// Match a process by name, ignoring case and a trailing ".exe"
+pub fn is_process_running(sys: &System, name: &str) -> bool {
+    let wanted = normalize_process_name(name);
+    if wanted.is_empty() {
+        return false;
+    }
+    sys.processes()
+        .values()
+        .any(|p| normalize_process_name(&p.name().to_string_lossy()) == wanted)
+}
+
+pub fn normalize_process_name(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    name.strip_suffix(".exe")
+        .map(str::to_string)
+        .unwrap_or(name)
+}
+
+/// Check whether the system is busy before suspending
+#[tauri::command]
+pub async fn check_busy(store: State<'_, SettingsStore>) -> Result<BusyReport, EepyError> {
+    let guard = store.get().busy_guard;
+    tauri::async_runtime::spawn_blocking(move || probe(&guard))
+        .await
+        .map_err(|e| EepyError::Other(format!("Busy check failed: {}", e)))
+}
@@ -0,0 +1,192 @@
+use sleepy_video_shared::control::{ControlCommand, StartRequest};
+use sleepy_video_shared::power::EndAction;
+use sleepy_video_shared::timer::MAX_MINUTES_LIMIT;
+
+pub const USAGE: &str = "\
+Usage: eepy [OPTIONS] [URL]
+
+Options:
+  -m, --minutes <N>     Start a sleep timer of N minutes (1-1440)
+  -u, --url <URL>       YouTube URL or video ID to play
+  -a, --action <ACTION> suspend, hibernate, shutdown, lock,
+                        screen_off or nothing
+      --headless        Stay in the tray instead of showing the window
+  -h, --help            Print this help";
+
+/// Command line arguments, parsed before the Tauri app is built
+#[derive(Debug, Default, PartialEq)]
+pub struct CliArgs {
+    pub minutes: Option<u32>,
+    pub video: Option<String>,
+    pub action: Option<EndAction>,
+    pub headless: bool,
+    pub help: bool,
+}
+
+impl CliArgs {
+    /// What the frontend should do with these args, if anything. Passing a
+    /// duration or an action starts the timer; a bare URL only loads it.
+    pub fn command(&self) -> Option<ControlCommand> {
+        if self.minutes.is_some() || self.action.is_some() {
+            Some(ControlCommand::Start(StartRequest {
+                minutes: self.minutes,
+                video: self.video.clone(),
+                action: self.action,
+                fade: None,
+            }))
+        } else {
+            self.video.clone().map(ControlCommand::LoadVideo)
+        }
+    }
+}
+
+/// Parse arguments (without the binary name)
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<CliArgs, String> {
+    let mut cli = CliArgs::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        // Accept both "--minutes 45" and "--minutes=45"
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_string(), Some(value.to_string()))
+            }
+            _ => (arg.clone(), None),
+        };
+        let mut value = |name: &str| {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} needs a value", name))
+        };
+
+        match flag.as_str() {
+            "-m" | "--minutes" => {
+                let raw = value("--minutes")?;
+                let minutes = raw
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|m| (1..=MAX_MINUTES_LIMIT).contains(m))
+                    .ok_or_else(|| format!("Invalid minutes: {}", raw))?;
+                cli.minutes = Some(minutes);
+            }
+            "-u" | "--url" => cli.video = Some(value("--url")?),
+            "-a" | "--action" => {
+                let raw = value("--action")?;
+                cli.action =
+                    Some(EndAction::parse(&raw).ok_or_else(|| format!("Unknown action: {}", raw))?);
+            }
+            "--headless" | "--tray" => cli.headless = true,
+            "-h" | "--help" => cli.help = true,
+            // Added by the OS login item, see `autostart`
+            "--autostart" => {}
+            // Deep links arrive as arguments on Windows and Linux, `deeplink` handles them
+            link if link.starts_with("eepy://") => {}
+            other if other.starts_with('-') => return Err(format!("Unknown option: {}", other)),
+            _ => cli.video = Some(arg),
+        }
+    }
+
+    Ok(cli)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(raw: &[&str]) -> Result<CliArgs, String> {
+        parse(raw.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn no_arguments_do_nothing() {
+        let cli = args(&[]).unwrap();
+        assert_eq!(cli, CliArgs::default());
+        assert_eq!(cli.command(), None);
+    }
+
+    #[test]
+    fn flags_take_separate_and_inline_values() {
+        let expected = CliArgs {
+            minutes: Some(45),
+            video: Some("dQw4w9WgXcQ".to_string()),
+            action: Some(EndAction::Hibernate),
+            ..CliArgs::default()
+        };
+        assert_eq!(
+            args(&["-m", "45", "-u", "dQw4w9WgXcQ", "-a", "hibernate"]).unwrap(),
+            expected
+        );
+        assert_eq!(
+            args(&["--minutes=45", "--url=dQw4w9WgXcQ", "--action=hibernate"]).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn a_bare_argument_is_the_video() {
+        let cli = args(&["https://youtu.be/dQw4w9WgXcQ?t=5"]).unwrap();
+        assert_eq!(
+            cli.video.as_deref(),
+            Some("https://youtu.be/dQw4w9WgXcQ?t=5")
+        );
+    }
+
+    #[test]
+    fn headless_help_and_launch_arguments() {
+        let cli = args(&["--tray", "--help", "--autostart", "eepy://start?minutes=30"]).unwrap();
+        assert!(cli.headless && cli.help);
+        assert_eq!(cli.video, None);
+        assert!(args(&["--headless"]).unwrap().headless);
+    }
+
+    #[test]
+    fn minutes_must_be_in_range() {
+        assert!(args(&["-m", "1"]).is_ok());
+        assert!(args(&["-m", &MAX_MINUTES_LIMIT.to_string()]).is_ok());
+        assert_eq!(args(&["-m", "0"]), Err("Invalid minutes: 0".to_string()));
+        assert_eq!(
+            args(&["--minutes", "soon"]),
+            Err("Invalid minutes: soon".to_string())
+        );
+        assert!(args(&["-m", &(MAX_MINUTES_LIMIT + 1).to_string()]).is_err());
+    }
+
+    #[test]
+    fn bad_arguments_are_errors() {
+        assert_eq!(args(&["-m"]), Err("--minutes needs a value".to_string()));
+        assert_eq!(
+            args(&["--action", "explode"]),
+            Err("Unknown action: explode".to_string())
+        );
+        assert_eq!(
+            args(&["--verbose"]),
+            Err("Unknown option: --verbose".to_string())
+        );
+    }
+
+    #[test]
+    fn minutes_or_an_action_start_a_session() {
+        let cli = args(&["-m", "30", "dQw4w9WgXcQ"]).unwrap();
+        assert_eq!(
+            cli.command(),
+            Some(ControlCommand::Start(StartRequest {
+                minutes: Some(30),
+                video: Some("dQw4w9WgXcQ".to_string()),
+                action: None,
+                fade: None,
+            }))
+        );
+        let cli = args(&["-a", "lock"]).unwrap();
+        assert!(matches!(cli.command(), Some(ControlCommand::Start(_))));
+    }
+
+    #[test]
+    fn a_video_alone_only_loads() {
+        let cli = args(&["dQw4w9WgXcQ"]).unwrap();
+        assert_eq!(
+            cli.command(),
+            Some(ControlCommand::LoadVideo("dQw4w9WgXcQ".to_string()))
+        );
+    }
+}
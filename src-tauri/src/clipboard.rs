@@ -0,0 +1,25 @@
+use tauri::{AppHandle, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::settings::SettingsStore;
+
+/// Anything longer is not a link someone just copied
+const MAX_URL_LEN: usize = 200;
+
+fn is_youtube_url(text: &str) -> bool {
+    let text = text.to_lowercase();
+    (text.starts_with("http://") || text.starts_with("https://"))
+        && (text.contains("youtube.com/") || text.contains("youtu.be/"))
+}
+
+/// The clipboard's text if it's a YouTube link. Anything else stays in the
+/// backend so the clipboard watcher can't leak unrelated content.
+#[tauri::command]
+pub fn read_clipboard_video(app: AppHandle, store: State<SettingsStore>) -> Option<String> {
+    if !store.get().clipboard.enabled {
+        return None;
+    }
+    let text = app.clipboard().read_text().ok()?;
+    let text = text.trim();
+    (text.len() <= MAX_URL_LEN && is_youtube_url(text)).then(|| text.to_string())
+}
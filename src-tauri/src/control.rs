@@ -0,0 +1,59 @@
+use std::sync::Mutex;
+
+use sleepy_video_shared::control::ControlCommand;
+use sleepy_video_shared::error::EepyError;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::strict::StrictLock;
+
+/// Event the frontend listens on for remote instructions
+pub const CONTROL_EVENT: &str = "control";
+
+/// Strict mode: cancelling or extending a locked session only works from
+/// the app, where the PIN can be asked for
+pub fn check_strict(app: &AppHandle, command: &ControlCommand) -> Result<(), EepyError> {
+    if command.needs_unlock() && app.state::<StrictLock>().locked(app) {
+        return Err(EepyError::Locked(
+            "Strict mode locks the session, unlock it in the app".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Forward a command to the frontend, which owns the timer. Ones strict mode
+/// holds back are dropped.
+pub fn send(app: &AppHandle, command: ControlCommand) {
+    if let Err(e) = check_strict(app, &command) {
+        tracing::warn!("Ignoring {:?}: {}", command, e);
+        return;
+    }
+    if let Err(e) = app.emit(CONTROL_EVENT, command) {
+        tracing::warn!("Failed to send control command: {}", e);
+    }
+}
+
+/// Commands issued before the frontend was listening, e.g. from launch args
+#[derive(Default)]
+pub struct PendingCommands(Mutex<Vec<ControlCommand>>);
+
+/// Hold a command until the frontend asks for it on startup
+pub fn queue(app: &AppHandle, command: ControlCommand) {
+    app.state::<PendingCommands>()
+        .0
+        .lock()
+        .unwrap()
+        .push(command);
+}
+
+/// Lets other windows, like the mini player, control the main one
+#[tauri::command]
+pub fn send_control(app: AppHandle, command: ControlCommand) -> Result<(), EepyError> {
+    check_strict(&app, &command)?;
+    send(&app, command);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn take_pending_commands(pending: State<PendingCommands>) -> Vec<ControlCommand> {
+    std::mem::take(&mut *pending.0.lock().unwrap())
+}
@@ -0,0 +1,28 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use sleepy_video_shared::display::CustomCss;
+use sleepy_video_shared::error::EepyError;
+use tauri::{AppHandle, Manager};
+
+const CUSTOM_CSS_FILE: &str = "custom.css";
+
+/// The stylesheet skins go in, next to settings.json
+#[tauri::command]
+pub fn custom_css(app: AppHandle) -> Result<CustomCss, EepyError> {
+    let path = app
+        .path()
+        .app_config_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join(CUSTOM_CSS_FILE);
+    let css = match fs::read_to_string(&path) {
+        Ok(css) => Some(css),
+        Err(e) if e.kind() == ErrorKind::NotFound => None,
+        Err(e) => return Err(EepyError::io("read custom.css", e)),
+    };
+    Ok(CustomCss {
+        path: path.to_string_lossy().into_owned(),
+        css,
+    })
+}
@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+
+use sleepy_video_shared::control::{ControlCommand, StartRequest, DEFAULT_EXTEND_MINUTES};
+use sleepy_video_shared::power::EndAction;
+use sleepy_video_shared::timer::MAX_MINUTES_LIMIT;
+use tauri::{AppHandle, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+use tauri_plugin_opener::OpenerExt;
+
+use crate::session::SessionState;
+use crate::{control, handoff, window};
+
+pub const SCHEME: &str = "eepy";
+
+/// Where Shortcuts and other x-callback-url clients want to hear back
+const SUCCESS_PARAM: &str = "x-success";
+/// Schemes an `x-success` URL may use. Any web page can open an `eepy://`
+/// link, so files, web addresses and other apps are never opened for it.
+const CALLBACK_SCHEMES: &[&str] = &["shortcuts"];
+
+/// What the link asks for. `eepy://x-callback-url/start?...` is the same as
+/// `eepy://start?...`.
+fn action_of(url: &Url) -> Option<&str> {
+    match url.host_str() {
+        Some("x-callback-url") => url.path_segments()?.next(),
+        host => host,
+    }
+}
+
+/// What a link asks for
+#[derive(Debug, PartialEq)]
+enum Link {
+    Command(ControlCommand),
+    /// A tab sent over by the browser extension, which the handoff settings
+    /// load or start
+    Send(String),
+}
+
+/// Read an `eepy://` URL:
+///
+/// - `eepy://start?minutes=30&video=<id or url>&action=hibernate`
+/// - `eepy://extend?minutes=15`
+/// - `eepy://cancel`
+/// - `eepy://load?video=<id or url>`
+/// - `eepy://send?url=<tab url>`, from the browser extension handoff
+fn parse(url: &Url) -> Option<Link> {
+    if url.scheme() != SCHEME {
+        return None;
+    }
+
+    let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    let video = params.get("video").or_else(|| params.get("url")).cloned();
+    let minutes = params.get("minutes").and_then(|m| m.parse::<u32>().ok());
+
+    let command = match action_of(url) {
+        Some("start") => ControlCommand::Start(StartRequest {
+            minutes: minutes.filter(|m| (1..=MAX_MINUTES_LIMIT).contains(m)),
+            video,
+            action: params.get("action").and_then(|a| EndAction::parse(a)),
+            fade: None,
+        }),
+        Some("extend") => ControlCommand::Extend {
+            minutes: minutes.unwrap_or(DEFAULT_EXTEND_MINUTES).max(1),
+        },
+        Some("cancel") => ControlCommand::Cancel,
+        Some("load") => ControlCommand::LoadVideo(video?),
+        Some("send") => return video.map(Link::Send),
+        _ => return None,
+    };
+    Some(Link::Command(command))
+}
+
+/// Translate an `eepy://` URL into a control command
+pub fn command_for(app: &AppHandle, url: &Url) -> Option<ControlCommand> {
+    match parse(url)? {
+        Link::Command(command) => Some(command),
+        Link::Send(video) => Some(handoff::command(app, video)),
+    }
+}
+
+/// The link's `x-success` URL, if it has one with a scheme in
+/// `CALLBACK_SCHEMES`
+fn callback_of(url: &Url) -> Option<Url> {
+    let (_, success) = url.query_pairs().find(|(key, _)| key == SUCCESS_PARAM)?;
+    let Ok(callback) = Url::parse(&success) else {
+        tracing::warn!("Ignoring invalid x-success URL: {}", success);
+        return None;
+    };
+    if !CALLBACK_SCHEMES.contains(&callback.scheme()) {
+        tracing::warn!("Ignoring x-success URL with scheme {}", callback.scheme());
+        return None;
+    }
+    Some(callback)
+}
+
+/// Open the link's `x-success` URL, if it has one. `eepy://status` answers
+/// with the session as `running`, `remaining` (seconds) and `action`.
+fn call_back(app: &AppHandle, url: &Url) {
+    let Some(mut callback) = callback_of(url) else {
+        return;
+    };
+    if action_of(url) == Some("status") {
+        let status = app.state::<SessionState>().status();
+        callback
+            .query_pairs_mut()
+            .append_pair("running", &status.running.to_string())
+            .append_pair("remaining", &status.remaining_seconds.to_string())
+            .append_pair("action", status.action.as_str());
+    }
+    if let Err(e) = app.opener().open_url(callback.as_str(), None::<&str>) {
+        tracing::warn!("Failed to open x-success URL: {}", e);
+    }
+}
+
+/// Listen for deep links and pick up the one we may have been launched with
+pub fn init(app: &AppHandle) {
+    // Installers register the scheme; in development we have to do it ourselves
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
+    if let Err(e) = app.deep_link().register_all() {
+        tracing::warn!("Failed to register deep link scheme: {}", e);
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            match command_for(&handle, &url) {
+                Some(command) => {
+                    // Links that bring a video want it on screen
+                    if matches!(
+                        command,
+                        ControlCommand::LoadVideo(_)
+                            | ControlCommand::Start(StartRequest { video: Some(_), .. })
+                    ) {
+                        window::show_main_window(&handle);
+                    }
+                    control::send(&handle, command);
+                }
+                // Only asks for the state, answered below
+                None if action_of(&url) == Some("status") => {}
+                None => tracing::warn!("Ignoring unknown deep link: {}", url),
+            }
+            call_back(&handle, &url);
+        }
+    });
+
+    // The frontend isn't listening yet when we were launched by a link
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        for url in urls {
+            if let Some(command) = command_for(app, &url) {
+                control::queue(app, command);
+            }
+            call_back(app, &url);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(raw: &str) -> Option<Link> {
+        parse(&Url::parse(raw).unwrap())
+    }
+
+    fn start(minutes: Option<u32>, video: Option<&str>, action: Option<EndAction>) -> Link {
+        Link::Command(ControlCommand::Start(StartRequest {
+            minutes,
+            video: video.map(str::to_string),
+            action,
+            fade: None,
+        }))
+    }
+
+    #[test]
+    fn start_takes_minutes_video_and_action() {
+        assert_eq!(
+            link("eepy://start?minutes=30&video=dQw4w9WgXcQ&action=hibernate"),
+            Some(start(
+                Some(30),
+                Some("dQw4w9WgXcQ"),
+                Some(EndAction::Hibernate)
+            ))
+        );
+    }
+
+    #[test]
+    fn start_without_parameters_uses_the_defaults() {
+        assert_eq!(link("eepy://start"), Some(start(None, None, None)));
+    }
+
+    #[test]
+    fn start_drops_minutes_out_of_range_and_unknown_actions() {
+        let too_long = format!("eepy://start?minutes={}", MAX_MINUTES_LIMIT + 1);
+        assert_eq!(link(&too_long), Some(start(None, None, None)));
+        assert_eq!(
+            link("eepy://start?minutes=0"),
+            Some(start(None, None, None))
+        );
+        assert_eq!(
+            link("eepy://start?minutes=abc&action=explode"),
+            Some(start(None, None, None))
+        );
+    }
+
+    #[test]
+    fn start_reads_an_encoded_video_url() {
+        assert_eq!(
+            link("eepy://start?url=https%3A%2F%2Fyoutu.be%2FdQw4w9WgXcQ%3Ft%3D5"),
+            Some(start(None, Some("https://youtu.be/dQw4w9WgXcQ?t=5"), None))
+        );
+    }
+
+    #[test]
+    fn x_callback_url_links_are_the_same_as_plain_ones() {
+        assert_eq!(
+            link("eepy://x-callback-url/start?minutes=45&x-success=shortcuts%3A%2F%2F"),
+            Some(start(Some(45), None, None))
+        );
+    }
+
+    #[test]
+    fn extend_defaults_and_keeps_at_least_a_minute() {
+        assert_eq!(
+            link("eepy://extend?minutes=5"),
+            Some(Link::Command(ControlCommand::Extend { minutes: 5 }))
+        );
+        assert_eq!(
+            link("eepy://extend"),
+            Some(Link::Command(ControlCommand::Extend {
+                minutes: DEFAULT_EXTEND_MINUTES
+            }))
+        );
+        assert_eq!(
+            link("eepy://extend?minutes=0"),
+            Some(Link::Command(ControlCommand::Extend { minutes: 1 }))
+        );
+    }
+
+    #[test]
+    fn cancel_and_load() {
+        assert_eq!(
+            link("eepy://cancel"),
+            Some(Link::Command(ControlCommand::Cancel))
+        );
+        assert_eq!(
+            link("eepy://load?video=dQw4w9WgXcQ"),
+            Some(Link::Command(ControlCommand::LoadVideo(
+                "dQw4w9WgXcQ".to_string()
+            )))
+        );
+        assert_eq!(link("eepy://load"), None);
+    }
+
+    #[test]
+    fn send_is_left_to_the_handoff() {
+        assert_eq!(
+            link("eepy://send?url=https%3A%2F%2Fwww.youtube.com%2Fwatch%3Fv%3DdQw4w9WgXcQ"),
+            Some(Link::Send(
+                "https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string()
+            ))
+        );
+        assert_eq!(link("eepy://send"), None);
+    }
+
+    fn callback(raw: &str) -> Option<String> {
+        callback_of(&Url::parse(raw).unwrap()).map(|url| url.to_string())
+    }
+
+    #[test]
+    fn shortcuts_callbacks_are_answered() {
+        assert_eq!(
+            callback(
+                "eepy://x-callback-url/status?x-success=shortcuts%3A%2F%2Fx-callback-url%2Fdone"
+            ),
+            Some("shortcuts://x-callback-url/done".to_string())
+        );
+        assert_eq!(callback("eepy://cancel"), None);
+    }
+
+    #[test]
+    fn other_callback_schemes_are_refused() {
+        for success in [
+            "file%3A%2F%2F%2FApplications%2FCalculator.app",
+            "https%3A%2F%2Fexample.com%2Fcollect",
+            "http%3A%2F%2Fexample.com%2Fcollect",
+            "javascript%3Aalert(1)",
+            "someapp%3A%2F%2Frun",
+            "not%20a%20url",
+        ] {
+            assert_eq!(
+                callback(&format!("eepy://status?x-success={}", success)),
+                None,
+                "{}",
+                success
+            );
+        }
+    }
+
+    #[test]
+    fn other_links_are_ignored() {
+        assert_eq!(link("eepy://status"), None);
+        assert_eq!(link("eepy://explode"), None);
+        assert_eq!(link("https://start?minutes=30"), None);
+    }
+}
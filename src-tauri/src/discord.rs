@@ -0,0 +1,147 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use discord_rich_presence::activity::{Activity, Timestamps};
+use discord_rich_presence::{DiscordIpc, DiscordIpcClient};
+use sleepy_video_shared::discord::DiscordSettings;
+use sleepy_video_shared::session::{SessionEvent, SessionStatus};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast;
+
+use crate::session::SessionState;
+use crate::video::VideoCache;
+
+/// Don't hammer a Discord client that isn't running
+const RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// The task mirroring the session to Discord, if enabled
+#[derive(Default)]
+pub struct DiscordPresence(Mutex<Option<JoinHandle<()>>>);
+
+/// Stop any running presence and start a new one if it's enabled
+pub fn restart(app: &AppHandle, settings: &DiscordSettings) {
+    let presence = app.state::<DiscordPresence>();
+    let mut running = presence.0.lock().unwrap();
+    if let Some(handle) = running.take() {
+        handle.abort();
+    }
+    if !settings.enabled || settings.application_id.trim().is_empty() {
+        return;
+    }
+
+    let events = app.state::<SessionState>().subscribe();
+    *running = Some(tauri::async_runtime::spawn(follow_session(
+        app.clone(),
+        settings.clone(),
+        events,
+    )));
+}
+
+/// Lazily connected IPC client, dropped and reconnected after any error
+struct Presence {
+    application_id: String,
+    client: Option<DiscordIpcClient>,
+    last_attempt: Option<Instant>,
+}
+
+impl Presence {
+    // The IPC calls are blocking but only talk to a local socket, so they
+    // run inline rather than on a separate thread
+    fn client(&mut self) -> Option<&mut DiscordIpcClient> {
+        if self.client.is_none() {
+            if self
+                .last_attempt
+                .is_some_and(|at| at.elapsed() < RETRY_DELAY)
+            {
+                return None;
+            }
+            self.last_attempt = Some(Instant::now());
+
+            let mut client = DiscordIpcClient::new(self.application_id.trim()).ok()?;
+            if let Err(e) = client.connect() {
+                tracing::warn!("Failed to connect to Discord: {}", e);
+                return None;
+            }
+            self.client = Some(client);
+        }
+        self.client.as_mut()
+    }
+
+    fn show(&mut self, status: &SessionStatus, title: Option<&str>) {
+        let minutes = status.remaining_seconds.div_ceil(60);
+        let details = format!("getting eepy — {} min left 🐳", minutes);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+
+        let mut activity = Activity::new()
+            .details(&details)
+            .timestamps(Timestamps::new().end(now + status.remaining_seconds as i64));
+        if let Some(title) = title {
+            activity = activity.state(title);
+        }
+
+        let Some(client) = self.client() else { return };
+        if let Err(e) = client.set_activity(activity) {
+            tracing::warn!("Failed to update Discord presence: {}", e);
+            self.client = None;
+        }
+    }
+
+    fn clear(&mut self) {
+        let Some(client) = self.client.as_mut() else {
+            return;
+        };
+        if let Err(e) = client.clear_activity() {
+            tracing::warn!("Failed to clear Discord presence: {}", e);
+            self.client = None;
+        }
+    }
+}
+
+/// Show the countdown while a session runs and clear it when it ends or is
+/// cancelled. Discord rate-limits updates, so only send when the minute
+/// changes.
+async fn follow_session(
+    app: AppHandle,
+    settings: DiscordSettings,
+    mut events: broadcast::Receiver<SessionEvent>,
+) {
+    let mut presence = Presence {
+        application_id: settings.application_id.clone(),
+        client: None,
+        last_attempt: None,
+    };
+    let mut shown: Option<(u32, Option<String>)> = None;
+
+    loop {
+        let status = match events.recv().await {
+            Ok(SessionEvent::Status(status)) => status,
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+
+        if !status.running {
+            if shown.take().is_some() {
+                presence.clear();
+            }
+            continue;
+        }
+
+        let current = (
+            status.remaining_seconds.div_ceil(60),
+            status.video_id.clone(),
+        );
+        if shown.as_ref() == Some(&current) {
+            continue;
+        }
+        let title = match (&status.video_id, settings.show_title) {
+            (Some(id), true) => app.state::<VideoCache>().title(id).await,
+            _ => None,
+        };
+        presence.show(&status, title.as_deref());
+        shown = Some(current);
+    }
+}
@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::playback::PlaybackSettings;
+use tauri::State;
+use tokio::process::Command;
+
+use crate::network;
+use crate::settings::SettingsStore;
+
+const DEFAULT_PROGRAM: &str = "yt-dlp";
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The yt-dlp set in settings, or the one on the PATH
+pub fn program(playback: &PlaybackSettings) -> String {
+    match playback.ytdlp_path.trim() {
+        "" => DEFAULT_PROGRAM.to_string(),
+        path => path.to_string(),
+    }
+}
+
+/// Resolve a video into a direct audio stream URL with yt-dlp, so it can play
+/// natively instead of in the embedded player
+#[tauri::command]
+pub async fn resolve_audio(
+    store: State<'_, SettingsStore>,
+    video: String,
+) -> Result<String, EepyError> {
+    let playback = store.get().playback;
+    if !playback.extract_audio {
+        return Err(EepyError::Invalid(
+            "Audio extraction is turned off".to_string(),
+        ));
+    }
+    let program = program(&playback);
+    // Not knowing counts as unmetered
+    let metered = playback.low_data_when_metered
+        && matches!(
+            tauri::async_runtime::spawn_blocking(network::metered).await,
+            Ok(Ok(true))
+        );
+    let format = if playback.saves_data(metered) {
+        "worstaudio"
+    } else {
+        "bestaudio"
+    };
+
+    let run = Command::new(&program)
+        .args(["--no-playlist", "--no-warnings", "-f", format, "--get-url"])
+        .arg(format!("https://www.youtube.com/watch?v={}", video))
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(TIMEOUT, run)
+        .await
+        .map_err(|_| EepyError::CommandFailed(format!("{} took too long", program)))?
+        .map_err(|e| EepyError::spawn(&program, e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(EepyError::CommandFailed(format!(
+            "{} failed: {}",
+            program,
+            stderr.lines().last().unwrap_or("unknown error").trim()
+        )));
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("http"))
+        .map(str::to_string)
+        .ok_or_else(|| EepyError::CommandFailed(format!("{} returned no audio stream", program)))
+}
@@ -0,0 +1,211 @@
+use std::process::Command;
+use std::sync::Mutex;
+
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::focus::DndSettings;
+use tauri::State;
+
+use crate::settings::SettingsStore;
+
+/// What needs undoing once the session ends
+enum Restore {
+    #[cfg(target_os = "macos")]
+    MacShortcut { off: String },
+    #[cfg(target_os = "windows")]
+    WindowsToasts { previous: Option<String> },
+    #[cfg(target_os = "linux")]
+    GnomeBanners { previous: String },
+    /// Inhibitions are dropped when the connection closes, so it is kept here
+    #[cfg(target_os = "linux")]
+    NotificationInhibit {
+        connection: zbus::blocking::Connection,
+        cookie: u32,
+    },
+}
+
+/// Do Not Disturb state enabled by us, if any
+#[derive(Default)]
+pub struct FocusState(Mutex<Option<Restore>>);
+
+impl FocusState {
+    /// Restore whatever notification state we changed. Safe to call when
+    /// nothing is active.
+    pub fn restore(&self) -> Result<(), String> {
+        match self.0.lock().unwrap().take() {
+            Some(restore) => undo(restore),
+            None => Ok(()),
+        }
+    }
+}
+
+pub fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    tracing::info!("Running {} {}", program, args.join(" "));
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn enable(settings: &DndSettings) -> Result<Restore, String> {
+    run("shortcuts", &["run", &settings.mac_shortcut_on])?;
+    Ok(Restore::MacShortcut {
+        off: settings.mac_shortcut_off.clone(),
+    })
+}
+
+#[cfg(target_os = "windows")]
+const TOASTS_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Notifications\Settings";
+#[cfg(target_os = "windows")]
+const TOASTS_VALUE: &str = "NOC_GLOBAL_SETTING_TOASTS_ENABLED";
+
+#[cfg(target_os = "windows")]
+fn enable(_settings: &DndSettings) -> Result<Restore, String> {
+    // `reg query` prints "NAME    REG_DWORD    0x1"; a missing value means enabled
+    let previous = run("reg", &["query", TOASTS_KEY, "/v", TOASTS_VALUE])
+        .ok()
+        .and_then(|out| out.split_whitespace().last().map(str::to_string));
+    run(
+        "reg",
+        &[
+            "add",
+            TOASTS_KEY,
+            "/v",
+            TOASTS_VALUE,
+            "/t",
+            "REG_DWORD",
+            "/d",
+            "0",
+            "/f",
+        ],
+    )?;
+    Ok(Restore::WindowsToasts { previous })
+}
+
+#[cfg(target_os = "linux")]
+fn enable(_settings: &DndSettings) -> Result<Restore, String> {
+    let desktop = std::env::var("XDG_CURRENT_DESKTOP")
+        .unwrap_or_default()
+        .to_uppercase();
+
+    if desktop.contains("GNOME") {
+        let previous = run(
+            "gsettings",
+            &["get", "org.gnome.desktop.notifications", "show-banners"],
+        )?;
+        run(
+            "gsettings",
+            &[
+                "set",
+                "org.gnome.desktop.notifications",
+                "show-banners",
+                "false",
+            ],
+        )?;
+        return Ok(Restore::GnomeBanners { previous });
+    }
+
+    // KDE Plasma (and a few other servers) implement the Inhibit extension
+    let connection = zbus::blocking::Connection::session()
+        .map_err(|e| format!("Failed to reach the session bus: {}", e))?;
+    let hints = std::collections::HashMap::<&str, zbus::zvariant::Value>::new();
+    let reply = connection
+        .call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "Inhibit",
+            &("eepy", "Sleep session in progress", hints),
+        )
+        .map_err(|e| format!("Notification server can't be inhibited: {}", e))?;
+    let cookie: u32 = reply
+        .body()
+        .deserialize()
+        .map_err(|e| format!("Unexpected inhibit reply: {}", e))?;
+    Ok(Restore::NotificationInhibit { connection, cookie })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn enable(_settings: &DndSettings) -> Result<Restore, String> {
+    Err("Do Not Disturb is not supported on this platform".to_string())
+}
+
+fn undo(restore: Restore) -> Result<(), String> {
+    match restore {
+        #[cfg(target_os = "macos")]
+        Restore::MacShortcut { off } => run("shortcuts", &["run", &off]).map(|_| ()),
+        #[cfg(target_os = "windows")]
+        Restore::WindowsToasts { previous } => match previous {
+            Some(value) => run(
+                "reg",
+                &[
+                    "add",
+                    TOASTS_KEY,
+                    "/v",
+                    TOASTS_VALUE,
+                    "/t",
+                    "REG_DWORD",
+                    "/d",
+                    &value,
+                    "/f",
+                ],
+            )
+            .map(|_| ()),
+            None => run("reg", &["delete", TOASTS_KEY, "/v", TOASTS_VALUE, "/f"]).map(|_| ()),
+        },
+        #[cfg(target_os = "linux")]
+        Restore::GnomeBanners { previous } => run(
+            "gsettings",
+            &[
+                "set",
+                "org.gnome.desktop.notifications",
+                "show-banners",
+                &previous,
+            ],
+        )
+        .map(|_| ()),
+        #[cfg(target_os = "linux")]
+        Restore::NotificationInhibit { connection, cookie } => connection
+            .call_method(
+                Some("org.freedesktop.Notifications"),
+                "/org/freedesktop/Notifications",
+                Some("org.freedesktop.Notifications"),
+                "UnInhibit",
+                &(cookie,),
+            )
+            .map(|_| ())
+            .map_err(|e| format!("Failed to lift notification inhibit: {}", e)),
+    }
+}
+
+/// Turn on Do Not Disturb for the session, remembering the previous state
+#[tauri::command]
+pub async fn enable_dnd(
+    store: State<'_, SettingsStore>,
+    focus: State<'_, FocusState>,
+) -> Result<(), EepyError> {
+    let settings = store.get().dnd;
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    // Don't stack restores if a previous session never cleaned up
+    focus.restore()?;
+    let restore = enable(&settings)?;
+    *focus.0.lock().unwrap() = Some(restore);
+    Ok(())
+}
+
+/// Put notifications back the way they were before the session
+#[tauri::command]
+pub async fn restore_dnd(focus: State<'_, FocusState>) -> Result<(), EepyError> {
+    Ok(focus.restore()?)
+}
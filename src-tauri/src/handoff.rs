@@ -0,0 +1,79 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use sleepy_video_shared::control::{ControlCommand, StartRequest};
+use tauri::{AppHandle, Manager};
+
+use crate::settings::SettingsStore;
+use crate::{control, window};
+
+const LAST_SESSION_FILE: &str = "last_session.json";
+
+#[derive(Serialize, Deserialize)]
+struct LastSession {
+    minutes: u32,
+}
+
+/// Duration of the most recent session, kept across restarts so a handed-off
+/// video can start with it even on a fresh launch
+pub struct LastDuration {
+    path: PathBuf,
+    minutes: Mutex<Option<u32>>,
+}
+
+impl LastDuration {
+    pub fn load(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .app_config_dir()
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .join(LAST_SESSION_FILE);
+        let minutes = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<LastSession>(&raw).ok())
+            .map(|last| last.minutes);
+
+        Self {
+            path,
+            minutes: Mutex::new(minutes),
+        }
+    }
+
+    pub fn get(&self) -> Option<u32> {
+        *self.minutes.lock().unwrap()
+    }
+
+    pub fn remember(&self, minutes: u32) {
+        if minutes == 0 || self.get() == Some(minutes) {
+            return;
+        }
+        *self.minutes.lock().unwrap() = Some(minutes);
+        if let Ok(raw) = serde_json::to_string(&LastSession { minutes }) {
+            if let Err(e) = fs::write(&self.path, raw) {
+                tracing::warn!("Failed to remember session length: {}", e);
+            }
+        }
+    }
+}
+
+/// Load a handed-off video, starting it too if the user asked for that
+pub fn command(app: &AppHandle, video: String) -> ControlCommand {
+    if app.state::<SettingsStore>().get().handoff.auto_start {
+        ControlCommand::Start(StartRequest {
+            minutes: app.state::<LastDuration>().get(),
+            video: Some(video),
+            action: None,
+            fade: None,
+        })
+    } else {
+        ControlCommand::LoadVideo(video)
+    }
+}
+
+/// Bring the window up with the handed-off video
+pub fn send(app: &AppHandle, video: String) {
+    window::show_main_window(app);
+    control::send(app, command(app, video));
+}
@@ -0,0 +1,26 @@
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::haptics::Haptic;
+use tauri::AppHandle;
+
+/// Buzz the phone. Desktops have nothing to buzz.
+#[tauri::command]
+pub fn vibrate(app: AppHandle, haptic: Haptic) -> Result<(), EepyError> {
+    #[cfg(target_os = "android")]
+    {
+        use tauri::Manager;
+        app.state::<crate::android::Countdown>()
+            .vibrate(haptic.pattern())
+    }
+    #[cfg(target_os = "ios")]
+    {
+        use tauri::Manager;
+        app.state::<crate::ios::Haptics>().impact(haptic)
+    }
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    {
+        let _ = (app, haptic);
+        Err(EepyError::UnsupportedPlatform(
+            "There's nothing to vibrate here".to_string(),
+        ))
+    }
+}
@@ -0,0 +1,499 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use std::collections::BTreeSet;
+
+use chrono::{Datelike, Local, TimeZone, Timelike};
+use serde_json::json;
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::history::{HistoryFormat, SessionOutcome, SessionRecord, HISTORY_LIMIT};
+use sleepy_video_shared::schedule::BedtimeSchedule;
+use sleepy_video_shared::session::{SessionEvent, SessionStatus};
+use sleepy_video_shared::streaks::{kept_night, Day, StreakReport};
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_dialog::DialogExt;
+use tokio::sync::broadcast;
+
+use crate::session::SessionState;
+use crate::settings::SettingsStore;
+
+const CSV_HEADER: &str =
+    "started,ended,planned_minutes,actual_minutes,video,action,outcome,bedtime_note,wake_note\n";
+/// The first columns of Fitbit's sleep export
+const SLEEP_CSV_HEADER: &str =
+    "Start Time,End Time,Minutes Asleep,Minutes Awake,Number of Awakenings,Time in Bed\n";
+
+/// Every session of the active profile that finished or was cancelled,
+/// oldest first
+pub struct History {
+    path: Mutex<PathBuf>,
+    records: Mutex<Vec<SessionRecord>>,
+    /// Noted at bedtime, kept until the running session is recorded
+    bedtime_note: Mutex<Option<String>>,
+}
+
+impl History {
+    pub fn load(app: &AppHandle, file: &str) -> Self {
+        let path = app
+            .path()
+            .app_config_dir()
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .join(file);
+        let records = read_records(&path);
+
+        Self {
+            path: Mutex::new(path),
+            records: Mutex::new(records),
+            bedtime_note: Mutex::new(None),
+        }
+    }
+
+    /// Use another profile's history file from now on
+    pub fn switch(&self, path: PathBuf) {
+        *self.records.lock().unwrap() = read_records(&path);
+        *self.path.lock().unwrap() = path;
+    }
+
+    fn add(&self, record: SessionRecord) -> Result<(), String> {
+        let mut records = self.records.lock().unwrap();
+        records.push(record);
+        let over = records.len().saturating_sub(HISTORY_LIMIT);
+        records.drain(..over);
+        self.save(&records)
+    }
+
+    fn save(&self, records: &[SessionRecord]) -> Result<(), String> {
+        let path = self.path.lock().unwrap();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+        }
+        let raw = serde_json::to_string(records)
+            .map_err(|e| format!("Failed to serialize history: {}", e))?;
+        fs::write(&*path, raw).map_err(|e| format!("Failed to write history: {}", e))
+    }
+
+    /// Streaks of nights on the schedule's days kept by starting a session
+    /// by bedtime. Tonight counts once it's kept, and doesn't break the
+    /// streak before then.
+    pub fn streak(&self, schedule: &BedtimeSchedule) -> StreakReport {
+        let Some((hour, minute)) = schedule.parsed_time() else {
+            return StreakReport::default();
+        };
+        let records = self.records.lock().unwrap();
+        let kept: BTreeSet<Day> = records
+            .iter()
+            .filter(|record| record.outcome == SessionOutcome::Finished)
+            .filter_map(|record| {
+                let start = Local
+                    .timestamp_millis_opt(record.started_at as i64)
+                    .single()?;
+                kept_night(
+                    start.date_naive().num_days_from_ce(),
+                    start.hour() * 60 + start.minute(),
+                    hour * 60 + minute,
+                )
+            })
+            .collect();
+        let today = Local::now().date_naive().num_days_from_ce();
+        StreakReport::from_nights(&kept, today, &schedule.days)
+    }
+}
+
+/// The records in a history file, none if it's missing or unreadable
+fn read_records(path: &Path) -> Vec<SessionRecord> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Record each session as it ends
+pub fn spawn(app: AppHandle) {
+    let events = app.state::<SessionState>().subscribe();
+    tauri::async_runtime::spawn(follow_session(app, events));
+}
+
+async fn follow_session(app: AppHandle, mut events: broadcast::Receiver<SessionEvent>) {
+    // When the running session started and how it looks now
+    let mut current: Option<(u64, SessionStatus)> = None;
+    loop {
+        let (outcome, action) = match events.recv().await {
+            Ok(SessionEvent::Started(status)) => {
+                current = Some((now_millis(), status));
+                app.state::<History>().bedtime_note.lock().unwrap().take();
+                continue;
+            }
+            Ok(SessionEvent::Status(status)) if status.running => {
+                if let Some((_, last)) = current.as_mut() {
+                    *last = status;
+                }
+                continue;
+            }
+            Ok(SessionEvent::Ended(action)) => (SessionOutcome::Finished, Some(action)),
+            Ok(SessionEvent::Cancelled) => (SessionOutcome::Cancelled, None),
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+        let Some((started_at, status)) = current.take() else {
+            continue;
+        };
+        let history = app.state::<History>();
+        let record = SessionRecord {
+            started_at,
+            ended_at: now_millis(),
+            planned_seconds: status.total_seconds,
+            video_id: status.video_id,
+            action: action.unwrap_or(status.action),
+            outcome,
+            bedtime_note: history.bedtime_note.lock().unwrap().take(),
+            wake_note: None,
+            woke_at: None,
+        };
+        if let Err(e) = history.add(record) {
+            tracing::warn!("{}", e);
+        }
+    }
+}
+
+/// Local time in a form spreadsheets read as a date
+fn local_time(millis: u64) -> String {
+    Local
+        .timestamp_millis_opt(millis as i64)
+        .single()
+        .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+/// Quote a CSV field if it needs it
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_csv(records: &[SessionRecord]) -> String {
+    let mut csv = CSV_HEADER.to_string();
+    for record in records {
+        let actual = record.ended_at.saturating_sub(record.started_at) as f64 / 60_000.0;
+        csv.push_str(&format!(
+            "{},{},{},{:.1},{},{},{},{},{}\n",
+            local_time(record.started_at),
+            local_time(record.ended_at),
+            record.planned_seconds / 60,
+            actual,
+            csv_field(record.video_id.as_deref().unwrap_or_default()),
+            record.action.as_str(),
+            record.outcome.as_str(),
+            csv_field(record.bedtime_note.as_deref().unwrap_or_default()),
+            csv_field(record.wake_note.as_deref().unwrap_or_default()),
+        ));
+    }
+    csv
+}
+
+fn minutes(from: u64, to: u64) -> u64 {
+    to.saturating_sub(from) / 60_000
+}
+
+/// Nights with a known wake-up time, one row each. The countdown is the
+/// time it took to fall asleep; the rest of the night counts as asleep.
+fn to_sleep_csv(records: &[SessionRecord]) -> String {
+    let mut csv = SLEEP_CSV_HEADER.to_string();
+    for record in records {
+        let Some((start, end)) = record.sleep() else {
+            continue;
+        };
+        let at = |millis: u64| {
+            Local
+                .timestamp_millis_opt(millis as i64)
+                .single()
+                .map(|time| time.format("%Y-%m-%d %I:%M%p").to_string())
+                .unwrap_or_default()
+        };
+        csv.push_str(&format!(
+            "{},{},{},{},0,{}\n",
+            at(start),
+            at(end),
+            minutes(record.ended_at, end),
+            minutes(start, record.ended_at),
+            minutes(start, end),
+        ));
+    }
+    csv
+}
+
+/// Local time with its offset, as Open mHealth wants it
+fn rfc3339(millis: u64) -> String {
+    Local
+        .timestamp_millis_opt(millis as i64)
+        .single()
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_default()
+}
+
+/// Nights with a known wake-up time as Open mHealth `sleep-episode` 1.0
+/// data points
+fn to_open_mhealth(records: &[SessionRecord]) -> serde_json::Value {
+    let created = rfc3339(now_millis());
+    let points: Vec<_> = records
+        .iter()
+        .filter_map(|record| {
+            let (start, end) = record.sleep()?;
+            Some(json!({
+                "header": {
+                    "id": format!("eepy-sleep-{}", start),
+                    "creation_date_time": created,
+                    "schema_id": {
+                        "namespace": "omh",
+                        "name": "sleep-episode",
+                        "version": "1.0",
+                    },
+                    "acquisition_provenance": {
+                        "source_name": "eepy",
+                        "modality": "self-reported",
+                    },
+                },
+                "body": {
+                    "effective_time_frame": {
+                        "time_interval": {
+                            "start_date_time": rfc3339(start),
+                            "end_date_time": rfc3339(end),
+                        },
+                    },
+                    "latency_to_sleep_onset": {
+                        "value": minutes(start, record.ended_at),
+                        "unit": "min",
+                    },
+                    "total_sleep_time": {
+                        "value": minutes(record.ended_at, end),
+                        "unit": "min",
+                    },
+                },
+            }))
+        })
+        .collect();
+    serde_json::Value::Array(points)
+}
+
+#[tauri::command]
+pub fn history_list(history: State<History>) -> Vec<SessionRecord> {
+    history.records.lock().unwrap().clone()
+}
+
+/// The session that ended last, to ask how it went
+#[tauri::command]
+pub fn history_last(history: State<History>) -> Option<SessionRecord> {
+    history.records.lock().unwrap().last().cloned()
+}
+
+#[tauri::command]
+pub fn history_streak(history: State<History>, store: State<SettingsStore>) -> StreakReport {
+    history.streak(&store.get().schedule)
+}
+
+/// Note how they feel going to bed, for the running session
+#[tauri::command]
+pub fn journal_bedtime(history: State<History>, note: String) {
+    let note = note.trim();
+    *history.bedtime_note.lock().unwrap() = (!note.is_empty()).then(|| note.to_string());
+}
+
+/// Note how they slept, on the session that ended last. An empty note
+/// answers without saying anything, so it isn't asked again.
+#[tauri::command]
+pub fn journal_wake(history: State<History>, note: String) -> Result<(), EepyError> {
+    let mut records = history.records.lock().unwrap();
+    let Some(last) = records.last_mut() else {
+        return Err(EepyError::Invalid("No session to note".to_string()));
+    };
+    last.wake_note = Some(note.trim().to_string());
+    last.woke_at.get_or_insert_with(now_millis);
+    history.save(&records).map_err(EepyError::from)
+}
+
+/// Take now as when they woke up from the session that ended last, for
+/// when the journal doesn't ask
+#[tauri::command]
+pub fn history_woke(history: State<History>) -> Result<(), EepyError> {
+    let mut records = history.records.lock().unwrap();
+    let Some(last) = records.last_mut() else {
+        return Ok(());
+    };
+    if last.woke_at.is_some() {
+        return Ok(());
+    }
+    last.woke_at = Some(now_millis());
+    history.save(&records).map_err(EepyError::from)
+}
+
+/// Write the history to a file the user picks. Returns where it went, or
+/// nothing if they cancelled.
+#[tauri::command]
+pub async fn history_export(
+    app: AppHandle,
+    history: State<'_, History>,
+    format: HistoryFormat,
+) -> Result<Option<String>, EepyError> {
+    let extension = format.extension();
+    let Some(picked) = app
+        .dialog()
+        .file()
+        .add_filter("eepy history", &[extension])
+        .set_file_name(format!("{}.{}", format.file_stem(), extension))
+        .blocking_save_file()
+    else {
+        return Ok(None);
+    };
+    let path = picked
+        .into_path()
+        .map_err(|e| EepyError::Invalid(format!("Can't save there: {}", e)))?;
+
+    let records = history.records.lock().unwrap().clone();
+    let raw = match format {
+        HistoryFormat::Csv => to_csv(&records),
+        HistoryFormat::Json => serde_json::to_string_pretty(&records)
+            .map_err(|e| EepyError::Other(format!("Failed to serialize history: {}", e)))?,
+        HistoryFormat::SleepCsv => to_sleep_csv(&records),
+        HistoryFormat::OpenMHealth => serde_json::to_string_pretty(&to_open_mhealth(&records))
+            .map_err(|e| EepyError::Other(format!("Failed to serialize history: {}", e)))?,
+    };
+    fs::write(&path, raw).map_err(|e| EepyError::io("write the history", e))?;
+    Ok(Some(path.to_string_lossy().into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use sleepy_video_shared::power::EndAction;
+
+    use super::*;
+
+    /// 2024-03-01 22:00 UTC
+    const STARTED: u64 = 1_709_330_400_000;
+    const MINUTE: u64 = 60_000;
+
+    fn record(outcome: SessionOutcome, woke_after: Option<u64>) -> SessionRecord {
+        SessionRecord {
+            started_at: STARTED,
+            ended_at: STARTED + 30 * MINUTE,
+            planned_seconds: 30 * 60,
+            video_id: Some("dQw4w9WgXcQ".to_string()),
+            action: EndAction::Suspend,
+            outcome,
+            bedtime_note: None,
+            wake_note: None,
+            woke_at: woke_after.map(|minutes| STARTED + minutes * MINUTE),
+        }
+    }
+
+    #[test]
+    fn csv_field_quotes_only_when_needed() {
+        assert_eq!(csv_field("rested"), "rested");
+        assert_eq!(csv_field(""), "");
+        assert_eq!(csv_field("tired, but ok"), "\"tired, but ok\"");
+        assert_eq!(csv_field("a \"good\" night"), "\"a \"\"good\"\" night\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn csv_has_a_row_per_session() {
+        let mut finished = record(SessionOutcome::Finished, None);
+        finished.ended_at = STARTED + 90_000;
+        finished.bedtime_note = Some("tired, but ok".to_string());
+        let mut cancelled = record(SessionOutcome::Cancelled, None);
+        cancelled.video_id = None;
+        cancelled.wake_note = Some("slept \"well\"".to_string());
+
+        let csv = to_csv(&[finished, cancelled]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(format!("{}\n", lines[0]), CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            format!(
+                "{},{},30,1.5,dQw4w9WgXcQ,suspend,finished,\"tired, but ok\",",
+                local_time(STARTED),
+                local_time(STARTED + 90_000)
+            )
+        );
+        assert_eq!(
+            lines[2],
+            format!(
+                "{},{},30,30.0,,suspend,cancelled,,\"slept \"\"well\"\"\"",
+                local_time(STARTED),
+                local_time(STARTED + 30 * MINUTE)
+            )
+        );
+    }
+
+    #[test]
+    fn csv_of_no_sessions_is_the_header() {
+        assert_eq!(to_csv(&[]), CSV_HEADER);
+        assert_eq!(to_sleep_csv(&[]), SLEEP_CSV_HEADER);
+    }
+
+    #[test]
+    fn sleep_csv_only_has_nights_with_a_wake_up() {
+        let records = [
+            record(SessionOutcome::Finished, Some(8 * 60)),
+            record(SessionOutcome::Finished, None),
+            record(SessionOutcome::Cancelled, Some(8 * 60)),
+        ];
+        let csv = to_sleep_csv(&records);
+        let rows: Vec<&str> = csv.lines().skip(1).collect();
+        assert_eq!(rows.len(), 1);
+        // Asleep from the end of the countdown, awake during it
+        assert!(rows[0].ends_with(",450,30,0,480"), "{}", rows[0]);
+    }
+
+    #[test]
+    fn open_mhealth_has_a_sleep_episode_per_night() {
+        let records = [
+            record(SessionOutcome::Finished, Some(8 * 60)),
+            record(SessionOutcome::Finished, None),
+            record(SessionOutcome::Cancelled, Some(8 * 60)),
+        ];
+        let points = to_open_mhealth(&records);
+        let points = points.as_array().unwrap();
+        assert_eq!(points.len(), 1);
+
+        let point = &points[0];
+        assert_eq!(point["header"]["id"], format!("eepy-sleep-{}", STARTED));
+        assert_eq!(point["header"]["schema_id"]["namespace"], "omh");
+        assert_eq!(point["header"]["schema_id"]["name"], "sleep-episode");
+        assert_eq!(point["header"]["schema_id"]["version"], "1.0");
+        let body = &point["body"];
+        assert_eq!(
+            body["effective_time_frame"]["time_interval"]["start_date_time"],
+            rfc3339(STARTED)
+        );
+        assert_eq!(
+            body["effective_time_frame"]["time_interval"]["end_date_time"],
+            rfc3339(STARTED + 8 * 60 * MINUTE)
+        );
+        assert_eq!(body["latency_to_sleep_onset"]["value"], 30);
+        assert_eq!(body["latency_to_sleep_onset"]["unit"], "min");
+        assert_eq!(body["total_sleep_time"]["value"], 450);
+        assert_eq!(body["total_sleep_time"]["unit"], "min");
+    }
+
+    #[test]
+    fn open_mhealth_times_carry_their_offset() {
+        let time = rfc3339(STARTED);
+        assert!(
+            chrono::DateTime::parse_from_rfc3339(&time).is_ok(),
+            "{}",
+            time
+        );
+    }
+}
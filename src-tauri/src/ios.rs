@@ -0,0 +1,35 @@
+use serde::Serialize;
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::haptics::Haptic;
+use tauri::plugin::{Builder, PluginHandle, TauriPlugin};
+use tauri::{Manager, Wry};
+
+// Swift half of the plugin, in `ios/`, linked by the build script
+tauri::ios_plugin_binding!(init_plugin_haptics);
+
+#[derive(Serialize)]
+struct ImpactArgs {
+    haptic: Haptic,
+}
+
+/// iOS has no vibration patterns, only the taps of its feedback generators
+pub struct Haptics(PluginHandle<Wry>);
+
+impl Haptics {
+    pub fn impact(&self, haptic: Haptic) -> Result<(), EepyError> {
+        self.0
+            .run_mobile_plugin::<serde_json::Value>("impact", ImpactArgs { haptic })
+            .map(|_| ())
+            .map_err(|e| EepyError::Other(format!("iOS impact failed: {}", e)))
+    }
+}
+
+pub fn plugin() -> TauriPlugin<Wry> {
+    Builder::new("haptics")
+        .setup(|app, api| {
+            let handle = api.register_ios_plugin(init_plugin_haptics)?;
+            app.manage(Haptics(handle));
+            Ok(())
+        })
+        .build()
+}
@@ -0,0 +1,26 @@
+use tauri::AppHandle;
+
+use crate::autostart::AUTOSTART_ARG;
+use crate::cli;
+use crate::control;
+use crate::window;
+
+/// Another `eepy` was started: it exits immediately and hands us its args
+pub fn handle_second_instance(app: &AppHandle, argv: Vec<String>) {
+    // A login item firing while we already run shouldn't pop the window up
+    let from_login = argv.iter().any(|arg| arg == AUTOSTART_ARG);
+    let args = match cli::parse(argv.into_iter().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            tracing::warn!("Ignoring arguments from second instance: {}", e);
+            return;
+        }
+    };
+
+    if !args.headless && !from_login {
+        window::show_main_window(app);
+    }
+    if let Some(command) = args.command() {
+        control::send(app, command);
+    }
+}
@@ -1,40 +1,345 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 
-/// Suspend/sleep the system. Cross-platform support for macOS, Windows, and Linux.
-#[tauri::command]
-fn suspend_system() -> Result<(), String> {
-    #[cfg(target_os = "macos")]
-    {
-        std::process::Command::new("pmset")
-            .args(["sleepnow"])
-            .output()
-            .map_err(|e| format!("Failed to suspend: {}", e))?;
-    }
+#[cfg(target_os = "android")]
+mod android;
+mod apps;
+#[cfg(desktop)]
+mod autostart;
+mod backup;
+mod busy;
+mod cli;
+mod clipboard;
+mod control;
+mod custom_css;
+mod deeplink;
+mod discord;
+mod extract;
+mod focus;
+mod handoff;
+mod haptics;
+mod history;
+#[cfg(target_os = "ios")]
+mod ios;
+#[cfg(desktop)]
+mod launch;
+mod library;
+mod lights;
+mod logging;
+mod loudness;
+mod mascot;
+mod media;
+#[cfg(desktop)]
+mod mediakeys;
+mod mini;
+#[cfg(desktop)]
+mod monitor_dim;
+mod mqtt;
+mod network;
+mod notifications;
+mod offline;
+mod parental;
+mod pipeline;
+mod plugins;
+#[cfg(target_os = "linux")]
+mod portal;
+mod power;
+mod profiles;
+mod push;
+mod recovery;
+mod remote;
+mod resume;
+mod scale;
+mod schedule;
+mod screen_dim;
+mod search;
+mod session;
+mod settings;
+mod sonos;
+mod sponsorblock;
+mod spotify;
+mod strict;
+mod taskbar;
+#[cfg(desktop)]
+mod tray;
+mod video;
+mod webhooks;
+#[cfg(desktop)]
+mod widget;
+mod window;
+mod windows_integration;
 
-    #[cfg(target_os = "windows")]
-    {
-        std::process::Command::new("rundll32.exe")
-            .args(["powrprof.dll,SetSuspendState", "0", "1", "0"])
-            .output()
-            .map_err(|e| format!("Failed to suspend: {}", e))?;
-    }
+use sleepy_video_shared::offline::OFFLINE_AUDIO_SCHEME;
+use tauri::{Manager, RunEvent};
+#[cfg(desktop)]
+use tauri_plugin_autostart::MacosLauncher;
 
-    #[cfg(target_os = "linux")]
-    {
-        std::process::Command::new("systemctl")
-            .args(["suspend"])
-            .output()
-            .map_err(|e| format!("Failed to suspend: {}", e))?;
-    }
-
-    Ok(())
-}
+use control::PendingCommands;
+use discord::DiscordPresence;
+use focus::FocusState;
+use handoff::LastDuration;
+use history::History;
+use library::Library;
+use lights::LightController;
+use loudness::Loudness;
+#[cfg(desktop)]
+use monitor_dim::MonitorDim;
+use mqtt::MqttBridge;
+use offline::OfflineAudio;
+use parental::ProfileState;
+use power::Power;
+use profiles::Profiles;
+use recovery::SessionRecovery;
+use remote::RemoteServer;
+use resume::PlaybackPositions;
+use screen_dim::ScreenDim;
+use search::VideoSearch;
+use session::SessionState;
+use settings::SettingsStore;
+use sonos::Sonos;
+use sponsorblock::SponsorBlock;
+use spotify::Spotify;
+use strict::StrictLock;
+use video::VideoCache;
+use webhooks::Webhooks;
+use window::Theater;
+use windows_integration::WindowsIntegration;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    let args = match cli::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if args.help {
+        println!("{}", cli::USAGE);
+        return;
+    }
+
+    let builder = tauri::Builder::default();
+    // Must be registered first so a second launch exits before doing any work
+    #[cfg(desktop)]
+    let builder = builder.plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+        launch::handle_second_instance(app, argv);
+    }));
+    let builder = builder
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![suspend_system])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_dialog::init());
+    // Tray, media keys, window placement and login items are desktop only
+    #[cfg(desktop)]
+    let builder = builder
+        .plugin(mediakeys::plugin())
+        // The widget goes to its corner on its own, the dim overlays to their monitors
+        .plugin(
+            tauri_plugin_window_state::Builder::default()
+                .with_denylist(&[widget::WIDGET_LABEL])
+                .with_filter(|label| !monitor_dim::is_overlay(label))
+                .build(),
+        )
+        .plugin(tauri_plugin_autostart::init(
+            MacosLauncher::LaunchAgent,
+            Some(vec![autostart::AUTOSTART_ARG]),
+        ));
+    #[cfg(target_os = "android")]
+    let builder = builder.plugin(android::plugin());
+    #[cfg(target_os = "ios")]
+    let builder = builder.plugin(ios::plugin());
+    // Downloaded audio is read a range at a time, not sent over IPC
+    let builder = builder.register_asynchronous_uri_scheme_protocol(
+        OFFLINE_AUDIO_SCHEME,
+        |ctx, request, responder| {
+            let app = ctx.app_handle().clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                responder.respond(offline::serve(&app, &request));
+            });
+        },
+    );
+
+    builder
+        .setup(move |app| {
+            app.manage(logging::init(app.handle()));
+            let store = SettingsStore::load(app.handle());
+            let settings = store.get();
+            app.manage(store);
+            app.manage(FocusState::default());
+            app.manage(WindowsIntegration::default());
+            app.manage(ScreenDim::default());
+            #[cfg(desktop)]
+            app.manage(MonitorDim::default());
+            app.manage(Theater::default());
+            app.manage(PendingCommands::default());
+            app.manage(SessionState::default());
+            app.manage(RemoteServer::default());
+            app.manage(VideoCache::load(app.handle()));
+            app.manage(Webhooks::default());
+            app.manage(MqttBridge::default());
+            app.manage(LightController::default());
+            app.manage(DiscordPresence::default());
+            app.manage(Spotify::load(app.handle()));
+            app.manage(Sonos::default());
+            app.manage(LastDuration::load(app.handle()));
+            let profiles = Profiles::load(app.handle());
+            app.manage(Library::load(app.handle(), &profiles.library_file()));
+            app.manage(History::load(app.handle(), &profiles.history_file()));
+            app.manage(profiles);
+            app.manage(PlaybackPositions::load(app.handle()));
+            app.manage(SponsorBlock::default());
+            app.manage(VideoSearch::default());
+            app.manage(Loudness::default());
+            app.manage(OfflineAudio::load(app.handle()));
+            app.manage(SessionRecovery::load(app.handle()));
+            app.manage(StrictLock::default());
+            app.manage(ProfileState::default());
+            #[cfg(target_os = "android")]
+            app.manage(Power::android(app.handle()));
+            #[cfg(not(target_os = "android"))]
+            app.manage(Power::native());
+
+            // The frontend isn't listening yet, it picks this up on startup
+            if let Some(command) = args.command() {
+                control::queue(app.handle(), command);
+            }
+
+            deeplink::init(app.handle());
+            #[cfg(desktop)]
+            {
+                tray::create(app.handle(), &settings.templates)?;
+                // The window starts hidden so headless and login launches can stay in the tray
+                let minimized = args.headless
+                    || (autostart::launched_at_login() && settings.autostart.start_minimized);
+                if !minimized {
+                    window::show_main_window(app.handle());
+                }
+            }
+            schedule::spawn(app.handle().clone());
+            history::spawn(app.handle().clone());
+            #[cfg(target_os = "linux")]
+            portal::spawn(app.handle().clone());
+            #[cfg(target_os = "android")]
+            android::spawn(app.handle().clone());
+            remote::restart(app.handle(), &settings.remote);
+            mqtt::restart(app.handle(), &settings.mqtt);
+            lights::restart(app.handle(), &settings.lights);
+            discord::restart(app.handle(), &settings.discord);
+            spotify::restart(app.handle(), &settings.spotify);
+            sonos::restart(app.handle(), &settings.sonos);
+            windows_integration::restart(app.handle(), &settings.windows);
+            #[cfg(desktop)]
+            {
+                mediakeys::restart(app.handle(), settings.playback.media_keys);
+                widget::restart(
+                    app.handle(),
+                    &settings.widget,
+                    settings.display.monitor.as_deref(),
+                );
+                monitor_dim::restart(app.handle(), settings.display.dim_all_monitors);
+            }
+            scale::apply(app.handle(), settings.display.scale);
+            screen_dim::restart(app.handle(), &settings.display);
+            Ok(())
+        })
+        .on_window_event(window::on_window_event)
+        .invoke_handler(tauri::generate_handler![
+            power::run_end_action,
+            pipeline::run_end_pipeline,
+            pipeline::run_test_pipeline,
+            plugins::plugin_list,
+            power::power_capabilities,
+            notifications::request_notifications,
+            power::run_diagnostics,
+            settings::get_settings,
+            settings::save_settings,
+            backup::settings_export,
+            backup::settings_import,
+            backup::settings_import_pick,
+            busy::check_busy,
+            apps::quit_apps_before_suspend,
+            focus::enable_dnd,
+            focus::restore_dnd,
+            windows_integration::power_plans,
+            screen_dim::system_dim_backend,
+            network::network_metered,
+            session::report_milestone,
+            session::report_cancelled,
+            session::report_status,
+            session::session_status,
+            control::take_pending_commands,
+            control::send_control,
+            remote::remote_pairing,
+            lights::hue_pair,
+            lights::hue_lights,
+            lights::lifx_discover,
+            spotify::spotify_connect,
+            spotify::spotify_disconnect,
+            spotify::spotify_connected,
+            sonos::sonos_discover,
+            push::push_test,
+            clipboard::read_clipboard_video,
+            library::library_list,
+            library::library_record,
+            library::library_favorite,
+            library::library_volume,
+            offline::offline_list,
+            offline::offline_audio,
+            offline::offline_download,
+            offline::offline_download_favorites,
+            offline::offline_remove,
+            offline::offline_clear,
+            library::library_remove,
+            custom_css::custom_css,
+            mascot::mascot_sprite,
+            haptics::vibrate,
+            history::history_list,
+            history::history_last,
+            history::journal_bedtime,
+            history::journal_wake,
+            history::history_woke,
+            history::history_streak,
+            history::history_export,
+            resume::get_playback_position,
+            resume::save_playback_position,
+            recovery::take_recovered_session,
+            recovery::save_session,
+            strict::strict_status,
+            strict::strict_unlock,
+            strict::strict_request_unlock,
+            strict::strict_set_pin,
+            parental::active_profile,
+            parental::parent_sign_in,
+            parental::parent_sign_out,
+            parental::parental_set_password,
+            profiles::profile_list,
+            profiles::profile_create,
+            profiles::profile_delete,
+            profiles::profile_switch,
+            extract::resolve_audio,
+            sponsorblock::sponsor_segments,
+            search::search_videos,
+            loudness::video_loudness,
+            video::video_info,
+            mini::open_mini_player,
+            mini::show_main_window,
+            window::set_always_on_top,
+            window::set_fullscreen,
+            window::monitor_list,
+            window::hide_main_window,
+            logging::log_tail,
+            logging::copy_diagnostics,
+            window::quit_app
+        ])
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                // Never leave the user's notifications silenced
+                let _ = app.state::<FocusState>().restore();
+                app.state::<WindowsIntegration>().restore();
+                app.state::<ScreenDim>().restore();
+            }
+        });
 }
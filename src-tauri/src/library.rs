@@ -0,0 +1,167 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::library::{LibraryEntry, RECENT_LIMIT};
+use tauri::{AppHandle, Manager, State};
+
+use crate::offline;
+use crate::settings::SettingsStore;
+use crate::video::VideoCache;
+
+/// Recently played and favorite videos of the active profile, most recent
+/// first
+pub struct Library {
+    path: Mutex<PathBuf>,
+    entries: Mutex<Vec<LibraryEntry>>,
+}
+
+pub fn read_entries(path: &Path) -> Vec<LibraryEntry> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+impl Library {
+    pub fn load(app: &AppHandle, file: &str) -> Self {
+        let path = app
+            .path()
+            .app_config_dir()
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .join(file);
+        let entries = read_entries(&path);
+
+        Self {
+            path: Mutex::new(path),
+            entries: Mutex::new(entries),
+        }
+    }
+
+    /// Use another profile's library file from now on
+    pub fn switch(&self, path: PathBuf) {
+        *self.entries.lock().unwrap() = read_entries(&path);
+        *self.path.lock().unwrap() = path;
+    }
+
+    pub fn entries(&self) -> Vec<LibraryEntry> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    /// Apply a change and write the result to disk
+    fn update(
+        &self,
+        change: impl FnOnce(&mut Vec<LibraryEntry>),
+    ) -> Result<Vec<LibraryEntry>, String> {
+        let mut entries = self.entries.lock().unwrap();
+        change(&mut entries);
+
+        let path = self.path.lock().unwrap();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+        }
+        let raw = serde_json::to_string_pretty(&*entries)
+            .map_err(|e| format!("Failed to serialize library: {}", e))?;
+        fs::write(&*path, raw).map_err(|e| format!("Failed to write library: {}", e))?;
+        Ok(entries.clone())
+    }
+}
+
+#[tauri::command]
+pub fn library_list(library: State<Library>) -> Vec<LibraryEntry> {
+    library.entries()
+}
+
+/// Move a played video to the front, dropping the oldest non-favorites
+#[tauri::command]
+pub async fn library_record(
+    library: State<'_, Library>,
+    videos: State<'_, VideoCache>,
+    video: String,
+) -> Result<Vec<LibraryEntry>, EepyError> {
+    let known = library
+        .entries
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|entry| entry.video_id == video)
+        .map(|entry| (entry.title.clone(), entry.volume));
+    let (known, volume) = known.unwrap_or_default();
+    let title = match known {
+        Some(title) => Some(title),
+        None => videos.title(&video).await,
+    };
+
+    library
+        .update(|entries| {
+            let favorite = entries
+                .iter()
+                .any(|entry| entry.video_id == video && entry.favorite);
+            entries.retain(|entry| entry.video_id != video);
+            entries.insert(
+                0,
+                LibraryEntry {
+                    video_id: video,
+                    title,
+                    favorite,
+                    volume,
+                },
+            );
+
+            let mut recent = 0;
+            entries.retain(|entry| {
+                recent += usize::from(!entry.favorite);
+                entry.favorite || recent <= RECENT_LIMIT
+            });
+        })
+        .map_err(EepyError::Io)
+}
+
+/// Favorites are downloaded for offline if that's on
+#[tauri::command]
+pub fn library_favorite(
+    app: AppHandle,
+    library: State<Library>,
+    store: State<SettingsStore>,
+    video: String,
+    favorite: bool,
+) -> Result<Vec<LibraryEntry>, EepyError> {
+    let entries = library
+        .update(|entries| {
+            if let Some(entry) = entries.iter_mut().find(|entry| entry.video_id == video) {
+                entry.favorite = favorite;
+            }
+        })
+        .map_err(EepyError::Io)?;
+    if favorite && store.get().offline.enabled {
+        offline::spawn_download(&app, video);
+    }
+    Ok(entries)
+}
+
+/// Remember the volume a video was set to
+#[tauri::command]
+pub fn library_volume(
+    library: State<Library>,
+    video: String,
+    volume: u32,
+) -> Result<Vec<LibraryEntry>, EepyError> {
+    library
+        .update(|entries| {
+            if let Some(entry) = entries.iter_mut().find(|entry| entry.video_id == video) {
+                entry.volume = Some(volume.min(100));
+            }
+        })
+        .map_err(EepyError::Io)
+}
+
+#[tauri::command]
+pub fn library_remove(
+    library: State<Library>,
+    video: String,
+) -> Result<Vec<LibraryEntry>, EepyError> {
+    library
+        .update(|entries| entries.retain(|entry| entry.video_id != video))
+        .map_err(EepyError::Io)
+}
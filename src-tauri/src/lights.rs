@@ -0,0 +1,145 @@
+mod hue;
+mod lifx;
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::lights::{dim_level, FoundLight, HueSettings, LightSettings};
+use sleepy_video_shared::session::SessionEvent;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast;
+
+use crate::session::SessionState;
+
+/// Each step blends into the next one instead of jumping
+const STEP_TRANSITION_MS: u32 = 1000;
+const OFF_TRANSITION_MS: u32 = 3000;
+const DISCOVERY_WAIT: Duration = Duration::from_secs(2);
+
+/// The task following the session with the lights, if enabled
+#[derive(Default)]
+pub struct LightController(Mutex<Option<JoinHandle<()>>>);
+
+struct Lights {
+    client: reqwest::Client,
+    settings: LightSettings,
+}
+
+impl Lights {
+    async fn set(&self, (brightness, kelvin): (u8, u16), transition_ms: u32) {
+        if !self.settings.hue.lights.is_empty() {
+            hue::set(
+                &self.client,
+                &self.settings.hue,
+                brightness,
+                kelvin,
+                transition_ms,
+            )
+            .await;
+        }
+        lifx::set(
+            &self.settings.lifx.lights,
+            brightness,
+            kelvin,
+            transition_ms,
+        )
+        .await;
+    }
+
+    async fn off(&self) {
+        if !self.settings.hue.lights.is_empty() {
+            hue::off(&self.client, &self.settings.hue, OFF_TRANSITION_MS).await;
+        }
+        lifx::off(&self.settings.lifx.lights, OFF_TRANSITION_MS).await;
+    }
+}
+
+/// Stop following the session and start again with new settings
+pub fn restart(app: &AppHandle, settings: &LightSettings) {
+    let controller = app.state::<LightController>();
+    let mut running = controller.0.lock().unwrap();
+    if let Some(handle) = running.take() {
+        handle.abort();
+    }
+    if !settings.enabled || !settings.has_lights() {
+        return;
+    }
+
+    let lights = Lights {
+        client: reqwest::Client::new(),
+        settings: settings.clone(),
+    };
+    let events = app.state::<SessionState>().subscribe();
+    *running = Some(tauri::async_runtime::spawn(follow_session(lights, events)));
+}
+
+/// Take the lights down at the end of a session: off, or as low as the fade
+/// goes if they're set to stay on
+pub async fn at_end(settings: &LightSettings) {
+    if !settings.enabled || !settings.has_lights() {
+        return;
+    }
+    let lights = Lights {
+        client: reqwest::Client::new(),
+        settings: settings.clone(),
+    };
+    if settings.off_at_end {
+        lights.off().await;
+    } else {
+        lights.set(settings.at(1.0), OFF_TRANSITION_MS).await;
+    }
+}
+
+/// Dim the lights on the screen's curve and put them back when the session
+/// is cancelled. The end-of-session pipeline takes them down at the end.
+async fn follow_session(lights: Lights, mut events: broadcast::Receiver<SessionEvent>) {
+    // Only send when the rounded target changes, the bridge rate-limits
+    let mut last = None;
+    loop {
+        match events.recv().await {
+            Ok(SessionEvent::Status(status)) if status.running => {
+                let dim = dim_level(status.total_seconds, status.remaining_seconds);
+                let target = lights.settings.at(dim);
+                if last != Some(target) {
+                    lights.set(target, STEP_TRANSITION_MS).await;
+                    last = Some(target);
+                }
+            }
+            Ok(SessionEvent::Ended(_)) => last = None,
+            Ok(SessionEvent::Cancelled) => {
+                if last.is_some() {
+                    lights
+                        .set(lights.settings.at(0.0), STEP_TRANSITION_MS)
+                        .await;
+                }
+                last = None;
+            }
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+/// Pair with a Hue bridge after its link button was pressed
+#[tauri::command]
+pub async fn hue_pair(bridge: String) -> Result<String, EepyError> {
+    hue::pair(&reqwest::Client::new(), bridge.trim())
+        .await
+        .map_err(EepyError::Network)
+}
+
+#[tauri::command]
+pub async fn hue_lights(hue: HueSettings) -> Result<Vec<FoundLight>, EepyError> {
+    hue::lights(&reqwest::Client::new(), &hue)
+        .await
+        .map_err(EepyError::Network)
+}
+
+#[tauri::command]
+pub async fn lifx_discover() -> Result<Vec<FoundLight>, EepyError> {
+    lifx::discover(DISCOVERY_WAIT)
+        .await
+        .map_err(EepyError::Network)
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sleepy_video_shared::lights::{FoundLight, HueSettings};
+
+/// Hue's colour temperature range, in mireds
+const MIN_MIRED: u32 = 153;
+const MAX_MIRED: u32 = 500;
+
+#[derive(Deserialize)]
+struct BridgeLight {
+    name: String,
+}
+
+/// The bridge answers with a list of `{"success": ...}` or `{"error": ...}`
+fn check(reply: &Value) -> Result<(), String> {
+    let error = reply
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find_map(|item| item.pointer("/error/description"))
+        .and_then(Value::as_str);
+    match error {
+        Some(description) => Err(format!("Hue bridge: {}", description)),
+        None => Ok(()),
+    }
+}
+
+/// Create an API user. Only works within 30 seconds of pressing the link
+/// button on the bridge.
+pub async fn pair(client: &reqwest::Client, bridge_ip: &str) -> Result<String, String> {
+    let reply: Value = client
+        .post(format!("http://{}/api", bridge_ip))
+        .json(&json!({ "devicetype": "eepy#desktop" }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach the Hue bridge: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected Hue bridge reply: {}", e))?;
+    check(&reply)?;
+
+    reply
+        .pointer("/0/success/username")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| "Hue bridge didn't return a username".to_string())
+}
+
+pub async fn lights(
+    client: &reqwest::Client,
+    settings: &HueSettings,
+) -> Result<Vec<FoundLight>, String> {
+    let url = format!(
+        "http://{}/api/{}/lights",
+        settings.bridge_ip, settings.username
+    );
+    let reply: Value = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach the Hue bridge: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected Hue bridge reply: {}", e))?;
+    check(&reply)?;
+
+    let lights: HashMap<String, BridgeLight> =
+        serde_json::from_value(reply).map_err(|e| format!("Unexpected Hue bridge reply: {}", e))?;
+    let mut found: Vec<FoundLight> = lights
+        .into_iter()
+        .map(|(id, light)| FoundLight {
+            id,
+            name: light.name,
+        })
+        .collect();
+    found.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(found)
+}
+
+async fn set_state(client: &reqwest::Client, settings: &HueSettings, state: Value) {
+    for id in &settings.lights {
+        let url = format!(
+            "http://{}/api/{}/lights/{}/state",
+            settings.bridge_ip, settings.username, id
+        );
+        let result = client.put(url).json(&state).send().await;
+        if let Err(e) = result {
+            tracing::warn!("Failed to update Hue light {}: {}", id, e);
+        }
+    }
+}
+
+pub async fn set(
+    client: &reqwest::Client,
+    settings: &HueSettings,
+    brightness: u8,
+    kelvin: u16,
+    transition_ms: u32,
+) {
+    let bri = (brightness as u32 * 254 / 100).clamp(1, 254);
+    let ct = (1_000_000 / kelvin.max(1) as u32).clamp(MIN_MIRED, MAX_MIRED);
+    let state = json!({
+        "on": true,
+        "bri": bri,
+        "ct": ct,
+        // Hue counts transitions in tenths of a second
+        "transitiontime": transition_ms / 100,
+    });
+    set_state(client, settings, state).await;
+}
+
+pub async fn off(client: &reqwest::Client, settings: &HueSettings, transition_ms: u32) {
+    let state = json!({ "on": false, "transitiontime": transition_ms / 100 });
+    set_state(client, settings, state).await;
+}
@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use sleepy_video_shared::lights::FoundLight;
+use tokio::net::UdpSocket;
+
+const PORT: u16 = 56700;
+const HEADER_LEN: usize = 36;
+/// Identifies our packets to the bulbs; any non-zero value works
+const SOURCE: u32 = 0x6565_7079;
+
+const GET_LABEL: u16 = 23;
+const STATE_LABEL: u16 = 25;
+const SET_COLOR: u16 = 102;
+const SET_LIGHT_POWER: u16 = 117;
+
+/// Build a LIFX LAN packet addressed to every bulb that receives it
+fn packet(kind: u16, payload: &[u8]) -> Vec<u8> {
+    let size = (HEADER_LEN + payload.len()) as u16;
+    // Protocol 1024, addressable, tagged (all targets)
+    let protocol: u16 = 1024 | (1 << 12) | (1 << 13);
+
+    let mut buf = Vec::with_capacity(size as usize);
+    buf.extend_from_slice(&size.to_le_bytes());
+    buf.extend_from_slice(&protocol.to_le_bytes());
+    buf.extend_from_slice(&SOURCE.to_le_bytes());
+    // Frame address: target, reserved, flags, sequence
+    buf.extend_from_slice(&[0; 8]);
+    buf.extend_from_slice(&[0; 6]);
+    buf.extend_from_slice(&[0, 0]);
+    // Protocol header: reserved, type, reserved
+    buf.extend_from_slice(&[0; 8]);
+    buf.extend_from_slice(&kind.to_le_bytes());
+    buf.extend_from_slice(&[0; 2]);
+    buf.extend_from_slice(payload);
+    buf
+}
+
+async fn send_all(lights: &[String], message: &[u8]) {
+    let socket = match UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            tracing::warn!("Failed to open LIFX socket: {}", e);
+            return;
+        }
+    };
+    for ip in lights {
+        let Ok(ip) = ip.parse::<Ipv4Addr>() else {
+            tracing::warn!("Invalid LIFX bulb address: {}", ip);
+            continue;
+        };
+        if let Err(e) = socket.send_to(message, (ip, PORT)).await {
+            tracing::warn!("Failed to update LIFX bulb {}: {}", ip, e);
+        }
+    }
+}
+
+pub async fn set(lights: &[String], brightness: u8, kelvin: u16, transition_ms: u32) {
+    let level = (brightness.min(100) as u32 * 65535 / 100) as u16;
+    let mut payload = vec![0];
+    // Hue and saturation at zero give plain white light
+    payload.extend_from_slice(&0u16.to_le_bytes());
+    payload.extend_from_slice(&0u16.to_le_bytes());
+    payload.extend_from_slice(&level.to_le_bytes());
+    payload.extend_from_slice(&kelvin.clamp(1500, 9000).to_le_bytes());
+    payload.extend_from_slice(&transition_ms.to_le_bytes());
+    send_all(lights, &packet(SET_COLOR, &payload)).await;
+
+    // SetColor doesn't turn a bulb on, so make sure it is
+    let mut power = 65535u16.to_le_bytes().to_vec();
+    power.extend_from_slice(&0u32.to_le_bytes());
+    send_all(lights, &packet(SET_LIGHT_POWER, &power)).await;
+}
+
+pub async fn off(lights: &[String], transition_ms: u32) {
+    let mut payload = 0u16.to_le_bytes().to_vec();
+    payload.extend_from_slice(&transition_ms.to_le_bytes());
+    send_all(lights, &packet(SET_LIGHT_POWER, &payload)).await;
+}
+
+/// Broadcast a label request and collect the bulbs that answer in time
+pub async fn discover(wait: Duration) -> Result<Vec<FoundLight>, String> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .await
+        .map_err(|e| format!("Failed to open LIFX socket: {}", e))?;
+    socket
+        .set_broadcast(true)
+        .map_err(|e| format!("Failed to enable broadcast: {}", e))?;
+    socket
+        .send_to(&packet(GET_LABEL, &[]), (Ipv4Addr::BROADCAST, PORT))
+        .await
+        .map_err(|e| format!("Failed to search for LIFX bulbs: {}", e))?;
+
+    // Keyed by address so bulbs answering twice only show once
+    let mut found = BTreeMap::new();
+    let mut buf = [0u8; 128];
+    let deadline = tokio::time::Instant::now() + wait;
+    while let Ok(Ok((len, from))) =
+        tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await
+    {
+        if let Some(label) = parse_label(&buf[..len]) {
+            let SocketAddr::V4(from) = from else { continue };
+            found.insert(from.ip().to_string(), label);
+        }
+    }
+
+    Ok(found
+        .into_iter()
+        .map(|(id, name)| FoundLight { id, name })
+        .collect())
+}
+
+fn parse_label(reply: &[u8]) -> Option<String> {
+    if reply.len() < HEADER_LEN + 32 {
+        return None;
+    }
+    let kind = u16::from_le_bytes([reply[32], reply[33]]);
+    if kind != STATE_LABEL {
+        return None;
+    }
+    let label = &reply[HEADER_LEN..HEADER_LEN + 32];
+    let end = label.iter().position(|b| *b == 0).unwrap_or(label.len());
+    Some(String::from_utf8_lossy(&label[..end]).to_string())
+}
@@ -0,0 +1,131 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use sleepy_video_shared::error::EepyError;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter};
+
+use crate::power::Power;
+use crate::settings::SettingsStore;
+
+const LOG_PREFIX: &str = "eepy";
+const LOG_SUFFIX: &str = "log";
+/// One file a day, a week's worth
+const KEPT_FILES: usize = 7;
+/// Lines shown in the log viewer
+const VIEWER_LINES: usize = 500;
+/// Lines of log that go with the diagnostics
+const DIAGNOSTICS_LINES: usize = 200;
+/// Used when `RUST_LOG` isn't set
+const DEFAULT_FILTER: &str = "info";
+
+/// Where the logs go. Holding it keeps the file writer flushing.
+pub struct Logs {
+    dir: PathBuf,
+    _guard: Option<WorkerGuard>,
+}
+
+/// Log to stderr and to a daily file in the app log directory. Logging to
+/// the file is skipped if the directory can't be used.
+pub fn init(app: &AppHandle) -> Logs {
+    let dir = app
+        .path()
+        .app_log_dir()
+        .unwrap_or_else(|_| PathBuf::from("logs"));
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_PREFIX)
+        .filename_suffix(LOG_SUFFIX)
+        .max_log_files(KEPT_FILES)
+        .build(&dir);
+    let (file, guard) = match appender {
+        Ok(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            (
+                Some(fmt::layer().with_ansi(false).with_writer(writer)),
+                Some(guard),
+            )
+        }
+        Err(e) => {
+            eprintln!("Failed to open the log file in {}: {}", dir.display(), e);
+            (None, None)
+        }
+    };
+
+    let _ = tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .with(file)
+        .try_init();
+
+    Logs { dir, _guard: guard }
+}
+
+/// The last `count` lines of the newest log file
+fn tail(dir: &Path, count: usize) -> Vec<String> {
+    let newest = fs::read_dir(dir).ok().and_then(|entries| {
+        entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with(LOG_PREFIX))
+            })
+            // Names carry the date, so the last one is the newest
+            .max()
+    });
+    let Some(raw) = newest.and_then(|path| fs::read_to_string(path).ok()) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = raw.lines().collect();
+    lines[lines.len().saturating_sub(count)..]
+        .iter()
+        .map(|line| line.to_string())
+        .collect()
+}
+
+#[tauri::command]
+pub fn log_tail(logs: State<Logs>) -> Vec<String> {
+    tail(&logs.dir, VIEWER_LINES)
+}
+
+/// Put what a bug report needs on the clipboard: versions, platform, what
+/// the end of a session is set to do, and the recent log
+#[tauri::command]
+pub fn copy_diagnostics(
+    app: AppHandle,
+    logs: State<Logs>,
+    store: State<SettingsStore>,
+    power: State<Power>,
+) -> Result<(), EepyError> {
+    let settings = store.get();
+    let stages: Vec<&str> = settings
+        .pipeline
+        .stages
+        .iter()
+        .filter(|step| step.enabled)
+        .map(|step| step.stage.label())
+        .collect();
+
+    let mut report = vec![
+        format!("eepy {}", app.package_info().version),
+        format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        format!("End action: {}", settings.end_action.as_str()),
+        format!("Supported: {:?}", power.capabilities()),
+        format!("Stages: {}", stages.join(", ")),
+        format!("Logs: {}", logs.dir.display()),
+        String::new(),
+    ];
+    report.extend(tail(&logs.dir, DIAGNOSTICS_LINES));
+
+    app.clipboard()
+        .write_text(report.join("\n"))
+        .map_err(|e| EepyError::Other(format!("Failed to copy diagnostics: {}", e)))
+}
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use sleepy_video_shared::error::EepyError;
+use tauri::State;
+
+use crate::settings::SettingsStore;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+/// How YouTube's player response reports a video's loudness against its
+/// reference level
+const LOUDNESS_KEY: &str = "\"loudnessDb\":";
+
+/// Loudness of YouTube videos in dB, cached per video
+pub struct Loudness {
+    client: reqwest::Client,
+    cache: Mutex<HashMap<String, f64>>,
+}
+
+impl Default for Loudness {
+    fn default() -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+/// First number after `"loudnessDb":` in the watch page
+fn parse_loudness(page: &str) -> Option<f64> {
+    let start = page.find(LOUDNESS_KEY)? + LOUDNESS_KEY.len();
+    let rest = &page[start..];
+    let end = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == '-' || c == '.'))
+        .unwrap_or(rest.len());
+    rest[..end].parse().ok()
+}
+
+/// A video's loudness from the player response embedded in its watch page.
+/// `None` when normalization is off or the page doesn't say.
+#[tauri::command]
+pub async fn video_loudness(
+    loudness: State<'_, Loudness>,
+    store: State<'_, SettingsStore>,
+    video: String,
+) -> Result<Option<f64>, EepyError> {
+    if !store.get().playback.normalize {
+        return Ok(None);
+    }
+    if let Some(db) = loudness.cache.lock().unwrap().get(&video) {
+        return Ok(Some(*db));
+    }
+
+    let page = loudness
+        .client
+        .get(format!("https://www.youtube.com/watch?v={}", video))
+        // Skips the cookie consent page in the EU
+        .header("Cookie", "SOCS=CAI")
+        .send()
+        .await
+        .and_then(|res| res.error_for_status())
+        .map_err(|e| EepyError::Network(format!("Failed to load the video page: {}", e)))?
+        .text()
+        .await
+        .map_err(|e| EepyError::Network(format!("Failed to read the video page: {}", e)))?;
+
+    let db = parse_loudness(&page);
+    if let Some(db) = db {
+        loudness.cache.lock().unwrap().insert(video, db);
+    }
+    Ok(db)
+}
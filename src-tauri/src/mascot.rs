@@ -0,0 +1,59 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::mascot::{Mascot, MAX_SPRITE_BYTES};
+use tauri::{AppHandle, Manager, State};
+
+use crate::settings::SettingsStore;
+
+fn mime_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    Some(match extension.as_str() {
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "jpg" | "jpeg" => "image/jpeg",
+        _ => return None,
+    })
+}
+
+/// The custom mascot inlined as a data URL, so the webview can show a file
+/// from anywhere. Nothing when another mascot is picked.
+#[tauri::command]
+pub fn mascot_sprite(
+    app: AppHandle,
+    store: State<SettingsStore>,
+) -> Result<Option<String>, EepyError> {
+    let settings = store.get().mascot;
+    let sprite = settings.sprite_path.trim();
+    if settings.mascot != Mascot::Custom || sprite.is_empty() {
+        return Ok(None);
+    }
+    let path = app
+        .path()
+        .app_config_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join(sprite);
+    let mime = mime_type(&path).ok_or_else(|| {
+        EepyError::Invalid("The mascot has to be a PNG, GIF, WebP, SVG or JPEG".to_string())
+    })?;
+    let size = fs::metadata(&path)
+        .map_err(|e| EepyError::io("read the mascot", e))?
+        .len();
+    if size > MAX_SPRITE_BYTES {
+        return Err(EepyError::Invalid(format!(
+            "The mascot is {} KB, over the {} KB limit",
+            size / 1024,
+            MAX_SPRITE_BYTES / 1024
+        )));
+    }
+    let bytes = fs::read(&path).map_err(|e| EepyError::io("read the mascot", e))?;
+    Ok(Some(format!(
+        "data:{};base64,{}",
+        mime,
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    )))
+}
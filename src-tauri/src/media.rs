@@ -0,0 +1,193 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sleepy_video_shared::media::{MediaServer, MediaServerKind, MediaServerSettings};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+/// Sent to Plex so it knows who is controlling its players
+const PLEX_CLIENT_ID: &str = "eepy";
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PlexSessions {
+    media_container: PlexContainer,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PlexContainer {
+    #[serde(default)]
+    metadata: Vec<PlexItem>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PlexItem {
+    player: PlexPlayer,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlexPlayer {
+    machine_identifier: String,
+    state: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct JellyfinSession {
+    id: String,
+    now_playing_item: Option<Value>,
+    play_state: Option<JellyfinPlayState>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct JellyfinPlayState {
+    #[serde(default)]
+    is_paused: bool,
+}
+
+#[derive(Deserialize)]
+struct KodiPlayer {
+    playerid: i64,
+}
+
+fn request_error(server: &MediaServer, e: reqwest::Error) -> String {
+    format!(
+        "Failed to reach {} at {}: {}",
+        server.kind.label(),
+        server.url,
+        e
+    )
+}
+
+async fn pause_plex(client: &reqwest::Client, server: &MediaServer) -> Result<usize, String> {
+    let base = server.url.trim_end_matches('/');
+    let sessions: PlexSessions = client
+        .get(format!("{}/status/sessions", base))
+        .header("Accept", "application/json")
+        .header("X-Plex-Token", &server.token)
+        .send()
+        .await
+        .and_then(|res| res.error_for_status())
+        .map_err(|e| request_error(server, e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected Plex reply: {}", e))?;
+
+    let mut paused = 0;
+    for item in sessions.media_container.metadata {
+        if item.player.state != "playing" {
+            continue;
+        }
+        // The server relays player commands to the client it names
+        client
+            .get(format!("{}/player/playback/pause", base))
+            .query(&[("type", "video"), ("commandID", "1")])
+            .header("X-Plex-Token", &server.token)
+            .header("X-Plex-Client-Identifier", PLEX_CLIENT_ID)
+            .header(
+                "X-Plex-Target-Client-Identifier",
+                &item.player.machine_identifier,
+            )
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|e| request_error(server, e))?;
+        paused += 1;
+    }
+    Ok(paused)
+}
+
+async fn pause_jellyfin(client: &reqwest::Client, server: &MediaServer) -> Result<usize, String> {
+    let base = server.url.trim_end_matches('/');
+    let sessions: Vec<JellyfinSession> = client
+        .get(format!("{}/Sessions", base))
+        .header("X-Emby-Token", &server.token)
+        .send()
+        .await
+        .and_then(|res| res.error_for_status())
+        .map_err(|e| request_error(server, e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected Jellyfin reply: {}", e))?;
+
+    let mut paused = 0;
+    for session in sessions {
+        let playing = session.now_playing_item.is_some()
+            && !session.play_state.is_some_and(|state| state.is_paused);
+        if !playing {
+            continue;
+        }
+        client
+            .post(format!("{}/Sessions/{}/Playing/Pause", base, session.id))
+            .header("X-Emby-Token", &server.token)
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|e| request_error(server, e))?;
+        paused += 1;
+    }
+    Ok(paused)
+}
+
+async fn kodi_call(
+    client: &reqwest::Client,
+    server: &MediaServer,
+    method: &str,
+    params: Value,
+) -> Result<Value, String> {
+    let reply: Value = client
+        .post(format!("{}/jsonrpc", server.url.trim_end_matches('/')))
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+        .send()
+        .await
+        .and_then(|res| res.error_for_status())
+        .map_err(|e| request_error(server, e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected Kodi reply: {}", e))?;
+
+    if let Some(error) = reply.get("error") {
+        return Err(format!("Kodi {} failed: {}", method, error));
+    }
+    Ok(reply.get("result").cloned().unwrap_or(Value::Null))
+}
+
+async fn pause_kodi(client: &reqwest::Client, server: &MediaServer) -> Result<usize, String> {
+    let active = kodi_call(client, server, "Player.GetActivePlayers", json!({})).await?;
+    let players: Vec<KodiPlayer> =
+        serde_json::from_value(active).map_err(|e| format!("Unexpected Kodi reply: {}", e))?;
+
+    for player in &players {
+        // `play: false` pauses without toggling an already paused player back on
+        let params = json!({ "playerid": player.playerid, "play": false });
+        kodi_call(client, server, "Player.PlayPause", params).await?;
+    }
+    Ok(players.len())
+}
+
+/// Pause whatever is playing on every enabled server, logging failures so
+/// one unreachable server doesn't stop the others
+pub async fn pause_all(settings: &MediaServerSettings) {
+    if !settings.enabled {
+        return;
+    }
+    let client = reqwest::Client::builder()
+        .timeout(TIMEOUT)
+        .build()
+        .unwrap_or_default();
+
+    for server in settings.servers.iter().filter(|s| s.enabled) {
+        let result = match server.kind {
+            MediaServerKind::Plex => pause_plex(&client, server).await,
+            MediaServerKind::Jellyfin => pause_jellyfin(&client, server).await,
+            MediaServerKind::Kodi => pause_kodi(&client, server).await,
+        };
+        if let Err(e) = result {
+            tracing::warn!("{}", e);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use sleepy_video_shared::control::ControlCommand;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Wry};
+use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::control;
+
+/// Seconds the next and previous keys skip, like the transport bar buttons
+const SKIP_SECONDS: i32 = 30;
+
+const KEYS: [Code; 4] = [
+    Code::MediaPlayPause,
+    Code::MediaStop,
+    Code::MediaTrackNext,
+    Code::MediaTrackPrevious,
+];
+
+fn command_for(key: Code) -> Option<ControlCommand> {
+    match key {
+        Code::MediaPlayPause => Some(ControlCommand::TogglePlay),
+        Code::MediaStop => Some(ControlCommand::Pause),
+        Code::MediaTrackNext => Some(ControlCommand::Seek {
+            seconds: SKIP_SECONDS,
+        }),
+        Code::MediaTrackPrevious => Some(ControlCommand::Seek {
+            seconds: -SKIP_SECONDS,
+        }),
+        _ => None,
+    }
+}
+
+/// Global shortcut plugin that forwards media key presses to the player
+pub fn plugin() -> TauriPlugin<Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, shortcut, event| {
+            if event.state() != ShortcutState::Pressed {
+                return;
+            }
+            if let Some(command) = command_for(shortcut.key) {
+                control::send(app, command);
+            }
+        })
+        .build()
+}
+
+/// Take or give back the media keys. While registered, other players don't
+/// get them, so this is opt-in.
+pub fn restart(app: &AppHandle, enabled: bool) {
+    let shortcuts = app.global_shortcut();
+    for key in KEYS {
+        let shortcut = Shortcut::new(None, key);
+        let registered = shortcuts.is_registered(shortcut);
+        let result = if enabled && !registered {
+            shortcuts.register(shortcut)
+        } else if !enabled && registered {
+            shortcuts.unregister(shortcut)
+        } else {
+            continue;
+        };
+        if let Err(e) = result {
+            tracing::warn!("Failed to update media key {:?}: {}", key, e);
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use sleepy_video_shared::error::EepyError;
+use tauri::AppHandle;
+#[cfg(desktop)]
+use tauri::{Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::window;
+
+/// Window label of the mini player; the frontend picks its view from the URL
+pub const MINI_LABEL: &str = "mini";
+#[cfg(desktop)]
+const MINI_URL: &str = "index.html?view=mini";
+
+/// Open the always-on-top mini player, or bring it forward if it's open
+#[cfg(desktop)]
+#[tauri::command]
+pub fn open_mini_player(app: AppHandle) -> Result<(), EepyError> {
+    if let Some(window) = app.get_webview_window(MINI_LABEL) {
+        return window
+            .set_focus()
+            .map_err(|e| EepyError::Other(format!("Failed to focus the mini player: {}", e)));
+    }
+    WebviewWindowBuilder::new(&app, MINI_LABEL, WebviewUrl::App(MINI_URL.into()))
+        .title("eepy")
+        .inner_size(300.0, 260.0)
+        .min_inner_size(220.0, 200.0)
+        .always_on_top(true)
+        .build()
+        .map_err(|e| EepyError::Other(format!("Failed to open the mini player: {}", e)))?;
+    Ok(())
+}
+
+/// Phones and tablets have only the one window
+#[cfg(mobile)]
+#[tauri::command]
+pub fn open_mini_player(_app: AppHandle) -> Result<(), EepyError> {
+    Err(EepyError::UnsupportedPlatform(
+        "The mini player needs a desktop".to_string(),
+    ))
+}
+
+/// The mini player's way back to the full window
+#[tauri::command]
+pub fn show_main_window(app: AppHandle) {
+    window::show_main_window(&app);
+}
@@ -0,0 +1,114 @@
+use std::sync::Mutex;
+
+use sleepy_video_shared::pipeline::{Stage, StageOutcome, StageReport};
+use sleepy_video_shared::power::EndAction;
+use sleepy_video_shared::session::SessionEvent;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager, Monitor, WebviewUrl, WebviewWindowBuilder};
+use tokio::sync::broadcast;
+
+use crate::session::SessionState;
+
+/// Overlay windows are labelled with this and their monitor's number
+const LABEL_PREFIX: &str = "dim-";
+const DIM_URL: &str = "index.html?view=dim";
+
+/// Whether `label` is one of the overlay windows
+pub fn is_overlay(label: &str) -> bool {
+    label.starts_with(LABEL_PREFIX)
+}
+
+/// The task opening a black overlay on the other monitors for each session
+#[derive(Default)]
+pub struct MonitorDim(Mutex<Option<JoinHandle<()>>>);
+
+/// Stop following the session, close the overlays and start again if enabled
+pub fn restart(app: &AppHandle, enabled: bool) {
+    let monitor_dim = app.state::<MonitorDim>();
+    let mut running = monitor_dim.0.lock().unwrap();
+    if let Some(handle) = running.take() {
+        handle.abort();
+    }
+    close(app);
+    if !enabled {
+        return;
+    }
+
+    let events = app.state::<SessionState>().subscribe();
+    *running = Some(tauri::async_runtime::spawn(follow_session(
+        app.clone(),
+        events,
+    )));
+}
+
+/// Monitors other than the main window's, which has its own overlay
+fn other_monitors(app: &AppHandle) -> tauri::Result<Vec<Monitor>> {
+    let Some(main) = app.get_webview_window("main") else {
+        return Ok(Vec::new());
+    };
+    let current = main.current_monitor()?.map(|m| *m.position());
+    Ok(main
+        .available_monitors()?
+        .into_iter()
+        .filter(|monitor| Some(*monitor.position()) != current)
+        .collect())
+}
+
+/// Cover each of the other monitors with a click-through window, which
+/// darkens itself from the session ticks
+fn open(app: &AppHandle) -> tauri::Result<()> {
+    for (i, monitor) in other_monitors(app)?.into_iter().enumerate() {
+        let label = format!("{}{}", LABEL_PREFIX, i);
+        if app.get_webview_window(&label).is_some() {
+            continue;
+        }
+        let window = WebviewWindowBuilder::new(app, label, WebviewUrl::App(DIM_URL.into()))
+            .title("eepy dim")
+            .decorations(false)
+            .transparent(true)
+            .shadow(false)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .resizable(false)
+            .focused(false)
+            .visible(false)
+            .build()?;
+        window.set_position(*monitor.position())?;
+        window.set_size(*monitor.size())?;
+        window.set_ignore_cursor_events(true)?;
+        window.show()?;
+    }
+    Ok(())
+}
+
+fn close(app: &AppHandle) {
+    for (label, window) in app.webview_windows() {
+        if is_overlay(&label) {
+            let _ = window.close();
+        }
+    }
+}
+
+/// Open the overlays when a session starts. Close them when it's cancelled,
+/// or once the end action returns.
+async fn follow_session(app: AppHandle, mut events: broadcast::Receiver<SessionEvent>) {
+    loop {
+        match events.recv().await {
+            Ok(SessionEvent::Started(_)) => {
+                if let Err(e) = open(&app) {
+                    tracing::warn!("Failed to dim the other monitors: {}", e);
+                }
+            }
+            Ok(
+                SessionEvent::Stage(StageReport {
+                    stage: Stage::EndAction,
+                    outcome: StageOutcome::Done | StageOutcome::Failed(_),
+                })
+                | SessionEvent::Ended(EndAction::Nothing)
+                | SessionEvent::Cancelled,
+            ) => close(&app),
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
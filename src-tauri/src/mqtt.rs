@@ -0,0 +1,130 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use rumqttc::{AsyncClient, Event, EventLoop, LastWill, MqttOptions, Packet, QoS};
+use sleepy_video_shared::mqtt::MqttSettings;
+use sleepy_video_shared::session::{SessionEvent, SessionStatus};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast;
+
+use crate::session::SessionState;
+
+/// Wait between reconnect attempts when the broker is unreachable
+const RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// The running MQTT publisher, if enabled
+#[derive(Default)]
+pub struct MqttBridge(Mutex<Option<JoinHandle<()>>>);
+
+/// Stop any running publisher and start a new one if MQTT is enabled
+pub fn restart(app: &AppHandle, settings: &MqttSettings) {
+    let bridge = app.state::<MqttBridge>();
+    let mut running = bridge.0.lock().unwrap();
+    if let Some(handle) = running.take() {
+        handle.abort();
+    }
+    if !settings.enabled || settings.host.trim().is_empty() {
+        return;
+    }
+
+    let mut options = MqttOptions::new(&settings.client_id, settings.host.trim(), settings.port);
+    options.set_keep_alive(Duration::from_secs(30));
+    options.set_last_will(LastWill::new(
+        &settings.state_topic,
+        "offline",
+        QoS::AtLeastOnce,
+        true,
+    ));
+    if !settings.username.is_empty() {
+        options.set_credentials(&settings.username, &settings.password);
+    }
+    let (client, event_loop) = AsyncClient::new(options, 16);
+
+    let session = app.state::<SessionState>();
+    let initial = session.status();
+    let events = session.subscribe();
+    let settings = settings.clone();
+    *running = Some(tauri::async_runtime::spawn(publish_session(
+        client, event_loop, settings, initial, events,
+    )));
+}
+
+/// Keep the broker connection alive and mirror every session event to it
+async fn publish_session(
+    client: AsyncClient,
+    mut event_loop: EventLoop,
+    settings: MqttSettings,
+    initial: SessionStatus,
+    mut events: broadcast::Receiver<SessionEvent>,
+) {
+    let publisher = Publisher { client, settings };
+    let mut last = initial;
+
+    loop {
+        tokio::select! {
+            polled = event_loop.poll() => match polled {
+                // Also overwrites the retained "offline" will after a reconnect
+                Ok(Event::Incoming(Packet::ConnAck(_))) => publisher.status(&last, None),
+                Ok(_) => {}
+                // rumqttc reconnects on the next poll; don't spin while the broker is down
+                Err(e) => {
+                    tracing::warn!("MQTT connection error: {}", e);
+                    tokio::time::sleep(RETRY_DELAY).await;
+                }
+            },
+            received = events.recv() => match received {
+                Ok(SessionEvent::Status(status)) => {
+                    publisher.status(&status, Some(&last));
+                    last = status;
+                }
+                Ok(event) => publisher.event(&event),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+        }
+    }
+}
+
+struct Publisher {
+    client: AsyncClient,
+    settings: MqttSettings,
+}
+
+impl Publisher {
+    /// Queue a message without waiting, so a slow broker can't stall the
+    /// event loop that drains the queue
+    fn send(&self, topic: &str, retain: bool, payload: String) {
+        if topic.is_empty() {
+            return;
+        }
+        if let Err(e) = self
+            .client
+            .try_publish(topic, QoS::AtLeastOnce, retain, payload)
+        {
+            tracing::warn!("Failed to publish to {}: {}", topic, e);
+        }
+    }
+
+    /// Publish whatever changed since `previous`
+    fn status(&self, status: &SessionStatus, previous: Option<&SessionStatus>) {
+        if previous.map(|p| p.running) != Some(status.running) {
+            let state = if status.running { "running" } else { "idle" };
+            self.send(&self.settings.state_topic, true, state.to_string());
+        }
+        if previous.map(|p| p.remaining_seconds) != Some(status.remaining_seconds) {
+            self.send(
+                &self.settings.remaining_topic,
+                true,
+                status.remaining_seconds.to_string(),
+            );
+        }
+    }
+
+    fn event(&self, event: &SessionEvent) {
+        match serde_json::to_string(event) {
+            Ok(json) => self.send(&self.settings.event_topic, false, json),
+            Err(e) => tracing::warn!("Failed to serialize session event: {}", e),
+        }
+    }
+}
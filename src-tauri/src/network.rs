@@ -0,0 +1,42 @@
+use sleepy_video_shared::error::EepyError;
+
+#[cfg(target_os = "windows")]
+use crate::focus::run;
+
+/// `NetworkCostType` of the connection to the internet: Unrestricted,
+/// Fixed, Variable or Unknown
+#[cfg(target_os = "windows")]
+const COST_SCRIPT: &str = "[Windows.Networking.Connectivity.NetworkInformation,Windows.Networking.Connectivity,ContentType=WindowsRuntime] | Out-Null
+$profile = [Windows.Networking.Connectivity.NetworkInformation]::GetInternetConnectionProfile()
+if ($profile) { $profile.GetConnectionCost().NetworkCostType } else { 'Unknown' }";
+
+/// Whether the connection is metered, like a phone's hotspot. Blocks on
+/// D-Bus or PowerShell.
+pub fn metered() -> Result<bool, EepyError> {
+    #[cfg(target_os = "linux")]
+    {
+        Ok(crate::portal::metered()?)
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let cost = run(
+            "powershell",
+            &["-NoProfile", "-NonInteractive", "-Command", COST_SCRIPT],
+        )?;
+        Ok(matches!(cost.trim(), "Fixed" | "Variable"))
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    {
+        Err(EepyError::UnsupportedPlatform(
+            "Metered connections can't be told apart here".to_string(),
+        ))
+    }
+}
+
+/// Whether the connection is metered, so playback should save data
+#[tauri::command]
+pub async fn network_metered() -> Result<bool, EepyError> {
+    tauri::async_runtime::spawn_blocking(metered)
+        .await
+        .map_err(|e| EepyError::Other(e.to_string()))?
+}
@@ -0,0 +1,28 @@
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::settings::Settings;
+use tauri::AppHandle;
+use tauri_plugin_notification::{NotificationExt, PermissionState};
+
+/// Show a native notification from eepy
+pub fn notify(app: &AppHandle, body: &str) {
+    if let Err(e) = app.notification().builder().title("eepy").body(body).show() {
+        tracing::warn!("Failed to show notification: {}", e);
+    }
+}
+
+/// Announce a milestone, as `message` words it in the UI's language
+pub fn on_milestone(app: &AppHandle, settings: &Settings, message: &str) {
+    if settings.notifications.enabled {
+        notify(app, &format!("{} 🐳", message));
+    }
+}
+
+/// Ask the OS to let eepy show notifications. Returns whether it may.
+#[tauri::command]
+pub fn request_notifications(app: AppHandle) -> Result<bool, EepyError> {
+    let state = app
+        .notification()
+        .request_permission()
+        .map_err(|e| EepyError::Other(format!("Failed to ask for notifications: {}", e)))?;
+    Ok(state == PermissionState::Granted)
+}
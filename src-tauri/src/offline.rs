@@ -0,0 +1,454 @@
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::offline::{CachedAudio, OfflineCache};
+use tauri::http::header::{ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, RANGE};
+use tauri::http::{Request, Response, StatusCode};
+use tauri::{AppHandle, Manager, State};
+use tokio::process::Command;
+
+use crate::extract;
+use crate::library::Library;
+use crate::settings::SettingsStore;
+use crate::video::valid_id;
+
+const DIR: &str = "audio";
+/// A long video on a slow connection takes a while
+const TIMEOUT: Duration = Duration::from_secs(15 * 60);
+/// Left behind by yt-dlp while it's still downloading, some with a fragment
+/// number after them
+const PARTIAL: [&str; 2] = ["part", "ytdl"];
+/// Most one range request gets back; the player asks for the rest as it goes
+const CHUNK: u64 = 1024 * 1024;
+
+/// Audio of favorites downloaded ahead of time, one file per video named
+/// after its ID. A file's modified time is when it last played, so the
+/// longest unplayed are evicted first.
+pub struct OfflineAudio {
+    dir: PathBuf,
+    downloading: Mutex<HashSet<String>>,
+}
+
+struct CachedFile {
+    path: PathBuf,
+    video_id: String,
+    bytes: u64,
+    played: SystemTime,
+}
+
+impl OfflineAudio {
+    pub fn load(app: &AppHandle) -> Self {
+        let dir = app
+            .path()
+            .app_cache_dir()
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .join(DIR);
+        Self {
+            dir,
+            downloading: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Finished downloads, most recently played first. Only `<id>.<ext>`
+    /// counts, not yt-dlp's `<id>.<ext>.part` and the like.
+    fn files(&self) -> Vec<CachedFile> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut files: Vec<CachedFile> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                let extension = path.extension()?.to_str()?;
+                let video_id = path.file_stem()?.to_str()?;
+                if !valid_id(video_id) || PARTIAL.iter().any(|p| extension.starts_with(p)) {
+                    return None;
+                }
+                let metadata = entry.metadata().ok()?;
+                Some(CachedFile {
+                    video_id: video_id.to_string(),
+                    bytes: metadata.len(),
+                    played: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                    path,
+                })
+            })
+            .collect();
+        files.sort_by(|a, b| b.played.cmp(&a.played));
+        files
+    }
+
+    fn find(&self, video: &str) -> Option<PathBuf> {
+        self.files()
+            .into_iter()
+            .find(|file| file.video_id == video)
+            .map(|file| file.path)
+    }
+
+    fn report(&self) -> OfflineCache {
+        OfflineCache {
+            videos: self
+                .files()
+                .into_iter()
+                .map(|file| CachedAudio {
+                    video_id: file.video_id,
+                    bytes: file.bytes,
+                })
+                .collect(),
+            downloading: self.downloading.lock().unwrap().iter().cloned().collect(),
+        }
+    }
+
+    /// Delete the longest unplayed files until the cache fits in `max_mb`,
+    /// never `keep`
+    pub fn evict(&self, max_mb: u32, keep: Option<&str>) {
+        let files = self.files();
+        for file in over_limit(&files, max_mb, keep) {
+            if let Err(e) = fs::remove_file(&file.path) {
+                tracing::warn!("Failed to evict {}: {}", file.path.display(), e);
+                break;
+            }
+        }
+    }
+
+    fn remove(&self, video: &str) -> Result<(), EepyError> {
+        for file in self
+            .files()
+            .into_iter()
+            .filter(|file| file.video_id == video)
+        {
+            fs::remove_file(&file.path).map_err(|e| EepyError::io("delete the audio", e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Which of `files`, most recently played first, have to go for the rest to
+/// fit in `max_mb`: the longest unplayed, never `keep`
+fn over_limit<'a>(files: &'a [CachedFile], max_mb: u32, keep: Option<&str>) -> Vec<&'a CachedFile> {
+    let max_bytes = max_mb as u64 * 1024 * 1024;
+    let mut total: u64 = files.iter().map(|file| file.bytes).sum();
+    files
+        .iter()
+        .rev()
+        .filter(|file| Some(file.video_id.as_str()) != keep)
+        .take_while(|file| {
+            let over = total > max_bytes;
+            if over {
+                total -= file.bytes;
+            }
+            over
+        })
+        .collect()
+}
+
+/// Download a video's audio in the background, unless it's there or on its
+/// way already
+pub fn spawn_download(app: &AppHandle, video: String) {
+    let offline = app.state::<OfflineAudio>();
+    if !valid_id(&video)
+        || offline.find(&video).is_some()
+        || !offline.downloading.lock().unwrap().insert(video.clone())
+    {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = download(&app, &video).await;
+        let offline = app.state::<OfflineAudio>();
+        offline.downloading.lock().unwrap().remove(&video);
+        match result {
+            Ok(()) => {
+                let max_mb = app.state::<SettingsStore>().get().offline.max_mb;
+                offline.evict(max_mb, Some(&video));
+            }
+            Err(e) => tracing::warn!("Failed to download {} for offline: {}", video, e),
+        }
+    });
+}
+
+async fn download(app: &AppHandle, video: &str) -> Result<(), EepyError> {
+    let offline = app.state::<OfflineAudio>();
+    fs::create_dir_all(&offline.dir).map_err(|e| EepyError::io("create the cache dir", e))?;
+    let program = extract::program(&app.state::<SettingsStore>().get().playback);
+
+    let run = Command::new(&program)
+        .args(["--no-playlist", "--no-warnings", "-f", "bestaudio", "-o"])
+        .arg(offline.dir.join(format!("{}.%(ext)s", video)))
+        .arg(format!("https://www.youtube.com/watch?v={}", video))
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(TIMEOUT, run)
+        .await
+        .map_err(|_| EepyError::CommandFailed(format!("{} took too long", program)))?
+        .map_err(|e| EepyError::spawn(&program, e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(EepyError::CommandFailed(format!(
+            "{} failed: {}",
+            program,
+            stderr.lines().last().unwrap_or("unknown error").trim()
+        )));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn offline_list(offline: State<OfflineAudio>) -> OfflineCache {
+    offline.report()
+}
+
+/// Mark a video's downloaded audio as just played, before the player loads
+/// it from the offline audio scheme. Fails if it isn't downloaded, for the
+/// player to stream it instead.
+#[tauri::command]
+pub fn offline_audio(offline: State<OfflineAudio>, video: String) -> Result<(), EepyError> {
+    let path = offline
+        .find(&video)
+        .ok_or_else(|| EepyError::Invalid(format!("{} isn't downloaded", video)))?;
+    if let Err(e) = File::options()
+        .append(true)
+        .open(&path)
+        .and_then(|file| file.set_modified(SystemTime::now()))
+    {
+        tracing::warn!("Failed to mark {} as played: {}", path.display(), e);
+    }
+    Ok(())
+}
+
+/// Answer the offline audio scheme: the file of the video ID in the path,
+/// a range at a time so the player can seek without reading it all
+pub fn serve(app: &AppHandle, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let video = request.uri().path().trim_start_matches('/');
+    let path = valid_id(video)
+        .then(|| app.state::<OfflineAudio>().find(video))
+        .flatten();
+    let range = request
+        .headers()
+        .get(RANGE)
+        .and_then(|range| range.to_str().ok());
+    let response = match path {
+        Some(path) => respond(&path, range),
+        None => Ok(status(StatusCode::NOT_FOUND)),
+    };
+    response.unwrap_or_else(|e| {
+        tracing::warn!("Failed to serve the offline audio of {}: {}", video, e);
+        status(StatusCode::INTERNAL_SERVER_ERROR)
+    })
+}
+
+fn status(code: StatusCode) -> Response<Vec<u8>> {
+    let mut response = Response::new(Vec::new());
+    *response.status_mut() = code;
+    response
+}
+
+fn respond(path: &Path, range: Option<&str>) -> io::Result<Response<Vec<u8>>> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let builder = Response::builder()
+        .header(CONTENT_TYPE, content_type(path))
+        .header(ACCEPT_RANGES, "bytes");
+    let response = match range.and_then(|range| byte_range(range, len)) {
+        Some((start, end)) => {
+            let mut body = vec![0; (end - start + 1) as usize];
+            file.seek(SeekFrom::Start(start))?;
+            file.read_exact(&mut body)?;
+            builder
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len))
+                .header(CONTENT_LENGTH, body.len())
+                .body(body)
+        }
+        None => {
+            let mut body = Vec::new();
+            file.read_to_end(&mut body)?;
+            builder.header(CONTENT_LENGTH, body.len()).body(body)
+        }
+    };
+    response.map_err(io::Error::other)
+}
+
+/// First and last byte of a `bytes=<start>-[<end>]` range, or of the last
+/// bytes for `bytes=-<count>`, at most `CHUNK` of them
+fn byte_range(range: &str, len: u64) -> Option<(u64, u64)> {
+    let (start, end) = range.strip_prefix("bytes=")?.split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", count) => {
+            let count: u64 = count.parse().ok()?;
+            (len.saturating_sub(count), u64::MAX)
+        }
+        (start, end) => (start.parse().ok()?, end.parse().unwrap_or(u64::MAX)),
+    };
+    if start >= len || end < start {
+        return None;
+    }
+    Some((start, end.min(len - 1).min(start + CHUNK - 1)))
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("webm") => "audio/webm",
+        Some("m4a" | "mp4") => "audio/mp4",
+        Some("opus" | "ogg") => "audio/ogg",
+        Some("mp3") => "audio/mpeg",
+        _ => "application/octet-stream",
+    }
+}
+
+#[tauri::command]
+pub fn offline_download(app: AppHandle, video: String) -> Result<OfflineCache, EepyError> {
+    if !valid_id(&video) {
+        return Err(EepyError::Invalid(format!("Not a video ID: {}", video)));
+    }
+    spawn_download(&app, video);
+    Ok(app.state::<OfflineAudio>().report())
+}
+
+/// Download every favorite that isn't yet
+#[tauri::command]
+pub fn offline_download_favorites(app: AppHandle, library: State<Library>) -> OfflineCache {
+    for entry in library.entries().into_iter().filter(|entry| entry.favorite) {
+        spawn_download(&app, entry.video_id);
+    }
+    app.state::<OfflineAudio>().report()
+}
+
+#[tauri::command]
+pub fn offline_remove(
+    offline: State<OfflineAudio>,
+    video: String,
+) -> Result<OfflineCache, EepyError> {
+    offline.remove(&video)?;
+    Ok(offline.report())
+}
+
+#[tauri::command]
+pub fn offline_clear(offline: State<OfflineAudio>) -> Result<OfflineCache, EepyError> {
+    for file in offline.files() {
+        fs::remove_file(&file.path).map_err(|e| EepyError::io("delete the audio", e))?;
+    }
+    Ok(offline.report())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: u64 = 1024 * 1024;
+
+    /// Cached files named after the IDs, most recently played first
+    fn cached(files: &[(&str, u64)]) -> Vec<CachedFile> {
+        let now = SystemTime::now();
+        files
+            .iter()
+            .enumerate()
+            .map(|(i, (id, bytes))| CachedFile {
+                path: PathBuf::from(DIR).join(format!("{}.webm", id)),
+                video_id: id.to_string(),
+                bytes: *bytes,
+                played: now - Duration::from_secs(i as u64 * 60),
+            })
+            .collect()
+    }
+
+    fn evicted(files: &[CachedFile], max_mb: u32, keep: Option<&str>) -> Vec<String> {
+        over_limit(files, max_mb, keep)
+            .into_iter()
+            .map(|file| file.video_id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn ranges_with_both_ends_are_served_as_asked() {
+        assert_eq!(byte_range("bytes=0-99", 1000), Some((0, 99)));
+        assert_eq!(byte_range("bytes=500-500", 1000), Some((500, 500)));
+        assert_eq!(byte_range("bytes=900-5000", 1000), Some((900, 999)));
+    }
+
+    #[test]
+    fn open_ended_ranges_run_to_the_end() {
+        assert_eq!(byte_range("bytes=0-", 1000), Some((0, 999)));
+        assert_eq!(byte_range("bytes=999-", 1000), Some((999, 999)));
+    }
+
+    #[test]
+    fn suffix_ranges_take_the_last_bytes() {
+        assert_eq!(byte_range("bytes=-100", 1000), Some((900, 999)));
+        assert_eq!(byte_range("bytes=-5000", 1000), Some((0, 999)));
+        assert_eq!(byte_range("bytes=-0", 1000), None);
+    }
+
+    #[test]
+    fn ranges_outside_the_file_are_refused() {
+        for range in [
+            "bytes=1000-",
+            "bytes=1000-1200",
+            "bytes=500-100",
+            "bytes=-",
+            "bytes=a-b",
+            "items=0-99",
+            "0-99",
+        ] {
+            assert_eq!(byte_range(range, 1000), None, "{}", range);
+        }
+        assert_eq!(byte_range("bytes=0-", 0), None);
+    }
+
+    #[test]
+    fn ranges_are_capped_at_a_chunk() {
+        let len = 10 * CHUNK;
+        assert_eq!(byte_range("bytes=0-", len), Some((0, CHUNK - 1)));
+        assert_eq!(
+            byte_range(&format!("bytes={}-{}", CHUNK, len - 1), len),
+            Some((CHUNK, 2 * CHUNK - 1))
+        );
+        assert_eq!(
+            byte_range(&format!("bytes=-{}", 3 * CHUNK), len),
+            Some((7 * CHUNK, 8 * CHUNK - 1))
+        );
+    }
+
+    #[test]
+    fn eviction_drops_the_longest_unplayed_first() {
+        let files = cached(&[("new", 4 * MB), ("older", 4 * MB), ("oldest", 4 * MB)]);
+        assert_eq!(evicted(&files, 8, None), ["oldest"]);
+        assert_eq!(evicted(&files, 5, None), ["oldest", "older"]);
+    }
+
+    #[test]
+    fn eviction_leaves_a_cache_under_the_limit_alone() {
+        let files = cached(&[("new", 4 * MB), ("older", 4 * MB)]);
+        assert!(evicted(&files, 8, None).is_empty());
+        assert!(evicted(&[], 0, None).is_empty());
+    }
+
+    #[test]
+    fn eviction_never_drops_the_kept_video() {
+        let files = cached(&[("new", 4 * MB), ("older", 4 * MB), ("oldest", 4 * MB)]);
+        assert_eq!(evicted(&files, 5, Some("oldest")), ["older", "new"]);
+        assert_eq!(evicted(&files, 0, Some("new")), ["oldest", "older"]);
+    }
+
+    #[test]
+    fn ids_that_leave_the_cache_dir_are_refused() {
+        assert!(valid_id("dQw4w9WgXcQ"));
+        for id in [
+            "",
+            "..",
+            "../dQw4w9WgXcQ",
+            "..\\settings",
+            "/etc/passwd",
+            "a/b",
+            "dQw4w9WgXcQ.webm",
+            "%2e%2e",
+        ] {
+            assert!(!valid_id(id), "{}", id);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use std::sync::Mutex;
+
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::parental::Profile;
+use sleepy_video_shared::settings::Settings;
+use tauri::State;
+
+use crate::settings::SettingsStore;
+use crate::strict::{hash_secret, needs_rehash, verify_secret};
+
+/// Shortest parent password accepted
+const MIN_PASSWORD_LENGTH: usize = 4;
+
+/// Whether the parent has signed in since launch. Without a parental profile
+/// everyone is the parent.
+#[derive(Default)]
+pub struct ProfileState {
+    parent_signed_in: Mutex<bool>,
+}
+
+impl ProfileState {
+    pub fn active(&self, settings: &Settings) -> Profile {
+        if settings.parental.enabled && !*self.parent_signed_in.lock().unwrap() {
+            Profile::Child
+        } else {
+            Profile::Parent
+        }
+    }
+
+    pub fn sign_in(&self) {
+        *self.parent_signed_in.lock().unwrap() = true;
+    }
+}
+
+#[tauri::command]
+pub fn active_profile(store: State<SettingsStore>, profiles: State<ProfileState>) -> Profile {
+    profiles.active(&store.get())
+}
+
+#[tauri::command]
+pub fn parent_sign_in(
+    store: State<SettingsStore>,
+    profiles: State<ProfileState>,
+    password: String,
+) -> Result<Profile, EepyError> {
+    let mut settings = store.get();
+    if !verify_secret(&password, &settings.parental.password_hash) {
+        return Err(EepyError::Invalid("Wrong password".to_string()));
+    }
+    if needs_rehash(&settings.parental.password_hash) {
+        settings.parental.password_hash = hash_secret(&password);
+        if let Err(e) = store.save(settings.clone()) {
+            tracing::warn!("Failed to store the salted password: {}", e);
+        }
+    }
+    profiles.sign_in();
+    Ok(profiles.active(&settings))
+}
+
+/// Hand the app back to the child
+#[tauri::command]
+pub fn parent_sign_out(store: State<SettingsStore>, profiles: State<ProfileState>) -> Profile {
+    *profiles.parent_signed_in.lock().unwrap() = false;
+    profiles.active(&store.get())
+}
+
+/// Set the parent's password. Only the parent can change it once the
+/// parental profile is on.
+#[tauri::command]
+pub fn parental_set_password(
+    store: State<SettingsStore>,
+    profiles: State<ProfileState>,
+    password: String,
+) -> Result<Settings, EepyError> {
+    let mut settings = store.get();
+    if profiles.active(&settings) == Profile::Child {
+        return Err(EepyError::Locked(
+            "Only the parent can change the password".to_string(),
+        ));
+    }
+    let password = password.trim();
+    if password.len() < MIN_PASSWORD_LENGTH {
+        return Err(EepyError::Invalid(format!(
+            "The password needs at least {} characters",
+            MIN_PASSWORD_LENGTH
+        )));
+    }
+
+    settings.parental.password_hash = hash_secret(password);
+    store.save(settings.clone()).map_err(EepyError::Io)?;
+    Ok(settings)
+}
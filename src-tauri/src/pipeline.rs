@@ -0,0 +1,164 @@
+use std::time::Duration;
+
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::pipeline::{Stage, StageOutcome, StageReport, FADE_OUT_SECONDS};
+use sleepy_video_shared::power::EndAction;
+use sleepy_video_shared::session::{SessionEvent, SessionStatus, STAGE_EVENT};
+use sleepy_video_shared::settings::Settings;
+use sleepy_video_shared::webhooks::WebhookEvent;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::plugins::{self, Hook};
+use crate::power::{self, Power};
+use crate::session::SessionState;
+use crate::settings::SettingsStore;
+use crate::{lights, media, notifications, sonos, spotify, webhooks};
+
+/// Stages a test run goes through; the rest reach outside the app
+const TEST_STAGES: [Stage; 3] = [Stage::FadeAudio, Stage::PauseMedia, Stage::EndAction];
+/// Pause between stages of a test run, so each can be seen
+const TEST_STEP: Duration = Duration::from_secs(1);
+
+fn report(app: &AppHandle, session: &SessionState, stage: Stage, outcome: StageOutcome) {
+    session.publish(app, SessionEvent::Stage(StageReport { stage, outcome }));
+}
+
+/// Like `report`, but only to the main window
+fn report_test(app: &AppHandle, stage: Stage, outcome: StageOutcome) {
+    let _ = app.emit_to("main", STAGE_EVENT, StageReport { stage, outcome });
+}
+
+async fn run_stage(
+    app: &AppHandle,
+    settings: &Settings,
+    session: &SessionState,
+    power: &Power,
+    stage: Stage,
+    action: EndAction,
+) -> Result<(), EepyError> {
+    match stage {
+        // The window fades its own player when told the stage started
+        Stage::FadeAudio => {
+            tokio::time::sleep(Duration::from_secs(FADE_OUT_SECONDS as u64)).await;
+            Ok(())
+        }
+        // And pauses it
+        Stage::PauseMedia => {
+            media::pause_all(&settings.media_servers).await;
+            spotify::pause(app, &settings.spotify).await;
+            sonos::stop(app, &settings.sonos).await;
+            #[cfg(target_os = "android")]
+            crate::android::pause_media(app)?;
+            Ok(())
+        }
+        Stage::RunHook => {
+            if action != EndAction::Nothing {
+                let status = SessionStatus {
+                    action,
+                    ..session.status()
+                };
+                webhooks::fire_and_wait(app, &settings.webhooks, WebhookEvent::Suspend, status)
+                    .await;
+            }
+            Ok(())
+        }
+        Stage::DimLights => {
+            lights::at_end(&settings.lights).await;
+            Ok(())
+        }
+        Stage::Lock => power.lock(),
+        Stage::Plugins => {
+            let status = SessionStatus {
+                action,
+                ..session.status()
+            };
+            plugins::run_stage(app, &settings.plugins, status).await
+        }
+        Stage::EndAction => power::end(settings, power, action).await,
+    }
+}
+
+/// Run the end-of-session stages in their configured order, reporting each
+/// as it starts and finishes. A failing stage doesn't hold up the rest; only
+/// a failed end action fails the whole.
+#[tauri::command]
+pub async fn run_end_pipeline(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    session: State<'_, SessionState>,
+    power: State<'_, Power>,
+    action: EndAction,
+) -> Result<(), EepyError> {
+    let settings = store.get();
+    session.publish(&app, SessionEvent::Ended(action));
+    plugins::fire(
+        &app,
+        &settings.plugins,
+        Hook::Finish,
+        SessionStatus {
+            action,
+            ..session.status()
+        },
+    );
+
+    let mut result = Ok(());
+    for step in settings.pipeline.stages.iter().filter(|step| step.enabled) {
+        if step.delay_seconds > 0 {
+            tokio::time::sleep(Duration::from_secs(step.delay_seconds as u64)).await;
+        }
+        tracing::info!("Stage {} started", step.stage.label());
+        report(&app, &session, step.stage, StageOutcome::Started);
+        let outcome = match run_stage(&app, &settings, &session, &power, step.stage, action).await {
+            Ok(()) => StageOutcome::Done,
+            Err(e) => {
+                tracing::warn!("{}: {}", step.stage.label(), e);
+                if step.stage == Stage::EndAction {
+                    result = Err(e.clone());
+                }
+                StageOutcome::Failed(e)
+            }
+        };
+        report(&app, &session, step.stage, outcome);
+    }
+    result
+}
+
+/// Dry run of the end of a session for the TEST button: the window fades and
+/// pauses its player as usual, and a notification stands in for the end
+/// action. Stage waits are skipped, and only the main window hears about it,
+/// so webhooks, lights and the like stay out of it. `notice` is that
+/// notification, worded in the UI's language.
+#[tauri::command]
+pub async fn run_test_pipeline(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    action: EndAction,
+    notice: String,
+) -> Result<(), EepyError> {
+    let settings = store.get();
+    let stages = settings
+        .pipeline
+        .stages
+        .iter()
+        .filter(|step| step.enabled && TEST_STAGES.contains(&step.stage));
+    for step in stages {
+        let stage = step.stage;
+        report_test(&app, stage, StageOutcome::Started);
+        match stage {
+            Stage::FadeAudio => {
+                tokio::time::sleep(Duration::from_secs(FADE_OUT_SECONDS as u64)).await;
+            }
+            Stage::EndAction => {
+                tracing::info!(
+                    "Test run reached the end action ({}), not run",
+                    action.as_str()
+                );
+                notifications::notify(&app, &notice);
+            }
+            _ => {}
+        }
+        report_test(&app, stage, StageOutcome::Done);
+        tokio::time::sleep(TEST_STEP).await;
+    }
+    Ok(())
+}
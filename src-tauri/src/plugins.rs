@@ -0,0 +1,253 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Scope, AST};
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::plugins::{PluginList, PluginSettings};
+use sleepy_video_shared::session::SessionStatus;
+use tauri::{AppHandle, Manager};
+
+use crate::notifications;
+
+/// Folder in the app config directory the scripts are loaded from
+const PLUGINS_DIR: &str = "plugins";
+const EXTENSION: &str = "rhai";
+
+/// Limits that keep a runaway script from hanging the end of a session
+const MAX_OPERATIONS: u64 = 5_000_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_STRING_SIZE: usize = 1 << 20;
+const MAX_COLLECTION_SIZE: usize = 10_000;
+const LONGEST_DELAY: Duration = Duration::from_secs(60);
+/// Wall-clock time all the scripts of one hook get together, `delay` and
+/// requests included
+const LONGEST_RUN: Duration = Duration::from_secs(120);
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// When scripts get called, and the function that's called then
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    Start,
+    Finish,
+    /// The plugins stage of the end-of-session pipeline
+    Stage,
+}
+
+impl Hook {
+    fn function(self) -> &'static str {
+        match self {
+            Hook::Start => "on_start",
+            Hook::Finish => "on_finish",
+            Hook::Stage => "stage",
+        }
+    }
+}
+
+struct Plugin {
+    name: String,
+    ast: AST,
+}
+
+fn plugins_dir(app: &AppHandle) -> PathBuf {
+    app.path()
+        .app_config_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join(PLUGINS_DIR)
+}
+
+/// Names of the scripts in the plugins folder, in the order they run
+fn script_names(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut scripts: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == EXTENSION))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().into_owned();
+            Some((name, path))
+        })
+        .collect();
+    scripts.sort();
+    scripts
+}
+
+/// An engine with nothing but the language, the limits and the small API
+/// below: `notify`, `run` (if allowed), `http` and `delay`. Scripts are
+/// stopped once `deadline` passes.
+fn engine(app: &AppHandle, settings: &PluginSettings, name: &str, deadline: Instant) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_string_size(MAX_STRING_SIZE)
+        .set_max_array_size(MAX_COLLECTION_SIZE)
+        .set_max_map_size(MAX_COLLECTION_SIZE)
+        .disable_symbol("eval");
+    engine
+        .on_progress(move |_| (Instant::now() > deadline).then(|| "Plugin ran out of time".into()));
+
+    let prefix = format!("[{}]", name);
+    engine.on_print(move |text| tracing::info!("{} {}", prefix, text));
+
+    let handle = app.clone();
+    engine.register_fn("notify", move |body: &str| {
+        notifications::notify(&handle, body);
+    });
+
+    let allow_commands = settings.allow_commands;
+    engine.register_fn(
+        "run",
+        move |program: &str, args: Array| -> Result<i64, Box<EvalAltResult>> {
+            if !allow_commands {
+                return Err("Running commands is turned off in settings".into());
+            }
+            let args: Vec<String> = args.into_iter().map(|arg| arg.to_string()).collect();
+            tracing::info!("Plugin running {} {}", program, args.join(" "));
+            let status = std::process::Command::new(program)
+                .args(&args)
+                .status()
+                .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+            Ok(status.code().unwrap_or(-1) as i64)
+        },
+    );
+
+    engine.register_fn(
+        "http",
+        |method: &str, url: &str, body: &str| -> Result<String, Box<EvalAltResult>> {
+            let method = reqwest::Method::from_bytes(method.to_uppercase().as_bytes())
+                .map_err(|_| format!("Unknown HTTP method: {}", method))?;
+            let client = reqwest::Client::builder()
+                .timeout(HTTP_TIMEOUT)
+                .build()
+                .map_err(|e| e.to_string())?;
+            let request = client.request(method, url).body(body.to_string());
+            // Scripts run on a blocking thread, so waiting here is fine
+            tauri::async_runtime::block_on(async move {
+                let response = request.send().await?.error_for_status()?;
+                response.text().await
+            })
+            .map_err(|e| format!("Request to {} failed: {}", url, e).into())
+        },
+    );
+
+    // Never past the deadline, where the next operation stops the script
+    engine.register_fn("delay", move |ms: i64| {
+        let left = deadline.saturating_duration_since(Instant::now());
+        std::thread::sleep(
+            Duration::from_millis(ms.max(0) as u64)
+                .min(LONGEST_DELAY)
+                .min(left),
+        );
+    });
+
+    engine
+}
+
+/// Compile every script that defines `hook`'s function, logging the ones
+/// that don't compile
+fn load(
+    app: &AppHandle,
+    settings: &PluginSettings,
+    hook: Hook,
+    deadline: Instant,
+) -> Vec<(Plugin, Engine)> {
+    script_names(&plugins_dir(app))
+        .into_iter()
+        .filter_map(|(name, path)| {
+            let engine = engine(app, settings, &name, deadline);
+            let ast = match engine.compile_file(path) {
+                Ok(ast) => ast,
+                Err(e) => {
+                    tracing::warn!("Plugin {} doesn't compile: {}", name, e);
+                    return None;
+                }
+            };
+            let wanted = ast
+                .iter_functions()
+                .any(|f| f.name == hook.function() && f.params.len() == 1);
+            wanted.then_some((Plugin { name, ast }, engine))
+        })
+        .collect()
+}
+
+/// Call `hook` in every plugin that has it, one after the other, with the
+/// session's status. Returns the plugins that failed and why.
+fn call(
+    app: &AppHandle,
+    settings: &PluginSettings,
+    hook: Hook,
+    status: SessionStatus,
+) -> Vec<String> {
+    let session = rhai::serde::to_dynamic(&status).unwrap_or(Dynamic::UNIT);
+    load(app, settings, hook, Instant::now() + LONGEST_RUN)
+        .into_iter()
+        .filter_map(|(plugin, engine)| {
+            tracing::info!("Calling {} in plugin {}", hook.function(), plugin.name);
+            engine
+                .call_fn::<Dynamic>(
+                    &mut Scope::new(),
+                    &plugin.ast,
+                    hook.function(),
+                    (session.clone(),),
+                )
+                .err()
+                .map(|e| format!("{}: {}", plugin.name, e))
+        })
+        .collect()
+}
+
+/// Run `hook` in the background, logging what fails
+pub fn fire(app: &AppHandle, settings: &PluginSettings, hook: Hook, status: SessionStatus) {
+    if !settings.enabled {
+        return;
+    }
+    let (app, settings) = (app.clone(), settings.clone());
+    tauri::async_runtime::spawn_blocking(move || {
+        for failure in call(&app, &settings, hook, status) {
+            tracing::warn!("Plugin {}", failure);
+        }
+    });
+}
+
+/// Run the plugins stage of the end-of-session pipeline and wait for it
+pub async fn run_stage(
+    app: &AppHandle,
+    settings: &PluginSettings,
+    status: SessionStatus,
+) -> Result<(), EepyError> {
+    if !settings.enabled {
+        return Ok(());
+    }
+    let (app, settings) = (app.clone(), settings.clone());
+    let running =
+        tauri::async_runtime::spawn_blocking(move || call(&app, &settings, Hook::Stage, status));
+    // A script stuck in a request or command isn't stopped by the deadline,
+    // so the pipeline moves on without it
+    let failures = tokio::time::timeout(LONGEST_RUN + HTTP_TIMEOUT, running)
+        .await
+        .map_err(|_| EepyError::CommandFailed("Plugins took too long".to_string()))?
+        .map_err(|e| EepyError::Other(format!("Plugins stopped: {}", e)))?;
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(EepyError::CommandFailed(format!(
+            "Plugin {}",
+            failures.join("; ")
+        )))
+    }
+}
+
+/// Where scripts go and which ones are there
+#[tauri::command]
+pub fn plugin_list(app: AppHandle) -> PluginList {
+    let dir = plugins_dir(&app);
+    PluginList {
+        names: script_names(&dir)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect(),
+        dir: dir.to_string_lossy().into_owned(),
+    }
+}
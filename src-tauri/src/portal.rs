@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use sleepy_video_shared::session::SessionEvent;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast;
+use zbus::blocking::Connection;
+use zbus::zvariant::{OwnedFd, OwnedObjectPath, Value};
+
+use crate::session::SessionState;
+
+const PORTAL: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+const LOGIND: &str = "org.freedesktop.login1";
+const LOGIND_PATH: &str = "/org/freedesktop/login1";
+const LOGIND_MANAGER: &str = "org.freedesktop.login1.Manager";
+/// Inhibit portal flag for the session going idle. Only idle: inhibiting
+/// sleep would also stop the end action.
+const INHIBIT_IDLE: u32 = 8;
+
+/// Whether this is a Flatpak build, where host programs like `systemctl`
+/// can't be reached and D-Bus and the portals stand in for them
+pub fn sandboxed() -> bool {
+    Path::new("/.flatpak-info").exists()
+}
+
+fn system() -> Result<Connection, String> {
+    Connection::system().map_err(|e| format!("Failed to reach the system bus: {}", e))
+}
+
+fn session() -> Result<Connection, String> {
+    Connection::session().map_err(|e| format!("Failed to reach the session bus: {}", e))
+}
+
+/// Call a logind method that takes whether to ask for a password, like
+/// `Suspend`. It doesn't ask; polkit has to allow it.
+pub fn logind(method: &str) -> Result<(), String> {
+    system()?
+        .call_method(
+            Some(LOGIND),
+            LOGIND_PATH,
+            Some(LOGIND_MANAGER),
+            method,
+            &(false,),
+        )
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// logind's answer to a `CanSuspend`-style method: yes, no, challenge or na
+pub fn logind_can(method: &str) -> Result<String, String> {
+    let reply = system()?
+        .call_method(Some(LOGIND), LOGIND_PATH, Some(LOGIND_MANAGER), method, &())
+        .map_err(|e| e.to_string())?;
+    reply
+        .body()
+        .deserialize()
+        .map_err(|e| format!("Unexpected logind reply: {}", e))
+}
+
+/// Lock through the desktop's screensaver interface
+pub fn lock_screen() -> Result<(), String> {
+    session()?
+        .call_method(
+            Some("org.freedesktop.ScreenSaver"),
+            "/org/freedesktop/ScreenSaver",
+            Some("org.freedesktop.ScreenSaver"),
+            "Lock",
+            &(),
+        )
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Whether the network the desktop reports is metered. The NetworkMonitor
+/// portal answers outside Flatpak too.
+pub fn metered() -> Result<bool, String> {
+    let reply = session()?
+        .call_method(
+            Some(PORTAL),
+            PORTAL_PATH,
+            Some("org.freedesktop.portal.NetworkMonitor"),
+            "GetMetered",
+            &(),
+        )
+        .map_err(|e| format!("NetworkMonitor portal: {}", e))?;
+    reply
+        .body()
+        .deserialize()
+        .map_err(|e| format!("Unexpected NetworkMonitor reply: {}", e))
+}
+
+/// Ask the Background portal to start `commandline` at login, or to stop.
+/// The desktop may ask the user first; that answer isn't waited for.
+pub fn request_background(autostart: bool, commandline: Vec<String>) -> Result<(), String> {
+    let mut options = HashMap::<&str, Value>::new();
+    options.insert("reason", Value::from("Start eepy for the bedtime schedule"));
+    options.insert("autostart", Value::from(autostart));
+    options.insert("commandline", Value::from(commandline));
+    session()?
+        .call_method(
+            Some(PORTAL),
+            PORTAL_PATH,
+            Some("org.freedesktop.portal.Background"),
+            "RequestBackground",
+            &("", options),
+        )
+        .map(|_| ())
+        .map_err(|e| format!("Background portal: {}", e))
+}
+
+/// Keeps the computer from idling into sleep until dropped
+enum Inhibitor {
+    /// Request from the Inhibit portal, closed to let go
+    Portal {
+        connection: Connection,
+        handle: OwnedObjectPath,
+    },
+    /// logind holds the inhibit while this stays open
+    Logind(OwnedFd),
+}
+
+impl Drop for Inhibitor {
+    fn drop(&mut self) {
+        if let Inhibitor::Portal { connection, handle } = self {
+            let closed = connection.call_method(
+                Some(PORTAL),
+                handle.as_str(),
+                Some("org.freedesktop.portal.Request"),
+                "Close",
+                &(),
+            );
+            if let Err(e) = closed {
+                tracing::warn!("Failed to lift the idle inhibit: {}", e);
+            }
+        }
+    }
+}
+
+fn inhibit(reason: &str) -> Result<Inhibitor, String> {
+    if sandboxed() {
+        let connection = session()?;
+        let mut options = HashMap::<&str, Value>::new();
+        options.insert("reason", Value::from(reason));
+        let reply = connection
+            .call_method(
+                Some(PORTAL),
+                PORTAL_PATH,
+                Some("org.freedesktop.portal.Inhibit"),
+                "Inhibit",
+                &("", INHIBIT_IDLE, options),
+            )
+            .map_err(|e| format!("Inhibit portal: {}", e))?;
+        let handle = reply
+            .body()
+            .deserialize()
+            .map_err(|e| format!("Unexpected inhibit reply: {}", e))?;
+        Ok(Inhibitor::Portal { connection, handle })
+    } else {
+        let reply = system()?
+            .call_method(
+                Some(LOGIND),
+                LOGIND_PATH,
+                Some(LOGIND_MANAGER),
+                "Inhibit",
+                &("idle", "eepy", reason, "block"),
+            )
+            .map_err(|e| format!("logind inhibit: {}", e))?;
+        let fd = reply
+            .body()
+            .deserialize()
+            .map_err(|e| format!("Unexpected inhibit reply: {}", e))?;
+        Ok(Inhibitor::Logind(fd))
+    }
+}
+
+/// Hold off idle sleep while a session runs, so it isn't cut short before
+/// the timer ends
+pub fn spawn(app: AppHandle) {
+    let events = app.state::<SessionState>().subscribe();
+    tauri::async_runtime::spawn(follow_session(events));
+}
+
+async fn follow_session(mut events: broadcast::Receiver<SessionEvent>) {
+    let mut held: Option<Inhibitor> = None;
+    loop {
+        match events.recv().await {
+            Ok(SessionEvent::Started(_)) if held.is_none() => {
+                match inhibit("Sleep timer running") {
+                    Ok(inhibitor) => held = Some(inhibitor),
+                    Err(e) => tracing::warn!("{}", e),
+                }
+            }
+            Ok(SessionEvent::Ended(_) | SessionEvent::Cancelled) => held = None,
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
@@ -0,0 +1,416 @@
+#[cfg(target_os = "android")]
+mod android;
+#[cfg(target_os = "linux")]
+mod flatpak;
+mod linux;
+mod macos;
+mod mock;
+mod windows;
+
+use chrono::{DateTime, Local};
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::power::{
+    CheckStatus, EndAction, PowerCapabilities, PowerCheck, PowerDiagnostics,
+};
+use sleepy_video_shared::settings::Settings;
+use tauri::State;
+
+use crate::settings::SettingsStore;
+use crate::{push, schedule};
+
+use mock::MockPower;
+
+/// Set to use the mock instead of really sleeping the computer, e.g. in CI.
+/// `fail` makes every action fail.
+const MOCK_ENV: &str = "EEPY_MOCK_POWER";
+
+/// Words in a failed program's output that mean the system said no
+const DENIED: [&str; 4] = ["not authorized", "access denied", "permission", "privilege"];
+
+fn run(program: &str, args: &[&str], what: &str) -> Result<(), EepyError> {
+    tracing::info!("Running {} {}", program, args.join(" "));
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| EepyError::spawn(program, e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    tracing::info!(
+        "{} exited with {}; stdout: {:?}; stderr: {:?}",
+        program,
+        output.status,
+        stdout.trim(),
+        stderr.trim()
+    );
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let reason = stderr.lines().last().unwrap_or("unknown error").trim();
+    let lower = reason.to_lowercase();
+    if DENIED.iter().any(|word| lower.contains(word)) {
+        Err(EepyError::PermissionDenied(format!(
+            "Not allowed to {}: {}",
+            what, reason
+        )))
+    } else {
+        Err(EepyError::CommandFailed(format!(
+            "Failed to {}: {}",
+            what, reason
+        )))
+    }
+}
+
+/// Run a read-only command for a diagnostics check, returning its trimmed
+/// output
+fn probe(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("{} can't be run: {}", program, e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() {
+        Ok(stdout)
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(stderr
+            .lines()
+            .last()
+            .unwrap_or("unknown error")
+            .trim()
+            .to_string())
+    }
+}
+
+/// Whether `program` is somewhere on the PATH
+fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH").is_some_and(|paths| {
+        std::env::split_paths(&paths).any(|dir| {
+            dir.join(program).is_file() || dir.join(format!("{}.exe", program)).is_file()
+        })
+    })
+}
+
+fn unsupported(what: &str) -> EepyError {
+    EepyError::UnsupportedPlatform(format!("{} is not supported on this platform", what))
+}
+
+/// What the operating system can do with the computer. Anything a platform
+/// can't do is left to the defaults, which fail as unsupported.
+pub trait SystemPower: Send + Sync {
+    fn capabilities(&self) -> PowerCapabilities;
+
+    fn suspend(&self) -> Result<(), EepyError> {
+        Err(unsupported("Sleep"))
+    }
+
+    fn hibernate(&self) -> Result<(), EepyError> {
+        Err(unsupported("Hibernate"))
+    }
+
+    fn shutdown(&self) -> Result<(), EepyError> {
+        Err(unsupported("Shutting down"))
+    }
+
+    fn lock(&self) -> Result<(), EepyError> {
+        Err(unsupported("Locking the screen"))
+    }
+
+    fn display_off(&self) -> Result<(), EepyError> {
+        Err(unsupported("Turning the screen off"))
+    }
+
+    /// Set the hardware clock to wake the computer at `at`
+    fn wake_at(&self, _at: DateTime<Local>) -> Result<(), EepyError> {
+        Err(unsupported("Waking from sleep"))
+    }
+
+    /// Check what the actions need without running any of them
+    fn diagnose(&self) -> Vec<PowerCheck> {
+        Vec::new()
+    }
+}
+
+/// Platforms without any power controls
+struct Unsupported;
+
+impl SystemPower for Unsupported {
+    fn capabilities(&self) -> PowerCapabilities {
+        PowerCapabilities::default()
+    }
+}
+
+/// The power controls in use, managed as app state
+pub struct Power(Box<dyn SystemPower>);
+
+impl Power {
+    /// This platform's controls, or the mock when `EEPY_MOCK_POWER` is set
+    pub fn native() -> Self {
+        if let Some(mock) = std::env::var_os(MOCK_ENV) {
+            return Power(Box::new(MockPower::new(mock == "fail")));
+        }
+        #[cfg(target_os = "linux")]
+        if crate::portal::sandboxed() {
+            return Power(Box::new(flatpak::Flatpak));
+        }
+        if cfg!(target_os = "linux") {
+            Power(Box::new(linux::Linux))
+        } else if cfg!(target_os = "macos") {
+            Power(Box::new(macos::MacOs))
+        } else if cfg!(target_os = "windows") {
+            Power(Box::new(windows::Windows))
+        } else {
+            Power(Box::new(Unsupported))
+        }
+    }
+
+    /// Android's controls go through the countdown plugin, so need the app
+    #[cfg(target_os = "android")]
+    pub fn android(app: &tauri::AppHandle) -> Self {
+        use tauri::Manager;
+
+        let countdown = app.state::<crate::android::Countdown>().inner().clone();
+        Power(Box::new(android::Android(countdown)))
+    }
+
+    pub fn capabilities(&self) -> PowerCapabilities {
+        self.0.capabilities()
+    }
+
+    pub fn lock(&self) -> Result<(), EepyError> {
+        self.0.lock()
+    }
+
+    pub fn run(&self, action: EndAction) -> Result<(), EepyError> {
+        match action {
+            EndAction::Suspend => self.0.suspend(),
+            EndAction::Hibernate => self.0.hibernate(),
+            EndAction::Shutdown => self.0.shutdown(),
+            EndAction::Lock => self.0.lock(),
+            EndAction::ScreenOff => self.0.display_off(),
+            EndAction::Nothing => Ok(()),
+        }
+    }
+}
+
+/// Troubleshoot sleeping: what this computer is, which ways to sleep it has,
+/// and whether eepy is allowed to use them
+#[tauri::command]
+pub async fn run_diagnostics(power: State<'_, Power>) -> Result<PowerDiagnostics, EepyError> {
+    let mut checks = power.0.diagnose();
+    if checks.is_empty() {
+        checks.push(PowerCheck::new(
+            "POWER CONTROLS",
+            CheckStatus::Fail,
+            "eepy can't put this platform to sleep",
+        ));
+    }
+    Ok(PowerDiagnostics {
+        platform: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        checks,
+    })
+}
+
+/// End actions this platform can run, so the others can be greyed out
+#[tauri::command]
+pub fn power_capabilities(power: State<Power>) -> PowerCapabilities {
+    power.capabilities()
+}
+
+/// Run `action`, setting the wake alarm first and pushing how it went
+pub async fn end(settings: &Settings, power: &Power, action: EndAction) -> Result<(), EepyError> {
+    // Sent first and awaited, the machine may be asleep right after
+    let push = settings.push.enabled && settings.push.on_end;
+    if push {
+        if let Err(e) = push::send(&settings.push, &push::end_message(action)).await {
+            tracing::warn!("{}", e);
+        }
+    }
+
+    // Only a sleeping computer can be woken by its clock
+    if matches!(action, EndAction::Suspend | EndAction::Hibernate) {
+        if let Some(at) = schedule::next_wake(&settings.wake, Local::now()) {
+            if let Err(e) = power.0.wake_at(at) {
+                tracing::warn!("{}", e);
+            }
+        }
+    }
+
+    tracing::info!("Running end action {}", action.as_str());
+    let result = power.run(action);
+    match &result {
+        Ok(()) => tracing::info!("End action {} done", action.as_str()),
+        Err(e) => tracing::error!("End action {} failed: {}", action.as_str(), e),
+    }
+    if let (true, Err(e)) = (push, &result) {
+        push::spawn(&settings.push, push::failed_message(&e.to_string()));
+    }
+    result
+}
+
+/// Try the end action again after it failed
+#[tauri::command]
+pub async fn run_end_action(
+    store: State<'_, SettingsStore>,
+    power: State<'_, Power>,
+    action: EndAction,
+) -> Result<(), EepyError> {
+    end(&store.get(), &power, action).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use serde_json::{json, Value};
+    use sleepy_video_shared::power::{CheckStatus, EndAction, PowerCapabilities, PowerDiagnostics};
+    use sleepy_video_shared::settings::Settings;
+    use tauri::ipc::{CallbackFn, InvokeBody};
+    use tauri::test::{
+        get_ipc_response, mock_builder, mock_context, noop_assets, MockRuntime, INVOKE_KEY,
+    };
+    use tauri::webview::InvokeRequest;
+    use tauri::{App, WebviewWindow, WebviewWindowBuilder};
+
+    use super::*;
+
+    type Actions = Arc<Mutex<Vec<String>>>;
+
+    /// An app with only the power commands, running on the mock, and what
+    /// the mock was asked to do
+    fn app(fail: bool) -> (App<MockRuntime>, WebviewWindow<MockRuntime>, Actions) {
+        let mock = MockPower::new(fail);
+        let actions = mock.actions();
+        let app = mock_builder()
+            .manage(Power(Box::new(mock)))
+            .manage(SettingsStore::in_memory(Settings::default()))
+            .invoke_handler(tauri::generate_handler![
+                power_capabilities,
+                run_end_action,
+                run_diagnostics
+            ])
+            .build(mock_context(noop_assets()))
+            .expect("the mock app builds");
+        let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+            .build()
+            .expect("the mock webview builds");
+        (app, webview, actions)
+    }
+
+    fn invoke(
+        webview: &WebviewWindow<MockRuntime>,
+        cmd: &str,
+        args: Value,
+    ) -> Result<Value, Value> {
+        let url = if cfg!(any(windows, target_os = "android")) {
+            "http://tauri.localhost"
+        } else {
+            "tauri://localhost"
+        };
+        get_ipc_response(
+            webview,
+            InvokeRequest {
+                cmd: cmd.into(),
+                callback: CallbackFn(0),
+                error: CallbackFn(1),
+                url: url.parse().unwrap(),
+                body: InvokeBody::Json(args),
+                headers: Default::default(),
+                invoke_key: INVOKE_KEY.to_string(),
+            },
+        )
+        .map(|body| body.deserialize().unwrap())
+    }
+
+    fn taken(actions: &Actions) -> Vec<String> {
+        actions.lock().unwrap().clone()
+    }
+
+    #[test]
+    fn capabilities_come_from_the_power_in_use() {
+        let (_app, webview, _) = app(false);
+        let capabilities: PowerCapabilities =
+            serde_json::from_value(invoke(&webview, "power_capabilities", json!({})).unwrap())
+                .unwrap();
+        assert!(capabilities.suspend && capabilities.display_off && capabilities.wake);
+    }
+
+    #[test]
+    fn run_end_action_runs_the_action() {
+        let (_app, webview, actions) = app(false);
+        assert!(invoke(&webview, "run_end_action", json!({ "action": "suspend" })).is_ok());
+        assert!(invoke(&webview, "run_end_action", json!({ "action": "lock" })).is_ok());
+        assert_eq!(taken(&actions), ["suspend", "lock the screen"]);
+    }
+
+    #[test]
+    fn run_end_action_does_nothing_for_nothing() {
+        let (_app, webview, actions) = app(false);
+        assert!(invoke(&webview, "run_end_action", json!({ "action": "nothing" })).is_ok());
+        assert!(taken(&actions).is_empty());
+    }
+
+    #[test]
+    fn run_end_action_reports_failures() {
+        let (_app, webview, actions) = app(true);
+        assert!(invoke(&webview, "run_end_action", json!({ "action": "hibernate" })).is_err());
+        assert_eq!(taken(&actions), ["hibernate"]);
+    }
+
+    #[test]
+    fn run_end_action_rejects_unknown_actions() {
+        let (_app, webview, actions) = app(false);
+        assert!(invoke(&webview, "run_end_action", json!({ "action": "explode" })).is_err());
+        assert!(taken(&actions).is_empty());
+    }
+
+    #[test]
+    fn diagnostics_say_the_mock_is_in_use() {
+        let (_app, webview, _) = app(false);
+        let diagnostics: PowerDiagnostics =
+            serde_json::from_value(invoke(&webview, "run_diagnostics", json!({})).unwrap())
+                .unwrap();
+        assert_eq!(diagnostics.checks.len(), 1);
+        assert_eq!(diagnostics.checks[0].status, CheckStatus::Warn);
+    }
+
+    #[test]
+    fn end_sets_the_wake_alarm_before_sleeping() {
+        let mock = MockPower::new(false);
+        let actions = mock.actions();
+        let power = Power(Box::new(mock));
+        let mut settings = Settings::default();
+        settings.wake.enabled = true;
+        settings.wake.days = (0..7).collect();
+
+        tauri::async_runtime::block_on(end(&settings, &power, EndAction::Suspend)).unwrap();
+        let taken = taken(&actions);
+        assert_eq!(taken.len(), 2);
+        assert!(taken[0].starts_with("wake at "));
+        assert_eq!(taken[1], "suspend");
+    }
+
+    #[test]
+    fn end_leaves_the_alarm_alone_when_not_sleeping() {
+        let mock = MockPower::new(false);
+        let actions = mock.actions();
+        let power = Power(Box::new(mock));
+        let mut settings = Settings::default();
+        settings.wake.enabled = true;
+        settings.wake.days = (0..7).collect();
+
+        tauri::async_runtime::block_on(end(&settings, &power, EndAction::Lock)).unwrap();
+        assert_eq!(taken(&actions), ["lock the screen"]);
+    }
+
+    #[test]
+    fn end_skips_the_wake_alarm_when_it_is_off() {
+        let mock = MockPower::new(false);
+        let actions = mock.actions();
+        let power = Power(Box::new(mock));
+
+        tauri::async_runtime::block_on(end(&Settings::default(), &power, EndAction::Suspend))
+            .unwrap();
+        assert_eq!(taken(&actions), ["suspend"]);
+    }
+}
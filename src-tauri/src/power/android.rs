@@ -0,0 +1,27 @@
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::power::PowerCapabilities;
+
+use super::SystemPower;
+use crate::android::Countdown;
+
+/// Apps can't put a phone to sleep or turn it off. Locking it, which also
+/// turns the screen off, goes through the device admin API.
+pub struct Android(pub Countdown);
+
+impl SystemPower for Android {
+    fn capabilities(&self) -> PowerCapabilities {
+        PowerCapabilities {
+            lock: true,
+            display_off: true,
+            ..PowerCapabilities::default()
+        }
+    }
+
+    fn lock(&self) -> Result<(), EepyError> {
+        self.0.lock()
+    }
+
+    fn display_off(&self) -> Result<(), EepyError> {
+        self.0.lock()
+    }
+}
@@ -0,0 +1,65 @@
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::power::{CheckStatus, PowerCapabilities, PowerCheck};
+
+use super::linux::logind_answer;
+use super::SystemPower;
+use crate::portal;
+
+/// The host's logind over the system bus, as `systemctl` isn't there inside
+/// Flatpak. Needs `--system-talk-name=org.freedesktop.login1`, and locking
+/// `--talk-name=org.freedesktop.ScreenSaver`.
+pub struct Flatpak;
+
+fn logind(method: &str, what: &str) -> Result<(), EepyError> {
+    portal::logind(method).map_err(|e| {
+        if e.contains("AccessDenied") || e.contains("InteractiveAuthorizationRequired") {
+            EepyError::PermissionDenied(format!("Not allowed to {}: {}", what, e))
+        } else {
+            EepyError::CommandFailed(format!("Failed to {}: {}", what, e))
+        }
+    })
+}
+
+impl SystemPower for Flatpak {
+    /// `xset` and `rtcwake` aren't in the sandbox
+    fn capabilities(&self) -> PowerCapabilities {
+        PowerCapabilities {
+            suspend: true,
+            hibernate: true,
+            shutdown: true,
+            lock: true,
+            display_off: false,
+            wake: false,
+        }
+    }
+
+    fn suspend(&self) -> Result<(), EepyError> {
+        logind("Suspend", "suspend")
+    }
+
+    fn hibernate(&self) -> Result<(), EepyError> {
+        logind("Hibernate", "hibernate")
+    }
+
+    fn shutdown(&self) -> Result<(), EepyError> {
+        logind("PowerOff", "shut down")
+    }
+
+    fn lock(&self) -> Result<(), EepyError> {
+        portal::lock_screen()
+            .map_err(|e| EepyError::CommandFailed(format!("Failed to lock the screen: {}", e)))
+    }
+
+    fn diagnose(&self) -> Vec<PowerCheck> {
+        vec![
+            PowerCheck::new(
+                "SANDBOX",
+                CheckStatus::Pass,
+                "Flatpak; logind is asked over D-Bus",
+            ),
+            logind_answer("SLEEP", portal::logind_can("CanSuspend")),
+            logind_answer("HIBERNATE", portal::logind_can("CanHibernate")),
+            logind_answer("SHUT DOWN", portal::logind_can("CanPowerOff")),
+        ]
+    }
+}
@@ -0,0 +1,194 @@
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Local};
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::power::{CheckStatus, PowerCapabilities, PowerCheck};
+
+use super::{on_path, probe, run, SystemPower};
+
+/// Ask logind whether a `CanSuspend`-style action is allowed, which is as far
+/// as trying it goes without doing it
+fn logind_allows(label: &str, method: &str) -> PowerCheck {
+    let answer = probe(
+        "busctl",
+        &[
+            "call",
+            "org.freedesktop.login1",
+            "/org/freedesktop/login1",
+            "org.freedesktop.login1.Manager",
+            method,
+        ],
+    );
+    // busctl prints the string reply as `s "yes"`
+    let answer = answer.map(|raw| raw.trim_start_matches("s ").trim_matches('"').to_string());
+    logind_answer(label, answer)
+}
+
+/// Explain logind's answer to a `CanSuspend`-style method
+pub fn logind_answer(label: &str, answer: Result<String, String>) -> PowerCheck {
+    match answer.as_deref() {
+        Ok("yes") => PowerCheck::new(label, CheckStatus::Pass, "Allowed"),
+        Ok("challenge") => PowerCheck::new(
+            label,
+            CheckStatus::Warn,
+            "polkit asks for a password first; add a polkit rule that allows it for your user",
+        ),
+        Ok("no") => PowerCheck::new(
+            label,
+            CheckStatus::Fail,
+            "polkit doesn't allow it; add a polkit rule that allows it for your user",
+        ),
+        Ok("na") => PowerCheck::new(
+            label,
+            CheckStatus::Fail,
+            "Not available on this hardware or with this kernel setup",
+        ),
+        Ok(other) => PowerCheck::new(label, CheckStatus::Warn, format!("logind said {}", other)),
+        Err(e) => PowerCheck::new(
+            label,
+            CheckStatus::Fail,
+            format!("Couldn't ask logind: {}", e),
+        ),
+    }
+}
+
+/// `PRETTY_NAME` from os-release, and the kernel version
+fn os_version() -> String {
+    let name = fs::read_to_string("/etc/os-release")
+        .ok()
+        .and_then(|raw| {
+            raw.lines()
+                .find_map(|line| line.strip_prefix("PRETTY_NAME="))
+                .map(|name| name.trim_matches('"').to_string())
+        })
+        .unwrap_or_else(|| "Linux".to_string());
+    let kernel = fs::read_to_string("/proc/sys/kernel/osrelease").unwrap_or_default();
+    format!("{}, kernel {}", name, kernel.trim())
+}
+
+/// `xset` talks to the X server, which Wayland sessions don't have
+fn wayland() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some()
+        || std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t == "wayland")
+}
+
+/// systemd and logind. Turning the screen off only works on X11.
+pub struct Linux;
+
+impl SystemPower for Linux {
+    fn capabilities(&self) -> PowerCapabilities {
+        PowerCapabilities {
+            suspend: true,
+            hibernate: true,
+            shutdown: true,
+            lock: true,
+            display_off: !wayland(),
+            wake: true,
+        }
+    }
+
+    fn suspend(&self) -> Result<(), EepyError> {
+        run("systemctl", &["suspend"], "suspend")
+    }
+
+    fn hibernate(&self) -> Result<(), EepyError> {
+        run("systemctl", &["hibernate"], "hibernate")
+    }
+
+    fn shutdown(&self) -> Result<(), EepyError> {
+        run("systemctl", &["poweroff"], "shut down")
+    }
+
+    fn lock(&self) -> Result<(), EepyError> {
+        run("loginctl", &["lock-session"], "lock the screen")
+    }
+
+    fn display_off(&self) -> Result<(), EepyError> {
+        if wayland() {
+            return Err(EepyError::UnsupportedPlatform(
+                "Turning the screen off only works on X11".to_string(),
+            ));
+        }
+        run("xset", &["dpms", "force", "off"], "turn the screen off")
+    }
+
+    /// Needs root, so it goes through `sudo -n` and relies on a passwordless
+    /// sudoers entry
+    fn wake_at(&self, at: DateTime<Local>) -> Result<(), EepyError> {
+        let when = at.timestamp().to_string();
+        run(
+            "sudo",
+            &["-n", "rtcwake", "-m", "no", "-t", &when],
+            "schedule wake",
+        )
+    }
+
+    fn diagnose(&self) -> Vec<PowerCheck> {
+        let mut checks = vec![PowerCheck::new("OS", CheckStatus::Pass, os_version())];
+
+        checks.push(if Path::new("/run/systemd/system").exists() {
+            PowerCheck::new("INIT SYSTEM", CheckStatus::Pass, "systemd")
+        } else {
+            let init = fs::read_to_string("/proc/1/comm").unwrap_or_default();
+            PowerCheck::new(
+                "INIT SYSTEM",
+                CheckStatus::Fail,
+                format!("{} — eepy sleeps the computer through systemd", init.trim()),
+            )
+        });
+
+        let states = fs::read_to_string("/sys/power/state").unwrap_or_default();
+        let mem_sleep = fs::read_to_string("/sys/power/mem_sleep").unwrap_or_default();
+        checks.push(PowerCheck::new(
+            "KERNEL SLEEP STATES",
+            if states.contains("mem") {
+                CheckStatus::Pass
+            } else {
+                CheckStatus::Fail
+            },
+            format!("{} (mem_sleep: {})", states.trim(), mem_sleep.trim()),
+        ));
+
+        checks.push(logind_allows("SLEEP", "CanSuspend"));
+        checks.push(logind_allows("HIBERNATE", "CanHibernate"));
+        checks.push(logind_allows("SHUT DOWN", "CanPowerOff"));
+
+        checks.push(if wayland() {
+            PowerCheck::new(
+                "SCREEN OFF",
+                CheckStatus::Warn,
+                "Only works on X11; this is a Wayland session",
+            )
+        } else if on_path("xset") {
+            PowerCheck::new("SCREEN OFF", CheckStatus::Pass, "xset found")
+        } else {
+            PowerCheck::new(
+                "SCREEN OFF",
+                CheckStatus::Warn,
+                "Install xset (x11-xserver-utils)",
+            )
+        });
+
+        checks.push(if !on_path("rtcwake") {
+            PowerCheck::new(
+                "WAKE ALARM",
+                CheckStatus::Warn,
+                "Install rtcwake (util-linux)",
+            )
+        } else if probe("sudo", &["-n", "-l", "rtcwake"]).is_ok() {
+            PowerCheck::new(
+                "WAKE ALARM",
+                CheckStatus::Pass,
+                "rtcwake can run through sudo",
+            )
+        } else {
+            PowerCheck::new(
+                "WAKE ALARM",
+                CheckStatus::Warn,
+                "Add a passwordless sudoers entry for rtcwake",
+            )
+        });
+        checks
+    }
+}
@@ -0,0 +1,103 @@
+use chrono::{DateTime, Local};
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::power::{CheckStatus, PowerCapabilities, PowerCheck};
+
+use super::{probe, run, SystemPower};
+
+/// macOS decides on its own when to hibernate, so that isn't offered
+pub struct MacOs;
+
+impl SystemPower for MacOs {
+    fn capabilities(&self) -> PowerCapabilities {
+        PowerCapabilities {
+            suspend: true,
+            hibernate: false,
+            shutdown: true,
+            lock: true,
+            display_off: true,
+            wake: true,
+        }
+    }
+
+    fn suspend(&self) -> Result<(), EepyError> {
+        run("pmset", &["sleepnow"], "suspend")
+    }
+
+    fn shutdown(&self) -> Result<(), EepyError> {
+        run(
+            "osascript",
+            &["-e", "tell application \"System Events\" to shut down"],
+            "shut down",
+        )
+    }
+
+    /// The lock screen shortcut; needs the accessibility permission
+    fn lock(&self) -> Result<(), EepyError> {
+        run(
+            "osascript",
+            &[
+                "-e",
+                "tell application \"System Events\" to keystroke \"q\" using {control down, command down}",
+            ],
+            "lock the screen",
+        )
+    }
+
+    fn display_off(&self) -> Result<(), EepyError> {
+        run("pmset", &["displaysleepnow"], "turn the screen off")
+    }
+
+    /// Needs root, so it goes through `sudo -n` and relies on a passwordless
+    /// sudoers entry
+    fn wake_at(&self, at: DateTime<Local>) -> Result<(), EepyError> {
+        let when = at.format("%m/%d/%y %H:%M:%S").to_string();
+        run(
+            "sudo",
+            &["-n", "pmset", "schedule", "wake", &when],
+            "schedule wake",
+        )
+    }
+
+    fn diagnose(&self) -> Vec<PowerCheck> {
+        let version = probe("sw_vers", &["-productVersion"]).unwrap_or_default();
+        let mut checks = vec![PowerCheck::new(
+            "OS",
+            CheckStatus::Pass,
+            format!("macOS {}", version),
+        )];
+
+        // `pmset -g cap` lists the sleep states the hardware supports
+        checks.push(match probe("pmset", &["-g", "cap"]) {
+            Ok(caps) if caps.contains("Sleep") => {
+                PowerCheck::new("SLEEP", CheckStatus::Pass, "pmset can sleep this Mac")
+            }
+            Ok(_) => PowerCheck::new(
+                "SLEEP",
+                CheckStatus::Fail,
+                "This Mac reports no sleep state",
+            ),
+            Err(e) => PowerCheck::new("SLEEP", CheckStatus::Fail, e),
+        });
+
+        checks.push(PowerCheck::new(
+            "LOCK",
+            CheckStatus::Warn,
+            "Needs eepy allowed under Privacy & Security > Accessibility",
+        ));
+
+        checks.push(if probe("sudo", &["-n", "-l", "pmset"]).is_ok() {
+            PowerCheck::new(
+                "WAKE ALARM",
+                CheckStatus::Pass,
+                "pmset can run through sudo",
+            )
+        } else {
+            PowerCheck::new(
+                "WAKE ALARM",
+                CheckStatus::Warn,
+                "Add a passwordless sudoers entry for pmset",
+            )
+        });
+        checks
+    }
+}
@@ -0,0 +1,84 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Local};
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::power::{CheckStatus, PowerCapabilities, PowerCheck};
+
+use super::SystemPower;
+
+/// Pretends to run every action and logs it, so end-of-session handling can
+/// be exercised without the computer going to sleep
+pub struct MockPower {
+    fail: bool,
+    /// What it was asked to do, in order
+    actions: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockPower {
+    pub fn new(fail: bool) -> Self {
+        Self {
+            fail,
+            actions: Arc::default(),
+        }
+    }
+
+    /// The actions it's asked to do from now on, kept after it's boxed up
+    #[cfg(test)]
+    pub fn actions(&self) -> Arc<Mutex<Vec<String>>> {
+        self.actions.clone()
+    }
+
+    fn record(&self, what: String) -> Result<(), EepyError> {
+        tracing::warn!("[mock power] {}", what);
+        self.actions.lock().unwrap().push(what.clone());
+        if self.fail {
+            return Err(EepyError::CommandFailed(format!("Mock failed to {}", what)));
+        }
+        Ok(())
+    }
+}
+
+impl SystemPower for MockPower {
+    fn capabilities(&self) -> PowerCapabilities {
+        PowerCapabilities {
+            suspend: true,
+            hibernate: true,
+            shutdown: true,
+            lock: true,
+            display_off: true,
+            wake: true,
+        }
+    }
+
+    fn suspend(&self) -> Result<(), EepyError> {
+        self.record("suspend".to_string())
+    }
+
+    fn hibernate(&self) -> Result<(), EepyError> {
+        self.record("hibernate".to_string())
+    }
+
+    fn shutdown(&self) -> Result<(), EepyError> {
+        self.record("shut down".to_string())
+    }
+
+    fn lock(&self) -> Result<(), EepyError> {
+        self.record("lock the screen".to_string())
+    }
+
+    fn display_off(&self) -> Result<(), EepyError> {
+        self.record("turn the screen off".to_string())
+    }
+
+    fn wake_at(&self, at: DateTime<Local>) -> Result<(), EepyError> {
+        self.record(format!("wake at {}", at.format("%Y-%m-%d %H:%M")))
+    }
+
+    fn diagnose(&self) -> Vec<PowerCheck> {
+        vec![PowerCheck::new(
+            "MOCK POWER",
+            CheckStatus::Warn,
+            "EEPY_MOCK_POWER is set, so nothing really sleeps",
+        )]
+    }
+}
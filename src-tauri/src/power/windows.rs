@@ -0,0 +1,87 @@
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::power::{CheckStatus, PowerCapabilities, PowerCheck};
+
+use super::{probe, run, SystemPower};
+
+/// Broadcasts the monitor power message to every window
+const MONITOR_OFF: &str = "(Add-Type '[DllImport(\"user32.dll\")] public static extern int SendMessage(int hWnd, int msg, int wParam, int lParam);' -Name Monitor -PassThru)::SendMessage(0xFFFF, 0x0112, 0xF170, 2)";
+
+/// Where `powercfg /a` starts listing the sleep states that can't be used
+const UNAVAILABLE: &str = "not available";
+
+/// Whether `powercfg /a` lists `state` as available
+fn sleep_state(label: &str, state: &str, report: &Result<String, String>) -> PowerCheck {
+    match report {
+        Ok(report) => {
+            let available = report.split(UNAVAILABLE).next().unwrap_or_default();
+            if available.contains(state) {
+                PowerCheck::new(label, CheckStatus::Pass, format!("{} is available", state))
+            } else {
+                PowerCheck::new(
+                    label,
+                    CheckStatus::Fail,
+                    format!("{} is off or unsupported; see powercfg /a", state),
+                )
+            }
+        }
+        Err(e) => PowerCheck::new(label, CheckStatus::Fail, e.clone()),
+    }
+}
+
+/// Waking from sleep would need a scheduled task, so it isn't offered
+pub struct Windows;
+
+impl SystemPower for Windows {
+    fn capabilities(&self) -> PowerCapabilities {
+        PowerCapabilities {
+            suspend: true,
+            hibernate: true,
+            shutdown: true,
+            lock: true,
+            display_off: true,
+            wake: false,
+        }
+    }
+
+    fn suspend(&self) -> Result<(), EepyError> {
+        run(
+            "rundll32.exe",
+            &["powrprof.dll,SetSuspendState", "0", "1", "0"],
+            "suspend",
+        )
+    }
+
+    fn hibernate(&self) -> Result<(), EepyError> {
+        run("shutdown", &["/h"], "hibernate")
+    }
+
+    fn shutdown(&self) -> Result<(), EepyError> {
+        run("shutdown", &["/s", "/t", "0"], "shut down")
+    }
+
+    fn lock(&self) -> Result<(), EepyError> {
+        run(
+            "rundll32.exe",
+            &["user32.dll,LockWorkStation"],
+            "lock the screen",
+        )
+    }
+
+    fn display_off(&self) -> Result<(), EepyError> {
+        run(
+            "powershell",
+            &["-NoProfile", "-Command", MONITOR_OFF],
+            "turn the screen off",
+        )
+    }
+
+    fn diagnose(&self) -> Vec<PowerCheck> {
+        let version = probe("cmd", &["/C", "ver"]).unwrap_or_default();
+        let report = probe("powercfg", &["/a"]);
+        vec![
+            PowerCheck::new("OS", CheckStatus::Pass, version),
+            sleep_state("SLEEP", "Standby", &report),
+            sleep_state("HIBERNATE", "Hibernate", &report),
+        ]
+    }
+}
@@ -0,0 +1,456 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::library::LibraryEntry;
+use sleepy_video_shared::parental::Profile;
+use sleepy_video_shared::playback::PlaybackSettings;
+use sleepy_video_shared::profiles::{ProfileList, UserProfile, DEFAULT_PROFILE};
+use sleepy_video_shared::settings::Settings;
+use sleepy_video_shared::timer::TimerSettings;
+use tauri::{AppHandle, Manager, State};
+
+use crate::history::History;
+use crate::library::{self, Library};
+use crate::parental::ProfileState;
+use crate::settings::{self, SettingsStore};
+
+const PROFILES_FILE: &str = "profiles.json";
+
+/// A profile with the settings it keeps to itself. The active profile's
+/// live in the settings store; these are only up to date for the others.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredProfile {
+    id: String,
+    name: String,
+    #[serde(default)]
+    timer: TimerSettings,
+    #[serde(default)]
+    playback: PlaybackSettings,
+}
+
+impl StoredProfile {
+    fn new(id: String, name: String) -> Self {
+        Self {
+            id,
+            name,
+            timer: TimerSettings::default(),
+            playback: PlaybackSettings::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfilesFile {
+    active: String,
+    profiles: Vec<StoredProfile>,
+}
+
+impl Default for ProfilesFile {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_PROFILE.to_string(),
+            profiles: vec![StoredProfile::new(
+                DEFAULT_PROFILE.to_string(),
+                "Me".to_string(),
+            )],
+        }
+    }
+}
+
+/// Named profiles for everyone sharing the app. Each has its own timer and
+/// playback settings and its own video library and history files.
+pub struct Profiles {
+    path: PathBuf,
+    file: Mutex<ProfilesFile>,
+}
+
+impl Profiles {
+    pub fn load(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .app_config_dir()
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .join(PROFILES_FILE);
+        let file = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+
+        Self {
+            path,
+            file: Mutex::new(file),
+        }
+    }
+
+    /// Library file of the active profile. The default profile keeps the
+    /// one from before there were profiles.
+    pub fn library_file(&self) -> String {
+        library_file(&self.file.lock().unwrap().active)
+    }
+
+    /// History file of the active profile, kept the same way as its library
+    pub fn history_file(&self) -> String {
+        history_file(&self.file.lock().unwrap().active)
+    }
+
+    /// Every profile and each one's library, by profile ID
+    pub fn backup(&self) -> (ProfilesFile, BTreeMap<String, Vec<LibraryEntry>>) {
+        let file = self.file.lock().unwrap().clone();
+        let libraries = file
+            .profiles
+            .iter()
+            .map(|profile| {
+                let path = self.dir().join(library_file(&profile.id));
+                (profile.id.clone(), library::read_entries(&path))
+            })
+            .collect();
+        (file, libraries)
+    }
+
+    /// Check a backup's profiles before restoring them. Returns the names
+    /// of the profiles here it would remove.
+    pub fn check_restore(&self, file: &ProfilesFile) -> Result<Vec<String>, String> {
+        for (i, profile) in file.profiles.iter().enumerate() {
+            // IDs name files in the config dir
+            if !valid_id(&profile.id) {
+                return Err(format!("Invalid profile ID {}", profile.id));
+            }
+            if file.profiles[..i]
+                .iter()
+                .any(|other| other.id == profile.id)
+            {
+                return Err(format!("Profile {} is in there twice", profile.id));
+            }
+        }
+        if !file
+            .profiles
+            .iter()
+            .any(|profile| profile.id == file.active)
+        {
+            return Err(format!("No profile {}", file.active));
+        }
+        Ok(self
+            .removed_by(file)
+            .into_iter()
+            .map(|profile| profile.name)
+            .collect())
+    }
+
+    /// Profiles here that `file` doesn't have
+    fn removed_by(&self, file: &ProfilesFile) -> Vec<StoredProfile> {
+        self.file
+            .lock()
+            .unwrap()
+            .profiles
+            .iter()
+            .filter(|old| !file.profiles.iter().any(|profile| profile.id == old.id))
+            .cloned()
+            .collect()
+    }
+
+    /// Replace every profile and library with those from a backup, then
+    /// load the active profile's library and history. Histories aren't in
+    /// backups; those of profiles that are kept stay as they are. Profiles
+    /// the backup doesn't have lose their files only once everything else
+    /// is written.
+    pub fn restore(
+        &self,
+        file: ProfilesFile,
+        libraries: BTreeMap<String, Vec<LibraryEntry>>,
+        library: &Library,
+        history: &History,
+    ) -> Result<(), String> {
+        self.check_restore(&file)?;
+        let dir = self.dir();
+        let removed = self.removed_by(&file);
+
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+        for profile in &file.profiles {
+            let entries = libraries.get(&profile.id).cloned().unwrap_or_default();
+            let raw = serde_json::to_string_pretty(&entries)
+                .map_err(|e| format!("Failed to serialize library: {}", e))?;
+            fs::write(dir.join(library_file(&profile.id)), raw)
+                .map_err(|e| format!("Failed to write library: {}", e))?;
+        }
+
+        let active = file.active.clone();
+        self.update(|current| {
+            *current = file;
+            Ok(())
+        })?;
+        library.switch(dir.join(library_file(&active)));
+        history.switch(dir.join(history_file(&active)));
+
+        for profile in removed {
+            let _ = fs::remove_file(dir.join(library_file(&profile.id)));
+            let _ = fs::remove_file(dir.join(history_file(&profile.id)));
+        }
+        Ok(())
+    }
+
+    fn dir(&self) -> PathBuf {
+        self.path
+            .parent()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    fn list(&self) -> ProfileList {
+        let file = self.file.lock().unwrap();
+        ProfileList {
+            active: file.active.clone(),
+            profiles: file
+                .profiles
+                .iter()
+                .map(|profile| UserProfile {
+                    id: profile.id.clone(),
+                    name: profile.name.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Apply a change and write the result to disk
+    fn update(
+        &self,
+        change: impl FnOnce(&mut ProfilesFile) -> Result<(), String>,
+    ) -> Result<ProfileList, String> {
+        {
+            let mut file = self.file.lock().unwrap();
+            change(&mut file)?;
+
+            if let Some(dir) = self.path.parent() {
+                fs::create_dir_all(dir)
+                    .map_err(|e| format!("Failed to create config dir: {}", e))?;
+            }
+            let raw = serde_json::to_string_pretty(&*file)
+                .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+            fs::write(&self.path, raw).map_err(|e| format!("Failed to write profiles: {}", e))?;
+        }
+        Ok(self.list())
+    }
+}
+
+fn library_file(profile: &str) -> String {
+    if profile == DEFAULT_PROFILE {
+        "library.json".to_string()
+    } else {
+        format!("library-{}.json", profile)
+    }
+}
+
+fn history_file(profile: &str) -> String {
+    if profile == DEFAULT_PROFILE {
+        "history.json".to_string()
+    } else {
+        format!("history-{}.json", profile)
+    }
+}
+
+/// Whether `id` looks like one `new_id` makes, so it can name files
+fn valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && !id.starts_with('-')
+        && !id.ends_with('-')
+        && id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// File-safe ID from a profile name, made unique among `taken`
+fn new_id(name: &str, taken: &[StoredProfile]) -> String {
+    let slug: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug.trim_matches('-');
+    let base = if slug.is_empty() { "profile" } else { slug };
+
+    let mut id = base.to_string();
+    let mut n = 2;
+    while taken.iter().any(|profile| profile.id == id) {
+        id = format!("{}-{}", base, n);
+        n += 1;
+    }
+    id
+}
+
+/// The child in a parental profile can't touch other people's profiles
+fn check_parent(store: &SettingsStore, app: &AppHandle) -> Result<(), EepyError> {
+    if app.state::<ProfileState>().active(&store.get()) == Profile::Child {
+        return Err(EepyError::Locked(
+            "Profiles are locked by the parental profile".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn profile_list(profiles: State<Profiles>) -> ProfileList {
+    profiles.list()
+}
+
+#[tauri::command]
+pub fn profile_create(
+    app: AppHandle,
+    store: State<SettingsStore>,
+    profiles: State<Profiles>,
+    name: String,
+) -> Result<ProfileList, EepyError> {
+    check_parent(&store, &app)?;
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(EepyError::Invalid("Give the profile a name".to_string()));
+    }
+    let list = profiles.update(|file| {
+        let id = new_id(&name, &file.profiles);
+        file.profiles.push(StoredProfile::new(id, name));
+        Ok(())
+    })?;
+    Ok(list)
+}
+
+/// Delete a profile other than the active and the default one, along with
+/// its library and history
+#[tauri::command]
+pub fn profile_delete(
+    app: AppHandle,
+    store: State<SettingsStore>,
+    profiles: State<Profiles>,
+    id: String,
+) -> Result<ProfileList, EepyError> {
+    check_parent(&store, &app)?;
+    let list = profiles.update(|file| {
+        if id == file.active || id == DEFAULT_PROFILE {
+            return Err("This profile can't be deleted".to_string());
+        }
+        if !file.profiles.iter().any(|profile| profile.id == id) {
+            return Err(format!("No profile {}", id));
+        }
+        file.profiles.retain(|profile| profile.id != id);
+        Ok(())
+    })?;
+
+    if let Some(dir) = profiles.path.parent() {
+        let _ = fs::remove_file(dir.join(library_file(&id)));
+        let _ = fs::remove_file(dir.join(history_file(&id)));
+    }
+    Ok(list)
+}
+
+/// Make another profile the active one: its timer and playback settings go
+/// into the settings store and its library and history are loaded. Returns
+/// the settings as they are now.
+#[tauri::command]
+pub fn profile_switch(
+    app: AppHandle,
+    store: State<SettingsStore>,
+    profiles: State<Profiles>,
+    library: State<Library>,
+    history: State<History>,
+    id: String,
+) -> Result<Settings, EepyError> {
+    check_parent(&store, &app)?;
+    let previous = store.get();
+    let mut settings = previous.clone();
+
+    profiles.update(|file| {
+        if !file.profiles.iter().any(|profile| profile.id == id) {
+            return Err(format!("No profile {}", id));
+        }
+        // Keep the outgoing profile's settings for when it comes back
+        if let Some(current) = file.profiles.iter_mut().find(|p| p.id == file.active) {
+            current.timer = previous.timer.clone();
+            current.playback = previous.playback.clone();
+        }
+        if let Some(next) = file.profiles.iter().find(|p| p.id == id) {
+            settings.timer = next.timer.clone();
+            settings.playback = next.playback.clone();
+        }
+        file.active = id.clone();
+        Ok(())
+    })?;
+
+    store.save(settings.clone()).map_err(EepyError::Io)?;
+    settings::apply(&app, &previous, &settings);
+    if let Some(dir) = profiles.path.parent() {
+        library.switch(dir.join(library_file(&id)));
+        history.switch(dir.join(history_file(&id)));
+    }
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profiles(ids: &[&str]) -> ProfilesFile {
+        ProfilesFile {
+            active: DEFAULT_PROFILE.to_string(),
+            profiles: ids
+                .iter()
+                .map(|id| StoredProfile::new(id.to_string(), id.to_uppercase()))
+                .collect(),
+        }
+    }
+
+    fn here(ids: &[&str]) -> Profiles {
+        Profiles {
+            path: PathBuf::from("config").join(PROFILES_FILE),
+            file: Mutex::new(profiles(ids)),
+        }
+    }
+
+    #[test]
+    fn new_ids_are_valid() {
+        let taken = [StoredProfile::new("kid".to_string(), "Kid".to_string())];
+        for name in ["Kid", "Grandma Jo", "  ", "Zoë", "../../x"] {
+            let id = new_id(name, &taken);
+            assert!(valid_id(&id), "{} made {}", name, id);
+        }
+    }
+
+    #[test]
+    fn ids_that_leave_the_config_dir_are_invalid() {
+        for id in [
+            "", "../../x", "..", "a/b", "a\\b", "Kid", "-kid", "kid-", "kid.json",
+        ] {
+            assert!(!valid_id(id), "{}", id);
+        }
+    }
+
+    #[test]
+    fn restore_refuses_invalid_ids() {
+        let mut file = profiles(&[DEFAULT_PROFILE]);
+        file.profiles
+            .push(StoredProfile::new("../../x".to_string(), "X".to_string()));
+        assert!(here(&[DEFAULT_PROFILE]).check_restore(&file).is_err());
+    }
+
+    #[test]
+    fn restore_refuses_duplicates_and_a_missing_active_profile() {
+        let here = here(&[DEFAULT_PROFILE]);
+        let twice = profiles(&[DEFAULT_PROFILE, "kid", "kid"]);
+        assert!(here.check_restore(&twice).is_err());
+        let mut elsewhere = profiles(&["kid"]);
+        elsewhere.active = "grandma".to_string();
+        assert!(here.check_restore(&elsewhere).is_err());
+    }
+
+    #[test]
+    fn restore_names_the_profiles_it_drops() {
+        let here = here(&[DEFAULT_PROFILE, "kid", "guest"]);
+        let dropped = here
+            .check_restore(&profiles(&[DEFAULT_PROFILE, "kid", "grandma"]))
+            .unwrap();
+        assert_eq!(dropped, ["GUEST"]);
+        let kept = here.check_restore(&profiles(&[DEFAULT_PROFILE, "kid", "guest"]));
+        assert_eq!(kept.unwrap(), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use serde_json::json;
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::power::EndAction;
+use sleepy_video_shared::push::{PushService, PushSettings};
+use sysinfo::System;
+
+const TITLE: &str = "eepy";
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Name the machine so one topic can serve several computers
+fn machine() -> String {
+    System::host_name().unwrap_or_else(|| "This computer".to_string())
+}
+
+fn now() -> String {
+    chrono::Local::now().format("%H:%M").to_string()
+}
+
+pub fn start_message(minutes: u32) -> String {
+    format!(
+        "{} started a {} minute sleep timer at {}",
+        machine(),
+        minutes,
+        now()
+    )
+}
+
+pub fn end_message(action: EndAction) -> String {
+    let what = match action {
+        EndAction::Suspend => "suspended",
+        EndAction::Hibernate => "hibernated",
+        EndAction::Shutdown => "shut down",
+        EndAction::Lock => "locked its screen",
+        EndAction::ScreenOff => "turned its screen off",
+        EndAction::Nothing => "finished its sleep timer",
+    };
+    format!("{} {} at {}", machine(), what, now())
+}
+
+pub fn failed_message(error: &str) -> String {
+    format!("{} couldn't run its end action: {}", machine(), error)
+}
+
+/// Post one message to the configured service
+pub async fn send(settings: &PushSettings, message: &str) -> Result<(), String> {
+    let client = reqwest::Client::builder()
+        .timeout(TIMEOUT)
+        .build()
+        .unwrap_or_default();
+    let server = settings.server.trim().trim_end_matches('/');
+
+    let request = match settings.service {
+        PushService::Ntfy => client
+            .post(format!("{}/{}", server, settings.target.trim()))
+            .header("Title", TITLE)
+            .header("Tags", "whale")
+            .body(message.to_string()),
+        PushService::Gotify => client
+            .post(format!("{}/message", server))
+            .header("X-Gotify-Key", settings.token.trim())
+            .json(&json!({ "title": TITLE, "message": message, "priority": 5 })),
+        PushService::Telegram => client
+            .post(format!(
+                "https://api.telegram.org/bot{}/sendMessage",
+                settings.token.trim()
+            ))
+            .json(&json!({ "chat_id": settings.target.trim(), "text": message })),
+    };
+
+    tracing::info!("Sending {} push: {}", settings.service.label(), message);
+    request
+        .send()
+        .await
+        .and_then(|res| res.error_for_status())
+        .map(|_| ())
+        .map_err(|e| format!("Failed to send {} push: {}", settings.service.label(), e))
+}
+
+/// Send in the background, for moments where nothing waits on delivery
+pub fn spawn(settings: &PushSettings, message: String) {
+    let settings = settings.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = send(&settings, &message).await {
+            tracing::warn!("{}", e);
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn push_test(push: PushSettings) -> Result<(), EepyError> {
+    send(&push, &format!("Test message from {}", machine()))
+        .await
+        .map_err(EepyError::Network)
+}
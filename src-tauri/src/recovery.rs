@@ -0,0 +1,69 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::session::SavedSession;
+use tauri::{AppHandle, Manager, State};
+
+const SESSION_FILE: &str = "session.json";
+
+/// The running session on disk, so a crash or an accidental quit doesn't
+/// lose it. Whatever the last run left behind is offered once on startup.
+pub struct SessionRecovery {
+    path: PathBuf,
+    recovered: Mutex<Option<SavedSession>>,
+}
+
+impl SessionRecovery {
+    pub fn load(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .app_config_dir()
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .join(SESSION_FILE);
+        let recovered = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok());
+
+        Self {
+            path,
+            recovered: Mutex::new(recovered),
+        }
+    }
+
+    /// Forget the saved session, e.g. once it ended
+    pub fn clear(&self) -> Result<(), String> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => {
+                Err(format!("Failed to remove saved session: {}", e))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Session the last run was in the middle of, if any. Only handed out once.
+#[tauri::command]
+pub fn take_recovered_session(recovery: State<SessionRecovery>) -> Option<SavedSession> {
+    recovery.recovered.lock().unwrap().take()
+}
+
+/// Write the running session down; `None` once it's over
+#[tauri::command]
+pub fn save_session(
+    recovery: State<SessionRecovery>,
+    session: Option<SavedSession>,
+) -> Result<(), EepyError> {
+    let Some(session) = session else {
+        return recovery.clear().map_err(EepyError::Io);
+    };
+
+    if let Some(dir) = recovery.path.parent() {
+        fs::create_dir_all(dir).map_err(|e| EepyError::io("create config dir", e))?;
+    }
+    let raw = serde_json::to_string_pretty(&session)
+        .map_err(|e| format!("Failed to serialize session: {}", e))?;
+    fs::write(&recovery.path, raw).map_err(|e| EepyError::io("write session", e))
+}
@@ -0,0 +1,390 @@
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Mutex;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use qrcode::render::svg;
+use qrcode::QrCode;
+use serde::Deserialize;
+use sleepy_video_shared::control::{ControlCommand, StartRequest, DEFAULT_EXTEND_MINUTES};
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::remote::{Pairing, RemoteSettings};
+use sleepy_video_shared::session::{SessionEvent, SessionStatus};
+use subtle::ConstantTimeEq;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager, State as TauriState};
+use tokio::sync::broadcast;
+
+use crate::session::SessionState;
+use crate::settings::SettingsStore;
+use crate::{control, handoff};
+
+/// Mobile-friendly remote control page, served at `/`
+const REMOTE_PAGE: &str = include_str!("../remote/index.html");
+
+/// The running API server, if enabled
+#[derive(Default)]
+pub struct RemoteServer(Mutex<Option<JoinHandle<()>>>);
+
+#[derive(Clone)]
+struct ApiState {
+    app: AppHandle,
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ExtendBody {
+    minutes: u32,
+}
+
+#[derive(Deserialize)]
+struct SendBody {
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct MinutesQuery {
+    minutes: Option<u32>,
+}
+
+impl ApiState {
+    /// Accept the token as a bearer header or, for WebSockets and plain
+    /// links, as a `?token=` query parameter
+    fn authorize(&self, headers: &HeaderMap, query: &TokenQuery) -> Result<(), StatusCode> {
+        let bearer = headers
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        let given = bearer.or(query.token.as_deref()).unwrap_or_default();
+
+        // Constant time, so the token can't be guessed from how long a
+        // wrong one takes to turn away
+        let matches: bool = given.as_bytes().ct_eq(self.token.as_bytes()).into();
+        if !self.token.is_empty() && matches {
+            Ok(())
+        } else {
+            Err(StatusCode::UNAUTHORIZED)
+        }
+    }
+}
+
+/// Fill in a random token if none is set yet
+pub fn ensure_token(settings: &mut RemoteSettings) {
+    if settings.token.is_empty() {
+        settings.token = format!("{:032x}", rand::random::<u128>());
+    }
+}
+
+/// Stop any running server and start a new one if the API is enabled
+pub fn restart(app: &AppHandle, settings: &RemoteSettings) {
+    let server = app.state::<RemoteServer>();
+    let mut running = server.0.lock().unwrap();
+    if let Some(handle) = running.take() {
+        handle.abort();
+    }
+    if !settings.enabled {
+        return;
+    }
+
+    let host = if settings.bind_lan {
+        Ipv4Addr::UNSPECIFIED
+    } else {
+        Ipv4Addr::LOCALHOST
+    };
+    let addr = SocketAddr::from((host, settings.port));
+    let state = ApiState {
+        app: app.clone(),
+        token: settings.token.clone(),
+    };
+
+    *running = Some(tauri::async_runtime::spawn(async move {
+        let listener = match tokio::net::TcpListener::bind(addr).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                tracing::warn!("Remote API could not listen on {}: {}", addr, e);
+                return;
+            }
+        };
+        if let Err(e) = axum::serve(listener, router(state)).await {
+            tracing::warn!("Remote API stopped: {}", e);
+        }
+    }));
+}
+
+fn router(state: ApiState) -> Router {
+    Router::new()
+        .route("/", get(remote_page))
+        .route("/api/status", get(status))
+        .route("/api/start", post(start))
+        .route("/api/extend", post(extend))
+        .route("/api/cancel", post(cancel))
+        .route("/api/send", post(send))
+        .route("/api/events", get(events))
+        .route("/api/key/start", get(key_start))
+        .route("/api/key/extend", get(key_extend))
+        .route("/api/key/cancel", get(key_cancel))
+        .route("/api/key/toggle", get(key_toggle))
+        .route("/api/key/state", get(key_state))
+        .route("/api/key/icon.svg", get(key_icon))
+        .with_state(state)
+}
+
+/// Forward `command`, or answer 423 while strict mode locks the session
+fn forward(state: &ApiState, command: ControlCommand) -> Result<StatusCode, StatusCode> {
+    control::check_strict(&state.app, &command).map_err(|_| StatusCode::LOCKED)?;
+    control::send(&state.app, command);
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// The page holds no data itself, the token is checked by the API it calls
+async fn remote_page() -> Html<&'static str> {
+    Html(REMOTE_PAGE)
+}
+
+async fn status(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+) -> Result<Json<SessionStatus>, StatusCode> {
+    state.authorize(&headers, &query)?;
+    Ok(Json(state.app.state::<SessionState>().status()))
+}
+
+async fn start(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+    body: Option<Json<StartRequest>>,
+) -> Result<StatusCode, StatusCode> {
+    state.authorize(&headers, &query)?;
+    let request = body.map(|Json(request)| request).unwrap_or_default();
+    control::send(&state.app, ControlCommand::Start(request));
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn extend(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+    Json(body): Json<ExtendBody>,
+) -> Result<StatusCode, StatusCode> {
+    state.authorize(&headers, &query)?;
+    if body.minutes == 0 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    forward(
+        &state,
+        ControlCommand::Extend {
+            minutes: body.minutes,
+        },
+    )
+}
+
+async fn cancel(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+) -> Result<StatusCode, StatusCode> {
+    state.authorize(&headers, &query)?;
+    forward(&state, ControlCommand::Cancel)
+}
+
+// Key actions are plain GETs with everything in the query string, which is
+// all most Stream Deck and macro-pad "open URL" actions can send
+
+async fn key_start(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+    Query(minutes): Query<MinutesQuery>,
+) -> Result<StatusCode, StatusCode> {
+    state.authorize(&headers, &query)?;
+    let request = StartRequest {
+        minutes: minutes.minutes,
+        ..Default::default()
+    };
+    control::send(&state.app, ControlCommand::Start(request));
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn key_extend(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+    Query(minutes): Query<MinutesQuery>,
+) -> Result<StatusCode, StatusCode> {
+    state.authorize(&headers, &query)?;
+    let minutes = minutes.minutes.unwrap_or(DEFAULT_EXTEND_MINUTES);
+    if minutes == 0 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    forward(&state, ControlCommand::Extend { minutes })
+}
+
+async fn key_cancel(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+) -> Result<StatusCode, StatusCode> {
+    state.authorize(&headers, &query)?;
+    forward(&state, ControlCommand::Cancel)
+}
+
+/// One button that starts a session when idle and cancels a running one
+async fn key_toggle(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+    Query(minutes): Query<MinutesQuery>,
+) -> Result<StatusCode, StatusCode> {
+    state.authorize(&headers, &query)?;
+    let command = if state.app.state::<SessionState>().status().running {
+        ControlCommand::Cancel
+    } else {
+        ControlCommand::Start(StartRequest {
+            minutes: minutes.minutes,
+            ..Default::default()
+        })
+    };
+    forward(&state, command)
+}
+
+/// Short button title: minutes left, or "ZZZ" when idle
+fn key_title(status: &SessionStatus) -> String {
+    if status.running {
+        format!("{}m", status.remaining_seconds.div_ceil(60))
+    } else {
+        "ZZZ".to_string()
+    }
+}
+
+/// Plain text for plugins that poll a URL into the button title
+async fn key_state(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+) -> Result<String, StatusCode> {
+    state.authorize(&headers, &query)?;
+    Ok(key_title(&state.app.state::<SessionState>().status()))
+}
+
+/// 144px button image for plugins that poll a URL into the key icon
+async fn key_icon(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+) -> Result<Response, StatusCode> {
+    state.authorize(&headers, &query)?;
+    let status = state.app.state::<SessionState>().status();
+    let background = if status.running { "#1d3557" } else { "#222222" };
+    let svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"144\" height=\"144\">\
+         <rect width=\"144\" height=\"144\" fill=\"{}\"/>\
+         <text x=\"72\" y=\"58\" font-size=\"36\" text-anchor=\"middle\">🐳</text>\
+         <text x=\"72\" y=\"112\" font-size=\"34\" font-family=\"monospace\" \
+         fill=\"#ffffff\" text-anchor=\"middle\">{}</text></svg>",
+        background,
+        key_title(&status)
+    );
+    Ok(([(header::CONTENT_TYPE, "image/svg+xml")], svg).into_response())
+}
+
+/// Browser extension handoff: load the tab's video, maybe starting it
+async fn send(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+    Json(body): Json<SendBody>,
+) -> Result<StatusCode, StatusCode> {
+    state.authorize(&headers, &query)?;
+    if body.url.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    handoff::send(&state.app, body.url.trim().to_string());
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn events(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    if let Err(code) = state.authorize(&headers, &query) {
+        return code.into_response();
+    }
+    let session = state.app.state::<SessionState>();
+    let initial = session.status();
+    let events = session.subscribe();
+    ws.on_upgrade(move |socket| stream_events(socket, initial, events))
+}
+
+/// Send the current status, then every session event until the client leaves
+async fn stream_events(
+    mut socket: WebSocket,
+    initial: SessionStatus,
+    mut events: broadcast::Receiver<SessionEvent>,
+) {
+    let mut next = Some(SessionEvent::Status(initial));
+    loop {
+        let event = match next.take() {
+            Some(event) => event,
+            None => match events.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+        };
+        let Ok(json) = serde_json::to_string(&event) else {
+            continue;
+        };
+        if socket.send(Message::Text(json)).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Build the remote page URL (with the token in the fragment) and its QR code
+#[tauri::command]
+pub fn remote_pairing(store: TauriState<SettingsStore>) -> Result<Pairing, EepyError> {
+    let settings = store.get().remote;
+    if !settings.enabled || settings.token.is_empty() {
+        return Err(EepyError::Invalid(
+            "Enable the remote API and save first".to_string(),
+        ));
+    }
+
+    let host = if settings.bind_lan {
+        local_ip_address::local_ip()
+            .map_err(|e| {
+                EepyError::Network(format!(
+                    "Could not find this computer's network address: {}",
+                    e
+                ))
+            })?
+            .to_string()
+    } else {
+        Ipv4Addr::LOCALHOST.to_string()
+    };
+    let url = format!(
+        "http://{}:{}/#token={}",
+        host, settings.port, settings.token
+    );
+
+    let qr_svg = QrCode::new(url.as_bytes())
+        .map_err(|e| EepyError::Other(format!("Failed to build QR code: {}", e)))?
+        .render::<svg::Color>()
+        .min_dimensions(200, 200)
+        .build();
+
+    Ok(Pairing { url, qr_svg })
+}
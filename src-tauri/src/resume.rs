@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use sleepy_video_shared::error::EepyError;
+use tauri::{AppHandle, Manager, State};
+
+const POSITIONS_FILE: &str = "positions.json";
+
+/// Where playback of each video was last seen, in seconds, keyed by video ID
+pub struct PlaybackPositions {
+    path: PathBuf,
+    positions: Mutex<HashMap<String, u32>>,
+}
+
+impl PlaybackPositions {
+    pub fn load(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .app_config_dir()
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .join(POSITIONS_FILE);
+        let positions = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+
+        Self {
+            path,
+            positions: Mutex::new(positions),
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_playback_position(positions: State<PlaybackPositions>, video: String) -> Option<u32> {
+    positions.positions.lock().unwrap().get(&video).copied()
+}
+
+/// Remember how far a video got; zero forgets it, e.g. once it finished
+#[tauri::command]
+pub fn save_playback_position(
+    positions: State<PlaybackPositions>,
+    video: String,
+    seconds: u32,
+) -> Result<(), EepyError> {
+    let mut saved = positions.positions.lock().unwrap();
+    if seconds == 0 {
+        saved.remove(&video);
+    } else {
+        saved.insert(video, seconds);
+    }
+
+    if let Some(dir) = positions.path.parent() {
+        fs::create_dir_all(dir).map_err(|e| EepyError::io("create config dir", e))?;
+    }
+    let raw = serde_json::to_string_pretty(&*saved)
+        .map_err(|e| format!("Failed to serialize playback positions: {}", e))?;
+    fs::write(&positions.path, raw).map_err(|e| EepyError::io("write playback positions", e))
+}
@@ -0,0 +1,52 @@
+use std::process::Command;
+
+use sleepy_video_shared::display::{MAX_SCALE, MIN_SCALE};
+use tauri::{AppHandle, Manager};
+
+/// Text size the user set in the OS, as a factor. Windows calls it "Make text
+/// bigger", GNOME "Large text"; macOS has no system-wide setting.
+fn system_text_scale() -> f64 {
+    let found = if cfg!(target_os = "windows") {
+        Command::new("reg")
+            .args([
+                "query",
+                r"HKCU\Software\Microsoft\Accessibility",
+                "/v",
+                "TextScaleFactor",
+            ])
+            .output()
+            .ok()
+            .and_then(|out| {
+                // "    TextScaleFactor    REG_DWORD    0x7d"
+                let raw = String::from_utf8_lossy(&out.stdout).into_owned();
+                let hex = raw.split_whitespace().last()?.strip_prefix("0x")?;
+                let percent = u32::from_str_radix(hex, 16).ok()?;
+                Some(percent as f64 / 100.0)
+            })
+    } else if cfg!(target_os = "linux") {
+        Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.interface", "text-scaling-factor"])
+            .output()
+            .ok()
+            .and_then(|out| String::from_utf8_lossy(&out.stdout).trim().parse().ok())
+    } else {
+        None
+    };
+    found
+        .unwrap_or(1.0)
+        .clamp(MIN_SCALE as f64 / 100.0, MAX_SCALE as f64 / 100.0)
+}
+
+/// Zoom the main window to `scale` percent, or to the system's text size
+pub fn apply(app: &AppHandle, scale: Option<u32>) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let zoom = match scale {
+        Some(percent) => percent.clamp(MIN_SCALE, MAX_SCALE) as f64 / 100.0,
+        None => system_text_scale(),
+    };
+    if let Err(e) = window.set_zoom(zoom) {
+        tracing::warn!("Failed to scale the UI: {}", e);
+    }
+}
@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, Timelike};
+use sleepy_video_shared::control::{ControlCommand, StartRequest};
+use sleepy_video_shared::schedule::{reminder_message, WakeAlarm, REMINDER_EVENT};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::control;
+use crate::history::History;
+use crate::notifications;
+use crate::session::SessionState;
+use crate::settings::SettingsStore;
+use crate::window;
+
+/// Often enough to never miss a minute
+const CHECK_INTERVAL: Duration = Duration::from_secs(20);
+/// The weekly summary comes on Sunday evening, ahead of the new week
+const SUMMARY_WEEKDAY: u32 = 6;
+const SUMMARY_HOUR: u32 = 19;
+
+/// Watch the clock, nudge ahead of bedtime, start a session at the
+/// configured bedtime, sum up the week and sound the morning alarm. Settings are re-read on
+/// every check, so edits take effect without a restart.
+pub fn spawn(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut last_fired: Option<NaiveDate> = None;
+        let mut last_alarm: Option<NaiveDate> = None;
+        let mut last_reminder: Option<(NaiveDate, u32)> = None;
+        let mut last_summary: Option<NaiveDate> = None;
+        loop {
+            let settings = app.state::<SettingsStore>().get();
+            let now = Local::now();
+            let today = now.date_naive();
+
+            // Nothing to nudge towards once the timer runs, or at bedtime
+            // when the schedule starts it anyway
+            let minutes_left = settings.schedule.minutes_until(
+                now.weekday().num_days_from_monday(),
+                now.hour(),
+                now.minute(),
+            );
+            let minutes_left = minutes_left
+                .filter(|m| settings.reminders.is_due(*m))
+                .filter(|m| *m > 0 || !settings.schedule.enabled);
+            if let Some(minutes_left) = minutes_left {
+                if last_reminder != Some((today, minutes_left))
+                    && !app.state::<SessionState>().status().running
+                {
+                    last_reminder = Some((today, minutes_left));
+                    notifications::notify(&app, &reminder_message(minutes_left));
+                    if let Err(e) = app.emit(REMINDER_EVENT, minutes_left) {
+                        tracing::warn!("Failed to emit the bedtime reminder: {}", e);
+                    }
+                }
+            }
+
+            if last_fired != Some(today)
+                && settings.schedule.is_due(
+                    now.weekday().num_days_from_monday(),
+                    now.hour(),
+                    now.minute(),
+                )
+            {
+                last_fired = Some(today);
+                control::send(
+                    &app,
+                    ControlCommand::Start(StartRequest {
+                        minutes: Some(settings.schedule.minutes),
+                        ..Default::default()
+                    }),
+                );
+                if settings.notifications.enabled {
+                    notifications::notify(
+                        &app,
+                        &format!(
+                            "Bedtime! {} minute sleep timer started 🐳",
+                            settings.schedule.minutes
+                        ),
+                    );
+                }
+            }
+
+            if settings.streaks.weekly_summary
+                && last_summary != Some(today)
+                && now.weekday().num_days_from_monday() == SUMMARY_WEEKDAY
+                && now.hour() >= SUMMARY_HOUR
+            {
+                last_summary = Some(today);
+                let report = app.state::<History>().streak(&settings.schedule);
+                notifications::notify(&app, &report.summary_message());
+            }
+
+            // Checked right after a resume, which is when it matters
+            if last_alarm != Some(today)
+                && settings.wake.is_due(
+                    now.weekday().num_days_from_monday(),
+                    now.hour(),
+                    now.minute(),
+                )
+            {
+                last_alarm = Some(today);
+                window::show_main_window(&app);
+                control::send(&app, ControlCommand::WakeAlarm);
+            }
+
+            std::thread::sleep(CHECK_INTERVAL);
+        }
+    });
+}
+
+/// When the alarm next goes off after `now`, to wake the computer for it
+pub fn next_wake(alarm: &WakeAlarm, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    let (hour, minute) = alarm.parsed_time().filter(|_| alarm.enabled)?;
+    (0..=7)
+        .filter_map(|offset| now.date_naive().checked_add_days(Days::new(offset)))
+        .filter(|day| alarm.days.contains(&day.weekday().num_days_from_monday()))
+        .filter_map(|day| {
+            day.and_hms_opt(hour, minute, 0)?
+                .and_local_timezone(Local)
+                .earliest()
+        })
+        .find(|at| *at > now)
+}
@@ -0,0 +1,459 @@
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+
+use sleepy_video_shared::display::{DisplaySettings, SYSTEM_DIM_EVENT};
+use sleepy_video_shared::lights::{dim_level, MAX_DIM};
+use sleepy_video_shared::pipeline::{Stage, StageOutcome, StageReport};
+use sleepy_video_shared::power::EndAction;
+use sleepy_video_shared::session::SessionEvent;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast;
+
+use crate::focus::run;
+use crate::session::SessionState;
+
+/// The screen never goes darker than this, so the countdown stays readable
+const MIN_BRIGHTNESS: f64 = 0.1;
+/// Colour temperature the night light starts from and warms to
+const DAY_KELVIN: u32 = 6500;
+const NIGHT_KELVIN: u32 = 2700;
+/// Only change the screen in steps this big, every call is a process
+const STEP: f64 = 0.05;
+
+const GNOME_COLOR: &str = "org.gnome.settings-daemon.plugins.color";
+/// Night light settings changed for the session, put back after it
+const GNOME_NIGHT_KEYS: [&str; 5] = [
+    "night-light-enabled",
+    "night-light-temperature",
+    "night-light-schedule-automatic",
+    "night-light-schedule-from",
+    "night-light-schedule-to",
+];
+
+/// How this desktop lets the whole screen be dimmed, found at startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    /// `xrandr` brightness and gamma on every output
+    X11,
+    /// `gammastep` with the wlr-gamma-control protocol, for Sway and the like
+    Wlroots,
+    /// The backlight through gnome-settings-daemon, and its night light
+    Gnome,
+    /// The backlight through PowerDevil, and KWin's night light preview
+    Kde,
+}
+
+fn available(program: &str) -> bool {
+    Command::new(program)
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok()
+}
+
+impl Backend {
+    /// X11 is worked out first: GNOME and KDE on X11 go through xrandr too
+    fn detect() -> Option<Self> {
+        if !cfg!(target_os = "linux") {
+            return None;
+        }
+        let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some()
+            || std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t == "wayland");
+        if !wayland {
+            return available("xrandr").then_some(Backend::X11);
+        }
+        let desktop = std::env::var("XDG_CURRENT_DESKTOP")
+            .unwrap_or_default()
+            .to_uppercase();
+        if desktop.contains("GNOME") {
+            Some(Backend::Gnome)
+        } else if desktop.contains("KDE") {
+            Some(Backend::Kde)
+        } else {
+            available("gammastep").then_some(Backend::Wlroots)
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Backend::X11 => "X11 (xrandr)",
+            Backend::Wlroots => "Wayland (gammastep)",
+            Backend::Gnome => "GNOME",
+            Backend::Kde => "KDE Plasma",
+        }
+    }
+}
+
+/// What to put back once the session is over
+enum Restore {
+    X11 {
+        outputs: Vec<String>,
+    },
+    /// The gamma lasts as long as gammastep runs
+    Wlroots(Option<Child>),
+    Gnome {
+        brightness: Option<String>,
+        night_light: Vec<(&'static str, String)>,
+    },
+    Kde {
+        brightness: Option<String>,
+        night_light: bool,
+    },
+}
+
+/// The desktop's dimming, the task following the session with it, and what
+/// it changed
+pub struct ScreenDim {
+    backend: Option<Backend>,
+    task: Mutex<Option<JoinHandle<()>>>,
+    changed: Mutex<Option<Restore>>,
+}
+
+impl Default for ScreenDim {
+    fn default() -> Self {
+        Self {
+            backend: Backend::detect(),
+            task: Mutex::new(None),
+            changed: Mutex::new(None),
+        }
+    }
+}
+
+fn gdbus(dest: &str, path: &str, method: &str, args: &[&str]) -> Result<String, String> {
+    let mut all = vec![
+        "call",
+        "--session",
+        "--dest",
+        dest,
+        "--object-path",
+        path,
+        "--method",
+        method,
+    ];
+    all.extend_from_slice(args);
+    run("gdbus", &all)
+}
+
+/// The number in a gdbus reply like `(<int32 40>,)`. GNOME answers -1
+/// without a backlight.
+fn number(reply: &str) -> Option<String> {
+    if reply.contains('-') {
+        return None;
+    }
+    let digits: String = reply
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    (!digits.is_empty()).then_some(digits)
+}
+
+/// Brightness and colour temperature for a dim level
+fn target(dim: f64, night_light: bool) -> (f64, u32) {
+    let brightness = (1.0 - dim).max(MIN_BRIGHTNESS);
+    let kelvin = if night_light {
+        DAY_KELVIN - ((DAY_KELVIN - NIGHT_KELVIN) as f64 * dim / MAX_DIM).round() as u32
+    } else {
+        DAY_KELVIN
+    };
+    (brightness, kelvin.max(NIGHT_KELVIN))
+}
+
+/// xrandr gamma for a colour temperature, taking blue down more than green
+fn gamma(kelvin: u32) -> String {
+    let warmth = (DAY_KELVIN - kelvin) as f64 / (DAY_KELVIN - NIGHT_KELVIN) as f64;
+    format!("1:{:.2}:{:.2}", 1.0 - 0.3 * warmth, 1.0 - 0.65 * warmth)
+}
+
+fn x11_outputs() -> Result<Vec<String>, String> {
+    let out = run("xrandr", &["--query"])?;
+    Ok(out
+        .lines()
+        .filter(|line| line.contains(" connected"))
+        .filter_map(|line| line.split_whitespace().next().map(str::to_string))
+        .collect())
+}
+
+fn x11_set(outputs: &[String], brightness: f64, gamma: &str) -> Result<(), String> {
+    for output in outputs {
+        run(
+            "xrandr",
+            &[
+                "--output",
+                output,
+                "--brightness",
+                &format!("{:.2}", brightness),
+                "--gamma",
+                gamma,
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+const GNOME_POWER: &str = "org.gnome.SettingsDaemon.Power";
+const GNOME_POWER_PATH: &str = "/org/gnome/SettingsDaemon/Power";
+const GNOME_SCREEN: &str = "org.gnome.SettingsDaemon.Power.Screen";
+
+fn gnome_brightness() -> Option<String> {
+    let reply = gdbus(
+        GNOME_POWER,
+        GNOME_POWER_PATH,
+        "org.freedesktop.DBus.Properties.Get",
+        &[GNOME_SCREEN, "Brightness"],
+    );
+    reply.ok().as_deref().and_then(number)
+}
+
+fn gnome_set_brightness(percent: &str) -> Result<(), String> {
+    gdbus(
+        GNOME_POWER,
+        GNOME_POWER_PATH,
+        "org.freedesktop.DBus.Properties.Set",
+        &[GNOME_SCREEN, "Brightness", &format!("<int32 {}>", percent)],
+    )
+    .map(|_| ())
+}
+
+fn gsettings_set(key: &str, value: &str) -> Result<(), String> {
+    run("gsettings", &["set", GNOME_COLOR, key, value]).map(|_| ())
+}
+
+const KDE_POWER: &str = "org.kde.Solid.PowerManagement";
+const KDE_BRIGHTNESS_PATH: &str = "/org/kde/Solid/PowerManagement/Actions/BrightnessControl";
+const KDE_BRIGHTNESS: &str = "org.kde.Solid.PowerManagement.Actions.BrightnessControl";
+
+fn kde_brightness() -> Option<String> {
+    let method = format!("{}.brightness", KDE_BRIGHTNESS);
+    let reply = gdbus(KDE_POWER, KDE_BRIGHTNESS_PATH, &method, &[]);
+    reply.ok().as_deref().and_then(number)
+}
+
+fn kde_set_brightness(value: &str) -> Result<(), String> {
+    let method = format!("{}.setBrightness", KDE_BRIGHTNESS);
+    gdbus(KDE_POWER, KDE_BRIGHTNESS_PATH, &method, &[value]).map(|_| ())
+}
+
+fn kde_night_light(method: &str, args: &[&str]) -> Result<(), String> {
+    let method = format!("org.kde.KWin.NightLight.{}", method);
+    gdbus("org.kde.KWin", "/org/kde/KWin/NightLight", &method, args).map(|_| ())
+}
+
+/// Remember what the screen was like and get it ready, the first time in a
+/// session
+fn begin(backend: Backend, night_light: bool) -> Result<Restore, String> {
+    Ok(match backend {
+        Backend::X11 => Restore::X11 {
+            outputs: x11_outputs()?,
+        },
+        Backend::Wlroots => Restore::Wlroots(None),
+        Backend::Gnome => {
+            let mut previous = Vec::new();
+            if night_light {
+                for key in GNOME_NIGHT_KEYS {
+                    previous.push((key, run("gsettings", &["get", GNOME_COLOR, key])?));
+                }
+                // Night light only shows inside its schedule
+                gsettings_set("night-light-schedule-automatic", "false")?;
+                gsettings_set("night-light-schedule-from", "0.0")?;
+                gsettings_set("night-light-schedule-to", "23.99")?;
+                gsettings_set("night-light-enabled", "true")?;
+            }
+            Restore::Gnome {
+                brightness: gnome_brightness(),
+                night_light: previous,
+            }
+        }
+        Backend::Kde => Restore::Kde {
+            brightness: kde_brightness(),
+            night_light,
+        },
+    })
+}
+
+impl ScreenDim {
+    fn set(&self, backend: Backend, dim: f64, night_light: bool) -> Result<(), String> {
+        let mut changed = self.changed.lock().unwrap();
+        if changed.is_none() {
+            *changed = Some(begin(backend, night_light)?);
+        }
+        let (brightness, kelvin) = target(dim, night_light);
+        match changed.as_mut() {
+            Some(Restore::X11 { outputs }) => x11_set(outputs, brightness, &gamma(kelvin)),
+            Some(Restore::Wlroots(running)) => {
+                if let Some(mut child) = running.take() {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                }
+                *running = Some(
+                    Command::new("gammastep")
+                        .args(["-m", "wayland", "-O", &kelvin.to_string()])
+                        .args(["-b", &format!("{:.2}", brightness)])
+                        .stdout(Stdio::null())
+                        .stderr(Stdio::null())
+                        .spawn()
+                        .map_err(|e| format!("Failed to run gammastep: {}", e))?,
+                );
+                Ok(())
+            }
+            Some(Restore::Gnome {
+                brightness: previous,
+                ..
+            }) => {
+                if night_light {
+                    // GNOME's night light stops at 1700K and starts warm
+                    let kelvin = format!("uint32 {}", kelvin.min(4700));
+                    gsettings_set("night-light-temperature", &kelvin)?;
+                }
+                match previous {
+                    Some(start) => {
+                        let start: f64 = start.parse().unwrap_or(100.0);
+                        gnome_set_brightness(&((start * brightness).round() as u32).to_string())
+                    }
+                    // No backlight, e.g. an external monitor
+                    None if night_light => Ok(()),
+                    None => Err("There's no backlight GNOME can dim".to_string()),
+                }
+            }
+            Some(Restore::Kde {
+                brightness: previous,
+                ..
+            }) => {
+                if night_light {
+                    kde_night_light("preview", &[&kelvin.to_string()])?;
+                }
+                match previous {
+                    Some(start) => {
+                        let start: f64 = start.parse().unwrap_or(100.0);
+                        kde_set_brightness(&((start * brightness).round() as u32).to_string())
+                    }
+                    None if night_light => Ok(()),
+                    None => Err("There's no backlight Plasma can dim".to_string()),
+                }
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Put the screen back. Safe to call when nothing changed.
+    pub fn restore(&self) {
+        let Some(changed) = self.changed.lock().unwrap().take() else {
+            return;
+        };
+        let result = match changed {
+            Restore::X11 { outputs } => x11_set(&outputs, 1.0, "1:1:1"),
+            Restore::Wlroots(None) => Ok(()),
+            Restore::Wlroots(Some(mut child)) => {
+                let _ = child.kill();
+                child.wait().map(|_| ()).map_err(|e| e.to_string())
+            }
+            Restore::Gnome {
+                brightness,
+                night_light,
+            } => night_light
+                .iter()
+                .try_for_each(|(key, value)| gsettings_set(key, value))
+                .and_then(|_| brightness.map_or(Ok(()), |b| gnome_set_brightness(&b))),
+            Restore::Kde {
+                brightness,
+                night_light,
+            } => {
+                let stopped = if night_light {
+                    kde_night_light("stopPreview", &[])
+                } else {
+                    Ok(())
+                };
+                stopped.and_then(|_| brightness.map_or(Ok(()), |b| kde_set_brightness(&b)))
+            }
+        };
+        if let Err(e) = result {
+            tracing::warn!("Failed to put the screen back: {}", e);
+        }
+    }
+}
+
+/// Stop following the session, put the screen back and start again with new
+/// settings
+pub fn restart(app: &AppHandle, settings: &DisplaySettings) {
+    let screen = app.state::<ScreenDim>();
+    let mut running = screen.task.lock().unwrap();
+    if let Some(handle) = running.take() {
+        handle.abort();
+    }
+    screen.restore();
+    let Some(backend) = screen.backend.filter(|_| settings.system_dim) else {
+        let _ = app.emit(SYSTEM_DIM_EVENT, None::<&str>);
+        return;
+    };
+
+    let _ = app.emit(SYSTEM_DIM_EVENT, Some(backend.label()));
+    let events = app.state::<SessionState>().subscribe();
+    *running = Some(tauri::async_runtime::spawn(follow_session(
+        app.clone(),
+        backend,
+        settings.night_light,
+        events,
+    )));
+}
+
+/// Dim along with the overlay's curve. If the desktop won't, hand the dim
+/// back to the overlay for the rest of the session. Put the screen back when
+/// the session is cancelled, or once the end action returns.
+async fn follow_session(
+    app: AppHandle,
+    backend: Backend,
+    night_light: bool,
+    mut events: broadcast::Receiver<SessionEvent>,
+) {
+    let mut last: Option<f64> = None;
+    let mut failed = false;
+    loop {
+        let screen = app.state::<ScreenDim>();
+        match events.recv().await {
+            Ok(SessionEvent::Status(status)) if status.running && !failed => {
+                let dim = dim_level(status.total_seconds, status.remaining_seconds);
+                let dim = (dim / STEP).round() * STEP;
+                if last == Some(dim) {
+                    continue;
+                }
+                last = Some(dim);
+                if let Err(e) = screen.set(backend, dim, night_light) {
+                    tracing::warn!(
+                        "Failed to dim the screen through {}: {}",
+                        backend.label(),
+                        e
+                    );
+                    failed = true;
+                    screen.restore();
+                    let _ = app.emit(SYSTEM_DIM_EVENT, None::<&str>);
+                }
+            }
+            Ok(
+                SessionEvent::Stage(StageReport {
+                    stage: Stage::EndAction,
+                    outcome: StageOutcome::Done | StageOutcome::Failed(_),
+                })
+                | SessionEvent::Ended(EndAction::Nothing)
+                | SessionEvent::Cancelled,
+            ) => {
+                screen.restore();
+                last = None;
+                if failed {
+                    failed = false;
+                    let _ = app.emit(SYSTEM_DIM_EVENT, Some(backend.label()));
+                }
+            }
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+/// Which desktop would dim the screen, for settings; `None` leaves it to the
+/// window's overlay
+#[tauri::command]
+pub fn system_dim_backend(screen: tauri::State<ScreenDim>) -> Option<String> {
+    screen.backend.map(|backend| backend.label().to_string())
+}
@@ -0,0 +1,165 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::search::{SearchResult, SearchSettings};
+use tauri::State;
+
+use crate::settings::SettingsStore;
+
+const DATA_API: &str = "https://www.googleapis.com/youtube/v3/search";
+const MAX_RESULTS: usize = 12;
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Searches YouTube for the search box, so bedtime doesn't need a browser
+pub struct VideoSearch {
+    client: reqwest::Client,
+}
+
+impl Default for VideoSearch {
+    fn default() -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct DataApiResults {
+    items: Vec<DataApiItem>,
+}
+
+#[derive(Deserialize)]
+struct DataApiItem {
+    id: DataApiId,
+    snippet: DataApiSnippet,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DataApiId {
+    video_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DataApiSnippet {
+    title: String,
+    channel_title: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InvidiousVideo {
+    video_id: String,
+    title: String,
+    author: String,
+}
+
+/// The Data API returns titles HTML-escaped
+fn unescape(raw: &str) -> String {
+    raw.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+impl VideoSearch {
+    async fn data_api(&self, key: &str, query: &str) -> Result<Vec<SearchResult>, String> {
+        let max_results = MAX_RESULTS.to_string();
+        let results = self
+            .client
+            .get(DATA_API)
+            .query(&[
+                ("part", "snippet"),
+                ("type", "video"),
+                ("maxResults", max_results.as_str()),
+                ("q", query),
+                ("key", key),
+            ])
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|e| format!("YouTube search failed: {}", e))?
+            .json::<DataApiResults>()
+            .await
+            .map_err(|e| format!("Unexpected YouTube search reply: {}", e))?;
+
+        Ok(results
+            .items
+            .into_iter()
+            .filter_map(|item| {
+                Some(SearchResult {
+                    video_id: item.id.video_id?,
+                    title: unescape(&item.snippet.title),
+                    channel: unescape(&item.snippet.channel_title),
+                })
+            })
+            .collect())
+    }
+
+    async fn invidious(&self, instance: &str, query: &str) -> Result<Vec<SearchResult>, String> {
+        let videos = self
+            .client
+            .get(format!(
+                "{}/api/v1/search",
+                instance.trim().trim_end_matches('/')
+            ))
+            .query(&[("q", query), ("type", "video")])
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|e| format!("Invidious search failed: {}", e))?
+            .json::<Vec<InvidiousVideo>>()
+            .await
+            .map_err(|e| format!("Unexpected Invidious search reply: {}", e))?;
+
+        Ok(videos
+            .into_iter()
+            .take(MAX_RESULTS)
+            .map(|video| SearchResult {
+                video_id: video.video_id,
+                title: video.title,
+                channel: video.author,
+            })
+            .collect())
+    }
+
+    pub async fn search(
+        &self,
+        settings: &SearchSettings,
+        query: &str,
+    ) -> Result<Vec<SearchResult>, String> {
+        let key = settings.api_key.trim();
+        if !key.is_empty() {
+            self.data_api(key, query).await
+        } else if !settings.invidious_url.trim().is_empty() {
+            self.invidious(&settings.invidious_url, query).await
+        } else {
+            Err(
+                "Add a YouTube API key or an Invidious instance under SEARCH in settings"
+                    .to_string(),
+            )
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn search_videos(
+    search: State<'_, VideoSearch>,
+    store: State<'_, SettingsStore>,
+    query: String,
+) -> Result<Vec<SearchResult>, EepyError> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    search
+        .search(&store.get().search, query)
+        .await
+        .map_err(EepyError::Network)
+}
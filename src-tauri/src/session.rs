@@ -0,0 +1,127 @@
+use std::sync::Mutex;
+
+use sleepy_video_shared::milestones::Milestone;
+use sleepy_video_shared::session::{SessionEvent, SessionStatus};
+use sleepy_video_shared::webhooks::WebhookEvent;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::broadcast;
+
+use crate::handoff::LastDuration;
+use crate::plugins::{self, Hook};
+use crate::settings::SettingsStore;
+use crate::strict::StrictLock;
+use crate::{notifications, push, taskbar, webhooks};
+
+/// Backend mirror of the frontend countdown, plus the one stream of changes
+/// that the windows, the remote API and every integration follow
+pub struct SessionState {
+    status: Mutex<SessionStatus>,
+    events: broadcast::Sender<SessionEvent>,
+}
+
+impl Default for SessionState {
+    fn default() -> Self {
+        let (events, _) = broadcast::channel(64);
+        Self {
+            status: Mutex::new(SessionStatus::default()),
+            events,
+        }
+    }
+}
+
+impl SessionState {
+    pub fn status(&self) -> SessionStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<SessionEvent> {
+        self.events.subscribe()
+    }
+
+    /// Send `event` to the windows as its own Tauri event, then to every
+    /// backend subscriber
+    pub fn publish(&self, app: &AppHandle, event: SessionEvent) {
+        let name = event.name();
+        let emitted = match &event {
+            SessionEvent::Started(status) | SessionEvent::Status(status) => app.emit(name, status),
+            SessionEvent::Milestone(milestone) => app.emit(name, milestone),
+            SessionEvent::Ended(action) => app.emit(name, action),
+            SessionEvent::Cancelled => app.emit(name, ()),
+            SessionEvent::Stage(report) => app.emit(name, report),
+        };
+        if let Err(e) = emitted {
+            tracing::warn!("Failed to send {}: {}", name, e);
+        }
+        // Sending only fails when nobody is subscribed
+        let _ = self.events.send(event);
+    }
+}
+
+/// The frontend reports the countdown every time it changes
+#[tauri::command]
+pub fn report_status(
+    app: AppHandle,
+    store: State<SettingsStore>,
+    session: State<SessionState>,
+    status: SessionStatus,
+) {
+    let previous = std::mem::replace(&mut *session.status.lock().unwrap(), status.clone());
+    if status.running && !previous.running {
+        app.state::<StrictLock>().reset();
+        app.state::<LastDuration>()
+            .remember(status.total_seconds / 60);
+        let settings = store.get();
+        webhooks::fire(
+            &app,
+            &settings.webhooks,
+            WebhookEvent::Start,
+            None,
+            status.clone(),
+        );
+        plugins::fire(&app, &settings.plugins, Hook::Start, status.clone());
+        if settings.push.enabled && settings.push.on_start {
+            push::spawn(
+                &settings.push,
+                push::start_message(status.total_seconds / 60),
+            );
+        }
+        session.publish(&app, SessionEvent::Started(status.clone()));
+    }
+    taskbar::update(&app, &previous, &status);
+    session.publish(&app, SessionEvent::Status(status));
+}
+
+#[tauri::command]
+pub fn session_status(session: State<SessionState>) -> SessionStatus {
+    session.status()
+}
+
+/// The frontend reports a session stopped early, so integrations can undo
+/// what they did for it
+#[tauri::command]
+pub fn report_cancelled(app: AppHandle, session: State<SessionState>) {
+    session.publish(&app, SessionEvent::Cancelled);
+}
+
+/// The frontend reports each milestone as the countdown reaches it, with
+/// `message` wording it in the UI's language; fan it out to every enabled
+/// integration
+#[tauri::command]
+pub fn report_milestone(
+    app: AppHandle,
+    store: State<SettingsStore>,
+    session: State<SessionState>,
+    milestone: Milestone,
+    message: String,
+) {
+    let settings = store.get();
+    notifications::on_milestone(&app, &settings, &message);
+    webhooks::fire(
+        &app,
+        &settings.webhooks,
+        WebhookEvent::Milestone,
+        Some(&message),
+        session.status(),
+    );
+    session.publish(&app, SessionEvent::Milestone(milestone));
+}
@@ -0,0 +1,180 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::parental::Profile;
+use sleepy_video_shared::settings::Settings;
+use tauri::{AppHandle, Manager, State};
+
+use crate::offline::OfflineAudio;
+use crate::parental::ProfileState;
+use crate::strict::StrictLock;
+#[cfg(desktop)]
+use crate::{autostart, mediakeys, monitor_dim, tray, widget};
+use crate::{
+    discord, lights, mqtt, remote, scale, screen_dim, sonos, spotify, windows_integration,
+};
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Settings loaded from disk and kept in sync with it on every save
+pub struct SettingsStore {
+    path: PathBuf,
+    settings: Mutex<Settings>,
+}
+
+impl SettingsStore {
+    /// Load settings from the app config directory, falling back to defaults
+    /// if the file is missing or unreadable
+    pub fn load(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .app_config_dir()
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .join(SETTINGS_FILE);
+
+        let mut settings: Settings = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        settings.pipeline.fill_missing();
+
+        Self {
+            path,
+            settings: Mutex::new(settings),
+        }
+    }
+
+    /// Settings that are never written to disk
+    #[cfg(test)]
+    pub fn in_memory(settings: Settings) -> Self {
+        Self {
+            path: PathBuf::new(),
+            settings: Mutex::new(settings),
+        }
+    }
+
+    pub fn get(&self) -> Settings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    pub fn save(&self, settings: Settings) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+        }
+        let raw = serde_json::to_string_pretty(&settings)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        fs::write(&self.path, raw).map_err(|e| format!("Failed to write settings: {}", e))?;
+
+        *self.settings.lock().unwrap() = settings;
+        Ok(())
+    }
+}
+
+#[tauri::command]
+pub fn get_settings(store: State<SettingsStore>) -> Settings {
+    store.get()
+}
+
+/// Persist new settings, apply the ones with side effects and return what
+/// was actually saved (the backend may fill in generated values)
+#[tauri::command]
+pub fn save_settings(
+    app: AppHandle,
+    store: State<SettingsStore>,
+    mut settings: Settings,
+) -> Result<Settings, EepyError> {
+    let previous = store.get();
+    let profiles = app.state::<ProfileState>();
+    if profiles.active(&previous) == Profile::Child {
+        return Err(EepyError::Locked(
+            "Settings are locked by the parental profile".to_string(),
+        ));
+    }
+
+    // The password only changes through `parental_set_password`, and the
+    // profile can't be switched on without one
+    settings.parental.password_hash = previous.parental.password_hash.clone();
+    if settings.parental.enabled && settings.parental.password_hash.is_empty() {
+        return Err(EepyError::Invalid(
+            "Set a parent password first".to_string(),
+        ));
+    }
+
+    // The PIN only changes through `strict_set_pin`, and strict mode itself
+    // not at all while it locks a session
+    settings.strict.pin_hash = previous.strict.pin_hash.clone();
+    if settings.strict != previous.strict && app.state::<StrictLock>().locked(&app) {
+        return Err(EepyError::Locked(
+            "Strict mode can't be changed while it locks a session".to_string(),
+        ));
+    }
+
+    #[cfg(desktop)]
+    autostart::apply(&app, &settings.autostart)?;
+    if settings.remote.enabled {
+        remote::ensure_token(&mut settings.remote);
+    }
+    store.save(settings.clone()).map_err(EepyError::Io)?;
+    // Whoever switches the parental profile on is the parent, until they sign out
+    if settings.parental.enabled && !previous.parental.enabled {
+        profiles.sign_in();
+    }
+
+    apply(&app, &previous, &settings);
+    Ok(settings)
+}
+
+/// Restart the services whose settings changed
+pub fn apply(app: &AppHandle, previous: &Settings, settings: &Settings) {
+    if previous.remote != settings.remote {
+        remote::restart(app, &settings.remote);
+    }
+    if previous.mqtt != settings.mqtt {
+        mqtt::restart(app, &settings.mqtt);
+    }
+    if previous.lights != settings.lights {
+        lights::restart(app, &settings.lights);
+    }
+    if previous.discord != settings.discord {
+        discord::restart(app, &settings.discord);
+    }
+    if previous.spotify != settings.spotify {
+        spotify::restart(app, &settings.spotify);
+    }
+    if previous.sonos != settings.sonos {
+        sonos::restart(app, &settings.sonos);
+    }
+    if previous.windows != settings.windows {
+        windows_integration::restart(app, &settings.windows);
+    }
+    #[cfg(desktop)]
+    if previous.playback.media_keys != settings.playback.media_keys {
+        mediakeys::restart(app, settings.playback.media_keys);
+    }
+    #[cfg(desktop)]
+    if previous.widget != settings.widget || previous.display.monitor != settings.display.monitor {
+        widget::restart(app, &settings.widget, settings.display.monitor.as_deref());
+    }
+    #[cfg(desktop)]
+    if previous.templates != settings.templates {
+        tray::refresh(app, &settings.templates);
+    }
+    if previous.display.scale != settings.display.scale {
+        scale::apply(app, settings.display.scale);
+    }
+    if (previous.display.system_dim, previous.display.night_light)
+        != (settings.display.system_dim, settings.display.night_light)
+    {
+        screen_dim::restart(app, &settings.display);
+    }
+    if settings.offline.max_mb < previous.offline.max_mb {
+        app.state::<OfflineAudio>()
+            .evict(settings.offline.max_mb, None);
+    }
+    #[cfg(desktop)]
+    if previous.display.dim_all_monitors != settings.display.dim_all_monitors {
+        monitor_dim::restart(app, settings.display.dim_all_monitors);
+    }
+}
@@ -0,0 +1,329 @@
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use reqwest::Url;
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::session::SessionEvent;
+use sleepy_video_shared::sonos::{SonosGroup, SonosSettings};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager};
+use tokio::net::UdpSocket;
+use tokio::sync::broadcast;
+
+use crate::session::SessionState;
+
+const PORT: u16 = 1400;
+const SSDP_ADDR: (Ipv4Addr, u16) = (Ipv4Addr::new(239, 255, 255, 250), 1900);
+const SEARCH: &str = "M-SEARCH * HTTP/1.1\r\nHOST: 239.255.255.250:1900\r\n\
+    MAN: \"ssdp:discover\"\r\nMX: 1\r\nST: urn:schemas-upnp-org:device:ZonePlayer:1\r\n\r\n";
+const DISCOVERY_WAIT: Duration = Duration::from_secs(2);
+const TIMEOUT: Duration = Duration::from_secs(5);
+/// Smallest volume change worth a request
+const FADE_STEP: u32 = 2;
+
+/// UPnP services as (control path, service name)
+const GROUP_RENDERING: (&str, &str) = (
+    "/MediaRenderer/GroupRenderingControl/Control",
+    "GroupRenderingControl",
+);
+const AV_TRANSPORT: (&str, &str) = ("/MediaRenderer/AVTransport/Control", "AVTransport");
+const TOPOLOGY: (&str, &str) = ("/ZoneGroupTopology/Control", "ZoneGroupTopology");
+
+/// Fade state shared between the session follower and the end action
+pub struct Sonos {
+    client: reqwest::Client,
+    /// Group volumes before the fade, keyed by coordinator host
+    faded_from: Mutex<HashMap<String, u32>>,
+    follower: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Default for Sonos {
+    fn default() -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            faded_from: Mutex::new(HashMap::new()),
+            follower: Mutex::new(None),
+        }
+    }
+}
+
+/// Undo the XML escaping Sonos applies to nested documents and attributes
+fn unescape(raw: &str) -> String {
+    raw.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Text of the first `<name>` element
+fn element<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{}>", name))? + name.len() + 2;
+    let end = start + xml[start..].find(&format!("</{}>", name))?;
+    Some(&xml[start..end])
+}
+
+/// Value of `name="..."` inside a single tag
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let start = tag.find(&format!(" {}=\"", name))? + name.len() + 3;
+    let end = start + tag[start..].find('"')?;
+    Some(unescape(&tag[start..end]))
+}
+
+/// Every opening `<name ...>` tag in a document
+fn tags<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    xml.split(&format!("<{} ", name))
+        .skip(1)
+        .filter_map(|rest| rest.find('>').map(|end| &rest[..end]))
+        .collect()
+}
+
+/// Host part of a speaker's description URL
+fn host_of(location: &str) -> Option<String> {
+    Url::parse(location).ok()?.host_str().map(str::to_string)
+}
+
+impl Sonos {
+    async fn soap(
+        &self,
+        host: &str,
+        (control, service): (&str, &str),
+        action: &str,
+        args: &str,
+    ) -> Result<String, String> {
+        let body = format!(
+            "<?xml version=\"1.0\"?><s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+             s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\"><s:Body>\
+             <u:{action} xmlns:u=\"urn:schemas-upnp-org:service:{service}:1\">{args}</u:{action}>\
+             </s:Body></s:Envelope>"
+        );
+        self.client
+            .post(format!("http://{}:{}{}", host, PORT, control))
+            .header("Content-Type", "text/xml; charset=\"utf-8\"")
+            .header(
+                "SOAPACTION",
+                format!("\"urn:schemas-upnp-org:service:{}:1#{}\"", service, action),
+            )
+            .body(body)
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|e| format!("Sonos {} on {} failed: {}", action, host, e))?
+            .text()
+            .await
+            .map_err(|e| format!("Unexpected Sonos reply: {}", e))
+    }
+
+    async fn group_volume(&self, host: &str) -> Result<u32, String> {
+        let reply = self
+            .soap(
+                host,
+                GROUP_RENDERING,
+                "GetGroupVolume",
+                "<InstanceID>0</InstanceID>",
+            )
+            .await?;
+        element(&reply, "CurrentVolume")
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| "Unexpected Sonos volume reply".to_string())
+    }
+
+    async fn set_group_volume(&self, host: &str, volume: u32) {
+        let args = format!(
+            "<InstanceID>0</InstanceID><DesiredVolume>{}</DesiredVolume>",
+            volume.min(100)
+        );
+        if let Err(e) = self
+            .soap(host, GROUP_RENDERING, "SetGroupVolume", &args)
+            .await
+        {
+            tracing::warn!("{}", e);
+        }
+    }
+
+    /// Put back every volume the fade changed
+    async fn restore_volumes(&self) {
+        let faded: Vec<_> = self.faded_from.lock().unwrap().drain().collect();
+        for (host, volume) in faded {
+            self.set_group_volume(&host, volume).await;
+        }
+    }
+}
+
+/// Search the network for speakers and ask one of them for the groups
+pub async fn discover(sonos: &Sonos) -> Result<Vec<SonosGroup>, String> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .await
+        .map_err(|e| format!("Failed to open discovery socket: {}", e))?;
+    socket
+        .send_to(SEARCH.as_bytes(), SSDP_ADDR)
+        .await
+        .map_err(|e| format!("Failed to search for Sonos speakers: {}", e))?;
+
+    let mut buf = [0u8; 2048];
+    let deadline = tokio::time::Instant::now() + DISCOVERY_WAIT;
+    let mut speaker = None;
+    while let Ok(Ok((len, _))) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await
+    {
+        let reply = String::from_utf8_lossy(&buf[..len]);
+        let location = reply.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.eq_ignore_ascii_case("location")
+                .then(|| value.trim().to_string())
+        });
+        if let Some(host) = location.as_deref().and_then(host_of) {
+            speaker = Some(host);
+            break;
+        }
+    }
+    let speaker = speaker.ok_or_else(|| "No Sonos speakers found".to_string())?;
+
+    let reply = sonos
+        .soap(&speaker, TOPOLOGY, "GetZoneGroupState", "")
+        .await?;
+    let state = unescape(element(&reply, "ZoneGroupState").unwrap_or_default());
+
+    let mut groups = Vec::new();
+    for group in state.split("</ZoneGroup>") {
+        let coordinator = tags(group, "ZoneGroup")
+            .first()
+            .and_then(|tag| attribute(tag, "Coordinator"));
+        let Some(coordinator) = coordinator else {
+            continue;
+        };
+        let mut names = Vec::new();
+        let mut host = None;
+        for member in tags(group, "ZoneGroupMember") {
+            // Surrounds and subs are invisible members of their room
+            if attribute(member, "Invisible").as_deref() == Some("1") {
+                continue;
+            }
+            if let Some(name) = attribute(member, "ZoneName") {
+                names.push(name);
+            }
+            if attribute(member, "UUID").as_deref() == Some(coordinator.as_str()) {
+                host = attribute(member, "Location").as_deref().and_then(host_of);
+            }
+        }
+        if let Some(host) = host {
+            groups.push(SonosGroup {
+                id: coordinator,
+                name: names.join(" + "),
+                host,
+            });
+        }
+    }
+    groups.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(groups)
+}
+
+/// Pause the selected groups, then put their volume back for next time
+pub async fn stop(app: &AppHandle, settings: &SonosSettings) {
+    if !settings.enabled {
+        return;
+    }
+    let sonos = app.state::<Sonos>();
+    for group in &settings.groups {
+        let result = sonos
+            .soap(
+                &group.host,
+                AV_TRANSPORT,
+                "Pause",
+                "<InstanceID>0</InstanceID>",
+            )
+            .await;
+        if let Err(e) = result {
+            tracing::warn!("{}", e);
+        }
+    }
+    sonos.restore_volumes().await;
+}
+
+/// Stop following the session and start again with new settings
+pub fn restart(app: &AppHandle, settings: &SonosSettings) {
+    let sonos = app.state::<Sonos>();
+    let mut running = sonos.follower.lock().unwrap();
+    if let Some(handle) = running.take() {
+        handle.abort();
+    }
+    if !settings.enabled || settings.groups.is_empty() {
+        return;
+    }
+
+    let events = app.state::<SessionState>().subscribe();
+    *running = Some(tauri::async_runtime::spawn(follow_session(
+        app.clone(),
+        settings.clone(),
+        events,
+    )));
+}
+
+/// Ramp group volumes down over the last 10% of the session, alongside the
+/// in-app volume fade
+async fn follow_session(
+    app: AppHandle,
+    settings: SonosSettings,
+    mut events: broadcast::Receiver<SessionEvent>,
+) {
+    let sonos = app.state::<Sonos>();
+    // Volume last sent to each coordinator
+    let mut sent: HashMap<String, u32> = HashMap::new();
+    loop {
+        match events.recv().await {
+            Ok(SessionEvent::Status(status)) if status.running => {
+                let window = status.total_seconds / 10;
+                if window == 0 || status.remaining_seconds > window {
+                    continue;
+                }
+
+                for group in &settings.groups {
+                    let start = sonos.faded_from.lock().unwrap().get(&group.host).copied();
+                    let start = match start {
+                        Some(start) => start,
+                        None => match sonos.group_volume(&group.host).await {
+                            Ok(volume) => {
+                                sonos
+                                    .faded_from
+                                    .lock()
+                                    .unwrap()
+                                    .insert(group.host.clone(), volume);
+                                sent.insert(group.host.clone(), volume);
+                                volume
+                            }
+                            Err(e) => {
+                                tracing::warn!("{}", e);
+                                continue;
+                            }
+                        },
+                    };
+
+                    let target = start * status.remaining_seconds / window;
+                    let last = sent.get(&group.host).copied().unwrap_or(start);
+                    if last.saturating_sub(target) >= FADE_STEP || (target == 0 && last > 0) {
+                        sonos.set_group_volume(&group.host, target).await;
+                        sent.insert(group.host.clone(), target);
+                    }
+                }
+            }
+            Ok(SessionEvent::Cancelled) => {
+                sonos.restore_volumes().await;
+                sent.clear();
+            }
+            Ok(SessionEvent::Ended(_)) => sent.clear(),
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn sonos_discover(app: AppHandle) -> Result<Vec<SonosGroup>, EepyError> {
+    discover(&app.state::<Sonos>())
+        .await
+        .map_err(EepyError::Network)
+}
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::sponsorblock::SkipSegment;
+use tauri::State;
+
+use crate::settings::SettingsStore;
+
+const API: &str = "https://sponsor.ajay.app/api/skipSegments";
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Looks up SponsorBlock segments for the loaded video
+pub struct SponsorBlock {
+    client: reqwest::Client,
+}
+
+impl Default for SponsorBlock {
+    fn default() -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct VideoSegments {
+    #[serde(rename = "videoID")]
+    video_id: String,
+    segments: Vec<Segment>,
+}
+
+#[derive(Deserialize)]
+struct Segment {
+    segment: [f64; 2],
+    category: String,
+}
+
+/// Segments to skip in a video. Only the first four hex digits of the video
+/// ID's hash are sent, so SponsorBlock doesn't learn what's playing.
+#[tauri::command]
+pub async fn sponsor_segments(
+    sponsorblock: State<'_, SponsorBlock>,
+    store: State<'_, SettingsStore>,
+    video: String,
+) -> Result<Vec<SkipSegment>, EepyError> {
+    let settings = store.get().sponsorblock;
+    if !settings.enabled || settings.categories.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let hash = Sha256::digest(video.as_bytes());
+    let prefix = format!("{:02x}{:02x}", hash[0], hash[1]);
+    let categories = serde_json::to_string(&settings.categories)
+        .map_err(|e| format!("Failed to serialize categories: {}", e))?;
+    let res = sponsorblock
+        .client
+        .get(format!("{}/{}", API, prefix))
+        .query(&[("categories", categories.as_str()), ("actionType", "skip")])
+        .send()
+        .await
+        .map_err(|e| EepyError::Network(format!("Failed to reach SponsorBlock: {}", e)))?;
+    // Nothing submitted for any video with this prefix
+    if res.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(Vec::new());
+    }
+    let videos = res
+        .error_for_status()
+        .map_err(|e| EepyError::Network(format!("SponsorBlock lookup failed: {}", e)))?
+        .json::<Vec<VideoSegments>>()
+        .await
+        .map_err(|e| EepyError::Network(format!("Unexpected SponsorBlock reply: {}", e)))?;
+
+    Ok(videos
+        .into_iter()
+        .filter(|v| v.video_id == video)
+        .flat_map(|v| v.segments)
+        .map(|s| SkipSegment {
+            start: s.segment[0],
+            end: s.segment[1],
+            category: s.category,
+        })
+        .collect())
+}
@@ -0,0 +1,422 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use reqwest::{Method, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::session::SessionEvent;
+use sleepy_video_shared::spotify::SpotifySettings;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_opener::OpenerExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::broadcast;
+
+use crate::session::SessionState;
+
+const TOKEN_FILE: &str = "spotify.json";
+const AUTHORIZE_URL: &str = "https://accounts.spotify.com/authorize";
+const TOKEN_URL: &str = "https://accounts.spotify.com/api/token";
+const API_URL: &str = "https://api.spotify.com/v1";
+const SCOPES: &str = "user-read-playback-state user-modify-playback-state";
+/// Must be registered as a redirect URI on the Spotify developer app
+const REDIRECT_PORT: u16 = 4775;
+const REDIRECT_URI: &str = "http://127.0.0.1:4775/callback";
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(180);
+/// Smallest volume change worth an API call, Spotify rate-limits
+const FADE_STEP: u32 = 5;
+
+const CALLBACK_PAGE: &str = "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\n\
+    Connection: close\r\n\r\n<h1>eepy is connected to Spotify</h1><p>You can close this tab.</p>";
+
+#[derive(Serialize, Deserialize)]
+struct StoredToken {
+    refresh_token: String,
+}
+
+#[derive(Deserialize)]
+struct TokenReply {
+    access_token: String,
+    expires_in: u64,
+    /// Spotify may rotate the refresh token on every refresh
+    refresh_token: Option<String>,
+}
+
+struct AccessToken {
+    token: String,
+    expires: Instant,
+}
+
+/// Spotify login and playback state. The refresh token lives in its own file
+/// so saving the settings panel's draft can't overwrite a rotated one.
+pub struct Spotify {
+    path: PathBuf,
+    client: reqwest::Client,
+    refresh_token: Mutex<Option<String>>,
+    access: Mutex<Option<AccessToken>>,
+    /// Device volume before the fade, put back once playback is paused
+    faded_from: Mutex<Option<u32>>,
+    follower: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Spotify {
+    pub fn load(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .app_config_dir()
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .join(TOKEN_FILE);
+        let refresh_token = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<StoredToken>(&raw).ok())
+            .map(|stored| stored.refresh_token);
+
+        Self {
+            path,
+            client: reqwest::Client::new(),
+            refresh_token: Mutex::new(refresh_token),
+            access: Mutex::new(None),
+            faded_from: Mutex::new(None),
+            follower: Mutex::new(None),
+        }
+    }
+
+    fn store_refresh_token(&self, token: Option<String>) -> Result<(), String> {
+        match &token {
+            Some(refresh_token) => {
+                if let Some(dir) = self.path.parent() {
+                    fs::create_dir_all(dir)
+                        .map_err(|e| format!("Failed to create config dir: {}", e))?;
+                }
+                let raw = serde_json::to_string(&StoredToken {
+                    refresh_token: refresh_token.clone(),
+                })
+                .map_err(|e| format!("Failed to serialize Spotify token: {}", e))?;
+                fs::write(&self.path, raw)
+                    .map_err(|e| format!("Failed to write Spotify token: {}", e))?;
+            }
+            None => {
+                let _ = fs::remove_file(&self.path);
+            }
+        }
+        *self.refresh_token.lock().unwrap() = token;
+        *self.access.lock().unwrap() = None;
+        Ok(())
+    }
+
+    async fn request_token(&self, form: &[(&str, &str)]) -> Result<TokenReply, String> {
+        self.client
+            .post(TOKEN_URL)
+            .form(form)
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|e| format!("Spotify login failed: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Unexpected Spotify reply: {}", e))
+    }
+
+    fn accept_token(&self, reply: TokenReply) -> Result<String, String> {
+        if let Some(refresh_token) = reply.refresh_token {
+            self.store_refresh_token(Some(refresh_token))?;
+        }
+        *self.access.lock().unwrap() = Some(AccessToken {
+            token: reply.access_token.clone(),
+            expires: Instant::now() + Duration::from_secs(reply.expires_in.saturating_sub(30)),
+        });
+        Ok(reply.access_token)
+    }
+
+    async fn access_token(&self, client_id: &str) -> Result<String, String> {
+        if let Some(access) = self.access.lock().unwrap().as_ref() {
+            if access.expires > Instant::now() {
+                return Ok(access.token.clone());
+            }
+        }
+
+        let refresh_token = self
+            .refresh_token
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| "Spotify is not connected".to_string())?;
+        let reply = self
+            .request_token(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", &refresh_token),
+                ("client_id", client_id),
+            ])
+            .await?;
+        self.accept_token(reply)
+    }
+
+    /// Call the Web API, returning the JSON body if there is one
+    async fn api(
+        &self,
+        settings: &SpotifySettings,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<Option<Value>, String> {
+        let token = self.access_token(&settings.client_id).await?;
+        let response = self
+            .client
+            .request(method, format!("{}{}", API_URL, path))
+            .bearer_auth(token)
+            .query(query)
+            // Spotify wants a length on bodiless PUTs
+            .header("Content-Length", "0")
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|e| format!("Spotify request failed: {}", e))?;
+        let body = response.text().await.unwrap_or_default();
+        Ok(serde_json::from_str(&body).ok())
+    }
+
+    /// Volume of the active device, if anything is playing
+    async fn playing_volume(&self, settings: &SpotifySettings) -> Option<u32> {
+        let player = self
+            .api(settings, Method::GET, "/me/player", &[])
+            .await
+            .ok()??;
+        if player.get("is_playing")?.as_bool()? {
+            player
+                .pointer("/device/volume_percent")?
+                .as_u64()
+                .map(|v| v as u32)
+        } else {
+            None
+        }
+    }
+
+    async fn set_volume(&self, settings: &SpotifySettings, volume: u32) {
+        let query = [("volume_percent", volume.min(100).to_string())];
+        if let Err(e) = self
+            .api(settings, Method::PUT, "/me/player/volume", &query)
+            .await
+        {
+            tracing::warn!("{}", e);
+        }
+    }
+
+    async fn restore_volume(&self, settings: &SpotifySettings) {
+        let previous = self.faded_from.lock().unwrap().take();
+        if let Some(volume) = previous {
+            self.set_volume(settings, volume).await;
+        }
+    }
+}
+
+/// Pause the active device, then put its volume back for next time
+pub async fn pause(app: &AppHandle, settings: &SpotifySettings) {
+    if !settings.enabled {
+        return;
+    }
+    let spotify = app.state::<Spotify>();
+    if spotify.refresh_token.lock().unwrap().is_none() {
+        return;
+    }
+    // Spotify answers 403 when nothing is playing, which is fine here
+    if spotify.playing_volume(settings).await.is_some() {
+        if let Err(e) = spotify
+            .api(settings, Method::PUT, "/me/player/pause", &[])
+            .await
+        {
+            tracing::warn!("{}", e);
+        }
+    }
+    spotify.restore_volume(settings).await;
+}
+
+/// Stop following the session and start again with new settings
+pub fn restart(app: &AppHandle, settings: &SpotifySettings) {
+    let spotify = app.state::<Spotify>();
+    let mut running = spotify.follower.lock().unwrap();
+    if let Some(handle) = running.take() {
+        handle.abort();
+    }
+    if !settings.enabled || !settings.fade {
+        return;
+    }
+
+    let events = app.state::<SessionState>().subscribe();
+    *running = Some(tauri::async_runtime::spawn(follow_session(
+        app.clone(),
+        settings.clone(),
+        events,
+    )));
+}
+
+/// Fade the device volume over the last 10% of the session, like the video
+async fn follow_session(
+    app: AppHandle,
+    settings: SpotifySettings,
+    mut events: broadcast::Receiver<SessionEvent>,
+) {
+    let spotify = app.state::<Spotify>();
+    // Volume last sent, so small steps don't each cost a request
+    let mut last = None;
+    loop {
+        match events.recv().await {
+            Ok(SessionEvent::Status(status)) if status.running => {
+                let window = status.total_seconds / 10;
+                if window == 0 || status.remaining_seconds > window {
+                    continue;
+                }
+
+                if last.is_none() {
+                    let Some(volume) = spotify.playing_volume(&settings).await else {
+                        // Nothing playing; check again on the next tick
+                        continue;
+                    };
+                    *spotify.faded_from.lock().unwrap() = Some(volume);
+                    last = Some(volume);
+                }
+                let Some(start) = *spotify.faded_from.lock().unwrap() else {
+                    continue;
+                };
+                let target = start * status.remaining_seconds / window;
+                if let Some(sent) = last {
+                    if sent.saturating_sub(target) >= FADE_STEP || (target == 0 && sent > 0) {
+                        spotify.set_volume(&settings, target).await;
+                        last = Some(target);
+                    }
+                }
+            }
+            Ok(SessionEvent::Cancelled) => {
+                spotify.restore_volume(&settings).await;
+                last = None;
+            }
+            Ok(SessionEvent::Ended(_)) => last = None,
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+/// Wait for Spotify to redirect the browser back with the login code
+async fn wait_for_code(listener: TcpListener, state: &str) -> Result<String, String> {
+    loop {
+        let (mut stream, _) = listener
+            .accept()
+            .await
+            .map_err(|e| format!("Spotify login failed: {}", e))?;
+        let mut buf = [0u8; 4096];
+        let len = stream.read(&mut buf).await.unwrap_or(0);
+        let request = String::from_utf8_lossy(&buf[..len]);
+
+        // "GET /callback?code=...&state=... HTTP/1.1"
+        let Some(path) = request.split_whitespace().nth(1) else {
+            continue;
+        };
+        let Ok(url) = Url::parse(&format!("http://127.0.0.1{}", path)) else {
+            continue;
+        };
+        if url.path() != "/callback" {
+            continue;
+        }
+        let _ = stream.write_all(CALLBACK_PAGE.as_bytes()).await;
+
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+        if let Some(error) = param("error") {
+            return Err(format!("Spotify login was refused: {}", error));
+        }
+        if param("state").as_deref() != Some(state) {
+            return Err("Spotify login reply didn't match the request".to_string());
+        }
+        return param("code").ok_or_else(|| "Spotify didn't send a login code".to_string());
+    }
+}
+
+/// Log in with the authorization code flow and PKCE. The browser opens on
+/// Spotify's consent page and comes back to a one-shot local listener.
+#[tauri::command]
+pub async fn spotify_connect(app: AppHandle, client: String) -> Result<(), EepyError> {
+    let client_id = client.trim().to_string();
+    if client_id.is_empty() {
+        return Err(EepyError::Invalid(
+            "Enter your Spotify client ID first".to_string(),
+        ));
+    }
+
+    let random = |len| {
+        rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(len)
+            .map(char::from)
+            .collect::<String>()
+    };
+    let verifier = random(64);
+    let state = random(16);
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+
+    let authorize = Url::parse_with_params(
+        AUTHORIZE_URL,
+        &[
+            ("client_id", client_id.as_str()),
+            ("response_type", "code"),
+            ("redirect_uri", REDIRECT_URI),
+            ("scope", SCOPES),
+            ("state", &state),
+            ("code_challenge_method", "S256"),
+            ("code_challenge", &challenge),
+        ],
+    )
+    .map_err(|e| format!("Failed to build Spotify login URL: {}", e))?;
+
+    let listener = TcpListener::bind(("127.0.0.1", REDIRECT_PORT))
+        .await
+        .map_err(|e| {
+            EepyError::Network(format!(
+                "Port {} is needed for the Spotify login: {}",
+                REDIRECT_PORT, e
+            ))
+        })?;
+    app.opener()
+        .open_url(authorize.as_str(), None::<&str>)
+        .map_err(|e| format!("Failed to open the browser: {}", e))?;
+
+    let code = tokio::time::timeout(LOGIN_TIMEOUT, wait_for_code(listener, &state))
+        .await
+        .map_err(|_| EepyError::Network("Spotify login timed out".to_string()))?
+        .map_err(EepyError::Network)?;
+
+    let spotify = app.state::<Spotify>();
+    let reply = spotify
+        .request_token(&[
+            ("grant_type", "authorization_code"),
+            ("code", &code),
+            ("redirect_uri", REDIRECT_URI),
+            ("client_id", &client_id),
+            ("code_verifier", &verifier),
+        ])
+        .await
+        .map_err(EepyError::Network)?;
+    spotify.accept_token(reply)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn spotify_disconnect(spotify: State<Spotify>) -> Result<(), EepyError> {
+    spotify.store_refresh_token(None).map_err(EepyError::Io)
+}
+
+#[tauri::command]
+pub fn spotify_connected(spotify: State<Spotify>) -> bool {
+    spotify.refresh_token.lock().unwrap().is_some()
+}
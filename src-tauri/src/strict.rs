@@ -0,0 +1,248 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use pbkdf2::pbkdf2_hmac;
+use sha2::{Digest, Sha256};
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::settings::Settings;
+use sleepy_video_shared::strict::StrictStatus;
+use subtle::ConstantTimeEq;
+use tauri::{AppHandle, Manager, State};
+
+use crate::session::SessionState;
+use crate::settings::SettingsStore;
+
+/// Shortest PIN accepted
+const MIN_PIN_LENGTH: usize = 4;
+/// Marks a salted hash from `hash_secret`, as opposed to the bare SHA-256
+/// stored before
+const KDF_NAME: &str = "pbkdf2-sha256";
+/// PBKDF2 rounds. A PIN has only so many guesses to try, so what keeps a
+/// copied settings file from giving it away is how slow each guess is.
+const KDF_ROUNDS: u32 = 600_000;
+const SALT_LENGTH: usize = 16;
+
+/// Lock on the running session while strict mode is on. Each new session
+/// starts out locked; the PIN or the cooldown opens it until the next one.
+#[derive(Default)]
+pub struct StrictLock {
+    state: Mutex<LockState>,
+}
+
+#[derive(Default)]
+struct LockState {
+    unlocked: bool,
+    cooldown_ends: Option<Instant>,
+}
+
+impl StrictLock {
+    /// Lock again for a session that just started
+    pub fn reset(&self) {
+        *self.state.lock().unwrap() = LockState::default();
+    }
+
+    pub fn status(&self, app: &AppHandle) -> StrictStatus {
+        let enabled = app.state::<SettingsStore>().get().strict.enabled;
+        let running = app.state::<SessionState>().status().running;
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        if state.cooldown_ends.is_some_and(|ends| now >= ends) {
+            state.unlocked = true;
+            state.cooldown_ends = None;
+        }
+
+        let locked = enabled && running && !state.unlocked;
+        StrictStatus {
+            locked,
+            unlocks_in: state
+                .cooldown_ends
+                .filter(|_| locked)
+                .map(|ends| ends.saturating_duration_since(now).as_secs()),
+        }
+    }
+
+    pub fn locked(&self, app: &AppHandle) -> bool {
+        self.status(app).locked
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn unhex(text: &str) -> Option<Vec<u8>> {
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn derive(secret: &str, salt: &[u8], rounds: u32) -> [u8; 32] {
+    let mut key = [0; 32];
+    pbkdf2_hmac::<Sha256>(secret.trim().as_bytes(), salt, rounds, &mut key);
+    key
+}
+
+/// Salted PBKDF2 of a PIN or password for storing in the settings, as
+/// `pbkdf2-sha256$rounds$salt$key` with the salt and key hex encoded
+pub fn hash_secret(secret: &str) -> String {
+    hash_with(secret, KDF_ROUNDS)
+}
+
+fn hash_with(secret: &str, rounds: u32) -> String {
+    let salt = rand::random::<[u8; SALT_LENGTH]>();
+    let key = derive(secret, &salt, rounds);
+    format!("{}${}${}${}", KDF_NAME, rounds, hex(&salt), hex(&key))
+}
+
+/// Whether `secret` is the one `stored` was hashed from. Also takes the
+/// unsalted SHA-256 older versions stored, until it's hashed again.
+pub fn verify_secret(secret: &str, stored: &str) -> bool {
+    let (expected, actual) = match stored.split('$').collect::<Vec<_>>()[..] {
+        [KDF_NAME, rounds, salt, key] => {
+            let (Ok(rounds), Some(salt), Some(key)) = (rounds.parse(), unhex(salt), unhex(key))
+            else {
+                return false;
+            };
+            (key, derive(secret, &salt, rounds).to_vec())
+        }
+        [legacy] if !legacy.is_empty() => match unhex(legacy) {
+            Some(key) => (key, Sha256::digest(secret.trim().as_bytes()).to_vec()),
+            None => return false,
+        },
+        _ => return false,
+    };
+    expected.ct_eq(&actual).into()
+}
+
+/// Whether `stored` is from before salting and should be replaced once the
+/// secret is known again
+pub fn needs_rehash(stored: &str) -> bool {
+    !stored.is_empty() && !stored.starts_with(KDF_NAME)
+}
+
+#[tauri::command]
+pub fn strict_status(app: AppHandle, lock: State<StrictLock>) -> StrictStatus {
+    lock.status(&app)
+}
+
+/// Open the lock for the rest of the session with the PIN
+#[tauri::command]
+pub fn strict_unlock(
+    app: AppHandle,
+    lock: State<StrictLock>,
+    store: State<SettingsStore>,
+    pin: String,
+) -> Result<StrictStatus, EepyError> {
+    let mut settings = store.get();
+    if settings.strict.pin_hash.is_empty() {
+        return Err(EepyError::Locked(
+            "No PIN is set, wait for the cooldown".to_string(),
+        ));
+    }
+    if !verify_secret(&pin, &settings.strict.pin_hash) {
+        return Err(EepyError::Invalid("Wrong PIN".to_string()));
+    }
+    if needs_rehash(&settings.strict.pin_hash) {
+        settings.strict.pin_hash = hash_secret(&pin);
+        if let Err(e) = store.save(settings) {
+            tracing::warn!("Failed to store the salted PIN: {}", e);
+        }
+    }
+    lock.state.lock().unwrap().unlocked = true;
+    Ok(lock.status(&app))
+}
+
+/// Start the cooldown that opens the lock without the PIN. Asking again
+/// doesn't restart it.
+#[tauri::command]
+pub fn strict_request_unlock(
+    app: AppHandle,
+    lock: State<StrictLock>,
+    store: State<SettingsStore>,
+) -> StrictStatus {
+    let cooldown = Duration::from_secs(store.get().strict.cooldown_minutes as u64 * 60);
+    lock.state
+        .lock()
+        .unwrap()
+        .cooldown_ends
+        .get_or_insert_with(|| Instant::now() + cooldown);
+    lock.status(&app)
+}
+
+/// Set a new PIN, or clear it with an empty one. Not while it's locking a
+/// session.
+#[tauri::command]
+pub fn strict_set_pin(
+    app: AppHandle,
+    lock: State<StrictLock>,
+    store: State<SettingsStore>,
+    pin: String,
+) -> Result<Settings, EepyError> {
+    if lock.locked(&app) {
+        return Err(EepyError::Locked(
+            "The PIN can't be changed while strict mode locks a session".to_string(),
+        ));
+    }
+    let pin = pin.trim();
+    if !pin.is_empty() && pin.len() < MIN_PIN_LENGTH {
+        return Err(EepyError::Invalid(format!(
+            "The PIN needs at least {} digits",
+            MIN_PIN_LENGTH
+        )));
+    }
+
+    let mut settings = store.get();
+    settings.strict.pin_hash = if pin.is_empty() {
+        String::new()
+    } else {
+        hash_secret(pin)
+    };
+    store.save(settings.clone()).map_err(EepyError::Io)?;
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Enough to tell hashes apart, without the wait of the real count
+    const TEST_ROUNDS: u32 = 1_000;
+
+    #[test]
+    fn hashes_verify_their_own_secret_only() {
+        let stored = hash_with("1234", TEST_ROUNDS);
+        assert!(verify_secret("1234", &stored));
+        assert!(verify_secret(" 1234\n", &stored));
+        assert!(!verify_secret("1235", &stored));
+        assert!(!needs_rehash(&stored));
+    }
+
+    #[test]
+    fn hashes_are_salted() {
+        let first = hash_with("1234", TEST_ROUNDS);
+        let second = hash_with("1234", TEST_ROUNDS);
+        assert_ne!(first, second);
+        assert!(first.starts_with("pbkdf2-sha256$1000$"));
+        assert_eq!(first.split('$').count(), 4);
+    }
+
+    #[test]
+    fn unsalted_hashes_still_verify_until_replaced() {
+        // SHA-256 of "1234", as stored before salting
+        let legacy = "03ac674216f3e15c761ee1a5e255f067953623c8b388b4459e13f978d7c846f4";
+        assert!(verify_secret("1234", legacy));
+        assert!(!verify_secret("4321", legacy));
+        assert!(needs_rehash(legacy));
+    }
+
+    #[test]
+    fn empty_or_garbled_hashes_verify_nothing() {
+        assert!(!verify_secret("", ""));
+        assert!(!verify_secret("1234", ""));
+        assert!(!verify_secret("1234", "not hex"));
+        assert!(!verify_secret("1234", "pbkdf2-sha256$many$00$00"));
+        assert!(!verify_secret("1234", "pbkdf2-sha256$1$zz$00"));
+        assert!(!needs_rehash(""));
+    }
+}
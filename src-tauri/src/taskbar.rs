@@ -0,0 +1,55 @@
+use sleepy_video_shared::session::SessionStatus;
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::{AppHandle, Manager};
+
+/// Window title between sessions, as in tauri.conf.json
+const IDLE_TITLE: &str = "Sleepy Whale Video Player";
+
+/// Show the countdown outside the window: the title (for the task switcher),
+/// taskbar progress on Windows and Linux, and the minutes left as the dock
+/// badge on macOS and Linux
+pub fn update(app: &AppHandle, previous: &SessionStatus, status: &SessionStatus) {
+    if !status.running && !previous.running {
+        return;
+    }
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+
+    if !status.running {
+        let _ = window.set_title(IDLE_TITLE);
+        let _ = window.set_progress_bar(ProgressBarState {
+            status: Some(ProgressBarStatus::None),
+            progress: None,
+        });
+        let _ = window.set_badge_count(None);
+        return;
+    }
+
+    let remaining = status.remaining_seconds;
+    let _ = window.set_title(&format!(
+        "eepy — {:02}:{:02}:{:02}",
+        remaining / 3600,
+        (remaining % 3600) / 60,
+        remaining % 60
+    ));
+
+    let progress = |s: &SessionStatus| {
+        (s.total_seconds > 0).then(|| {
+            (s.total_seconds - s.remaining_seconds.min(s.total_seconds)) as u64 * 100
+                / s.total_seconds as u64
+        })
+    };
+    if !previous.running || progress(previous) != progress(status) {
+        let _ = window.set_progress_bar(ProgressBarState {
+            status: Some(ProgressBarStatus::Normal),
+            progress: progress(status),
+        });
+    }
+
+    let minutes_left = |s: &SessionStatus| s.remaining_seconds.div_ceil(60);
+    if !previous.running || minutes_left(previous) != minutes_left(status) {
+        // Unsupported on Windows, where the taskbar progress has to do
+        let _ = window.set_badge_count(Some(minutes_left(status) as i64));
+    }
+}
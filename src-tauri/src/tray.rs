@@ -0,0 +1,88 @@
+use sleepy_video_shared::control::ControlCommand;
+use sleepy_video_shared::templates::SessionTemplate;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Manager, Wry};
+
+use crate::control;
+use crate::settings::SettingsStore;
+use crate::strict::StrictLock;
+use crate::window;
+
+const TRAY_ID: &str = "main";
+/// Menu item IDs for templates are this prefix plus the template's index
+const TEMPLATE_PREFIX: &str = "template:";
+
+pub fn create(app: &AppHandle, templates: &[SessionTemplate]) -> tauri::Result<()> {
+    let mut tray = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("eepy")
+        .menu(&menu(app, templates)?)
+        .on_menu_event(|app, event| match event.id.as_ref() {
+            "show" => window::show_main_window(app),
+            // Strict mode doesn't let the session be quit away
+            "quit" if app.state::<StrictLock>().locked(app) => window::show_main_window(app),
+            "quit" => app.exit(0),
+            id => {
+                if let Some(index) = id.strip_prefix(TEMPLATE_PREFIX) {
+                    start_template(app, index);
+                }
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)?;
+
+    Ok(())
+}
+
+/// Session templates first, then the fixed entries
+fn menu(app: &AppHandle, templates: &[SessionTemplate]) -> tauri::Result<Menu<Wry>> {
+    let menu = Menu::new(app)?;
+    for (i, template) in templates.iter().enumerate() {
+        let id = format!("{}{}", TEMPLATE_PREFIX, i);
+        menu.append(&MenuItem::with_id(
+            app,
+            id,
+            &template.name,
+            true,
+            None::<&str>,
+        )?)?;
+    }
+    if !templates.is_empty() {
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+    menu.append(&MenuItem::with_id(
+        app,
+        "show",
+        "Show eepy",
+        true,
+        None::<&str>,
+    )?)?;
+    menu.append(&MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?)?;
+    Ok(menu)
+}
+
+/// Rebuild the menu after the templates changed
+pub fn refresh(app: &AppHandle, templates: &[SessionTemplate]) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let result = menu(app, templates).and_then(|menu| tray.set_menu(Some(menu)));
+    if let Err(e) = result {
+        tracing::warn!("Failed to update tray menu: {}", e);
+    }
+}
+
+fn start_template(app: &AppHandle, index: &str) {
+    let templates = app.state::<SettingsStore>().get().templates;
+    let Some(template) = index.parse::<usize>().ok().and_then(|i| templates.get(i)) else {
+        return;
+    };
+    let request = template.start_request();
+    // Templates that bring a video want it on screen
+    if request.video.is_some() {
+        show_main_window(app);
+    }
+    control::send(app, ControlCommand::Start(request));
+}
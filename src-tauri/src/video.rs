@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::library::{thumbnail_url, VideoInfo};
+use tauri::{AppHandle, Manager, State};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+const FILE: &str = "videos.json";
+const THUMBNAILS: &str = "thumbnails";
+/// Titles and thumbnails rarely change, so they're looked up again only
+/// after this long
+const EXPIRY_SECS: i64 = 30 * 24 * 60 * 60;
+/// How YouTube's player response reports a video's length
+const LENGTH_KEY: &str = "\"lengthSeconds\":\"";
+
+/// What's known about a video and when it was looked up
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct CachedVideo {
+    title: Option<String>,
+    duration_secs: Option<u32>,
+    /// Unix seconds
+    fetched_at: i64,
+}
+
+/// Titles, lengths and thumbnails of YouTube videos, kept on disk so the
+/// library shows them offline and launches don't look them up again.
+/// Entries past `EXPIRY_SECS` are refreshed, and kept when that fails.
+pub struct VideoCache {
+    client: reqwest::Client,
+    path: PathBuf,
+    thumbnails: PathBuf,
+    videos: Mutex<HashMap<String, CachedVideo>>,
+}
+
+#[derive(Deserialize)]
+struct OEmbed {
+    title: String,
+}
+
+/// YouTube IDs are 11 of these, which also keeps them safe as file names
+pub fn valid_id(video: &str) -> bool {
+    !video.is_empty()
+        && video
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Number in the first `"lengthSeconds":"…"` of the watch page
+fn parse_length(page: &str) -> Option<u32> {
+    let start = page.find(LENGTH_KEY)? + LENGTH_KEY.len();
+    let rest = &page[start..];
+    let end = rest.find('"')?;
+    rest[..end].parse().ok()
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+impl VideoCache {
+    pub fn load(app: &AppHandle) -> Self {
+        let dir = app
+            .path()
+            .app_cache_dir()
+            .unwrap_or_else(|_| PathBuf::from("."));
+        let path = dir.join(FILE);
+        let videos = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            path,
+            thumbnails: dir.join(THUMBNAILS),
+            videos: Mutex::new(videos),
+        }
+    }
+
+    fn thumbnail_path(&self, video_id: &str) -> PathBuf {
+        self.thumbnails.join(format!("{}.jpg", video_id))
+    }
+
+    fn save(&self) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create cache dir: {}", e))?;
+        }
+        let raw = serde_json::to_string(&*self.videos.lock().unwrap())
+            .map_err(|e| format!("Failed to serialize video cache: {}", e))?;
+        fs::write(&self.path, raw).map_err(|e| format!("Failed to write video cache: {}", e))
+    }
+
+    /// The video as cached, looked up first if it's missing or expired
+    async fn cached(&self, video_id: &str) -> CachedVideo {
+        if !valid_id(video_id) {
+            return CachedVideo::default();
+        }
+        let known = self.videos.lock().unwrap().get(video_id).cloned();
+        let fresh = known
+            .as_ref()
+            .is_some_and(|video| now() - video.fetched_at < EXPIRY_SECS);
+        let known = known.unwrap_or_default();
+        if fresh {
+            return known;
+        }
+
+        let (title, duration_secs, thumbnail) = tokio::join!(
+            self.fetch_title(video_id),
+            self.fetch_length(video_id),
+            self.fetch_thumbnail(video_id),
+        );
+        // Offline: keep what's known and try again next time
+        if title.is_none() && duration_secs.is_none() && !thumbnail {
+            return known;
+        }
+        let video = CachedVideo {
+            title: title.or(known.title),
+            duration_secs: duration_secs.or(known.duration_secs),
+            fetched_at: now(),
+        };
+        self.videos
+            .lock()
+            .unwrap()
+            .insert(video_id.to_string(), video.clone());
+        if let Err(e) = self.save() {
+            tracing::warn!("{}", e);
+        }
+        video
+    }
+
+    /// Look up a video's title through oEmbed, which needs no API key
+    async fn fetch_title(&self, video_id: &str) -> Option<String> {
+        let url = format!(
+            "https://www.youtube.com/oembed?format=json&url=https://www.youtube.com/watch?v={}",
+            video_id
+        );
+        self.client
+            .get(url)
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .ok()?
+            .json::<OEmbed>()
+            .await
+            .ok()
+            .map(|oembed| oembed.title)
+    }
+
+    /// A video's length from the player response embedded in its watch page
+    async fn fetch_length(&self, video_id: &str) -> Option<u32> {
+        let page = self
+            .client
+            .get(format!("https://www.youtube.com/watch?v={}", video_id))
+            // Skips the cookie consent page in the EU
+            .header("Cookie", "SOCS=CAI")
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .ok()?
+            .text()
+            .await
+            .ok()?;
+        parse_length(&page)
+    }
+
+    /// Download the thumbnail next to the cache; whether it worked
+    async fn fetch_thumbnail(&self, video_id: &str) -> bool {
+        let bytes = match self
+            .client
+            .get(thumbnail_url(video_id))
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+        {
+            Ok(res) => match res.bytes().await {
+                Ok(bytes) => bytes,
+                Err(_) => return false,
+            },
+            Err(_) => return false,
+        };
+        let written = fs::create_dir_all(&self.thumbnails)
+            .and_then(|()| fs::write(self.thumbnail_path(video_id), bytes));
+        if let Err(e) = &written {
+            tracing::warn!("Failed to save the thumbnail of {}: {}", video_id, e);
+        }
+        written.is_ok()
+    }
+
+    /// A video's title, for integrations that show what's playing
+    pub async fn title(&self, video_id: &str) -> Option<String> {
+        self.cached(video_id).await.title
+    }
+
+    pub async fn info(&self, video_id: &str) -> VideoInfo {
+        let video = self.cached(video_id).await;
+        // Inlined, so the webview shows it without reaching YouTube
+        let thumbnail = fs::read(self.thumbnail_path(video_id)).ok().map(|bytes| {
+            format!(
+                "data:image/jpeg;base64,{}",
+                base64::engine::general_purpose::STANDARD.encode(bytes)
+            )
+        });
+        VideoInfo {
+            video_id: video_id.to_string(),
+            title: video.title,
+            duration_secs: video.duration_secs,
+            thumbnail,
+        }
+    }
+}
+
+/// Title, length and thumbnail of a video, from the cache when it can
+#[tauri::command]
+pub async fn video_info(
+    cache: State<'_, VideoCache>,
+    video: String,
+) -> Result<VideoInfo, EepyError> {
+    if !valid_id(&video) {
+        return Err(EepyError::Invalid(format!("Not a video ID: {}", video)));
+    }
+    Ok(cache.info(&video).await)
+}
@@ -0,0 +1,132 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use sleepy_video_shared::power::EndAction;
+use sleepy_video_shared::session::SessionStatus;
+use sleepy_video_shared::webhooks::{WebhookEvent, WebhookSettings};
+use tauri::{AppHandle, Manager};
+
+use crate::video::VideoCache;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// HTTP client shared by every webhook call
+pub struct Webhooks {
+    client: reqwest::Client,
+}
+
+impl Default for Webhooks {
+    fn default() -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// JSON body POSTed to every configured URL
+#[derive(Serialize)]
+struct Payload {
+    event: WebhookEvent,
+    /// Milestone in the UI's language, only set for milestone events
+    milestone: Option<String>,
+    remaining_seconds: u32,
+    total_seconds: u32,
+    action: EndAction,
+    video_id: Option<String>,
+    video_title: Option<String>,
+}
+
+impl Webhooks {
+    async fn send(
+        &self,
+        videos: &VideoCache,
+        settings: &WebhookSettings,
+        event: WebhookEvent,
+        milestone: Option<&str>,
+        status: SessionStatus,
+    ) {
+        let video_title = match &status.video_id {
+            Some(id) => videos.title(id).await,
+            None => None,
+        };
+        let payload = Payload {
+            event,
+            milestone: milestone.map(str::to_string),
+            remaining_seconds: status.remaining_seconds,
+            total_seconds: status.total_seconds,
+            action: status.action,
+            video_id: status.video_id,
+            video_title,
+        };
+
+        for url in &settings.urls {
+            let result = self
+                .client
+                .post(url)
+                .json(&payload)
+                .send()
+                .await
+                .and_then(|res| res.error_for_status());
+            // Webhook URLs often carry a secret in the path or query
+            let host = host(url);
+            match result {
+                Ok(res) => tracing::info!("Called webhook on {}: {}", host, res.status()),
+                Err(e) => tracing::warn!("Failed to call webhook on {}: {}", host, e.without_url()),
+            }
+        }
+    }
+}
+
+/// Host of a webhook URL, safe to log
+fn host(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| "an invalid URL".to_string())
+}
+
+/// Fire the webhooks for `event` in the background
+pub fn fire(
+    app: &AppHandle,
+    settings: &WebhookSettings,
+    event: WebhookEvent,
+    milestone: Option<&str>,
+    status: SessionStatus,
+) {
+    if !settings.wants(event) {
+        return;
+    }
+
+    let app = app.clone();
+    let settings = settings.clone();
+    let milestone = milestone.map(str::to_string);
+    tauri::async_runtime::spawn(async move {
+        app.state::<Webhooks>()
+            .send(
+                &app.state::<VideoCache>(),
+                &settings,
+                event,
+                milestone.as_deref(),
+                status,
+            )
+            .await;
+    });
+}
+
+/// Fire the webhooks for `event` and wait for them, for moments like suspend
+/// where the machine may be gone before a background task gets to run
+pub async fn fire_and_wait(
+    app: &AppHandle,
+    settings: &WebhookSettings,
+    event: WebhookEvent,
+    status: SessionStatus,
+) {
+    if settings.wants(event) {
+        app.state::<Webhooks>()
+            .send(&app.state::<VideoCache>(), settings, event, None, status)
+            .await;
+    }
+}
@@ -0,0 +1,87 @@
+use sleepy_video_shared::widget::{Corner, WidgetSettings};
+use tauri::{
+    AppHandle, Manager, PhysicalPosition, WebviewUrl, WebviewWindow, WebviewWindowBuilder,
+};
+
+use crate::window::find_monitor;
+
+/// Window label of the countdown widget; the frontend picks its view from the URL
+pub const WIDGET_LABEL: &str = "widget";
+const WIDGET_URL: &str = "index.html?view=widget";
+/// Logical size of the widget window
+const WIDTH: f64 = 180.0;
+const HEIGHT: f64 = 56.0;
+/// Logical gap between the widget and the screen edges
+const MARGIN: f64 = 24.0;
+
+/// Create or close the widget to match the settings, and move it to its corner
+/// of `monitor`, or of the monitor it's on
+pub fn restart(app: &AppHandle, settings: &WidgetSettings, monitor: Option<&str>) {
+    let existing = app.get_webview_window(WIDGET_LABEL);
+    if !settings.enabled {
+        if let Some(window) = existing {
+            let _ = window.close();
+        }
+        return;
+    }
+
+    let window = match existing {
+        Some(window) => window,
+        None => match create(app) {
+            Ok(window) => window,
+            Err(e) => {
+                tracing::warn!("Failed to open the countdown widget: {}", e);
+                return;
+            }
+        },
+    };
+    if let Err(e) = place(&window, settings.corner, monitor) {
+        tracing::warn!("Failed to place the countdown widget: {}", e);
+    }
+}
+
+fn create(app: &AppHandle) -> tauri::Result<WebviewWindow> {
+    let window = WebviewWindowBuilder::new(app, WIDGET_LABEL, WebviewUrl::App(WIDGET_URL.into()))
+        .title("eepy countdown")
+        .inner_size(WIDTH, HEIGHT)
+        .decorations(false)
+        .transparent(true)
+        .shadow(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .resizable(false)
+        .focused(false)
+        .build()?;
+    // Clicks go through to whatever is underneath
+    window.set_ignore_cursor_events(true)?;
+    Ok(window)
+}
+
+/// Pin the widget to a corner of the named monitor, or the one it's on
+fn place(window: &WebviewWindow, corner: Corner, monitor: Option<&str>) -> tauri::Result<()> {
+    let picked = find_monitor(window, monitor);
+    let Some(monitor) = picked
+        .or(window.current_monitor()?)
+        .or(window.primary_monitor()?)
+    else {
+        return Ok(());
+    };
+    let scale = monitor.scale_factor();
+    let (origin, size) = (monitor.position(), monitor.size());
+    let (width, height, margin) = (
+        (WIDTH * scale) as i32,
+        (HEIGHT * scale) as i32,
+        (MARGIN * scale) as i32,
+    );
+    let left = origin.x + margin;
+    let right = origin.x + size.width as i32 - width - margin;
+    let top = origin.y + margin;
+    let bottom = origin.y + size.height as i32 - height - margin;
+    let (x, y) = match corner {
+        Corner::TopLeft => (left, top),
+        Corner::TopRight => (right, top),
+        Corner::BottomLeft => (left, bottom),
+        Corner::BottomRight => (right, bottom),
+    };
+    window.set_position(PhysicalPosition::new(x, y))
+}
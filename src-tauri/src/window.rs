@@ -0,0 +1,150 @@
+use std::sync::Mutex;
+
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::timer::CloseBehaviour;
+use tauri::{
+    AppHandle, Emitter, Manager, Monitor, PhysicalPosition, State, WebviewWindow, Window,
+    WindowEvent,
+};
+
+use crate::mini::MINI_LABEL;
+use crate::recovery::SessionRecovery;
+use crate::session::SessionState;
+use crate::settings::SettingsStore;
+use crate::strict::StrictLock;
+
+/// Asks the main window whether to keep the running session in the tray
+pub const CLOSE_EVENT: &str = "close-requested";
+
+/// Bring the main window back from the tray and focus it
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// Keep the calling window above everything else
+#[tauri::command]
+pub fn set_always_on_top(window: WebviewWindow, enabled: bool) -> Result<(), EepyError> {
+    window
+        .set_always_on_top(enabled)
+        .map_err(|e| EepyError::Other(format!("Failed to change always on top: {}", e)))
+}
+
+/// Where the main window was before theater mode took it to another monitor
+#[derive(Default)]
+pub struct Theater {
+    back_to: Mutex<Option<PhysicalPosition<i32>>>,
+}
+
+/// The monitor called `name`, if it's connected
+pub fn find_monitor(window: &WebviewWindow, name: Option<&str>) -> Option<Monitor> {
+    let name = name?;
+    window
+        .available_monitors()
+        .ok()?
+        .into_iter()
+        .find(|monitor| monitor.name().is_some_and(|n| n == name))
+}
+
+/// Names of the connected monitors, for picking one in settings
+#[tauri::command]
+pub fn monitor_list(window: WebviewWindow) -> Vec<String> {
+    window
+        .available_monitors()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|monitor| monitor.name().cloned())
+        .collect()
+}
+
+/// Take the whole screen for theater mode, on the monitor picked in
+/// settings, or give it back
+#[tauri::command]
+pub fn set_fullscreen(
+    window: WebviewWindow,
+    store: State<SettingsStore>,
+    theater: State<Theater>,
+    enabled: bool,
+) -> Result<(), EepyError> {
+    let fail = |e: tauri::Error| EepyError::Other(format!("Failed to change fullscreen: {}", e));
+    let monitor = store.get().display.monitor;
+    if enabled {
+        if let Some(target) = find_monitor(&window, monitor.as_deref()) {
+            *theater.back_to.lock().unwrap() = window.outer_position().ok();
+            window.set_position(*target.position()).map_err(fail)?;
+        }
+        window.set_fullscreen(true).map_err(fail)
+    } else {
+        window.set_fullscreen(false).map_err(fail)?;
+        if let Some(position) = theater.back_to.lock().unwrap().take() {
+            window.set_position(position).map_err(fail)?;
+        }
+        Ok(())
+    }
+}
+
+/// Send the main window to the tray, after the close prompt
+#[tauri::command]
+pub fn hide_main_window(window: WebviewWindow) -> Result<(), EepyError> {
+    window
+        .hide()
+        .map_err(|e| EepyError::Other(format!("Failed to hide window: {}", e)))
+}
+
+/// Quit for good, after the close prompt. The session was ended on purpose,
+/// so it isn't offered for resuming next time.
+#[tauri::command]
+pub fn quit_app(app: AppHandle) -> Result<(), EepyError> {
+    if app.state::<StrictLock>().locked(&app) {
+        return Err(EepyError::Locked(
+            "Strict mode is locking the session".to_string(),
+        ));
+    }
+    let _ = app.state::<SessionRecovery>().clear();
+    app.exit(0);
+    Ok(())
+}
+
+/// The main window runs the timer and the player. While the mini player is
+/// open, closing it only hides it; while a timer runs, it does what the
+/// settings say. Once it's gone, the app quits, even with the countdown
+/// widget still up.
+pub fn on_window_event(window: &Window, event: &WindowEvent) {
+    if window.label() != "main" {
+        return;
+    }
+    let app = window.app_handle();
+    match event {
+        WindowEvent::CloseRequested { api, .. } if app.get_webview_window(MINI_LABEL).is_some() => {
+            api.prevent_close();
+            let _ = window.hide();
+        }
+        // Strict mode doesn't let the session be closed away
+        WindowEvent::CloseRequested { api, .. } if app.state::<StrictLock>().locked(app) => {
+            api.prevent_close();
+            let _ = window.hide();
+        }
+        WindowEvent::CloseRequested { api, .. } if app.state::<SessionState>().status().running => {
+            match app.state::<SettingsStore>().get().timer.on_close {
+                CloseBehaviour::Ask => {
+                    api.prevent_close();
+                    if let Err(e) = window.emit(CLOSE_EVENT, ()) {
+                        tracing::warn!("Failed to ask about closing: {}", e);
+                    }
+                }
+                CloseBehaviour::Tray => {
+                    api.prevent_close();
+                    let _ = window.hide();
+                }
+                CloseBehaviour::Quit => {
+                    let _ = app.state::<SessionRecovery>().clear();
+                }
+            }
+        }
+        WindowEvent::Destroyed => app.exit(0),
+        _ => {}
+    }
+}
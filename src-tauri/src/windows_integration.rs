@@ -0,0 +1,187 @@
+use std::sync::Mutex;
+
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::pipeline::{Stage, StageOutcome, StageReport};
+use sleepy_video_shared::power::EndAction;
+use sleepy_video_shared::session::SessionEvent;
+use sleepy_video_shared::windows_integration::{PowerPlan, WindowsIntegrationSettings};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast;
+
+use crate::focus::run;
+use crate::session::SessionState;
+
+/// Focus Assist has no public API. Its profile lives in an undocumented WNF
+/// state that the Settings app itself writes: 0 off, 1 priority only, 2
+/// alarms only.
+const WNF_SCRIPT: &str = r#"
+Add-Type -Namespace Eepy -Name Wnf -MemberDefinition '
+[DllImport("ntdll.dll")] public static extern int NtQueryWnfStateData(ref ulong name, IntPtr type, IntPtr scope, out uint stamp, byte[] buffer, ref uint size);
+[DllImport("ntdll.dll")] public static extern int NtUpdateWnfStateData(ref ulong name, byte[] buffer, uint size, IntPtr type, IntPtr scope, uint stamp, uint check);
+'
+$name = [uint64]0x0D83063EA3BF1C75
+"#;
+const ALARMS_ONLY: u32 = 2;
+
+/// What to put back once the session is over
+#[derive(Default)]
+struct Previous {
+    power_plan: Option<String>,
+    focus_assist: Option<u32>,
+}
+
+/// The task following the session, and the state it changed
+#[derive(Default)]
+pub struct WindowsIntegration {
+    task: Mutex<Option<JoinHandle<()>>>,
+    previous: Mutex<Previous>,
+}
+
+impl WindowsIntegration {
+    fn apply(&self, settings: &WindowsIntegrationSettings) {
+        // Don't stack if the last session never got restored
+        self.restore();
+        let mut previous = self.previous.lock().unwrap();
+        if let Some(guid) = &settings.power_plan {
+            match active_plan().and_then(|active| set_plan(guid).map(|_| active)) {
+                Ok(active) => previous.power_plan = Some(active),
+                Err(e) => tracing::warn!("Failed to switch the power plan: {}", e),
+            }
+        }
+        if settings.focus_assist {
+            match focus_assist().and_then(|mode| set_focus_assist(ALARMS_ONLY).map(|_| mode)) {
+                Ok(mode) => previous.focus_assist = Some(mode),
+                Err(e) => tracing::warn!("Failed to turn on Focus Assist: {}", e),
+            }
+        }
+    }
+
+    /// Put back what the session changed. Safe to call when nothing is.
+    pub fn restore(&self) {
+        let previous = std::mem::take(&mut *self.previous.lock().unwrap());
+        if let Some(guid) = previous.power_plan {
+            if let Err(e) = set_plan(&guid) {
+                tracing::warn!("Failed to restore the power plan: {}", e);
+            }
+        }
+        if let Some(mode) = previous.focus_assist {
+            if let Err(e) = set_focus_assist(mode) {
+                tracing::warn!("Failed to restore Focus Assist: {}", e);
+            }
+        }
+    }
+}
+
+/// Parse a `powercfg` line like
+/// `Power Scheme GUID: 381b4222-f694-41f0-9685-ff5bb260df2e  (Balanced) *`.
+/// The label is translated, so only its colon is relied on.
+fn parse_plan(line: &str) -> Option<PowerPlan> {
+    let (_, rest) = line.split_once(':')?;
+    let guid = rest.split_whitespace().next()?.to_string();
+    let name = rest.split_once('(')?.1.rsplit_once(')')?.0.to_string();
+    Some(PowerPlan {
+        guid,
+        name,
+        active: rest.trim_end().ends_with('*'),
+    })
+}
+
+fn active_plan() -> Result<String, String> {
+    let out = run("powercfg", &["/getactivescheme"])?;
+    parse_plan(&out)
+        .map(|plan| plan.guid)
+        .ok_or_else(|| format!("Unexpected powercfg output: {}", out))
+}
+
+fn set_plan(guid: &str) -> Result<(), String> {
+    run("powercfg", &["/setactive", guid]).map(|_| ())
+}
+
+fn powershell(script: &str) -> Result<String, String> {
+    run(
+        "powershell",
+        &[
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            &format!("{}{}", WNF_SCRIPT, script),
+        ],
+    )
+}
+
+fn focus_assist() -> Result<u32, String> {
+    let out = powershell(
+        "$buffer = New-Object byte[] 4; $size = [uint32]4; $stamp = [uint32]0
+[void][Eepy.Wnf]::NtQueryWnfStateData([ref]$name, [IntPtr]::Zero, [IntPtr]::Zero, [ref]$stamp, $buffer, [ref]$size)
+[BitConverter]::ToInt32($buffer, 0)",
+    )?;
+    out.parse()
+        .map_err(|_| format!("Unexpected Focus Assist state: {}", out))
+}
+
+fn set_focus_assist(mode: u32) -> Result<(), String> {
+    powershell(&format!(
+        "[void][Eepy.Wnf]::NtUpdateWnfStateData([ref]$name, [BitConverter]::GetBytes([int]{}), 4, [IntPtr]::Zero, [IntPtr]::Zero, 0, 0)",
+        mode
+    ))
+    .map(|_| ())
+}
+
+/// Stop following the session, put things back and start again with new
+/// settings
+pub fn restart(app: &AppHandle, settings: &WindowsIntegrationSettings) {
+    let integration = app.state::<WindowsIntegration>();
+    let mut running = integration.task.lock().unwrap();
+    if let Some(handle) = running.take() {
+        handle.abort();
+    }
+    integration.restore();
+    if !cfg!(target_os = "windows") || !settings.enabled() {
+        return;
+    }
+
+    let events = app.state::<SessionState>().subscribe();
+    *running = Some(tauri::async_runtime::spawn(follow_session(
+        app.clone(),
+        settings.clone(),
+        events,
+    )));
+}
+
+/// Switch when a session starts. Put things back when it's cancelled, or
+/// once the end action returns, which for sleep is after waking up.
+async fn follow_session(
+    app: AppHandle,
+    settings: WindowsIntegrationSettings,
+    mut events: broadcast::Receiver<SessionEvent>,
+) {
+    loop {
+        let integration = app.state::<WindowsIntegration>();
+        match events.recv().await {
+            Ok(SessionEvent::Started(_)) => integration.apply(&settings),
+            Ok(
+                SessionEvent::Stage(StageReport {
+                    stage: Stage::EndAction,
+                    outcome: StageOutcome::Done | StageOutcome::Failed(_),
+                })
+                | SessionEvent::Ended(EndAction::Nothing)
+                | SessionEvent::Cancelled,
+            ) => integration.restore(),
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+/// Power plans to pick from in settings
+#[tauri::command]
+pub fn power_plans() -> Result<Vec<PowerPlan>, EepyError> {
+    if !cfg!(target_os = "windows") {
+        return Err(EepyError::UnsupportedPlatform(
+            "Power plans are only on Windows".to_string(),
+        ));
+    }
+    let out = run("powercfg", &["/list"])?;
+    Ok(out.lines().filter_map(parse_plan).collect())
+}
@@ -0,0 +1,52 @@
+use leptos::prelude::*;
+use sleepy_video_shared::playback::AD_BREAK_LEVEL;
+use sleepy_video_shared::settings::Settings;
+
+/// Ads the embed plays in the middle of a video, which it reports as a
+/// shorter length than the video's. The volume ducks while one plays.
+#[derive(Clone, Copy)]
+pub struct AdBreak {
+    settings: RwSignal<Settings>,
+    /// Set while an ad plays
+    pub active: RwSignal<bool>,
+}
+
+impl AdBreak {
+    pub fn new(settings: RwSignal<Settings>) -> Self {
+        Self {
+            settings,
+            active: RwSignal::new(false),
+        }
+    }
+
+    /// No ad for a freshly loaded video
+    pub fn reset(&self) {
+        self.active.set(false);
+    }
+
+    /// Compare the `length` the embed reports with the `known` length of the
+    /// video. Before one is known, a longer length means a pre-roll ad was
+    /// taken for the video, so nothing is told apart.
+    pub fn watch(&self, length: f64, known: Option<f64>) {
+        if !self.settings.with_untracked(|s| s.playback.duck_spikes) {
+            return;
+        }
+        if let Some(known) = known {
+            self.active.set(length < known - 1.0);
+        }
+    }
+
+    /// Whether an ad plays, so the embed's times aren't the video's
+    pub fn playing(&self) -> bool {
+        self.active.get_untracked()
+    }
+
+    /// Volume in percent the ad leaves of the video's
+    pub fn level(&self) -> u32 {
+        if self.playing() {
+            AD_BREAK_LEVEL
+        } else {
+            100
+        }
+    }
+}
@@ -1,80 +1,69 @@
+use leptos::ev;
 use leptos::prelude::*;
 use leptos::task::spawn_local;
+use sleepy_video_shared::control::ControlCommand;
+use sleepy_video_shared::display::{format_clock, Theme, SYSTEM_DIM_EVENT};
+use sleepy_video_shared::library::LibraryEntry;
+use sleepy_video_shared::lights::dim_level;
+use sleepy_video_shared::parental::Profile;
+use sleepy_video_shared::profiles::ProfileList;
+use sleepy_video_shared::settings::Settings;
+use sleepy_video_shared::templates::SessionTemplate;
 use wasm_bindgen::prelude::*;
-use wasm_bindgen::JsCast;
-use web_sys::{window, HtmlIFrameElement};
-
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
-    async fn invoke(cmd: &str, args: JsValue) -> JsValue;
-}
-
-/// Extract YouTube video ID from various URL formats
-fn extract_youtube_id(url: &str) -> Option<String> {
-    let url = url.trim();
-    if url.is_empty() {
-        return None;
-    }
-
-    // Try various patterns
-    if let Ok(re) = regex_lite::Regex::new(
-        r"(?:youtube\.com/watch\?v=|youtu\.be/|youtube\.com/embed/)([a-zA-Z0-9_-]{11})",
-    ) {
-        if let Some(caps) = re.captures(url) {
-            if let Some(m) = caps.get(1) {
-                return Some(m.as_str().to_string());
-            }
-        }
-    }
-
-    // Check if it's a direct video ID (11 characters)
-    if url.len() == 11
-        && url
-            .chars()
-            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
-    {
-        return Some(url.to_string());
-    }
-
-    None
-}
+use web_sys::window;
+
+use crate::breathing::BreathingGuide;
+use crate::busy::BusyDialog;
+use crate::clipboard::ClipboardPrompt;
+use crate::close_prompt::ClosePrompt;
+use crate::control::Controls;
+use crate::dim::DimOverlay;
+use crate::end_error::EndErrorDialog;
+use crate::gamepad::GamepadNavigation;
+use crate::haptics;
+use crate::i18n::{provide_locale, t, t_status, Text};
+use crate::idle::{ChromeCatcher, PointerIdle};
+use crate::ipc::{call, listen_to};
+use crate::journal::JournalPrompt;
+use crate::mascot::Mascot;
+use crate::onboarding::Onboarding;
+use crate::parental::{ChildPicker, ParentSignIn};
+use crate::player::VideoPlayer;
+use crate::profiles::ProfileSwitcher;
+use crate::recovery::RecoveredDialog;
+use crate::reminder::ReminderBanner;
+use crate::session::SessionState;
+use crate::settings::SettingsPanel;
+use crate::sfx::{self, Sfx};
+use crate::shortcuts::KeyboardShortcuts;
+use crate::source::pause_video;
+use crate::status::StatusBar;
+use crate::streaks::StreakWhale;
+use crate::strict::StrictPrompt;
+use crate::sunrise::Sunrise;
+use crate::theater::{PinButton, TheaterCountdown};
+use crate::timer::TimerPanel;
+use crate::video::{LinkField, VideoState};
+use crate::zen::ZenClock;
 
 /// Format seconds to HH:MM:SS
-fn format_time(seconds: u32) -> String {
+pub fn format_time(seconds: u32) -> String {
     let h = seconds / 3600;
     let m = (seconds % 3600) / 60;
     let s = seconds % 60;
     format!("{:02}:{:02}:{:02}", h, m, s)
 }
 
-/// Send a command to the YouTube iframe via postMessage
-fn send_youtube_command(func: &str, args: &str) {
-    if let Some(document) = window().and_then(|w| w.document()) {
-        if let Some(iframe) = document.get_element_by_id("youtube-player") {
-            if let Ok(iframe) = iframe.dyn_into::<HtmlIFrameElement>() {
-                if let Some(content_window) = iframe.content_window() {
-                    let message = format!(
-                        r#"{{"event":"command","func":"{}","args":[{}]}}"#,
-                        func, args
-                    );
-                    let _ = content_window.post_message(&JsValue::from_str(&message), "*");
-                }
-            }
-        }
-    }
-}
-
-fn pause_video() {
-    send_youtube_command("pauseVideo", "");
-}
-
-fn set_video_volume(volume: u32) {
-    send_youtube_command("setVolume", &volume.to_string());
+/// Time of day `seconds` from now
+pub fn clock_in(seconds: u32, twelve_hour: bool) -> String {
+    let at = js_sys::Date::new(&JsValue::from_f64(
+        js_sys::Date::now() + seconds as f64 * 1000.0,
+    ));
+    format_clock(at.get_hours(), at.get_minutes(), twelve_hour)
 }
 
 /// Add or remove a class from the body
-fn toggle_body_class(class: &str, add: bool) {
+pub fn toggle_body_class(class: &str, add: bool) {
     if let Some(document) = window().and_then(|w| w.document()) {
         if let Some(body) = document.body() {
             let class_list = body.class_list();
@@ -87,214 +76,227 @@ fn toggle_body_class(class: &str, add: bool) {
     }
 }
 
-/// Update the dim overlay opacity
-fn set_dim_opacity(opacity: f64) {
-    if let Some(document) = window().and_then(|w| w.document()) {
-        if let Some(overlay) = document.get_element_by_id("dim-overlay") {
-            if let Some(el) = overlay.dyn_ref::<web_sys::HtmlElement>() {
-                let _ = el
-                    .style()
-                    .set_property("background-color", &format!("rgba(0, 0, 0, {})", opacity));
-            }
-        }
+/// Switch the stylesheet to `theme`, with `accent` over its accent color
+fn apply_theme(theme: Theme, accent: Option<&str>) {
+    for other in Theme::ALL {
+        toggle_body_class(other.class(), other == theme);
     }
+    let Some(body) = window().and_then(|w| w.document()).and_then(|d| d.body()) else {
+        return;
+    };
+    let style = body.style();
+    let _ = match accent {
+        Some(color) => style.set_property("--accent-warm", color),
+        None => style.remove_property("--accent-warm").map(|_| ()),
+    };
 }
 
 #[component]
 pub fn App() -> impl IntoView {
-    // Timer state
-    let (selected_minutes, set_selected_minutes) = signal(60u32);
-    let (remaining_seconds, set_remaining_seconds) = signal(0u32);
-    let (total_seconds, set_total_seconds) = signal(0u32);
-    let (is_running, set_is_running) = signal(false);
-
-    // Video state
-    let (video_url, set_video_url) = signal(String::new());
-    let (video_id, set_video_id) = signal(Option::<String>::None);
-    let (video_hint, set_video_hint) = signal(String::new());
-    let (video_hint_class, set_video_hint_class) = signal(String::new());
-
-    // Status
-    let (status_text, set_status_text) = signal("READY TO POD".to_string());
-    let (status_class, set_status_class) = signal(String::new());
-
-    // Timer interval handle
-    let (interval_handle, set_interval_handle) = signal(Option::<i32>::None);
-
-    // Load video handler
-    let load_video = move |_| {
-        let url = video_url.get();
-        if url.is_empty() {
-            set_video_hint.set("Please enter a YouTube URL".to_string());
-            set_video_hint_class.set("error".to_string());
-            return;
+    // Settings, shared with child components through context
+    let settings = RwSignal::new(Settings::default());
+    provide_context(settings);
+    provide_locale(settings);
+    let (show_settings, set_show_settings) = signal(false);
+    // With a parental profile, the child only picks allowed videos and starts
+    let profile = RwSignal::new(Profile::Parent);
+    provide_context(profile);
+    let is_child = move || profile.get() == Profile::Child;
+    // Everyone sharing the app, for the header switcher and settings
+    let profiles = RwSignal::new(ProfileList::default());
+    provide_context(profiles);
+    let (show_sign_in, set_show_sign_in) = signal(false);
+    // First launch walks through the basics before anything else
+    let (show_onboarding, set_show_onboarding) = signal(false);
+
+    // Timer state, shared with the components that show it
+    let session = SessionState::new(settings, profile);
+    provide_context(session);
+    let remaining_seconds = session.remaining.read_only();
+    let total_seconds = session.total.read_only();
+    let is_running = Memo::new(move |_| session.is_running());
+    let fade_level = session.fade_level.read_only();
+
+    // Video state, shared with the dialogs that load videos
+    let video = VideoState::new(settings, profile, session);
+    provide_context(video);
+    let media = video.media.read_only();
+    let (player, theater) = (video.player, video.theater);
+    let has_media = video.loaded();
+    video.follow_player();
+
+    // UI sounds go quiet once the fade is well under way
+    Effect::new(move |_| {
+        let level = fade_level.get();
+        settings.with(|s| sfx::set_volume(s.sounds.gain(level)));
+    });
+    Effect::new(move |_| settings.with(|s| haptics::set_enabled(s.haptics.enabled)));
+    let _ = window_event_listener(ev::click, |ev| {
+        let pressed = ev
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+            .and_then(|element| element.closest("button").ok().flatten());
+        if pressed.is_some() {
+            sfx::play(Sfx::Click);
         }
-
-        match extract_youtube_id(&url) {
-            Some(id) => {
-                set_video_id.set(Some(id));
-                toggle_body_class("video-active", true);
-                set_video_hint.set("Video loaded! Set your timer 🌙".to_string());
-                set_video_hint_class.set("success".to_string());
-            }
-            None => {
-                set_video_hint.set("Could not parse YouTube URL".to_string());
-                set_video_hint_class.set("error".to_string());
-            }
-        }
-    };
-
-    // Close video handler
-    let close_video = move |_| {
-        set_video_id.set(None);
-        set_video_url.set(String::new());
-        toggle_body_class("video-active", false);
-        toggle_body_class("dim-mode", false);
-        set_video_hint.set(String::new());
-        set_video_hint_class.set(String::new());
-    };
+    });
+
+    // Look for a freshly copied link whenever the window comes to the front
+    let clipboard = ClipboardPrompt::new(
+        video.url.read_only(),
+        Signal::derive(move || is_running.get() || has_media.get()),
+    );
+    let toggle_theater = Callback::new(move |_| theater.toggle());
+
+    // Fade the UI out while a video plays and the pointer rests
+    let pointer = PointerIdle::new();
+    let chrome_hidden = pointer.hide_chrome(video);
+
+    Effect::new(move |_| {
+        settings.with(|s| apply_theme(s.display.theme, s.display.accent.as_deref()))
+    });
+    Effect::new(move |_| toggle_body_class("bedside", settings.with(|s| s.display.bedside)));
+    Effect::new(move |_| toggle_body_class("leanback", settings.with(|s| s.display.leanback)));
+
+    // Breathing guide for the first minutes of the session. A memo, so the
+    // circle isn't rebuilt on every tick and can ease between sizes.
+    let session_elapsed =
+        Signal::derive(move || total_seconds.get().saturating_sub(remaining_seconds.get()));
+    let show_breathing = Memo::new(move |_| {
+        let (enabled, minutes) =
+            settings.with(|s| (s.display.breathing, s.display.breathing_minutes));
+        enabled && is_running.get() && session_elapsed.get() < minutes * 60
+    });
+
+    // Zen clock: once the dim passes the threshold, a resting pointer hands
+    // the screen over to a big dim clock
+    let zen_active = Memo::new(move |_| {
+        let (enabled, threshold) = settings.with(|s| (s.display.zen_clock, s.display.zen_dim));
+        enabled
+            && is_running.get()
+            && pointer.idle.get()
+            && dim_level(total_seconds.get(), remaining_seconds.get()) * 100.0 >= threshold as f64
+    });
+
+    // D turns the dim overlay off and back on
+    let (dim_enabled, set_dim_enabled) = signal(true);
+    let toggle_dim = Callback::new(move |_| {
+        set_dim_enabled.update(|on| *on = !*on);
+        toggle_body_class("undimmed", !dim_enabled.get_untracked());
+    });
 
     // Start timer handler
-    let start_timer = move |_| {
-        let minutes = selected_minutes.get();
-        if minutes < 1 || minutes > 480 {
-            set_status_text.set("INVALID TIME (1-480 MIN)".to_string());
-            set_status_class.set("warning".to_string());
-            return;
-        }
-
-        let total = minutes * 60;
-        set_total_seconds.set(total);
-        set_remaining_seconds.set(total);
-        set_is_running.set(true);
-        set_status_text.set("TIMER RUNNING".to_string());
-        set_status_class.set("running".to_string());
-
-        // Enable dim mode if video is loaded
-        if video_id.get().is_some() {
-            toggle_body_class("dim-mode", true);
-        }
-
-        // Start interval
-        if let Some(win) = window() {
-            let callback = Closure::<dyn Fn()>::new(move || {
-                let remaining = remaining_seconds.get();
-                let total = total_seconds.get();
-
-                if remaining > 0 {
-                    let new_remaining = remaining - 1;
-                    set_remaining_seconds.set(new_remaining);
-
-                    // Update status based on remaining time
-                    if new_remaining <= 10 {
-                        set_status_text.set("ALMOST THERE...".to_string());
-                        set_status_class.set("warning".to_string());
-                    } else if new_remaining <= 60 {
-                        set_status_text.set("GETTING SLEEPY...".to_string());
-                        set_status_class.set("warning".to_string());
-                    }
-
-                    // Progressive dimming
-                    if total > 0 {
-                        let progress = (total - new_remaining) as f64 / total as f64;
-                        let opacity = progress * 0.9;
-                        set_dim_opacity(opacity);
-                    }
-
-                    // Volume fade in last 10%
-                    if total > 0 && video_id.get_untracked().is_some() {
-                        let ten_percent = total / 10;
-                        if new_remaining <= ten_percent && ten_percent > 0 {
-                            let volume = (new_remaining as f64 / ten_percent as f64 * 100.0) as u32;
-                            set_video_volume(volume);
-                        }
-                    }
-                } else {
-                    // Timer finished
-                    set_is_running.set(false);
-                    set_status_text.set("SWEET DREAMS WHALE!".to_string());
-                    set_status_class.set(String::new());
-
-                    // Clear interval
-                    if let Some(handle) = interval_handle.get_untracked() {
-                        if let Some(win) = window() {
-                            win.clear_interval_with_handle(handle);
-                        }
-                    }
-                    set_interval_handle.set(None);
-
-                    // Pause video
-                    pause_video();
-
-                    // Call suspend
-                    spawn_local(async move {
-                        invoke("suspend_system", JsValue::NULL).await;
-                    });
-                }
-            });
-
-            if let Ok(handle) = win.set_interval_with_callback_and_timeout_and_arguments_0(
-                callback.as_ref().unchecked_ref(),
-                1000,
-            ) {
-                set_interval_handle.set(Some(handle));
+    let start_timer = Callback::new(move |_| {
+        session.start_selected(None, settings.with_untracked(|s| s.timer.fade), has_media)
+    });
+
+    // One click from the recent list: load the video and start right away
+    let play_recent = Callback::new(move |url: String| {
+        video.open(url);
+        start_timer.run(());
+    });
+
+    // A search result only loads, so the duration can still be picked
+    let pick_result = Callback::new(move |url: String| video.open(url));
+
+    let cancel_timer = Callback::new(move |_| session.cancel_unless_locked());
+    let toggle_pause = Callback::new(move |_| session.toggle_pause());
+    let run_test = Callback::new(move |_| session.run_test());
+
+    let controls = Controls::new(settings, profile, session, video);
+    session.follow_stages(move || media.with_untracked(|m| pause_video(m.as_ref())));
+    // The desktop dimming the whole screen takes over from the overlay
+    listen_to::<Option<String>>(SYSTEM_DIM_EVENT, move |backend| {
+        session.os_dim.set(backend.is_some());
+    });
+
+    spawn_local(async move {
+        if let Ok(loaded) = call::<Settings>("get_settings", &()).await {
+            session.selected_minutes.set(loaded.timer.default_minutes);
+            set_show_onboarding.set(!loaded.onboarded);
+            settings.set(loaded);
+            // Reworded in settings, which weren't in when it was first set
+            if !session.is_running() {
+                session.status_text.set(t_status(Text::ReadyToPod));
             }
-
-            callback.forget();
         }
-    };
-
-    // Cancel timer handler
-    let cancel_timer = move |_| {
-        set_is_running.set(false);
-        set_remaining_seconds.set(0);
-        set_total_seconds.set(0);
-        set_status_text.set("TIMER CANCELLED".to_string());
-        set_status_class.set(String::new());
-
-        // Clear interval
-        if let Some(handle) = interval_handle.get() {
-            if let Some(win) = window() {
-                win.clear_interval_with_handle(handle);
+        video.metered.check();
+        if settings.with_untracked(|s| s.display.system_dim) {
+            if let Ok(backend) = call::<Option<String>>("system_dim_backend", &()).await {
+                session.os_dim.set(backend.is_some());
             }
         }
-        set_interval_handle.set(None);
-
-        // Reset dimming
-        set_dim_opacity(0.0);
-
-        // Reset volume
-        if video_id.get().is_some() {
-            set_video_volume(100);
+        if let Ok(entries) = call::<Vec<LibraryEntry>>("library_list", &()).await {
+            video.library.set(entries);
         }
-    };
-
-    // Computed values
-    let timer_display = move || format_time(remaining_seconds.get());
-
-    let progress_percent = move || {
-        let total = total_seconds.get();
-        let remaining = remaining_seconds.get();
-        if total > 0 {
-            ((total - remaining) as f64 / total as f64 * 100.0) as u32
-        } else {
-            0
+        if let Ok(list) = call::<ProfileList>("profile_list", &()).await {
+            profiles.set(list);
         }
+        clipboard.check();
+        controls.take_pending().await;
+        session.check_recovered().await;
+    });
+
+    session.keep_saved(Signal::derive(move || {
+        has_media.get().then(|| video.url.get_untracked())
+    }));
+
+    // Who is using the app can change with the settings
+    Effect::new(move |_| {
+        settings.track();
+        spawn_local(async move {
+            if let Ok(active) = call::<Profile>("active_profile", &()).await {
+                profile.set(active);
+            }
+        });
+    });
+    let sign_out = move |_| {
+        set_show_settings.set(false);
+        spawn_local(async move {
+            if let Ok(active) = call::<Profile>("parent_sign_out", &()).await {
+                profile.set(active);
+            }
+        });
     };
 
-    let is_video_loaded = move || video_id.get().is_some();
-
-    let youtube_embed_url = move || {
-        video_id.get().map(|id| {
-            format!(
-                "https://www.youtube.com/embed/{}?autoplay=1&enablejsapi=1",
-                id
-            )
-        })
-    };
+    session.mirror_status(video.video_id);
 
     view! {
-        <div class="dim-overlay" id="dim-overlay"></div>
+        <DimOverlay/>
+
+        <GamepadNavigation on_input=Callback::new(move |_| pointer.wake())/>
+        <KeyboardShortcuts
+            media=media
+            player=player
+            adjust_timer=Callback::new(move |minutes| session.adjust(minutes))
+            close_video=Callback::new(move |_| {
+                if theater.active.get_untracked() {
+                    theater.set(false);
+                } else {
+                    video.close();
+                }
+            })
+            toggle_dim=toggle_dim
+            toggle_theater=toggle_theater
+        />
+        <ChromeCatcher pointer=pointer hidden=chrome_hidden/>
+        {move || show_breathing.get().then(|| view! {
+            <BreathingGuide
+                pattern=settings.with_untracked(|s| s.display.breathing_pattern)
+                elapsed=session_elapsed
+            />
+        })}
+        {move || controls.sunrise.get().map(|alarm| view! {
+            <Sunrise
+                alarm=alarm
+                on_snooze=Callback::new(move |_| controls.snooze())
+                on_dismiss=Callback::new(move |_| controls.sunrise.set(None))
+            />
+        })}
+        {move || zen_active.get().then(|| view! {
+            <ZenClock remaining=remaining_seconds on_wake=Callback::new(move |_| pointer.wake())/>
+        })}
+        <TheaterCountdown theater=theater/>
 
         <div class="corner-decor corner-tl"></div>
         <div class="corner-decor corner-tr"></div>
@@ -303,170 +305,129 @@ pub fn App() -> impl IntoView {
 
         <div class="app-container">
             <header class="header">
-                <span class="moon-icon">"🐳"</span>
-                <h1 class="title">"SLEEPY WHALE PLAYER"</h1>
-                <p class="subtitle">"Getting eepy?"</p>
+                <Mascot class="moon-icon"/>
+                <h1 class="title">{move || t(Text::Title)}</h1>
+                <p class="subtitle">{move || t(Text::Subtitle)}</p>
+                {move || (!is_child()).then(|| view! { <ProfileSwitcher library=video.library/> })}
+                <PinButton theater=theater/>
+                <button
+                    class="header-btn"
+                    title=move || t(Text::MiniPlayer)
+                    aria-label=move || t(Text::MiniPlayer)
+                    on:click=move |_| spawn_local(async move {
+                        if let Err(e) = call::<()>("open_mini_player", &()).await {
+                            leptos::logging::warn!("{}", e);
+                        }
+                    })
+                >"▣"</button>
+                {move || (settings.with(|s| s.parental.enabled) && !is_child()).then(|| view! {
+                    <button
+                        class="header-btn"
+                        title=move || t(Text::HandBack)
+                        aria-label=move || t(Text::HandBack)
+                        on:click=sign_out
+                    >"🔒"</button>
+                })}
+                <button
+                    class="header-btn"
+                    title=move || t(Text::SettingsTip)
+                    aria-label=move || t(Text::SettingsTip)
+                    on:click=move |_| if is_child() {
+                        set_show_sign_in.set(true);
+                    } else {
+                        set_show_settings.update(|open| *open = !*open);
+                    }
+                >"⚙"</button>
             </header>
 
+            {move || show_settings.get().then(|| view! { <SettingsPanel set_open=set_show_settings/> })}
+            {move || show_onboarding.get().then(|| view! {
+                <Onboarding on_done=Callback::new(move |_| set_show_onboarding.set(false))/>
+            })}
+
+            {move || show_sign_in.get().then(|| view! {
+                <ParentSignIn
+                    profile=profile
+                    on_close=Callback::new(move |_| set_show_sign_in.set(false))
+                />
+            })}
+
+            {move || session.strict_prompt.get().map(|(status, then)| view! {
+                <StrictPrompt
+                    status=status
+                    on_unlocked=Callback::new(move |_| {
+                        session.strict_prompt.set(None);
+                        then.run(());
+                    })
+                    on_close=Callback::new(move |_| session.strict_prompt.set(None))
+                />
+            })}
+
+            <EndErrorDialog/>
+            <ClosePrompt/>
+            <RecoveredDialog/>
+            <BusyDialog/>
+
             {move || {
-                if is_video_loaded() {
+                if has_media.get() {
                     view! {
-                        <section class="video-section">
-                            <div class="video-container pixel-border">
-                                <div class="video-wrapper">
-                                    {move || youtube_embed_url().map(|url| view! {
-                                        <iframe
-                                            id="youtube-player"
-                                            src=url
-                                            allow="accelerometer; autoplay; clipboard-write; encrypted-media; gyroscope; picture-in-picture"
-                                            allowfullscreen=true
-                                        ></iframe>
-                                    })}
-                                </div>
-                                <button
-                                    class="video-close-btn"
-                                    on:click=close_video
-                                    title="Close video"
-                                >"✕"</button>
-                            </div>
-                        </section>
+                        <VideoPlayer
+                            media=media
+                            player=player
+                            queue=video.queue
+                            audio_stream=video.audio_stream.read_only()
+                            resolving_audio=video.resolving_audio.read_only()
+                            resume_offer=video.resume.offer.read_only()
+                            theater=theater.active.read_only()
+                            on_resume=Callback::new(move |_| video.resume_playback())
+                            on_start_over=Callback::new(move |_| video.resume.dismiss())
+                            on_embed_loaded=Callback::new(move |_| video.apply_volume())
+                            on_stream_loaded=Callback::new(move |audio| video.stream_loaded(audio))
+                            on_progress=Callback::new(move |(time, duration)| {
+                                video.stream_progress(time, duration)
+                            })
+                            on_ended=Callback::new(move |_| video.advance_queue())
+                            on_stream_failed=Callback::new(move |_| video.audio_failed())
+                            on_toggle_audio_only=Callback::new(move |_| video.toggle_audio_only())
+                            on_toggle_theater=toggle_theater
+                            on_close=Callback::new(move |_| video.close())
+                        />
                     }.into_any()
+                } else if is_child() {
+                    view! { <ChildPicker library=video.library on_pick=pick_result/> }.into_any()
                 } else {
                     view! {
-                        <div class="video-input-group">
-                            <label for="video-url">"📺 VIDEO URL"</label>
-                            <div class="video-input-row">
-                                <input
-                                    type="url"
-                                    id="video-url"
-                                    class="video-input pixel-border"
-                                    placeholder="Paste YouTube link..."
-                                    prop:value=move || video_url.get()
-                                    on:input=move |ev| set_video_url.set(event_target_value(&ev))
-                                />
-                                <button
-                                    class="btn btn-load pixel-border"
-                                    on:click=load_video
-                                >"LOAD"</button>
-                            </div>
-                            <p class=move || format!("video-hint {}", video_hint_class.get())>
-                                {move || video_hint.get()}
-                            </p>
-                        </div>
+                        <LinkField
+                            video=video
+                            clipboard=clipboard
+                            on_pick=pick_result
+                            on_play=play_recent
+                        />
                     }.into_any()
                 }
             }}
 
+            <ReminderBanner on_start=start_timer/>
+            <StreakWhale/>
+            <JournalPrompt/>
+
             <main class="panel pixel-border">
-                <div class="timer-display pixel-border">
-                    <div class="timer-value">{timer_display}</div>
-                    <div class="timer-label">"REMAINING"</div>
-                    {move || if is_running.get() {
-                        view! {
-                            <span class="zzz">"z"</span>
-                            <span class="zzz">"z"</span>
-                            <span class="zzz">"z"</span>
-                        }.into_any()
-                    } else {
-                        view! { <span></span> }.into_any()
-                    }}
-                </div>
-
-                <div class="progress-container pixel-border">
-                    <div
-                        class=move || if is_running.get() { "progress-bar active" } else { "progress-bar" }
-                        style=move || format!("width: {}%", progress_percent())
-                    ></div>
-                </div>
-
-                <div class="preset-grid">
-                    <button
-                        class=move || if selected_minutes.get() == 15 && !is_running.get() { "preset-btn pixel-border selected" } else { "preset-btn pixel-border" }
-                        disabled=move || is_running.get()
-                        on:click=move |_| set_selected_minutes.set(15)
-                    >"15m"</button>
-                    <button
-                        class=move || if selected_minutes.get() == 30 && !is_running.get() { "preset-btn pixel-border selected" } else { "preset-btn pixel-border" }
-                        disabled=move || is_running.get()
-                        on:click=move |_| set_selected_minutes.set(30)
-                    >"30m"</button>
-                    <button
-                        class=move || if selected_minutes.get() == 45 && !is_running.get() { "preset-btn pixel-border selected" } else { "preset-btn pixel-border" }
-                        disabled=move || is_running.get()
-                        on:click=move |_| set_selected_minutes.set(45)
-                    >"45m"</button>
-                    <button
-                        class=move || if selected_minutes.get() == 60 && !is_running.get() { "preset-btn pixel-border selected" } else { "preset-btn pixel-border" }
-                        disabled=move || is_running.get()
-                        on:click=move |_| set_selected_minutes.set(60)
-                    >"1h"</button>
-                    <button
-                        class=move || if selected_minutes.get() == 90 && !is_running.get() { "preset-btn pixel-border selected" } else { "preset-btn pixel-border" }
-                        disabled=move || is_running.get()
-                        on:click=move |_| set_selected_minutes.set(90)
-                    >"1.5h"</button>
-                    <button
-                        class=move || if selected_minutes.get() == 120 && !is_running.get() { "preset-btn pixel-border selected" } else { "preset-btn pixel-border" }
-                        disabled=move || is_running.get()
-                        on:click=move |_| set_selected_minutes.set(120)
-                    >"2h"</button>
-                    <button
-                        class=move || if selected_minutes.get() == 180 && !is_running.get() { "preset-btn pixel-border selected" } else { "preset-btn pixel-border" }
-                        disabled=move || is_running.get()
-                        on:click=move |_| set_selected_minutes.set(180)
-                    >"3h"</button>
-                    <button
-                        class=move || if selected_minutes.get() == 240 && !is_running.get() { "preset-btn pixel-border selected" } else { "preset-btn pixel-border" }
-                        disabled=move || is_running.get()
-                        on:click=move |_| set_selected_minutes.set(240)
-                    >"4h"</button>
-                </div>
-
-                <div class="custom-input-group">
-                    <label for="custom-minutes">"CUSTOM:"</label>
-                    <input
-                        type="number"
-                        id="custom-minutes"
-                        class="custom-input pixel-border"
-                        min="1"
-                        max="480"
-                        placeholder="60"
-                        disabled=move || is_running.get()
-                        on:input=move |ev| {
-                            if let Ok(val) = event_target_value(&ev).parse::<u32>() {
-                                set_selected_minutes.set(val);
-                            }
-                        }
-                    />
-                    <span style="font-size: 8px; color: var(--text-dim);">"MIN"</span>
-                </div>
-
-                <div class="action-buttons">
-                    {move || if !is_running.get() {
-                        view! {
-                            <button
-                                class="btn btn-primary pixel-border"
-                                on:click=start_timer
-                            >"▶ START"</button>
-                        }.into_any()
-                    } else {
-                        view! {
-                            <button
-                                class="btn btn-danger pixel-border"
-                                on:click=cancel_timer
-                            >"■ CANCEL"</button>
-                        }.into_any()
-                    }}
-                </div>
-
-                <p class=move || format!("status {}", status_class.get())>
-                    {move || status_text.get()}
-                </p>
+                <TimerPanel
+                    on_start=start_timer
+                    on_cancel=cancel_timer
+                    on_pause=toggle_pause
+                    on_test=run_test
+                    testing=session.testing.read_only().into()
+                    on_template=Callback::new(move |template: SessionTemplate| {
+                        controls.handle(ControlCommand::Start(template.start_request()));
+                    })
+                />
+                <StatusBar/>
             </main>
 
             <footer class="footer">
                 <span class="footer-icon">"💤"</span>
-                " PAUSES MEDIA + SLEEPS COMPUTER "
+                {move || t(Text::FooterNote)}
                 <span class="footer-icon">"💤"</span>
             </footer>
         </div>
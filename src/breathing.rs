@@ -0,0 +1,43 @@
+use leptos::prelude::*;
+use sleepy_video_shared::display::{Breath, BreathingPattern};
+
+use crate::i18n::{t, Text};
+
+/// Circle size, as a scale, when breathed out and in
+const SMALLEST: f64 = 0.4;
+const LARGEST: f64 = 1.0;
+
+/// Expanding and contracting circle to breathe along with. It's redrawn on
+/// every tick of the countdown and eases over each second in between.
+#[component]
+pub fn BreathingGuide(pattern: BreathingPattern, elapsed: Signal<u32>) -> impl IntoView {
+    let step = move || pattern.step_at(elapsed.get());
+    // Size at the end of the current second, which the transition eases to
+    let scale = move || {
+        let (breath, into, length) = step();
+        let done = (into + 1) as f64 / length as f64;
+        match breath {
+            Breath::In => SMALLEST + (LARGEST - SMALLEST) * done,
+            Breath::Out => LARGEST - (LARGEST - SMALLEST) * done,
+            Breath::Hold
+                if pattern.step_at(elapsed.get().saturating_sub(into + 1)).0 == Breath::In =>
+            {
+                LARGEST
+            }
+            Breath::Hold => SMALLEST,
+        }
+    };
+
+    view! {
+        <div class="breathing-guide">
+            <div class="breathing-circle" style:transform=move || format!("scale({})", scale())></div>
+            <div class="breathing-label">
+                {move || t(match step().0 {
+                    Breath::In => Text::BreatheIn,
+                    Breath::Hold => Text::Hold,
+                    Breath::Out => Text::BreatheOut,
+                })}
+            </div>
+        </div>
+    }
+}
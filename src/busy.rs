@@ -0,0 +1,50 @@
+use leptos::prelude::*;
+use sleepy_video_shared::settings::Settings;
+
+use crate::dialog::{focus_on_open, trap_focus};
+use crate::i18n::{busy_reason, t, Text};
+use crate::session::SessionState;
+
+/// The session ran out on a busy system: sleep anyway, push the end back or
+/// cancel
+#[component]
+pub fn BusyDialog() -> impl IntoView {
+    let settings = expect_context::<RwSignal<Settings>>();
+    let session = expect_context::<SessionState>();
+
+    move || {
+        session.busy_prompt.get().map(|report| {
+            let postpone_minutes = settings.with_untracked(|s| s.busy_guard.postpone_minutes);
+            view! {
+                <div
+                    class="settings-overlay"
+                    role="dialog"
+                    aria-modal="true"
+                    node_ref=focus_on_open()
+                    on:keydown=trap_focus
+                >
+                    <div class="settings-panel pixel-border">
+                        <h2 class="settings-title">{move || t(Text::SystemLooksBusy)}</h2>
+                        <ul class="busy-reasons">
+                            {report.reasons.into_iter().map(|reason| view! { <li>{move || busy_reason(&reason)}</li> }).collect_view()}
+                        </ul>
+                        <div class="action-buttons">
+                            <button
+                                class="btn btn-danger pixel-border"
+                                on:click=move |_| session.suspend()
+                            >{move || t(Text::SleepAnyway)}</button>
+                            <button
+                                class="btn btn-load pixel-border"
+                                on:click=move |_| session.postpone(postpone_minutes)
+                            >{format!("+{}M", postpone_minutes)}</button>
+                            <button
+                                class="btn btn-load pixel-border"
+                                on:click=move |_| session.cancel_unless_locked()
+                            >{move || t(Text::Cancel)}</button>
+                        </div>
+                    </div>
+                </div>
+            }
+        })
+    }
+}
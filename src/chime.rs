@@ -0,0 +1,60 @@
+use std::cell::RefCell;
+
+use sleepy_video_shared::chime::ChimeTone;
+use wasm_bindgen::JsValue;
+use web_sys::{AudioContext, OscillatorType};
+
+/// Overtones of each note, as (frequency ratio, loudness), so it rings like
+/// a bell rather than beeps
+const PARTIALS: [(f32, f32); 3] = [(1.0, 1.0), (2.76, 0.3), (5.4, 0.1)];
+/// Seconds for a note to swell in, soft enough not to startle
+const ATTACK_SECONDS: f64 = 0.02;
+
+thread_local! {
+    // Webviews limit how many audio contexts can be open, so keep one
+    static CONTEXT: RefCell<Option<AudioContext>> = const { RefCell::new(None) };
+}
+
+/// The audio context chimes and sound effects share, resumed
+pub fn context() -> Result<AudioContext, JsValue> {
+    let context = CONTEXT.with(|c| -> Result<AudioContext, JsValue> {
+        let mut context = c.borrow_mut();
+        if context.is_none() {
+            *context = Some(AudioContext::new()?);
+        }
+        Ok(context.clone().unwrap())
+    })?;
+    // A context made before any user gesture starts out suspended
+    let _ = context.resume();
+    Ok(context)
+}
+
+/// Play a chime at `volume` (0.0 to 1.0), apart from the video's audio
+pub fn play(tone: ChimeTone, volume: f32) -> Result<(), JsValue> {
+    let context = context()?;
+
+    let now = context.current_time();
+    for &(start, frequency, decay) in tone.notes() {
+        for (ratio, loudness) in PARTIALS {
+            let oscillator = context.create_oscillator()?;
+            oscillator.set_type(OscillatorType::Sine);
+            oscillator.frequency().set_value(frequency * ratio);
+
+            // Higher partials die away sooner, like a real bell
+            let gain = context.create_gain()?;
+            let at = now + start;
+            let peak = (volume.clamp(0.0, 1.0) * loudness).max(0.0001);
+            let end = at + decay / ratio as f64;
+            gain.gain().set_value_at_time(0.0001, at)?;
+            gain.gain()
+                .exponential_ramp_to_value_at_time(peak, at + ATTACK_SECONDS)?;
+            gain.gain().exponential_ramp_to_value_at_time(0.0001, end)?;
+
+            oscillator.connect_with_audio_node(&gain)?;
+            gain.connect_with_audio_node(&context.destination())?;
+            oscillator.start_with_when(at)?;
+            oscillator.stop_with_when(end)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use leptos::ev;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::i18n::{t, Text};
+use crate::ipc::call;
+use crate::source::MediaSource;
+
+/// Video link found on the clipboard, offered whenever the window comes to
+/// the front with nothing playing
+#[derive(Clone, Copy)]
+pub struct ClipboardPrompt {
+    /// Link on offer
+    pub copied: RwSignal<Option<String>>,
+    /// The last link the user waved off, not offered again
+    dismissed: RwSignal<Option<String>>,
+    /// What's already in the link field
+    video_url: ReadSignal<String>,
+    /// Set while a session runs or a video is loaded, which a prompt would
+    /// only get in the way of
+    busy: Signal<bool>,
+}
+
+impl ClipboardPrompt {
+    pub fn new(video_url: ReadSignal<String>, busy: Signal<bool>) -> Self {
+        let prompt = Self {
+            copied: RwSignal::new(None),
+            dismissed: RwSignal::new(None),
+            video_url,
+            busy,
+        };
+        let _ = window_event_listener(ev::focus, move |_| prompt.check());
+        prompt
+    }
+
+    /// Look for a freshly copied link
+    pub fn check(self) {
+        if self.busy.get_untracked() {
+            return;
+        }
+        spawn_local(async move {
+            let Ok(Some(url)) = call::<Option<String>>("read_clipboard_video", &()).await else {
+                self.copied.set(None);
+                return;
+            };
+            let fresh = MediaSource::parse(&url).is_some()
+                && self.dismissed.get_untracked().as_ref() != Some(&url)
+                && self.video_url.get_untracked().trim() != url;
+            self.copied.set(fresh.then_some(url));
+        });
+    }
+
+    /// Wave the link off until another one is copied
+    fn dismiss(&self) {
+        self.dismissed.set(self.copied.get_untracked());
+        self.copied.set(None);
+    }
+}
+
+/// Chip over the link field offering the copied link
+#[component]
+pub fn CopiedLink(prompt: ClipboardPrompt, on_load: Callback<String>) -> impl IntoView {
+    let load = move |_| {
+        if let Some(url) = prompt.copied.get_untracked() {
+            prompt.copied.set(None);
+            on_load.run(url);
+        }
+    };
+
+    move || {
+        prompt.copied.get().map(|_| {
+            view! {
+                <div class="clipboard-chip pixel-border">
+                    <button class="clipboard-chip-load" on:click=load>
+                        {move || t(Text::LoadCopied)}
+                    </button>
+                    <button
+                        class="clipboard-chip-dismiss"
+                        title=move || t(Text::DismissTip)
+                        aria-label=move || t(Text::DismissTip)
+                        on:click=move |_| prompt.dismiss()
+                    >"✕"</button>
+                </div>
+            }
+        })
+    }
+}
@@ -0,0 +1,57 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::dialog::{focus_on_open, trap_focus};
+use crate::i18n::{t, Text};
+use crate::ipc::{call, listen_to};
+
+/// Closing the window while a timer runs: off to the tray, quit, or stay
+#[component]
+pub fn ClosePrompt() -> impl IntoView {
+    let (open, set_open) = signal(false);
+    // The backend asks instead of closing while a session runs
+    listen_to::<()>("close-requested", move |_| set_open.set(true));
+
+    move || {
+        open.get().then(|| {
+            view! {
+                <div
+                    class="settings-overlay"
+                    role="dialog"
+                    aria-modal="true"
+                    node_ref=focus_on_open()
+                    on:keydown=trap_focus
+                >
+                    <div class="settings-panel pixel-border">
+                        <h2 class="settings-title">{move || t(Text::TimerRunning)}</h2>
+                        <p class="busy-reasons">{move || t(Text::MinimizeToTray)}</p>
+                        <div class="action-buttons">
+                            <button
+                                class="btn btn-load pixel-border"
+                                on:click=move |_| {
+                                    set_open.set(false);
+                                    spawn_local(async move {
+                                        let _ = call::<()>("hide_main_window", &()).await;
+                                    });
+                                }
+                            >{move || t(Text::ToTray)}</button>
+                            <button
+                                class="btn btn-danger pixel-border"
+                                on:click=move |_| {
+                                    spawn_local(async move {
+                                        let _ = call::<()>("quit_app", &()).await;
+                                    });
+                                }
+                            >{move || t(Text::Quit)}</button>
+                            <button
+                                class="btn btn-load pixel-border"
+                                data-back=true
+                                on:click=move |_| set_open.set(false)
+                            >{move || t(Text::Cancel)}</button>
+                        </div>
+                    </div>
+                </div>
+            }
+        })
+    }
+}
@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+use leptos::prelude::*;
+use sleepy_video_shared::control::ControlCommand;
+use sleepy_video_shared::parental::Profile;
+use sleepy_video_shared::schedule::WakeAlarm;
+use sleepy_video_shared::settings::Settings;
+
+use crate::i18n::{t_with, Text};
+use crate::ipc::{call, listen_to};
+use crate::session::SessionState;
+use crate::video::VideoState;
+
+/// Instructions from the backend: bedtime schedule, launches, remote API, ...
+#[derive(Clone, Copy)]
+pub struct Controls {
+    settings: RwSignal<Settings>,
+    profile: RwSignal<Profile>,
+    session: SessionState,
+    video: VideoState,
+    /// Morning alarm being shown
+    pub sunrise: RwSignal<Option<WakeAlarm>>,
+}
+
+impl Controls {
+    pub fn new(
+        settings: RwSignal<Settings>,
+        profile: RwSignal<Profile>,
+        session: SessionState,
+        video: VideoState,
+    ) -> Self {
+        let controls = Self {
+            settings,
+            profile,
+            session,
+            video,
+            sunrise: RwSignal::new(None),
+        };
+        listen_to::<ControlCommand>("control", move |command| controls.handle(command));
+        controls
+    }
+
+    pub fn handle(self, command: ControlCommand) {
+        let (session, video) = (self.session, self.video);
+        let media = video.media.read_only();
+        match command {
+            ControlCommand::Start(request) => {
+                if session.is_running() {
+                    return;
+                }
+                if let Some(url) = request.video {
+                    video.open(url);
+                }
+                if let Some(minutes) = request.minutes {
+                    session.selected_minutes.set(minutes);
+                }
+                let fade = request
+                    .fade
+                    .unwrap_or_else(|| self.settings.with_untracked(|s| s.timer.fade));
+                session.start_selected(request.action, fade, video.loaded());
+            }
+            ControlCommand::LoadVideo(url) => {
+                if !session.is_running() {
+                    video.open(url);
+                }
+            }
+            ControlCommand::Extend { minutes } => {
+                if !session.is_running() || self.profile.get_untracked() == Profile::Child {
+                    return;
+                }
+                session.unless_locked(Callback::new(move |_| {
+                    session.extend(minutes);
+                    session
+                        .status_text
+                        .set(t_with(Text::Extended, &[("minutes", &minutes.to_string())]));
+                    session.status_class.set("running".to_string());
+                }));
+            }
+            ControlCommand::Cancel => {
+                if session.is_running() {
+                    session.cancel_unless_locked();
+                }
+            }
+            ControlCommand::TogglePlay => {
+                if video.loaded().get_untracked() {
+                    video.player.toggle_play(media);
+                }
+            }
+            ControlCommand::Pause => {
+                if video.loaded().get_untracked() && video.player.playing.get_untracked() {
+                    video.player.toggle_play(media);
+                }
+            }
+            ControlCommand::Seek { seconds } => {
+                if video.loaded().get_untracked() {
+                    video.player.seek_by(media, seconds as f64);
+                }
+            }
+            ControlCommand::WakeAlarm => {
+                if !session.is_running() {
+                    self.sunrise
+                        .set(Some(self.settings.with_untracked(|s| s.wake.clone())));
+                }
+            }
+        }
+    }
+
+    /// Launch arguments are held by the backend until we're ready for them
+    pub async fn take_pending(self) {
+        if let Ok(pending) = call::<Vec<ControlCommand>>("take_pending_commands", &()).await {
+            for command in pending {
+                self.handle(command);
+            }
+        }
+    }
+
+    /// Put the alarm off for its snooze time, unless a session starts first
+    pub fn snooze(self) {
+        let Some(alarm) = self.sunrise.get_untracked() else {
+            return;
+        };
+        self.sunrise.set(None);
+        let delay = Duration::from_secs(alarm.snooze_minutes as u64 * 60);
+        set_timeout(
+            move || {
+                if !self.session.is_running() {
+                    self.sunrise.set(Some(alarm));
+                }
+            },
+            delay,
+        );
+    }
+}
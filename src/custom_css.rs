@@ -0,0 +1,40 @@
+use leptos::task::spawn_local;
+use sleepy_video_shared::display::CustomCss;
+use web_sys::window;
+
+use crate::ipc::call;
+
+/// Id of the `<style>` the user's stylesheet goes in
+const STYLE_ID: &str = "custom-css";
+
+/// Load custom.css from the config folder over the built-in styles,
+/// replacing what an earlier call put there. Returns where the file goes.
+pub async fn load() -> Option<CustomCss> {
+    let loaded = match call::<CustomCss>("custom_css", &()).await {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            leptos::logging::warn!("Could not load custom.css: {}", e);
+            return None;
+        }
+    };
+    let document = window().and_then(|w| w.document())?;
+    if let Some(old) = document.get_element_by_id(STYLE_ID) {
+        old.remove();
+    }
+    if let Some(css) = &loaded.css {
+        let style = document.create_element("style").ok()?;
+        style.set_id(STYLE_ID);
+        style.set_text_content(Some(css));
+        // Last in the head, so it wins over styles.css
+        let head = document.query_selector("head").ok()??;
+        let _ = head.append_child(&style);
+    }
+    Some(loaded)
+}
+
+/// Load custom.css without waiting, for windows that only need the look
+pub fn spawn_load() {
+    spawn_local(async {
+        load().await;
+    });
+}
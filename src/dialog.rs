@@ -0,0 +1,70 @@
+use leptos::html::Div;
+use leptos::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{window, Element, HtmlElement, KeyboardEvent};
+
+/// What Tab can land on inside an overlay
+const FOCUSABLE: &str =
+    "button:not([disabled]), input:not([disabled]), select:not([disabled]), a[href], [tabindex]";
+
+pub fn focusable_in(root: &Element) -> Vec<HtmlElement> {
+    let Ok(found) = root.query_selector_all(FOCUSABLE) else {
+        return Vec::new();
+    };
+    (0..found.length())
+        .filter_map(|i| found.item(i))
+        .filter_map(|node| node.dyn_into::<HtmlElement>().ok())
+        .collect()
+}
+
+/// Move focus into an overlay as it opens. Goes on the overlay as its
+/// `node_ref`, along with `on:keydown=trap_focus`.
+pub fn focus_on_open() -> NodeRef<Div> {
+    let overlay = NodeRef::<Div>::new();
+    Effect::new(move |_| {
+        if let Some(first) = overlay
+            .get()
+            .and_then(|el| focusable_in(&el).into_iter().next())
+        {
+            let _ = first.focus();
+        }
+    });
+    overlay
+}
+
+/// Keep Tab and Shift+Tab cycling through the overlay instead of the page
+/// behind it
+pub fn trap_focus(ev: KeyboardEvent) {
+    if ev.key() != "Tab" {
+        return;
+    }
+    let Some(overlay) = ev
+        .current_target()
+        .and_then(|t| t.dyn_into::<Element>().ok())
+    else {
+        return;
+    };
+    let focusable = focusable_in(&overlay);
+    let (Some(first), Some(last)) = (focusable.first(), focusable.last()) else {
+        ev.prevent_default();
+        return;
+    };
+    let active = window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.active_element());
+    let at = |el: &HtmlElement| {
+        active
+            .as_ref()
+            .is_some_and(|a| a == el.unchecked_ref::<Element>())
+    };
+    let inside = active
+        .as_ref()
+        .is_some_and(|a| overlay.contains(Some(a.as_ref())));
+    if ev.shift_key() && (at(first) || !inside) {
+        ev.prevent_default();
+        let _ = last.focus();
+    } else if !ev.shift_key() && (at(last) || !inside) {
+        ev.prevent_default();
+        let _ = first.focus();
+    }
+}
@@ -0,0 +1,59 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use sleepy_video_shared::lights::dim_level;
+use sleepy_video_shared::session::{SessionStatus, TICK_EVENT};
+use web_sys::window;
+
+use crate::ipc::{call, listen_to};
+use crate::session::SessionState;
+
+/// Black layer over the whole window that darkens as the session runs out,
+/// unless the desktop dims the whole screen instead
+#[component]
+pub fn DimOverlay() -> impl IntoView {
+    let session = expect_context::<SessionState>();
+
+    view! {
+        <div
+            class="dim-overlay"
+            id="dim-overlay"
+            style:background-color=move || {
+                let dim = if session.os_dim.get() { 0.0 } else { session.dim.get() };
+                format!("rgba(0, 0, 0, {})", dim)
+            }
+        ></div>
+    }
+}
+
+/// The whole page of an overlay window the backend puts on the other
+/// monitors. It darkens along with the main window's and holds its last
+/// level once the timer is up, until the backend closes it.
+#[component]
+pub fn MonitorDim() -> impl IntoView {
+    if let Some(root) = window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.document_element())
+    {
+        let _ = root.class_list().add_1("monitor-dim");
+    }
+
+    let dim = RwSignal::new(0.0);
+    let update = move |status: SessionStatus| {
+        if status.running {
+            dim.set(dim_level(status.total_seconds, status.remaining_seconds));
+        }
+    };
+    listen_to::<SessionStatus>(TICK_EVENT, update);
+    spawn_local(async move {
+        if let Ok(current) = call::<SessionStatus>("session_status", &()).await {
+            update(current);
+        }
+    });
+
+    view! {
+        <div
+            class="dim-overlay"
+            style:background-color=move || format!("rgba(0, 0, 0, {})", dim.get())
+        ></div>
+    }
+}
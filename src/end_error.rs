@@ -0,0 +1,48 @@
+use leptos::prelude::*;
+
+use crate::dialog::{focus_on_open, trap_focus};
+use crate::i18n::{action_label, t, t_with, Text};
+use crate::session::SessionState;
+
+/// The end action failed: why, with a retry once the cause can be fixed
+#[component]
+pub fn EndErrorDialog() -> impl IntoView {
+    let session = expect_context::<SessionState>();
+
+    move || {
+        session.end_error.get().map(|(action, error)| {
+            let title = t_with(Text::CouldNot, &[("action", t(action_label(action)))]);
+            let retry = error.retryable().then(|| {
+                view! {
+                    <button
+                        class="btn btn-danger pixel-border"
+                        on:click=move |_| session.retry_end_action(action)
+                    >{move || t(Text::TryAgain)}</button>
+                }
+            });
+            view! {
+                <div
+                    class="settings-overlay"
+                    role="dialog"
+                    aria-modal="true"
+                    node_ref=focus_on_open()
+                    on:keydown=trap_focus
+                >
+                    <div class="settings-panel pixel-border">
+                        <h2 class="settings-title">{title}</h2>
+                        <p class="busy-reasons">{error.to_string().to_uppercase()}</p>
+                        {error.hint().map(|hint| view! { <p class="busy-reasons">{hint.to_uppercase()}</p> })}
+                        <div class="action-buttons">
+                            {retry}
+                            <button
+                                class="btn btn-load pixel-border"
+                                data-back=true
+                                on:click=move |_| session.end_error.set(None)
+                            >{move || t(Text::Dismiss)}</button>
+                        </div>
+                    </div>
+                </div>
+            }
+        })
+    }
+}
@@ -0,0 +1,334 @@
+use std::time::Duration;
+
+use leptos::ev;
+use leptos::prelude::*;
+use sleepy_video_shared::settings::Settings;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    window, Element, Event, EventInit, Gamepad, GamepadButton, HtmlElement, HtmlInputElement,
+    HtmlSelectElement, KeyboardEvent, KeyboardEventInit,
+};
+
+use crate::dialog::focusable_in;
+
+/// Buttons of the standard mapping, which most controllers and air remotes
+/// report in the browser
+const BUTTON_A: u32 = 0;
+const BUTTON_B: u32 = 1;
+const DPAD_UP: u32 = 12;
+const DPAD_DOWN: u32 = 13;
+const DPAD_LEFT: u32 = 14;
+const DPAD_RIGHT: u32 = 15;
+/// How far the left stick has to lean to count as the D-pad
+const STICK_THRESHOLD: f64 = 0.5;
+
+/// The Gamepad API has no events for buttons, so pads are polled while
+/// one is connected
+const POLL_EVERY: Duration = Duration::from_millis(50);
+/// Milliseconds a direction is held before it repeats, and between repeats
+const REPEAT_DELAY: f64 = 400.0;
+const REPEAT_EVERY: f64 = 150.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Input {
+    Move(Direction),
+    Activate,
+    Back,
+}
+
+/// What's held down on any of the connected pads
+fn held() -> Vec<Input> {
+    let Some(pads) = window().and_then(|w| w.navigator().get_gamepads().ok()) else {
+        return Vec::new();
+    };
+    let mut inputs = Vec::new();
+    for pad in pads.iter().filter_map(|pad| pad.dyn_into::<Gamepad>().ok()) {
+        let buttons = pad.buttons();
+        let pressed = |index: u32| {
+            buttons
+                .get(index)
+                .dyn_into::<GamepadButton>()
+                .is_ok_and(|button| button.pressed())
+        };
+        let axes = pad.axes();
+        let axis = |index: u32| axes.get(index).as_f64().unwrap_or_default();
+        let (x, y) = (axis(0), axis(1));
+
+        let found = [
+            (
+                pressed(DPAD_UP) || y < -STICK_THRESHOLD,
+                Input::Move(Direction::Up),
+            ),
+            (
+                pressed(DPAD_DOWN) || y > STICK_THRESHOLD,
+                Input::Move(Direction::Down),
+            ),
+            (
+                pressed(DPAD_LEFT) || x < -STICK_THRESHOLD,
+                Input::Move(Direction::Left),
+            ),
+            (
+                pressed(DPAD_RIGHT) || x > STICK_THRESHOLD,
+                Input::Move(Direction::Right),
+            ),
+            (pressed(BUTTON_A), Input::Activate),
+            (pressed(BUTTON_B), Input::Back),
+        ];
+        for (down, input) in found {
+            if down && !inputs.contains(&input) {
+                inputs.push(input);
+            }
+        }
+    }
+    inputs
+}
+
+fn active_element() -> Option<Element> {
+    window()?.document()?.active_element()
+}
+
+/// The open overlay, which keeps the focus to itself, or the whole page
+fn focus_scope() -> Option<Element> {
+    let document = window()?.document()?;
+    let overlays = document.query_selector_all("[aria-modal=\"true\"]").ok()?;
+    match overlays.length() {
+        0 => document.body().map(Into::into),
+        n => overlays.item(n - 1)?.dyn_into().ok(),
+    }
+}
+
+/// Focus the nearest control that way from the focused one, leaning on
+/// the ones lined up with it
+fn move_focus(direction: Direction) {
+    let Some(scope) = focus_scope() else {
+        return;
+    };
+    let candidates: Vec<HtmlElement> = focusable_in(&scope)
+        .into_iter()
+        .filter(|el| {
+            let rect = el.get_bounding_client_rect();
+            rect.width() > 0.0 && rect.height() > 0.0
+        })
+        .collect();
+    let active = active_element().filter(|a| scope.contains(Some(a.as_ref())));
+    let Some(active) = active else {
+        if let Some(first) = candidates.first() {
+            let _ = first.focus();
+        }
+        return;
+    };
+
+    let from = active.get_bounding_client_rect();
+    let (cx, cy) = (
+        from.x() + from.width() / 2.0,
+        from.y() + from.height() / 2.0,
+    );
+    let nearest = candidates
+        .iter()
+        .filter(|el| *el.unchecked_ref::<Element>() != active)
+        .filter_map(|el| {
+            let rect = el.get_bounding_client_rect();
+            let dx = rect.x() + rect.width() / 2.0 - cx;
+            let dy = rect.y() + rect.height() / 2.0 - cy;
+            let (along, across) = match direction {
+                Direction::Up => (-dy, dx),
+                Direction::Down => (dy, dx),
+                Direction::Left => (-dx, dy),
+                Direction::Right => (dx, dy),
+            };
+            (along > 1.0).then_some((el, along + across.abs() * 2.0))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+    if let Some((el, _)) = nearest {
+        let _ = el.focus();
+    }
+}
+
+/// Step a focused dropdown or slider with left and right, as it can't be
+/// opened from a pad. Whether it took the step.
+fn step_value(direction: Direction) -> bool {
+    let step = match direction {
+        Direction::Left => -1,
+        Direction::Right => 1,
+        Direction::Up | Direction::Down => return false,
+    };
+    let Some(active) = active_element() else {
+        return false;
+    };
+    let changed = if let Some(select) = active.dyn_ref::<HtmlSelectElement>() {
+        let index = select.selected_index() + step;
+        if index < 0 || index >= select.length() as i32 {
+            return true;
+        }
+        select.set_selected_index(index);
+        "change"
+    } else if let Some(input) = active.dyn_ref::<HtmlInputElement>() {
+        if !matches!(input.type_().as_str(), "range" | "number") {
+            return false;
+        }
+        let by = input.step().parse::<f64>().unwrap_or(1.0);
+        let min = input.min().parse::<f64>().unwrap_or(f64::MIN);
+        let max = input.max().parse::<f64>().unwrap_or(f64::MAX);
+        let value = input.value_as_number();
+        let value = if value.is_nan() { min.max(0.0) } else { value };
+        input.set_value_as_number((value + by * step as f64).clamp(min, max));
+        "input"
+    } else {
+        return false;
+    };
+    // Bubbling, for the delegated listeners
+    let init = EventInit::new();
+    init.set_bubbles(true);
+    if let Ok(event) = Event::new_with_event_init_dict(changed, &init) {
+        let _ = active.dispatch_event(&event);
+    }
+    true
+}
+
+/// Click the button marked `data-back` in the overlay on top, the one that
+/// only closes it, or press Escape to leave what's open. Overlays whose
+/// every button does something (discarding a session, cancelling the
+/// timer) aren't marked, so backing out never picks one of those.
+fn go_back() {
+    let dismiss = focus_scope()
+        .filter(|scope| scope.has_attribute("aria-modal"))
+        .and_then(|overlay| {
+            overlay
+                .query_selector("[data-back]:not([disabled])")
+                .ok()
+                .flatten()
+        })
+        .and_then(|button| button.dyn_into::<HtmlElement>().ok());
+    if let Some(button) = dismiss {
+        button.click();
+        return;
+    }
+    let init = KeyboardEventInit::new();
+    init.set_key("Escape");
+    init.set_bubbles(true);
+    let target = active_element().or_else(|| window()?.document()?.body().map(Into::into));
+    if let (Some(target), Ok(event)) = (
+        target,
+        KeyboardEvent::new_with_keyboard_event_init_dict("keydown", &init),
+    ) {
+        let _ = target.dispatch_event(&event);
+    }
+}
+
+fn activate() {
+    if let Some(el) = active_element().and_then(|a| a.dyn_into::<HtmlElement>().ok()) {
+        el.click();
+    }
+}
+
+fn handle(input: Input) {
+    match input {
+        Input::Move(direction) => {
+            if !step_value(direction) {
+                move_focus(direction);
+            }
+        }
+        Input::Activate => activate(),
+        Input::Back => go_back(),
+    }
+}
+
+/// Couch controls: the D-pad or left stick moves the focus, A presses what
+/// has it and B backs out. In leanback, the arrow keys of an air remote or
+/// keyboard do the same as the D-pad.
+#[component]
+pub fn GamepadNavigation(
+    /// Any input from a pad, to bring hidden controls back
+    on_input: Callback<()>,
+) -> impl IntoView {
+    let settings = expect_context::<RwSignal<Settings>>();
+    // Inputs held at the last poll, with when each fires again
+    let held_at = StoredValue::new(Vec::<(Input, f64)>::new());
+    let poller = StoredValue::new(None::<IntervalHandle>);
+
+    let poll = move || {
+        let now = js_sys::Date::now();
+        let inputs = held();
+        let mut fired = Vec::new();
+        held_at.update_value(|held_at| {
+            held_at.retain(|(input, _)| inputs.contains(input));
+            for input in inputs {
+                match held_at.iter_mut().find(|(held, _)| *held == input) {
+                    None => {
+                        held_at.push((input, now + REPEAT_DELAY));
+                        fired.push(input);
+                    }
+                    Some((Input::Move(_), next)) if now >= *next => {
+                        *next = now + REPEAT_EVERY;
+                        fired.push(input);
+                    }
+                    Some(_) => {}
+                }
+            }
+        });
+        if !fired.is_empty() {
+            on_input.run(());
+        }
+        for input in fired {
+            handle(input);
+        }
+    };
+
+    let _ = window_event_listener(ev::gamepadconnected, move |_| {
+        if poller.with_value(Option::is_none) {
+            poller.set_value(set_interval_with_handle(poll, POLL_EVERY).ok());
+        }
+    });
+    let _ = window_event_listener(ev::gamepaddisconnected, move |_| {
+        let connected = window()
+            .and_then(|w| w.navigator().get_gamepads().ok())
+            .is_some_and(|pads| pads.iter().any(|pad| pad.is_truthy()));
+        if !connected {
+            if let Some(handle) = poller.try_update_value(Option::take).flatten() {
+                handle.clear();
+            }
+            held_at.set_value(Vec::new());
+        }
+    });
+
+    let _ = window_event_listener(ev::keydown, move |ev| {
+        if !settings.with_untracked(|s| s.display.leanback) || ev.default_prevented() {
+            return;
+        }
+        let direction = match ev.key().as_str() {
+            "ArrowUp" => Direction::Up,
+            "ArrowDown" => Direction::Down,
+            "ArrowLeft" => Direction::Left,
+            "ArrowRight" => Direction::Right,
+            "GoBack" | "BrowserBack" => {
+                ev.prevent_default();
+                go_back();
+                return;
+            }
+            _ => return,
+        };
+        // Left and right move the caret in text fields
+        let typing = active_element().is_some_and(|a| {
+            a.tag_name().eq_ignore_ascii_case("textarea")
+                || a.dyn_ref::<HtmlInputElement>().is_some_and(|input| {
+                    !matches!(
+                        input.type_().as_str(),
+                        "checkbox" | "radio" | "button" | "range" | "number" | "color"
+                    )
+                })
+        });
+        if typing && matches!(direction, Direction::Left | Direction::Right) {
+            return;
+        }
+        ev.prevent_default();
+        handle(Input::Move(direction));
+    });
+}
@@ -0,0 +1,38 @@
+use std::cell::Cell;
+
+use leptos::task::spawn_local;
+use serde::Serialize;
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::haptics::Haptic;
+
+use crate::ipc::call;
+
+#[derive(Serialize)]
+struct VibrateArgs {
+    haptic: Haptic,
+}
+
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+    // Cleared the first time the backend says there's nothing to buzz,
+    // so desktops stop asking
+    static SUPPORTED: Cell<bool> = const { Cell::new(true) };
+}
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.with(|e| e.set(enabled));
+}
+
+/// Buzz the phone, when it has haptics and they're on
+pub fn buzz(haptic: Haptic) {
+    if !ENABLED.with(Cell::get) || !SUPPORTED.with(Cell::get) {
+        return;
+    }
+    spawn_local(async move {
+        match call::<()>("vibrate", &VibrateArgs { haptic }).await {
+            Ok(()) => {}
+            Err(EepyError::UnsupportedPlatform(_)) => SUPPORTED.with(|s| s.set(false)),
+            Err(e) => leptos::logging::warn!("Could not vibrate: {}", e),
+        }
+    });
+}
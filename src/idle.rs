@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use leptos::ev;
+use leptos::prelude::*;
+
+use crate::app::toggle_body_class;
+use crate::video::VideoState;
+
+/// How long the pointer rests before the UI fades out over a playing video
+const CHROME_IDLE: Duration = Duration::from_secs(3);
+
+/// Whether the pointer and keyboard have been resting, to fade the UI out
+/// over a playing video and hand the screen to the zen clock
+#[derive(Clone, Copy)]
+pub struct PointerIdle {
+    pub idle: RwSignal<bool>,
+    timer: RwSignal<Option<TimeoutHandle>>,
+}
+
+impl PointerIdle {
+    pub fn new() -> Self {
+        let pointer = Self {
+            idle: RwSignal::new(false),
+            timer: RwSignal::new(None),
+        };
+        pointer.wake();
+        let _ = window_event_listener(ev::mousemove, move |_| pointer.wake());
+        let _ = window_event_listener(ev::keydown, move |_| pointer.wake());
+        pointer
+    }
+
+    /// Something moved: back from idle, and start waiting again
+    pub fn wake(&self) {
+        if let Some(handle) = self.timer.get_untracked() {
+            handle.clear();
+        }
+        self.idle.set(false);
+        let idle = self.idle;
+        self.timer
+            .set(set_timeout_with_handle(move || idle.set(true), CHROME_IDLE).ok());
+    }
+
+    /// Hide the UI while `video` plays and the pointer rests
+    pub fn hide_chrome(self, video: VideoState) -> Memo<bool> {
+        let hidden = Memo::new(move |_| {
+            self.idle.get() && video.player.playing.get() && video.media.with(Option::is_some)
+        });
+        Effect::new(move |_| toggle_body_class("chrome-hidden", hidden.get()));
+        hidden
+    }
+}
+
+/// The iframe swallows mouse moves, so while the UI is hidden this catches
+/// the first one to bring it back
+#[component]
+pub fn ChromeCatcher(pointer: PointerIdle, hidden: Memo<bool>) -> impl IntoView {
+    move || {
+        hidden.get().then(|| {
+            view! { <div class="chrome-catcher" on:mousemove=move |_| pointer.wake()></div> }
+        })
+    }
+}
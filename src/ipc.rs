@@ -0,0 +1,68 @@
+use leptos::task::spawn_local;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sleepy_video_shared::error::EepyError;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], catch)]
+    async fn invoke(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
+
+    /// Address the webview can load `path` from, through one of the app's
+    /// URI schemes
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], js_name = convertFileSrc)]
+    pub fn convert_file_src(path: &str, protocol: &str) -> String;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"], catch)]
+    async fn listen(event: &str, handler: &Closure<dyn FnMut(JsValue)>)
+        -> Result<JsValue, JsValue>;
+}
+
+/// Errors from commands (and plugins, which fail with plain messages) come
+/// back as an `EepyError`
+fn command_error(e: JsValue) -> EepyError {
+    serde_wasm_bindgen::from_value::<EepyError>(e.clone())
+        .unwrap_or_else(|_| EepyError::Other(e.as_string().unwrap_or_else(|| format!("{:?}", e))))
+}
+
+async fn invoke_with(cmd: &str, args: &impl Serialize) -> Result<JsValue, EepyError> {
+    let args = args
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| EepyError::Other(e.to_string()))?;
+    invoke(cmd, args).await.map_err(command_error)
+}
+
+/// Invoke a backend command with serializable args and deserialize its result
+pub async fn call<T: DeserializeOwned>(cmd: &str, args: &impl Serialize) -> Result<T, EepyError> {
+    let result = invoke_with(cmd, args).await?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| EepyError::Other(e.to_string()))
+}
+
+/// Subscribe to a backend event for the lifetime of the page, handing each
+/// deserialized payload to `handler`
+pub fn listen_to<T: DeserializeOwned + 'static>(
+    event: &'static str,
+    mut handler: impl FnMut(T) + 'static,
+) {
+    #[derive(Deserialize)]
+    struct Event<P> {
+        payload: P,
+    }
+
+    let closure =
+        Closure::<dyn FnMut(JsValue)>::new(
+            move |raw: JsValue| match serde_wasm_bindgen::from_value::<Event<T>>(raw) {
+                Ok(received) => handler(received.payload),
+                Err(e) => leptos::logging::warn!("Bad {} event payload: {}", event, e),
+            },
+        );
+
+    spawn_local(async move {
+        match listen(event, &closure).await {
+            // The listener stays registered for as long as the app runs
+            Ok(_) => closure.forget(),
+            Err(e) => leptos::logging::warn!("Couldn't listen to {}: {}", event, command_error(e)),
+        }
+    });
+}
@@ -0,0 +1,130 @@
+use leptos::ev;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use serde::Serialize;
+use sleepy_video_shared::history::SessionRecord;
+use sleepy_video_shared::settings::Settings;
+
+use crate::i18n::{t, Text};
+use crate::ipc::call;
+use crate::session::SessionState;
+
+#[derive(Serialize)]
+struct NoteArgs {
+    note: String,
+}
+
+/// One-line prompt to note how they feel: at bedtime once the timer runs,
+/// and about the night the next time eepy comes up after a finished
+/// session. Each is optional under JOURNAL in settings. Also notes when
+/// they woke up, for the health exports.
+#[component]
+pub fn JournalPrompt() -> impl IntoView {
+    let settings = expect_context::<RwSignal<Settings>>();
+    let session = expect_context::<SessionState>();
+    let running = Memo::new(move |_| session.is_running());
+    // Answered or dismissed for the running session
+    let bedtime_done = RwSignal::new(false);
+    let awaiting_wake = RwSignal::new(false);
+    let note = RwSignal::new(String::new());
+
+    Effect::new(move |_| {
+        if running.get() {
+            bedtime_done.set(false);
+            note.set(String::new());
+        }
+    });
+
+    // Coming back after a finished session is taken as waking up from it,
+    // noted right away when the journal doesn't ask
+    let check_wake = move || {
+        if running.get_untracked() {
+            return;
+        }
+        spawn_local(async move {
+            let Ok(Some(last)) = call::<Option<SessionRecord>>("history_last", &()).await else {
+                return;
+            };
+            if !last.awaits_wake(js_sys::Date::now() as u64) {
+                return;
+            }
+            if settings.with_untracked(|s| s.journal.on_wake) {
+                awaiting_wake.try_set(true);
+            } else if let Err(e) = call::<()>("history_woke", &()).await {
+                leptos::logging::warn!("Could not note waking up: {}", e);
+            }
+        });
+    };
+    // Settings arrive after the first render; waiting for them keeps the
+    // defaults from noting waking up when the journal would have asked
+    Effect::new(move |loaded: Option<()>| {
+        settings.track();
+        if loaded.is_some() {
+            check_wake();
+        }
+    });
+    let _ = window_event_listener(ev::focus, move |_| check_wake());
+
+    let asking_wake =
+        move || awaiting_wake.get() && !running.get() && settings.with(|s| s.journal.on_wake);
+    let asking_bedtime =
+        move || running.get() && !bedtime_done.get() && settings.with(|s| s.journal.at_start);
+
+    let answer = move |text: String| {
+        let wake = asking_wake();
+        if wake {
+            awaiting_wake.set(false);
+        } else {
+            bedtime_done.set(true);
+        }
+        note.set(String::new());
+        spawn_local(async move {
+            let command = if wake {
+                "journal_wake"
+            } else {
+                "journal_bedtime"
+            };
+            if let Err(e) = call::<()>(command, &NoteArgs { note: text }).await {
+                leptos::logging::warn!("Could not save the note: {}", e);
+            }
+        });
+    };
+    let save = move || answer(note.get_untracked());
+
+    move || {
+        let prompt = if asking_wake() {
+            t(Text::HowDidYouSleep)
+        } else if asking_bedtime() {
+            t(Text::HowAreYouFeeling)
+        } else {
+            return None;
+        };
+        Some(view! {
+            <div class="journal-prompt pixel-border">
+                <label for="journal-note">{prompt}</label>
+                <div class="video-input-row">
+                    <input
+                        type="text"
+                        id="journal-note"
+                        class="video-input pixel-border"
+                        maxlength="140"
+                        prop:value=move || note.get()
+                        on:input=move |ev| note.set(event_target_value(&ev))
+                        on:keydown=move |ev| {
+                            if ev.key() == "Enter" {
+                                save();
+                            }
+                        }
+                    />
+                    <button class="btn btn-load pixel-border" on:click=move |_| save()>{move || t(Text::Save)}</button>
+                    <button
+                        class="clipboard-chip-dismiss"
+                        title=move || t(Text::SkipTip)
+                        aria-label=move || t(Text::SkipTip)
+                        on:click=move |_| answer(String::new())
+                    >"✕"</button>
+                </div>
+            </div>
+        })
+    }
+}
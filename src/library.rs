@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use serde::Serialize;
+use sleepy_video_shared::library::{LibraryEntry, VideoInfo};
+
+use crate::app::format_time;
+use crate::i18n::{t, Text};
+use crate::ipc::call;
+
+#[derive(Serialize)]
+struct FavoriteArgs {
+    video: String,
+    favorite: bool,
+}
+
+#[derive(Serialize)]
+struct VideoArgs {
+    video: String,
+}
+
+/// Recently played and favorite videos as a row of thumbnails. Favorites come
+/// first, clicking a card plays it right away. Titles, lengths and
+/// thumbnails come from the backend's cache, so they show offline too.
+#[component]
+pub fn RecentVideos(
+    library: RwSignal<Vec<LibraryEntry>>,
+    on_play: Callback<String>,
+) -> impl IntoView {
+    let infos = RwSignal::new(HashMap::<String, VideoInfo>::new());
+    Effect::new(move |_| {
+        let missing: Vec<String> = library.with(|entries| {
+            entries
+                .iter()
+                .map(|entry| entry.video_id.clone())
+                .filter(|video| !infos.with_untracked(|infos| infos.contains_key(video)))
+                .collect()
+        });
+        for video in missing {
+            spawn_local(async move {
+                match call::<VideoInfo>("video_info", &VideoArgs { video }).await {
+                    Ok(info) => {
+                        infos.try_update(|infos| infos.insert(info.video_id.clone(), info));
+                    }
+                    Err(e) => leptos::logging::warn!("Could not look up video: {}", e),
+                }
+            });
+        }
+    });
+
+    let set_favorite = move |video: String, favorite: bool| {
+        spawn_local(async move {
+            match call::<Vec<LibraryEntry>>("library_favorite", &FavoriteArgs { video, favorite })
+                .await
+            {
+                Ok(entries) => library.set(entries),
+                Err(e) => leptos::logging::warn!("Could not update favorite: {}", e),
+            }
+        });
+    };
+
+    let remove = move |video: String| {
+        spawn_local(async move {
+            match call::<Vec<LibraryEntry>>("library_remove", &VideoArgs { video }).await {
+                Ok(entries) => library.set(entries),
+                Err(e) => leptos::logging::warn!("Could not remove video: {}", e),
+            }
+        });
+    };
+
+    let sorted = move || {
+        let mut entries = library.get();
+        entries.sort_by_key(|entry| !entry.favorite);
+        entries
+    };
+
+    move || {
+        (!library.with(Vec::is_empty)).then(|| view! {
+            <div class="recent-videos">
+                <label>{move || t(Text::Recent)}</label>
+                <div class="recent-carousel">
+                    {sorted().into_iter().map(|entry| {
+                        let info = infos.with(|infos| infos.get(&entry.video_id).cloned());
+                        let info = info.unwrap_or_default();
+                        let thumbnail = info.thumbnail.unwrap_or_else(|| entry.thumbnail_url());
+                        let title = entry
+                            .title
+                            .clone()
+                            .or(info.title)
+                            .unwrap_or_else(|| entry.video_id.clone());
+                        let duration = info.duration_secs.map(format_time);
+                        let tooltip = title.clone();
+                        let play_id = entry.video_id.clone();
+                        let star_id = entry.video_id.clone();
+                        let remove_id = entry.video_id.clone();
+                        let favorite = entry.favorite;
+                        view! {
+                            <div class="recent-card pixel-border" title=tooltip>
+                                <button
+                                    class="recent-play"
+                                    on:click=move |_| on_play.run(play_id.clone())
+                                >
+                                    <span class="recent-thumb">
+                                        <img src=thumbnail alt="" loading="lazy"/>
+                                        {duration.map(|duration| view! {
+                                            <span class="recent-duration">{duration}</span>
+                                        })}
+                                    </span>
+                                    <span class="recent-title">{title}</span>
+                                </button>
+                                <button
+                                    class=if favorite { "recent-star starred" } else { "recent-star" }
+                                    title=if favorite { t(Text::Unfavorite) } else { t(Text::Favorite) }
+                                    aria-label=if favorite { t(Text::Unfavorite) } else { t(Text::Favorite) }
+                                    on:click=move |_| set_favorite(star_id.clone(), !favorite)
+                                >{if favorite { "★" } else { "☆" }}</button>
+                                {(!favorite).then(|| view! {
+                                    <button
+                                        class="recent-remove"
+                                        title=move || t(Text::RemoveTip)
+                                        aria-label=move || t(Text::RemoveTip)
+                                        on:click=move |_| remove(remove_id.clone())
+                                    >"✕"</button>
+                                })}
+                            </div>
+                        }
+                    }).collect_view()}
+                </div>
+            </div>
+        })
+    }
+}
@@ -1,13 +1,79 @@
+mod ad_break;
 mod app;
+mod breathing;
+mod busy;
+mod chime;
+mod clipboard;
+mod close_prompt;
+mod control;
+mod custom_css;
+mod dialog;
+mod dim;
+mod end_error;
+mod gamepad;
+mod haptics;
+mod i18n;
+mod idle;
+mod ipc;
+mod journal;
+mod library;
+mod mascot;
+mod metered;
+mod mini;
+mod muffle;
+mod onboarding;
+mod parental;
+mod player;
+mod presets;
+mod profiles;
+mod queue;
+mod recovery;
+mod reminder;
+mod resume;
+mod search;
+mod session;
+mod settings;
+mod sfx;
+mod shortcuts;
+mod source;
+mod speech;
+mod status;
+mod streaks;
+mod strict;
+mod sunrise;
+mod theater;
+mod ticker;
+mod timer;
+mod transport;
+mod video;
+mod volume_memory;
+mod widget;
+mod zen;
 
 use app::*;
+use dim::MonitorDim;
 use leptos::prelude::*;
+use mini::MiniPlayer;
+use widget::CountdownWidget;
 
 fn main() {
     console_error_panic_hook::set_once();
-    mount_to_body(|| {
-        view! {
-            <App/>
+    // The mini player, countdown widget and other monitors' dim windows load
+    // the same page with ?view=mini, ?view=widget or ?view=dim
+    let query = web_sys::window()
+        .and_then(|w| w.location().search().ok())
+        .unwrap_or_default();
+    // Every window wears the user's skin
+    custom_css::spawn_load();
+    mount_to_body(move || {
+        if query.contains("view=mini") {
+            view! { <MiniPlayer/> }.into_any()
+        } else if query.contains("view=widget") {
+            view! { <CountdownWidget/> }.into_any()
+        } else if query.contains("view=dim") {
+            view! { <MonitorDim/> }.into_any()
+        } else {
+            view! { <App/> }.into_any()
         }
     })
 }
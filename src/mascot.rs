@@ -0,0 +1,43 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use sleepy_video_shared::mascot::Mascot as MascotKind;
+use sleepy_video_shared::settings::Settings;
+
+use crate::ipc::call;
+
+/// The mascot picked in settings: an emoji, or their own sprite loaded from
+/// the config. `class` sizes and animates it where it's shown.
+#[component]
+pub fn Mascot(#[prop(into)] class: String) -> impl IntoView {
+    let settings = expect_context::<RwSignal<Settings>>();
+    let kind = Memo::new(move |_| settings.with(|s| s.mascot.mascot));
+    let sprite_path = Memo::new(move |_| settings.with(|s| s.mascot.sprite_path.clone()));
+    let sprite = RwSignal::new(Option::<String>::None);
+
+    Effect::new(move |_| {
+        sprite_path.track();
+        if kind.get() != MascotKind::Custom {
+            sprite.set(None);
+            return;
+        }
+        spawn_local(async move {
+            match call::<Option<String>>("mascot_sprite", &()).await {
+                Ok(loaded) => {
+                    sprite.try_set(loaded);
+                }
+                Err(e) => leptos::logging::warn!("Could not load the mascot: {}", e),
+            }
+        });
+    });
+
+    move || match sprite.get() {
+        Some(url) => view! {
+            <img class=format!("{} mascot-sprite", class) src=url alt="" aria-hidden="true"/>
+        }
+        .into_any(),
+        None => view! {
+            <span class=class.clone() aria-hidden="true">{move || kind.get().emoji()}</span>
+        }
+        .into_any(),
+    }
+}
@@ -0,0 +1,48 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use sleepy_video_shared::settings::Settings;
+
+use crate::app::toggle_body_class;
+use crate::ipc::call;
+
+/// Whether the connection is metered, for the bandwidth saver. The body
+/// gets `low-data` while data is being saved.
+#[derive(Clone, Copy)]
+pub struct Metered {
+    settings: RwSignal<Settings>,
+    /// Whether the connection was metered when last checked
+    pub metered: RwSignal<bool>,
+}
+
+impl Metered {
+    pub fn new(settings: RwSignal<Settings>) -> Self {
+        let metered = RwSignal::new(false);
+        Effect::new(move |_| {
+            let metered = metered.get();
+            toggle_body_class(
+                "low-data",
+                settings.with(|s| s.playback.saves_data(metered)),
+            );
+        });
+        Self { settings, metered }
+    }
+
+    /// Ask the backend again, if the settings care
+    pub fn check(&self) {
+        if !self
+            .settings
+            .with_untracked(|s| s.playback.low_data_when_metered)
+        {
+            return;
+        }
+        let metered = self.metered;
+        spawn_local(async move {
+            match call::<bool>("network_metered", &()).await {
+                Ok(is_metered) => metered.set(is_metered),
+                Err(e) => {
+                    leptos::logging::warn!("Could not tell if the connection is metered: {}", e)
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,87 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use serde::Serialize;
+use sleepy_video_shared::control::ControlCommand;
+use sleepy_video_shared::session::{SessionStatus, TICK_EVENT};
+
+use crate::app::format_time;
+use crate::i18n::{t, Text};
+use crate::ipc::{call, listen_to};
+
+/// Minutes the mini player's extend button adds, like the remote API's default
+const EXTEND_MINUTES: u32 = 15;
+
+#[derive(Serialize)]
+struct ControlArgs {
+    command: ControlCommand,
+}
+
+fn send(command: ControlCommand) {
+    spawn_local(async move {
+        if let Err(e) = call::<()>("send_control", &ControlArgs { command }).await {
+            leptos::logging::warn!("Could not reach the main window: {}", e);
+        }
+    });
+}
+
+/// Compact always-on-top window: the video's thumbnail, the time left and the
+/// session buttons. The timer and player keep running in the main window.
+#[component]
+pub fn MiniPlayer() -> impl IntoView {
+    let status = RwSignal::new(SessionStatus::default());
+    listen_to::<SessionStatus>(TICK_EVENT, move |update| status.set(update));
+    spawn_local(async move {
+        if let Ok(current) = call::<SessionStatus>("session_status", &()).await {
+            status.set(current);
+        }
+    });
+
+    let show_main = move |_| {
+        spawn_local(async move {
+            let _ = call::<()>("show_main_window", &()).await;
+        });
+    };
+
+    view! {
+        <div class="mini-player">
+            {move || status.with(|s| s.video_id.clone()).map(|id| view! {
+                <img
+                    class="mini-art pixel-border"
+                    src=format!("https://i.ytimg.com/vi/{}/mqdefault.jpg", id)
+                    alt=""
+                />
+            })}
+            <div class="mini-time">
+                {move || if status.with(|s| s.running) {
+                    format_time(status.with(|s| s.remaining_seconds))
+                } else {
+                    "ZZZ".to_string()
+                }}
+            </div>
+            <div class="mini-controls">
+                <button
+                    class="transport-btn"
+                    title=move || t(Text::PlayPauseTip)
+                    aria-label=move || t(Text::PlayPauseTip)
+                    on:click=move |_| send(ControlCommand::TogglePlay)
+                >"⏯"</button>
+                {move || status.with(|s| s.running).then(|| view! {
+                    <button
+                        class="btn btn-load pixel-border"
+                        on:click=move |_| send(ControlCommand::Extend { minutes: EXTEND_MINUTES })
+                    >{format!("+{}M", EXTEND_MINUTES)}</button>
+                    <button
+                        class="btn btn-danger pixel-border"
+                        on:click=move |_| send(ControlCommand::Cancel)
+                    >{t(Text::Cancel)}</button>
+                })}
+                <button
+                    class="transport-btn"
+                    title=move || t(Text::ShowEepy)
+                    aria-label=move || t(Text::ShowEepy)
+                    on:click=show_main
+                >"⤢"</button>
+            </div>
+        </div>
+    }
+}
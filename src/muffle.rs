@@ -0,0 +1,207 @@
+use std::cell::RefCell;
+use std::time::Duration;
+
+use leptos::prelude::{set_interval_with_handle, IntervalHandle};
+use sleepy_video_shared::equalizer::{EqSettings, BANDS_HZ};
+use sleepy_video_shared::playback::{spike_gain, PlaybackSettings};
+use wasm_bindgen::JsValue;
+use web_sys::{
+    AnalyserNode, AudioContext, AudioNode, BiquadFilterNode, BiquadFilterType, GainNode,
+    HtmlMediaElement,
+};
+
+/// Cutoff with no muffle, above what anyone hears
+const OPEN_HZ: f32 = 20000.0;
+/// Cutoff at the very end of the fade: rain through a wall
+const MUFFLED_HZ: f32 = 400.0;
+/// Seconds the cutoff takes to glide to a new value, so the steps aren't heard
+const GLIDE_SECONDS: f64 = 0.5;
+
+/// How often the loudness is measured for bursts
+const MEASURE_EVERY: Duration = Duration::from_millis(100);
+/// Samples per measurement
+const WINDOW_SAMPLES: u32 = 2048;
+/// Quieter than this is a pause or a gap, which says nothing about loudness
+const SILENCE_DB: f64 = -60.0;
+/// Share of each measurement the recent loudness takes in, so it follows
+/// over a few seconds
+const BASELINE_RATE: f64 = 0.02;
+/// Seconds a burst takes to be turned down, fast enough to catch the start
+const ATTACK_SECONDS: f64 = 0.03;
+/// Seconds the volume stays down once a burst is over, so a loud ad with
+/// short pauses isn't let through between them
+const HOLD_SECONDS: f64 = 3.0;
+/// Seconds the volume takes to come back up
+const RELEASE_SECONDS: f64 = 1.0;
+
+/// Where the ducking is: the loudness it measures against, and when it may
+/// let the volume back up
+#[derive(Default)]
+struct Spikes {
+    baseline_db: Option<f64>,
+    held_until: f64,
+}
+
+/// Equalizer, low-pass filter and the gain that turns bursts down, between
+/// the native audio player and the speakers
+struct Muffle {
+    element: HtmlMediaElement,
+    context: AudioContext,
+    bands: Vec<BiquadFilterNode>,
+    filter: BiquadFilterNode,
+    analyser: AnalyserNode,
+    duck: GainNode,
+    spikes: Spikes,
+    measuring: Option<IntervalHandle>,
+}
+
+thread_local! {
+    static MUFFLE: RefCell<Option<Muffle>> = const { RefCell::new(None) };
+}
+
+/// Route the native audio player through the filters, and turn bursts down
+/// if `duck_spikes` is on. An element can only be routed once, and every
+/// stream gets a new element, so this runs per stream.
+pub fn attach(element: &HtmlMediaElement, playback: &PlaybackSettings) -> Result<(), JsValue> {
+    if MUFFLE.with(|m| m.borrow().as_ref().is_some_and(|m| &m.element == element)) {
+        return Ok(());
+    }
+    detach();
+
+    let context = AudioContext::new()?;
+    let source = context.create_media_element_source(element)?;
+    let bands = BANDS_HZ
+        .iter()
+        .enumerate()
+        .map(|(i, hz)| {
+            let band = context.create_biquad_filter()?;
+            band.set_type(match i {
+                0 => BiquadFilterType::Lowshelf,
+                i if i == BANDS_HZ.len() - 1 => BiquadFilterType::Highshelf,
+                _ => BiquadFilterType::Peaking,
+            });
+            band.frequency().set_value(*hz);
+            Ok(band)
+        })
+        .collect::<Result<Vec<_>, JsValue>>()?;
+    let filter = context.create_biquad_filter()?;
+    filter.set_type(BiquadFilterType::Lowpass);
+    filter.frequency().set_value(OPEN_HZ);
+    let duck = context.create_gain()?;
+    // Measured before the duck, so it hears when the burst is over
+    let analyser = context.create_analyser()?;
+    analyser.set_fft_size(WINDOW_SAMPLES);
+    source.connect_with_audio_node(&analyser)?;
+    let mut last: &AudioNode = &source;
+    for band in &bands {
+        last.connect_with_audio_node(band)?;
+        last = band;
+    }
+    last.connect_with_audio_node(&filter)?;
+    filter.connect_with_audio_node(&duck)?;
+    duck.connect_with_audio_node(&context.destination())?;
+
+    let measuring = if playback.duck_spikes {
+        match set_interval_with_handle(measure, MEASURE_EVERY) {
+            Ok(handle) => Some(handle),
+            Err(e) => {
+                leptos::logging::warn!("Could not watch for loud bursts: {:?}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    MUFFLE.with(|m| {
+        *m.borrow_mut() = Some(Muffle {
+            element: element.clone(),
+            context,
+            bands,
+            filter,
+            analyser,
+            duck,
+            spikes: Spikes::default(),
+            measuring,
+        })
+    });
+    set_eq(&playback.eq);
+    Ok(())
+}
+
+/// Drop the filters once their player is gone
+pub fn detach() {
+    if let Some(muffle) = MUFFLE.with(|m| m.borrow_mut().take()) {
+        if let Some(measuring) = muffle.measuring {
+            measuring.clear();
+        }
+        let _ = muffle.context.close();
+    }
+}
+
+/// Muffle the sound by `amount`, from 0 (untouched) to 1 (fully muffled)
+pub fn set_amount(amount: f64) {
+    MUFFLE.with(|m| {
+        if let Some(muffle) = m.borrow().as_ref() {
+            // Pitch is heard logarithmically, so glide the cutoff exponentially
+            let cutoff = OPEN_HZ * (MUFFLED_HZ / OPEN_HZ).powf(amount.clamp(0.0, 1.0) as f32);
+            let _ = muffle.filter.frequency().set_target_at_time(
+                cutoff,
+                muffle.context.current_time(),
+                GLIDE_SECONDS,
+            );
+        }
+    });
+}
+
+/// Set the equalizer's bands, or flatten them when it's off
+pub fn set_eq(eq: &EqSettings) {
+    MUFFLE.with(|m| {
+        if let Some(muffle) = m.borrow().as_ref() {
+            for (band, gain) in muffle.bands.iter().zip(eq.gains) {
+                let gain = if eq.enabled { gain as f32 } else { 0.0 };
+                let _ = band.gain().set_target_at_time(
+                    gain,
+                    muffle.context.current_time(),
+                    GLIDE_SECONDS,
+                );
+            }
+        }
+    });
+}
+
+/// Loudness of the last window of samples in dB below full scale
+fn level_db(analyser: &AnalyserNode) -> f64 {
+    let mut samples = vec![0.0f32; WINDOW_SAMPLES as usize];
+    analyser.get_float_time_domain_data(&mut samples);
+    let power = samples.iter().map(|s| (*s as f64).powi(2)).sum::<f64>() / samples.len() as f64;
+    10.0 * power.max(1e-12).log10()
+}
+
+/// Turn the volume down while the sound jumps well over how loud it's been,
+/// and back up a moment after it settles
+fn measure() {
+    MUFFLE.with(|m| {
+        let mut m = m.borrow_mut();
+        let Some(muffle) = m.as_mut() else {
+            return;
+        };
+        let level = level_db(&muffle.analyser);
+        if level < SILENCE_DB {
+            return;
+        }
+        let now = muffle.context.current_time();
+        let spikes = &mut muffle.spikes;
+        let baseline = *spikes.baseline_db.get_or_insert(level);
+        let gain = spike_gain(level, baseline);
+        let duck = muffle.duck.gain();
+        if gain < 1.0 {
+            let _ = duck.set_target_at_time(gain as f32, now, ATTACK_SECONDS);
+            spikes.held_until = now + HOLD_SECONDS;
+        } else if now >= spikes.held_until {
+            let _ = duck.set_target_at_time(1.0, now, RELEASE_SECONDS);
+            // Only calm sound counts, so a long ad doesn't become the norm
+            spikes.baseline_db = Some(baseline + (level - baseline) * BASELINE_RATE);
+        }
+    });
+}
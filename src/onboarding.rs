@@ -0,0 +1,279 @@
+use std::time::Duration;
+
+use leptos::ev;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use sleepy_video_shared::lights::dim_level;
+use sleepy_video_shared::power::{EndAction, PowerCapabilities};
+use sleepy_video_shared::settings::Settings;
+
+use crate::chime;
+use crate::dialog::{focus_on_open, trap_focus};
+use crate::i18n::{action_label, t, t_with, Text};
+use crate::ipc::call;
+use crate::session::SessionState;
+use crate::settings::persist;
+
+/// Length of the trial run
+const TRIAL_SECONDS: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Check,
+    Defaults,
+    Extras,
+    Trial,
+}
+
+/// First-launch walk through: what this computer can do, the session picked
+/// at start, autostart and notifications, and a short taste of the dim and
+/// fade. Saves the settings when done or skipped, so it only shows once.
+#[component]
+pub fn Onboarding(on_done: Callback<()>) -> impl IntoView {
+    let settings = expect_context::<RwSignal<Settings>>();
+    let session = expect_context::<SessionState>();
+    let draft = RwSignal::new(settings.get_untracked());
+    let (step, set_step) = signal(Step::Check);
+    let (capabilities, set_capabilities) = signal(Option::<PowerCapabilities>::None);
+    let (error, set_error) = signal(String::new());
+
+    spawn_local(async move {
+        if let Ok(loaded) = call::<PowerCapabilities>("power_capabilities", &()).await {
+            // Start from an action this computer can do
+            if !loaded.supports(draft.with_untracked(|s| s.end_action)) {
+                if let Some(action) = EndAction::ALL.into_iter().find(|a| loaded.supports(*a)) {
+                    draft.update(|s| s.end_action = action);
+                }
+            }
+            set_capabilities.set(Some(loaded));
+        }
+    });
+    let supports = move |action: EndAction| {
+        capabilities.with(|c| c.as_ref().is_some_and(|c| c.supports(action)))
+    };
+
+    let toggle_notifications = move |ev: ev::Event| {
+        let wanted = event_target_checked(&ev);
+        draft.update(|s| s.notifications.enabled = wanted);
+        if !wanted {
+            return;
+        }
+        spawn_local(async move {
+            match call::<bool>("request_notifications", &()).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    draft.update(|s| s.notifications.enabled = false);
+                    set_error.set(t(Text::NotificationsBlocked).to_string());
+                }
+                Err(e) => set_error.set(e.explained()),
+            }
+        });
+    };
+
+    // The trial darkens the screen like the end of a session and rings the
+    // chime louder, then softer, the way the volume fades
+    let (trial_left, set_trial_left) = signal(Option::<u32>::None);
+    let trial = move |_| {
+        if trial_left.get_untracked().is_some() {
+            return;
+        }
+        let (tone, volume) = draft.with_untracked(|s| (s.chime.tone, s.chime.volume));
+        let ring = move |share: f32| {
+            if let Err(e) = chime::play(tone, share * volume as f32 / 100.0) {
+                leptos::logging::warn!("Could not play the chime: {:?}", e);
+            }
+        };
+        ring(1.0);
+        set_trial_left.set(Some(TRIAL_SECONDS));
+        let handle = StoredValue::new(None::<IntervalHandle>);
+        let tick = move || {
+            let left = trial_left
+                .get_untracked()
+                .unwrap_or_default()
+                .saturating_sub(1);
+            session.dim.set(dim_level(TRIAL_SECONDS, left));
+            if left == TRIAL_SECONDS / 2 {
+                ring(0.4);
+            }
+            if left == 0 {
+                ring(0.1);
+                session.dim.set(0.0);
+                set_trial_left.set(None);
+                if let Some(handle) = handle.get_value() {
+                    handle.clear();
+                }
+            } else {
+                set_trial_left.set(Some(left));
+            }
+        };
+        handle.set_value(set_interval_with_handle(tick, Duration::from_secs(1)).ok());
+    };
+
+    let finish = move || {
+        let mut next = draft.get_untracked();
+        next.onboarded = true;
+        spawn_local(async move {
+            match persist(next).await {
+                Ok(saved) => {
+                    session.selected_minutes.set(saved.timer.default_minutes);
+                    settings.set(saved);
+                    session.dim.set(0.0);
+                    on_done.run(());
+                }
+                Err(e) => set_error.set(e.explained()),
+            }
+        });
+    };
+
+    let check = move || {
+        view! {
+            <p class="settings-hint">{move || t(Text::OnboardingIntro)}</p>
+            <ul class="settings-list">
+                {EndAction::ALL.into_iter().map(|action| view! {
+                    <li class="settings-list-item">
+                        <span class="settings-list-name">{t(action_label(action))}</span>
+                        <span class=move || if supports(action) { "check-pass" } else { "check-fail" }>
+                            {move || match capabilities.get() {
+                                None => "...",
+                                Some(c) if c.supports(action) => t(Text::Supported),
+                                Some(_) => t(Text::Unsupported),
+                            }}
+                        </span>
+                    </li>
+                }).collect_view()}
+            </ul>
+            {move || (capabilities.get().is_some() && !supports(EndAction::Suspend)).then(|| view! {
+                <p class="settings-error">
+                    {t_with(Text::SleepUnavailable, &[("help", t(Text::HelpDidntSleep))])}
+                </p>
+            })}
+        }
+    };
+
+    let defaults = move || {
+        view! {
+            <p class="settings-hint">{move || t(Text::SessionLengthQuestion)}</p>
+            <div class="preset-grid">
+                {draft.with_untracked(|s| s.timer.presets.clone()).into_iter().map(|minutes| view! {
+                    <button
+                        class=move || if draft.with(|s| s.timer.default_minutes == minutes) {
+                            "preset-btn pixel-border selected"
+                        } else {
+                            "preset-btn pixel-border"
+                        }
+                        on:click=move |_| draft.update(|s| s.timer.default_minutes = minutes)
+                    >{format!("{}M", minutes)}</button>
+                }).collect_view()}
+            </div>
+            <label class="settings-row">
+                {move || t(Text::Then)}
+                <select
+                    class="settings-input"
+                    on:change=move |ev| {
+                        if let Some(action) = EndAction::parse(&event_target_value(&ev)) {
+                            draft.update(|s| s.end_action = action);
+                        }
+                    }
+                >
+                    {EndAction::ALL.into_iter().map(|action| view! {
+                        <option
+                            value=action.as_str()
+                            selected=move || draft.with(|s| s.end_action == action)
+                            disabled=move || capabilities.get().is_some() && !supports(action)
+                        >{t(action_label(action))}</option>
+                    }).collect_view()}
+                </select>
+            </label>
+        }
+    };
+
+    let extras = move || {
+        view! {
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.autostart.enabled)
+                    on:change=move |ev| draft.update(|s| s.autostart.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::StartEepyAtLogin)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.notifications.enabled)
+                    on:change=toggle_notifications
+                />
+                {move || t(Text::NotifyBeforeEnd)}
+            </label>
+            <p class="settings-hint">{move || t(Text::ChangeLater)}</p>
+        }
+    };
+
+    let try_it = move || {
+        view! {
+            <p class="settings-hint">
+                {move || t(Text::TrialIntro)}
+            </p>
+            <button
+                class="btn btn-load pixel-border"
+                disabled=move || trial_left.get().is_some()
+                on:click=trial
+            >
+                {move || match trial_left.get() {
+                    Some(left) => t_with(Text::TrialLeft, &[("seconds", &left.to_string())]),
+                    None => t(Text::TryIt).to_string(),
+                }}
+            </button>
+        }
+    };
+
+    view! {
+        <div
+            class="settings-overlay"
+            role="dialog"
+            aria-modal="true"
+            node_ref=focus_on_open()
+            on:keydown=trap_focus
+        >
+            <div class="settings-panel pixel-border">
+                <h2 class="settings-title">{move || t(Text::Welcome)}</h2>
+                <section class="settings-section">
+                    {move || match step.get() {
+                        Step::Check => check().into_any(),
+                        Step::Defaults => defaults().into_any(),
+                        Step::Extras => extras().into_any(),
+                        Step::Trial => try_it().into_any(),
+                    }}
+                </section>
+                <p class="settings-error">{move || error.get()}</p>
+                <div class="action-buttons">
+                    {move || match step.get() {
+                        Step::Trial => view! {
+                            <button
+                                class="btn btn-primary pixel-border"
+                                disabled=move || trial_left.get().is_some()
+                                on:click=move |_| finish()
+                            >{move || t(Text::Done)}</button>
+                        }.into_any(),
+                        current => view! {
+                            <button
+                                class="btn btn-primary pixel-border"
+                                on:click=move |_| set_step.set(match current {
+                                    Step::Check => Step::Defaults,
+                                    Step::Defaults => Step::Extras,
+                                    _ => Step::Trial,
+                                })
+                            >{move || t(Text::Next)}</button>
+                        }.into_any(),
+                    }}
+                    // Not while the trial runs, which has the interval to clear
+                    <button
+                        class="btn btn-danger pixel-border"
+                        disabled=move || trial_left.get().is_some()
+                        on:click=move |_| finish()
+                    >{move || t(Text::Skip)}</button>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,115 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use serde::Serialize;
+use sleepy_video_shared::library::{thumbnail_url, LibraryEntry};
+use sleepy_video_shared::parental::Profile;
+use sleepy_video_shared::settings::Settings;
+
+use crate::dialog::{focus_on_open, trap_focus};
+use crate::i18n::{t, Text};
+use crate::ipc::call;
+
+#[derive(Serialize)]
+struct PasswordArgs {
+    password: String,
+}
+
+/// Password prompt that switches the child profile over to the parent
+#[component]
+pub fn ParentSignIn(profile: RwSignal<Profile>, on_close: Callback<()>) -> impl IntoView {
+    let (password, set_password) = signal(String::new());
+    let (error, set_error) = signal(String::new());
+
+    let sign_in = move || {
+        let password = password.get_untracked();
+        spawn_local(async move {
+            match call::<Profile>("parent_sign_in", &PasswordArgs { password }).await {
+                Ok(signed_in) => {
+                    profile.set(signed_in);
+                    on_close.run(());
+                }
+                Err(e) => {
+                    set_error.set(e.to_string().to_uppercase());
+                    set_password.set(String::new());
+                }
+            }
+        });
+    };
+
+    view! {
+        <div
+            class="settings-overlay"
+            role="dialog"
+            aria-modal="true"
+            node_ref=focus_on_open()
+            on:keydown=trap_focus
+        >
+            <div class="settings-panel pixel-border">
+                <h2 class="settings-title">{move || t(Text::ParentsOnly)}</h2>
+                <div class="settings-row">
+                    <input
+                        type="password"
+                        class="settings-input wide"
+                        placeholder=move || t(Text::ParentPassword)
+                        autofocus=true
+                        prop:value=move || password.get()
+                        on:input=move |ev| set_password.set(event_target_value(&ev))
+                        on:keydown=move |ev| {
+                            if ev.key() == "Enter" {
+                                sign_in();
+                            }
+                        }
+                    />
+                </div>
+                <p class="settings-error">{move || error.get()}</p>
+                <div class="action-buttons">
+                    <button class="btn btn-load pixel-border" on:click=move |_| sign_in()>
+                        {move || t(Text::SignIn)}
+                    </button>
+                    <button
+                        class="btn btn-danger pixel-border"
+                        data-back=true
+                        on:click=move |_| on_close.run(())
+                    >
+                        {move || t(Text::Cancel)}
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+/// The videos the parent allowed, as the child's only way to pick one
+#[component]
+pub fn ChildPicker(
+    library: RwSignal<Vec<LibraryEntry>>,
+    on_pick: Callback<String>,
+) -> impl IntoView {
+    let settings = expect_context::<RwSignal<Settings>>();
+
+    view! {
+        <div class="recent-videos">
+            <label>{move || t(Text::PickVideo)}</label>
+            <div class="recent-carousel">
+                {move || settings.with(|s| s.parental.videos.clone()).into_iter().map(|video| {
+                    let title = library.with(|entries| {
+                        entries
+                            .iter()
+                            .find(|entry| entry.video_id == video)
+                            .and_then(|entry| entry.title.clone())
+                    }).unwrap_or_else(|| video.clone());
+                    let thumbnail = thumbnail_url(&video);
+                    let tooltip = title.clone();
+                    view! {
+                        <div class="recent-card pixel-border" title=tooltip>
+                            <button class="recent-play" on:click=move |_| on_pick.run(video.clone())>
+                                <img src=thumbnail alt="" loading="lazy"/>
+                                <span class="recent-title">{title}</span>
+                            </button>
+                        </div>
+                    }
+                }).collect_view()}
+            </div>
+        </div>
+    }
+}
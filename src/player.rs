@@ -0,0 +1,137 @@
+use leptos::prelude::*;
+use sleepy_video_shared::parental::Profile;
+use sleepy_video_shared::settings::Settings;
+use web_sys::HtmlMediaElement;
+
+use crate::app::format_time;
+use crate::i18n::{t, t_with, Text};
+use crate::queue::{PlayQueue, Queue, RepeatMode};
+use crate::source::{listen_to_player, MediaSource, AUDIO_PLAYER_ID, PLAYER_ID};
+use crate::transport::{PlayerState, TransportBar};
+
+/// The loaded video: the embed or the extracted audio stream, its buttons,
+/// the transport bar and the queue
+#[component]
+pub fn VideoPlayer(
+    media: ReadSignal<Option<MediaSource>>,
+    player: PlayerState,
+    queue: Queue,
+    /// Direct audio stream replacing the embed, and whether it's still being
+    /// looked up
+    audio_stream: ReadSignal<Option<String>>,
+    resolving_audio: ReadSignal<bool>,
+    /// Saved position offered for resuming
+    resume_offer: ReadSignal<Option<u32>>,
+    theater: ReadSignal<bool>,
+    on_resume: Callback<()>,
+    on_start_over: Callback<()>,
+    on_embed_loaded: Callback<()>,
+    on_stream_loaded: Callback<HtmlMediaElement>,
+    /// Playback position and duration of the stream, as it plays
+    on_progress: Callback<(f64, Option<f64>)>,
+    on_ended: Callback<()>,
+    on_stream_failed: Callback<()>,
+    on_toggle_audio_only: Callback<()>,
+    on_toggle_theater: Callback<()>,
+    on_close: Callback<()>,
+) -> impl IntoView {
+    let settings = expect_context::<RwSignal<Settings>>();
+    let profile = expect_context::<RwSignal<Profile>>();
+
+    let embed_url = move || {
+        media
+            .get()
+            .map(|source| settings.with(|s| source.embed_url(&s.playback)))
+    };
+
+    view! {
+        <section class="video-section">
+            {move || resume_offer.get().map(|seconds| view! {
+                <div class="clipboard-chip resume-chip pixel-border">
+                    <button class="clipboard-chip-load" on:click=move |_| on_resume.run(())>
+                        {move || t_with(Text::ResumeFrom, &[("time", &format_time(seconds))])}
+                    </button>
+                    <button
+                        class="clipboard-chip-dismiss"
+                        title=move || t(Text::StartOver)
+                        aria-label=move || t(Text::StartOver)
+                        on:click=move |_| on_start_over.run(())
+                    >"✕"</button>
+                </div>
+            })}
+            <div class="video-container pixel-border">
+                <div class="video-wrapper">
+                    {move || if resolving_audio.get() {
+                        view! {
+                            <div class="audio-stream-cover">{move || t(Text::FindingAudio)}</div>
+                        }.into_any()
+                    } else if let Some(src) = audio_stream.get() {
+                        view! {
+                            <audio
+                                id=AUDIO_PLAYER_ID
+                                src=src
+                                autoplay=true
+                                prop:loop=move || queue.repeat.get() == RepeatMode::One
+                                crossorigin=move || settings.with(|s| s.playback.filters_stream()).then_some("anonymous")
+                                on:loadedmetadata=move |ev| on_stream_loaded.run(event_target::<HtmlMediaElement>(&ev))
+                                on:timeupdate=move |ev| {
+                                    let audio = event_target::<HtmlMediaElement>(&ev);
+                                    let duration = Some(audio.duration()).filter(|d| d.is_finite());
+                                    player.position.set(audio.current_time());
+                                    player.duration.set(duration);
+                                    player.hold_loop(media);
+                                    on_progress.run((audio.current_time(), duration));
+                                }
+                                on:ended=move |_| on_ended.run(())
+                                on:play=move |_| player.playing.set(true)
+                                on:pause=move |_| player.playing.set(false)
+                                on:error=move |_| on_stream_failed.run(())
+                            ></audio>
+                            <div class="audio-stream-cover">{move || t(Text::AudioStream)}</div>
+                        }.into_any()
+                    } else {
+                        embed_url().map(|url| view! {
+                            <iframe
+                                id=PLAYER_ID
+                                src=url
+                                allow="accelerometer; autoplay; clipboard-write; encrypted-media; gyroscope; picture-in-picture"
+                                allowfullscreen=true
+                                on:load=move |_| {
+                                    listen_to_player();
+                                    on_embed_loaded.run(());
+                                }
+                            ></iframe>
+                        }).into_any()
+                    }}
+                    <div class="audio-only-cover">{move || t(Text::AudioOnlyCover)}</div>
+                </div>
+                <button
+                    class="video-audio-btn"
+                    on:click=move |_| on_toggle_audio_only.run(())
+                    title=move || if settings.with(|s| s.playback.audio_only) { t(Text::ShowVideo) } else { t(Text::AudioOnlyTip) }
+                    aria-label=move || if settings.with(|s| s.playback.audio_only) { t(Text::ShowVideo) } else { t(Text::AudioOnlyTip) }
+                >{move || if settings.with(|s| s.playback.audio_only) { "📺" } else { "🎧" }}</button>
+                <button
+                    class="video-theater-btn"
+                    on:click=move |_| on_toggle_theater.run(())
+                    title=move || if theater.get() { t(Text::LeaveTheater) } else { t(Text::TheaterMode) }
+                    aria-label=move || if theater.get() { t(Text::LeaveTheater) } else { t(Text::TheaterMode) }
+                >"⛶"</button>
+                <button
+                    class="video-close-btn"
+                    on:click=move |_| on_close.run(())
+                    title=move || t(Text::CloseVideo)
+                    aria-label=move || t(Text::CloseVideo)
+                >"✕"</button>
+            </div>
+            {move || {
+                let controllable = audio_stream.with(Option::is_some)
+                    || media.with(|m| settings.with(|s| {
+                        m.as_ref().is_some_and(|m| m.controllable(&s.playback))
+                    }));
+                controllable.then(|| view! { <TransportBar media=media player=player/> })
+            }}
+            {move || (profile.get() != Profile::Child).then(|| view! { <PlayQueue queue=queue/> })}
+        </section>
+    }
+}
@@ -0,0 +1,116 @@
+use std::time::Duration;
+
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use sleepy_video_shared::haptics::Haptic;
+use sleepy_video_shared::settings::Settings;
+use sleepy_video_shared::timer::preset_label;
+
+use crate::haptics;
+use crate::i18n::{t, Text};
+use crate::settings::persist;
+
+/// How long a press has to be held to edit a preset
+const LONG_PRESS: Duration = Duration::from_millis(600);
+
+/// Duration buttons from the settings. Right-click or long-press one to
+/// change its minutes in place.
+#[component]
+pub fn PresetGrid(
+    selected: ReadSignal<u32>,
+    set_selected: WriteSignal<u32>,
+    running: Signal<bool>,
+) -> impl IntoView {
+    let settings = expect_context::<RwSignal<Settings>>();
+    // Preset being edited, and the long press that may start editing one
+    let (editing, set_editing) = signal(Option::<usize>::None);
+    let (press_timer, set_press_timer) = signal(Option::<TimeoutHandle>::None);
+    let (long_pressed, set_long_pressed) = signal(false);
+
+    let cancel_press = move || {
+        if let Some(handle) = press_timer.get_untracked() {
+            handle.clear();
+        }
+        set_press_timer.set(None);
+    };
+
+    let start_press = move |index: usize| {
+        cancel_press();
+        set_long_pressed.set(false);
+        let handle = set_timeout_with_handle(
+            move || {
+                set_long_pressed.set(true);
+                set_editing.set(Some(index));
+            },
+            LONG_PRESS,
+        );
+        set_press_timer.set(handle.ok());
+    };
+
+    let save_preset = move |index: usize, raw: String| {
+        set_editing.set(None);
+        let Ok(minutes) = raw.trim().parse::<u32>() else {
+            return;
+        };
+        let minutes = minutes.clamp(1, settings.with_untracked(|s| s.timer.max_minutes()));
+        let mut next = settings.get_untracked();
+        match next.timer.presets.get_mut(index) {
+            Some(preset) if *preset != minutes => *preset = minutes,
+            _ => return,
+        }
+        spawn_local(async move {
+            match persist(next).await {
+                Ok(saved) => settings.set(saved),
+                Err(e) => leptos::logging::warn!("Could not save preset: {}", e),
+            }
+        });
+    };
+
+    view! {
+        <div class="preset-grid">
+            {move || settings.with(|s| s.timer.presets.clone()).into_iter().enumerate().map(|(index, minutes)| {
+                if editing.get() == Some(index) {
+                    view! {
+                        <input
+                            type="number"
+                            class="preset-btn preset-edit pixel-border"
+                            min="1"
+                            max=settings.with_untracked(|s| s.timer.max_minutes()).to_string()
+                            value=minutes.to_string()
+                            autofocus=true
+                            on:keydown=move |ev| match ev.key().as_str() {
+                                "Enter" => save_preset(index, event_target_value(&ev)),
+                                "Escape" => set_editing.set(None),
+                                _ => {}
+                            }
+                            on:blur=move |ev| save_preset(index, event_target_value(&ev))
+                        />
+                    }.into_any()
+                } else {
+                    view! {
+                        <button
+                            class=move || if selected.get() == minutes && !running.get() { "preset-btn pixel-border selected" } else { "preset-btn pixel-border" }
+                            disabled=move || running.get()
+                            title=move || t(Text::EditPresetTip)
+                            on:click=move |_| {
+                                // The click that ends a long press doesn't select
+                                if !long_pressed.get_untracked() {
+                                    set_selected.set(minutes);
+                                    haptics::buzz(Haptic::Selection);
+                                }
+                                set_long_pressed.set(false);
+                            }
+                            on:contextmenu=move |ev| {
+                                ev.prevent_default();
+                                set_editing.set(Some(index));
+                            }
+                            on:pointerdown=move |_| start_press(index)
+                            on:pointerup=move |_| cancel_press()
+                            on:pointerleave=move |_| cancel_press()
+                        >{preset_label(minutes)}</button>
+                    }.into_any()
+                }
+            }).collect_view()}
+        </div>
+    }
+}
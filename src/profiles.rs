@@ -0,0 +1,59 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use serde::Serialize;
+use sleepy_video_shared::library::LibraryEntry;
+use sleepy_video_shared::profiles::ProfileList;
+use sleepy_video_shared::settings::Settings;
+
+use crate::i18n::{t, Text};
+use crate::ipc::call;
+
+#[derive(Serialize)]
+struct IdArgs {
+    id: String,
+}
+
+/// Header picker for whose presets, preferences, library and history are
+/// in use. Only shows up once there's more than one profile.
+#[component]
+pub fn ProfileSwitcher(library: RwSignal<Vec<LibraryEntry>>) -> impl IntoView {
+    let settings = expect_context::<RwSignal<Settings>>();
+    let profiles = expect_context::<RwSignal<ProfileList>>();
+
+    let switch = move |id: String| {
+        spawn_local(async move {
+            match call::<Settings>("profile_switch", &IdArgs { id: id.clone() }).await {
+                Ok(saved) => {
+                    settings.set(saved);
+                    profiles.update(|list| list.active = id);
+                    if let Ok(entries) = call::<Vec<LibraryEntry>>("library_list", &()).await {
+                        library.set(entries);
+                    }
+                }
+                Err(e) => leptos::logging::warn!("Could not switch profile: {}", e),
+            }
+        });
+    };
+
+    move || {
+        (profiles.with(|list| list.profiles.len()) > 1).then(|| {
+            view! {
+                <select
+                    class="profile-select"
+                    title=move || t(Text::ProfileTip)
+                    on:change=move |ev| switch(event_target_value(&ev))
+                >
+                    {profiles.get().profiles.into_iter().map(|profile| {
+                        let id = profile.id.clone();
+                        view! {
+                            <option
+                                value=profile.id
+                                selected=move || profiles.with(|list| list.active == id)
+                            >{format!("👤 {}", profile.name)}</option>
+                        }
+                    }).collect_view()}
+                </select>
+            }
+        })
+    }
+}
@@ -0,0 +1,222 @@
+use leptos::prelude::*;
+
+use crate::i18n::{t, Text};
+use crate::source::MediaSource;
+
+/// Seconds the outgoing item fades out and the next one fades in
+pub const CROSSFADE_SECONDS: f64 = 8.0;
+
+/// Volume in percent for the queue crossfade at this point of an item.
+/// `fading_in` is set for items the queue advanced to; `has_next` for items
+/// something comes after.
+pub fn crossfade_level(
+    position: f64,
+    duration: Option<f64>,
+    fading_in: bool,
+    has_next: bool,
+) -> u32 {
+    let mut level: f64 = 1.0;
+    if let Some(duration) = duration.filter(|_| has_next) {
+        level = level.min((duration - position) / CROSSFADE_SECONDS);
+    }
+    if fading_in {
+        level = level.min(position / CROSSFADE_SECONDS);
+    }
+    (level.clamp(0.0, 1.0) * 100.0) as u32
+}
+
+/// What happens when an item ends
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RepeatMode {
+    #[default]
+    Off,
+    /// Play the current item again
+    One,
+    /// Put finished items back at the end of the queue
+    All,
+}
+
+impl RepeatMode {
+    /// Off, all, one, like most players' repeat button
+    fn next(self) -> Self {
+        match self {
+            RepeatMode::Off => RepeatMode::All,
+            RepeatMode::All => RepeatMode::One,
+            RepeatMode::One => RepeatMode::Off,
+        }
+    }
+
+    fn label(self) -> Text {
+        match self {
+            RepeatMode::Off => Text::RepeatOff,
+            RepeatMode::One => Text::RepeatOne,
+            RepeatMode::All => Text::RepeatAll,
+        }
+    }
+}
+
+/// What the player does once the queue has looked at where it is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Follow {
+    /// Keep playing
+    Play,
+    /// The item ended and plays again
+    Restart,
+    /// The item ended and the next one takes over
+    Advance,
+}
+
+/// Links to play after the current one and how to go through them
+#[derive(Clone, Copy)]
+pub struct Queue {
+    pub items: RwSignal<Vec<String>>,
+    pub repeat: RwSignal<RepeatMode>,
+    pub shuffle: RwSignal<bool>,
+    /// Where the crossfade between items is, in percent
+    pub level: RwSignal<u32>,
+    /// Set while an item the queue advanced to fades in
+    fading_in: RwSignal<bool>,
+}
+
+impl Queue {
+    pub fn new() -> Self {
+        Self {
+            items: RwSignal::new(Vec::new()),
+            repeat: RwSignal::new(RepeatMode::Off),
+            shuffle: RwSignal::new(false),
+            level: RwSignal::new(100),
+            fading_in: RwSignal::new(false),
+        }
+    }
+
+    /// Full volume, for an item that didn't come from the queue
+    pub fn reset_fade(&self) {
+        self.fading_in.set(false);
+        self.level.set(100);
+    }
+
+    /// Start the item the queue just advanced to from silence
+    pub fn fade_in(&self) {
+        self.fading_in.set(true);
+        self.level.set(0);
+    }
+
+    /// Crossfade around the end of the item at `position`, and say what
+    /// comes once it's over
+    pub fn follow(&self, position: f64, duration: Option<f64>) -> Follow {
+        let has_next = self.has_next();
+        if self.fading_in.get_untracked() && position >= CROSSFADE_SECONDS {
+            self.fading_in.set(false);
+        }
+        let level = crossfade_level(position, duration, self.fading_in.get_untracked(), has_next);
+        if level != self.level.get_untracked() {
+            self.level.set(level);
+        }
+        let ended = duration.is_some_and(|d| position >= d - 1.0);
+        if ended && self.repeat.get_untracked() == RepeatMode::One {
+            Follow::Restart
+        } else if ended && has_next {
+            Follow::Advance
+        } else {
+            Follow::Play
+        }
+    }
+
+    /// Whether another item follows the one playing
+    pub fn has_next(&self) -> bool {
+        self.repeat.get_untracked() == RepeatMode::All
+            || self.items.with_untracked(|items| !items.is_empty())
+    }
+
+    /// Take the item to play after `current`, which goes back at the end when
+    /// repeating the queue
+    pub fn take_next(&self, current: String) -> Option<String> {
+        let repeat = self.repeat.get_untracked();
+        let shuffle = self.shuffle.get_untracked();
+        let mut next = None;
+        self.items.update(|items| {
+            if repeat == RepeatMode::All && !current.trim().is_empty() {
+                items.push(current);
+            }
+            if items.is_empty() {
+                return;
+            }
+            let index = if shuffle {
+                (js_sys::Math::random() * items.len() as f64) as usize % items.len()
+            } else {
+                0
+            };
+            next = Some(items.remove(index));
+        });
+        next
+    }
+}
+
+/// Links to play after the current one, with a field to add more and the
+/// repeat and shuffle toggles
+#[component]
+pub fn PlayQueue(queue: Queue) -> impl IntoView {
+    let (link, set_link) = signal(String::new());
+    let (error, set_error) = signal(String::new());
+
+    let add = move || {
+        let url = link.get_untracked().trim().to_string();
+        if MediaSource::parse(&url).is_none() {
+            set_error.set(t(Text::LinkNotRecognized).to_string());
+            return;
+        }
+        queue.items.update(|items| items.push(url));
+        set_link.set(String::new());
+        set_error.set(String::new());
+    };
+
+    view! {
+        <div class="queue pixel-border">
+            <div class="queue-row">
+                <button
+                    class=move || if queue.repeat.get() == RepeatMode::Off { "transport-btn" } else { "transport-btn active" }
+                    title=move || t(queue.repeat.get().label())
+                    aria-label=move || t(queue.repeat.get().label())
+                    on:click=move |_| queue.repeat.update(|repeat| *repeat = repeat.next())
+                >{move || if queue.repeat.get() == RepeatMode::One { "🔂" } else { "🔁" }}</button>
+                <button
+                    class=move || if queue.shuffle.get() { "transport-btn active" } else { "transport-btn" }
+                    title=move || if queue.shuffle.get() { t(Text::ShuffleOn) } else { t(Text::ShuffleOff) }
+                    aria-label=move || if queue.shuffle.get() { t(Text::ShuffleOn) } else { t(Text::ShuffleOff) }
+                    on:click=move |_| queue.shuffle.update(|shuffle| *shuffle = !*shuffle)
+                >"🔀"</button>
+                <input
+                    type="url"
+                    class="settings-input wide"
+                    placeholder=move || t(Text::PlayNext)
+                    prop:value=move || link.get()
+                    on:input=move |ev| set_link.set(event_target_value(&ev))
+                    on:keydown=move |ev| {
+                        if ev.key() == "Enter" {
+                            add();
+                        }
+                    }
+                />
+                <button class="btn btn-load pixel-border" on:click=move |_| add()>{move || t(Text::AddToQueue)}</button>
+            </div>
+            <p class="settings-error">{move || error.get()}</p>
+            <ol class="queue-list">
+                {move || queue.items.get().into_iter().enumerate().map(|(index, url)| view! {
+                    <li>
+                        <span class="queue-link" title=url.clone()>{url.clone()}</span>
+                        <button
+                            class="transport-btn"
+                            title=move || t(Text::RemoveTip)
+                            aria-label=move || t(Text::RemoveTip)
+                            on:click=move |_| queue.items.update(|items| {
+                                if index < items.len() {
+                                    items.remove(index);
+                                }
+                            })
+                        >"✕"</button>
+                    </li>
+                }).collect_view()}
+            </ol>
+        </div>
+    }
+}
@@ -0,0 +1,65 @@
+use leptos::prelude::*;
+
+use crate::app::format_time;
+use crate::dialog::{focus_on_open, trap_focus};
+use crate::i18n::{action_label, t, t_with, Text};
+use crate::session::SessionState;
+use crate::video::VideoState;
+
+/// Session the app went away in the middle of, offered to pick back up
+#[component]
+pub fn RecoveredDialog() -> impl IntoView {
+    let session = expect_context::<SessionState>();
+    let video = expect_context::<VideoState>();
+
+    // Pick the recovered session back up where the clock says it should be
+    let resume = move |_| {
+        let Some((saved, left)) = session.take_recovered() else {
+            return;
+        };
+        if let Some(url) = saved.video {
+            video.open(url);
+        }
+        let total = saved.total_seconds.max(left);
+        session.start(total, left, saved.action, saved.fade, video.loaded());
+    };
+
+    move || {
+        session.recovered.get().map(|saved| {
+            let left = saved.ends_at.saturating_sub(js_sys::Date::now() as u64) / 1000;
+            let summary = t_with(
+                Text::LeftThen,
+                &[
+                    ("time", &format_time(left as u32)),
+                    ("action", t(action_label(saved.action))),
+                ],
+            );
+            view! {
+                <div
+                    class="settings-overlay"
+                    role="dialog"
+                    aria-modal="true"
+                    node_ref=focus_on_open()
+                    on:keydown=trap_focus
+                >
+                    <div class="settings-panel pixel-border">
+                        <h2 class="settings-title">{move || t(Text::SessionInterrupted)}</h2>
+                        <p class="busy-reasons">
+                            {summary}
+                        </p>
+                        <div class="action-buttons">
+                            <button
+                                class="btn btn-load pixel-border"
+                                on:click=resume
+                            >{move || t(Text::Continue)}</button>
+                            <button
+                                class="btn btn-danger pixel-border"
+                                on:click=move |_| session.discard_recovered()
+                            >{move || t(Text::Discard)}</button>
+                        </div>
+                    </div>
+                </div>
+            }
+        })
+    }
+}
@@ -0,0 +1,48 @@
+use leptos::prelude::*;
+use sleepy_video_shared::schedule::{REMINDER_EVENT, SOON_MINUTES};
+
+use crate::i18n::{t, t_with, Text};
+use crate::ipc::listen_to;
+use crate::session::SessionState;
+
+/// Banner nudging towards bedtime, from the backend's reminders, with a
+/// button to start the timer there and then. Gone once a session starts.
+#[component]
+pub fn ReminderBanner(on_start: Callback<()>) -> impl IntoView {
+    let session = expect_context::<SessionState>();
+    let minutes_left = RwSignal::new(Option::<u32>::None);
+    listen_to::<u32>(REMINDER_EVENT, move |minutes| {
+        minutes_left.set(Some(minutes))
+    });
+
+    move || {
+        let minutes = minutes_left.get().filter(|_| !session.is_running())?;
+        let text = match minutes {
+            0 => t(Text::ItsBedtime).to_string(),
+            m => t_with(Text::BedtimeIn, &[("minutes", &m.to_string())]),
+        };
+        let class = if minutes <= SOON_MINUTES {
+            "reminder-banner soon pixel-border"
+        } else {
+            "reminder-banner pixel-border"
+        };
+        Some(view! {
+            <div class=class role="status">
+                <span class="reminder-text">{text}</span>
+                <button
+                    class="btn btn-load pixel-border"
+                    on:click=move |_| {
+                        minutes_left.set(None);
+                        on_start.run(());
+                    }
+                >{move || t(Text::Start)}</button>
+                <button
+                    class="clipboard-chip-dismiss"
+                    title=move || t(Text::DismissTip)
+                    aria-label=move || t(Text::DismissTip)
+                    on:click=move |_| minutes_left.set(None)
+                >"✕"</button>
+            </div>
+        })
+    }
+}
@@ -0,0 +1,101 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use serde::Serialize;
+
+use crate::ipc::call;
+use crate::source::{player_ready, seek_video, MediaSource};
+
+/// Saved positions closer to the start than this aren't worth offering
+const RESUME_MIN_SECONDS: u32 = 60;
+/// Playback has to move this far before the position is saved again
+const POSITION_SAVE_STEP: u32 = 15;
+
+#[derive(Serialize)]
+struct VideoArgs {
+    video: String,
+}
+
+#[derive(Serialize)]
+struct PositionArgs {
+    video: String,
+    seconds: u32,
+}
+
+/// How far each video got, so it can pick up there next time
+#[derive(Clone, Copy)]
+pub struct ResumePosition {
+    /// Saved position offered when a video is loaded again
+    pub offer: RwSignal<Option<u32>>,
+    /// The last position written
+    saved: RwSignal<u32>,
+}
+
+impl ResumePosition {
+    pub fn new() -> Self {
+        Self {
+            offer: RwSignal::new(None),
+            saved: RwSignal::new(0),
+        }
+    }
+
+    /// Nothing on offer or saved, for another video or none
+    pub fn clear(&self) {
+        self.offer.set(None);
+        self.saved.set(0);
+    }
+
+    /// Look up where `video` got to, and offer it if it's far enough in
+    pub fn look_up(self, video: String) {
+        spawn_local(async move {
+            let position = call::<Option<u32>>("get_playback_position", &VideoArgs { video })
+                .await
+                .ok()
+                .flatten()
+                .unwrap_or(0);
+            self.saved.set(position);
+            if position >= RESUME_MIN_SECONDS {
+                self.offer.set(Some(position));
+            }
+        });
+    }
+
+    /// Note that `video` is at `time` of `duration`
+    pub fn record(self, video: String, time: f64, duration: Option<f64>) {
+        // Don't overwrite the position while it's still on offer
+        if self.offer.get_untracked().is_some() {
+            return;
+        }
+        // Finished videos start from the beginning next time
+        let seconds = match duration {
+            Some(duration) if duration > 0.0 && time > duration - 30.0 => 0,
+            _ => time as u32,
+        };
+        if seconds.abs_diff(self.saved.get_untracked()) < POSITION_SAVE_STEP {
+            return;
+        }
+        self.saved.set(seconds);
+        spawn_local(async move {
+            let _ = call::<()>("save_playback_position", &PositionArgs { video, seconds }).await;
+        });
+    }
+
+    /// Take the offer up and seek there. `embed` waits for the embed, which
+    /// a click right after loading can beat to it.
+    pub fn resume(self, media: ReadSignal<Option<MediaSource>>, embed: bool) {
+        let Some(seconds) = self.offer.get_untracked() else {
+            return;
+        };
+        self.offer.set(None);
+        spawn_local(async move {
+            if embed {
+                player_ready().await;
+            }
+            media.with_untracked(|m| seek_video(m.as_ref(), seconds as f64));
+        });
+    }
+
+    /// Start over instead; the position is saved again as it plays
+    pub fn dismiss(&self) {
+        self.offer.set(None);
+    }
+}
@@ -0,0 +1,88 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use serde::Serialize;
+use sleepy_video_shared::search::SearchResult;
+use sleepy_video_shared::settings::Settings;
+
+use crate::i18n::{t, Text};
+use crate::ipc::call;
+
+#[derive(Serialize)]
+struct SearchArgs {
+    query: String,
+}
+
+/// YouTube search box with thumbnail results; picking one loads it. Hidden
+/// until a search backend is set up in settings.
+#[component]
+pub fn SearchBox(on_pick: Callback<String>) -> impl IntoView {
+    let settings = expect_context::<RwSignal<Settings>>();
+    let (query, set_query) = signal(String::new());
+    let (results, set_results) = signal(Vec::<SearchResult>::new());
+    let (message, set_message) = signal(String::new());
+
+    let search = move || {
+        let query = query.get_untracked();
+        if query.trim().is_empty() {
+            return;
+        }
+        set_message.set(t(Text::Searching).to_string());
+        spawn_local(async move {
+            match call::<Vec<SearchResult>>("search_videos", &SearchArgs { query }).await {
+                Ok(found) => {
+                    set_message.set(if found.is_empty() {
+                        t(Text::NothingFound).to_string()
+                    } else {
+                        String::new()
+                    });
+                    set_results.set(found);
+                }
+                Err(e) => set_message.set(e.explained()),
+            }
+        });
+    };
+
+    move || {
+        settings.with(|s| s.search.is_configured()).then(|| view! {
+            <div class="video-search">
+                <div class="video-input-row">
+                    <input
+                        type="search"
+                        class="video-input pixel-border"
+                        placeholder=move || t(Text::SearchPlaceholder)
+                        prop:value=move || query.get()
+                        on:input=move |ev| set_query.set(event_target_value(&ev))
+                        on:keydown=move |ev| {
+                            if ev.key() == "Enter" {
+                                search();
+                            }
+                        }
+                    />
+                    <button class="btn btn-load pixel-border" on:click=move |_| search()>"🔍"</button>
+                </div>
+                <p class="video-hint">{move || message.get()}</p>
+                <div class="recent-carousel">
+                    {move || results.get().into_iter().map(|result| {
+                        let thumbnail = result.thumbnail_url();
+                        let tooltip = format!("{} - {}", result.title, result.channel);
+                        let video = result.video_id.clone();
+                        view! {
+                            <div class="recent-card pixel-border" title=tooltip>
+                                <button
+                                    class="recent-play"
+                                    on:click=move |_| {
+                                        set_results.set(Vec::new());
+                                        on_pick.run(video.clone());
+                                    }
+                                >
+                                    <img src=thumbnail alt="" loading="lazy"/>
+                                    <span class="recent-title">{result.title}</span>
+                                </button>
+                            </div>
+                        }
+                    }).collect_view()}
+                </div>
+            </div>
+        })
+    }
+}
@@ -0,0 +1,646 @@
+use std::time::Duration;
+
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use serde::Serialize;
+use sleepy_video_shared::apps::QuitResult;
+use sleepy_video_shared::busy::{BusyBehaviour, BusyReport};
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::haptics::Haptic;
+use sleepy_video_shared::lights::dim_level;
+use sleepy_video_shared::milestones::{due_milestones, Milestone};
+use sleepy_video_shared::parental::Profile;
+use sleepy_video_shared::pipeline::{Stage, StageOutcome, StageReport, FADE_OUT_SECONDS};
+use sleepy_video_shared::power::EndAction;
+use sleepy_video_shared::session::{
+    SavedSession, SessionPhase, SessionStatus, Transition, STAGE_EVENT,
+};
+use sleepy_video_shared::settings::Settings;
+use sleepy_video_shared::strict::StrictStatus;
+use sleepy_video_shared::timer::fade_window;
+
+use crate::app::toggle_body_class;
+use crate::chime;
+use crate::haptics;
+use crate::i18n::{action_label, milestone_message, stage_doing, t, t_status, t_with, Text};
+use crate::ipc::{call, listen_to};
+use crate::sfx::{self, Sfx};
+use crate::speech;
+use crate::ticker::Ticker;
+
+/// Longest gap between ticks whose milestones are still announced. Hidden
+/// windows get their timers throttled to about once a minute.
+const CATCH_UP_SECONDS: u32 = 120;
+/// Steps the fade-audio stage takes down to silence
+const FADE_OUT_STEPS: u32 = 10;
+/// How often the countdown is read off the end time
+const TICK: Duration = Duration::from_secs(1);
+/// How long a test run takes to dim the screen, before the pipeline starts
+const TEST_DIM_SECONDS: u32 = 4;
+
+#[derive(Serialize)]
+struct EndActionArgs {
+    action: EndAction,
+}
+
+#[derive(Serialize)]
+struct SaveSessionArgs {
+    session: Option<SavedSession>,
+}
+
+#[derive(Serialize)]
+struct MilestoneArgs {
+    milestone: Milestone,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct TestPipelineArgs {
+    action: EndAction,
+    notice: String,
+}
+
+#[derive(Serialize)]
+struct StatusArgs {
+    status: SessionStatus,
+}
+
+/// The running session, shared through context by `App` with the
+/// components that show or drive it
+#[derive(Clone, Copy)]
+pub struct SessionState {
+    settings: RwSignal<Settings>,
+    /// A child gets the length and end action the parent set
+    profile: RwSignal<Profile>,
+    /// Duration picked for the next session, in minutes
+    pub selected_minutes: RwSignal<u32>,
+    pub remaining: RwSignal<u32>,
+    pub total: RwSignal<u32>,
+    /// Where the session is; `remaining` is read off it on every tick
+    pub phase: RwSignal<SessionPhase>,
+    pub action: RwSignal<EndAction>,
+    pub fade: RwSignal<bool>,
+    /// Where the end-of-session fade is, in percent of the manual volume
+    pub fade_level: RwSignal<u32>,
+    pub status_text: RwSignal<String>,
+    pub status_class: RwSignal<String>,
+    /// Apps closed before the last end action, and how that went
+    pub quit_report: RwSignal<Vec<QuitResult>>,
+    /// Opacity of the dim overlay, darkening as the session runs out
+    pub dim: RwSignal<f64>,
+    /// The desktop dims the whole screen, so the overlay stays clear
+    pub os_dim: RwSignal<bool>,
+    /// Times the end was pushed back for a busy system
+    postpone_count: RwSignal<u32>,
+    /// Why the system looked busy, while the user is asked about sleeping
+    pub busy_prompt: RwSignal<Option<BusyReport>>,
+    /// End action that failed, offered again once the cause is fixed
+    pub end_error: RwSignal<Option<(EndAction, EepyError)>>,
+    /// Session the last run was in the middle of, offered for resuming
+    pub recovered: RwSignal<Option<SavedSession>>,
+    /// Strict mode holding back a cancel or extension until the PIN
+    pub strict_prompt: RwSignal<Option<(StrictStatus, Callback<()>)>>,
+    /// Set while a test run goes through the end of a session
+    pub testing: RwSignal<bool>,
+    /// Drives the countdown; dropped with the app
+    ticker: Ticker,
+}
+
+impl SessionState {
+    pub fn new(settings: RwSignal<Settings>, profile: RwSignal<Profile>) -> Self {
+        Self {
+            settings,
+            profile,
+            selected_minutes: RwSignal::new(60),
+            remaining: RwSignal::new(0),
+            total: RwSignal::new(0),
+            phase: RwSignal::new(SessionPhase::Idle),
+            action: RwSignal::new(EndAction::Suspend),
+            fade: RwSignal::new(true),
+            fade_level: RwSignal::new(100),
+            status_text: RwSignal::new(t(Text::ReadyToPod).to_string()),
+            status_class: RwSignal::new(String::new()),
+            quit_report: RwSignal::new(Vec::new()),
+            dim: RwSignal::new(0.0),
+            os_dim: RwSignal::new(false),
+            postpone_count: RwSignal::new(0),
+            busy_prompt: RwSignal::new(None),
+            end_error: RwSignal::new(None),
+            recovered: RwSignal::new(None),
+            strict_prompt: RwSignal::new(None),
+            testing: RwSignal::new(false),
+            ticker: Ticker::new(),
+        }
+    }
+
+    /// Whether a session is under way, counting down or not
+    pub fn is_running(&self) -> bool {
+        self.phase.with(|phase| phase.is_active())
+    }
+
+    /// Move the session on to its next phase
+    pub fn transition(&self, transition: Transition) {
+        self.phase
+            .update(|phase| *phase = phase.reduce(transition, js_sys::Date::now()));
+    }
+
+    /// How much of the session has passed, in percent
+    pub fn progress_percent(&self) -> u32 {
+        let total = self.total.get();
+        let remaining = self.remaining.get();
+        if total > 0 {
+            ((total - remaining) as f64 / total as f64 * 100.0) as u32
+        } else {
+            0
+        }
+    }
+
+    fn set_status(&self, text: String, class: &str) {
+        self.status_text.set(text);
+        self.status_class.set(class.to_string());
+    }
+
+    /// Count down the last `remaining` seconds of a session `total` seconds
+    /// long. The volume only fades, and the video only dims, while
+    /// `has_media`. Does nothing while another session is under way.
+    pub fn start(
+        self,
+        total: u32,
+        remaining: u32,
+        action: EndAction,
+        fade: bool,
+        has_media: Signal<bool>,
+    ) {
+        // A session under way keeps its countdown; it's cancelled first
+        if !self.phase.get_untracked().can_start() {
+            return;
+        }
+        self.total.set(total);
+        self.remaining.set(remaining);
+        self.transition(Transition::Start { seconds: remaining });
+        self.action.set(action);
+        self.fade.set(fade);
+        self.fade_level.set(100);
+        self.dim.set(dim_level(total, remaining));
+        self.postpone_count.set(0);
+        self.quit_report.set(Vec::new());
+        self.set_status(t_status(Text::TimerRunning), "running");
+        if has_media.get_untracked() {
+            toggle_body_class("dim-mode", true);
+        }
+        sfx::play(Sfx::Start);
+        haptics::buzz(Haptic::Impact);
+
+        // Silence notifications for the session
+        spawn_local(async move {
+            if let Err(e) = call::<()>("enable_dnd", &()).await {
+                leptos::logging::warn!("Could not enable Do Not Disturb: {}", e);
+            }
+        });
+
+        // Starting again replaces a ticker left running
+        self.ticker.start(TICK, move || self.tick(has_media));
+    }
+
+    /// Start a session with the selected duration. `action` overrides the
+    /// configured end action for this session only, `fade` turns the volume
+    /// fade off for templates that don't want it.
+    pub fn start_selected(self, action: Option<EndAction>, fade: bool, has_media: Signal<bool>) {
+        // The child gets the length and end action the parent set
+        if self.profile.get_untracked() == Profile::Child {
+            let (minutes, action) = self
+                .settings
+                .with_untracked(|s| (s.parental.minutes, s.parental.action));
+            self.start(minutes * 60, minutes * 60, action, fade, has_media);
+            return;
+        }
+        let minutes = self.selected_minutes.get_untracked();
+        let max = self.settings.with_untracked(|s| s.timer.max_minutes());
+        if minutes < 1 || minutes > max {
+            self.set_status(
+                t_with(Text::InvalidTime, &[("max", &max.to_string())]),
+                "warning",
+            );
+            return;
+        }
+
+        let action = action.unwrap_or_else(|| self.settings.with_untracked(|s| s.end_action));
+        self.start(minutes * 60, minutes * 60, action, fade, has_media);
+    }
+
+    /// Keyboard: +/- move the timer, or the selected duration before a session
+    pub fn adjust(self, minutes: i32) {
+        if !self.is_running() {
+            let max = self.settings.with_untracked(|s| s.timer.max_minutes());
+            self.selected_minutes
+                .update(|m| *m = (*m as i32 + minutes).clamp(1, max as i32) as u32);
+        } else if minutes > 0 && self.profile.get_untracked() == Profile::Child {
+            self.status_text.set(t(Text::AskParent).to_string());
+        } else if minutes > 0 {
+            self.unless_locked(Callback::new(move |_| {
+                self.extend(minutes as u32);
+                self.status_text
+                    .set(t_with(Text::Extended, &[("minutes", &minutes.to_string())]));
+            }));
+        } else {
+            let cut = self.shorten(minutes.unsigned_abs());
+            if cut > 0 {
+                let minutes = (cut / 60).to_string();
+                self.status_text
+                    .set(t_with(Text::Shortened, &[("minutes", &minutes)]));
+            }
+        }
+    }
+
+    fn tick(self, has_media: Signal<bool>) {
+        // Paused and finishing sessions sit still
+        let current = self.phase.get_untracked();
+        if !matches!(current, SessionPhase::Running { .. }) {
+            return;
+        }
+
+        let remaining = self.remaining.get_untracked();
+        let total = self.total.get_untracked();
+        if remaining == 0 {
+            self.finish();
+            return;
+        }
+
+        // Read off the end time rather than counted down, so throttled
+        // ticks or a sleeping system can't make it drift
+        let new_remaining = current.remaining(js_sys::Date::now()).min(remaining);
+        if new_remaining == remaining {
+            return;
+        }
+        self.remaining.set(new_remaining);
+        // Seconds that went by since the last tick, to not miss what was due
+        // in them. Only a short gap, a long one means the system slept and
+        // its milestones are stale.
+        let passed = if remaining - new_remaining <= CATCH_UP_SECONDS {
+            new_remaining..remaining
+        } else {
+            new_remaining..new_remaining + 1
+        };
+
+        if new_remaining <= 10 {
+            self.set_status(t_status(Text::AlmostThere), "warning");
+        } else if new_remaining <= 60 {
+            self.set_status(t_status(Text::GettingSleepy), "warning");
+        }
+
+        // Let the backend fan milestones out to notifications etc.
+        let milestones = self.settings.with_untracked(|s| {
+            passed
+                .clone()
+                .rev()
+                .flat_map(|second| due_milestones(&s.milestones, total, second))
+                .collect::<Vec<_>>()
+        });
+        for milestone in milestones {
+            sfx::play(Sfx::Milestone);
+            haptics::buzz(Haptic::Warning);
+            let message = milestone_message(&milestone);
+            if self.settings.with_untracked(|s| s.milestones.speak) {
+                let volume = self.fade_level.get_untracked() as f32 / 100.0;
+                if let Err(e) = speech::speak(&message, volume) {
+                    leptos::logging::warn!("Could not speak milestone: {:?}", e);
+                }
+            }
+            spawn_local(async move {
+                let args = MilestoneArgs { milestone, message };
+                let _ = call::<()>("report_milestone", &args).await;
+            });
+        }
+
+        // Last call, whatever the video is doing
+        let last_call = self.settings.with_untracked(|s| s.chime.clone());
+        if last_call.enabled
+            && last_call.seconds_before < total
+            && passed.contains(&last_call.seconds_before)
+        {
+            if let Err(e) = chime::play(last_call.tone, last_call.volume as f32 / 100.0) {
+                leptos::logging::warn!("Could not play the chime: {:?}", e);
+            }
+        }
+
+        self.dim.set(dim_level(total, new_remaining));
+
+        // Volume fade in the last 10%, capped for very long sessions
+        if total > 0 && self.fade.get_untracked() && has_media.get_untracked() {
+            let window = fade_window(total);
+            if new_remaining <= window && window > 0 {
+                let level = (new_remaining as f64 / window as f64 * 100.0) as u32;
+                if level != self.fade_level.get_untracked() {
+                    self.fade_level.set(level);
+                }
+            }
+        }
+    }
+
+    /// Add time to the running session
+    pub fn extend(self, minutes: u32) {
+        let extra = minutes * 60;
+        self.total.update(|t| *t += extra);
+        self.remaining.update(|r| *r += extra);
+        // A finished session waiting on the busy guard gets the full extra time
+        self.transition(Transition::Extend { seconds: extra });
+        self.busy_prompt.set(None);
+
+        // Back out of the volume fade if the extension left its window
+        if self.remaining.get_untracked() > fade_window(self.total.get_untracked()) {
+            self.fade_level.set(100);
+        }
+    }
+
+    /// Take up to `minutes` off the running session, always leaving the last
+    /// minute so the end can't be skipped to. The seconds it took off.
+    pub fn shorten(self, minutes: u32) -> u32 {
+        let cut = (minutes * 60).min(self.remaining.get_untracked().saturating_sub(60));
+        if cut > 0 {
+            self.total.update(|t| *t -= cut);
+            self.remaining.update(|r| *r -= cut);
+            self.transition(Transition::Shorten { seconds: cut });
+        }
+        cut
+    }
+
+    /// Push the end of the session back while the system is busy
+    pub fn postpone(self, minutes: u32) {
+        self.extend(minutes);
+        self.postpone_count.update(|c| *c += 1);
+        self.set_status(
+            t_with(Text::Postponed, &[("minutes", &minutes.to_string())]),
+            "warning",
+        );
+    }
+
+    /// Timer reached zero: consult the busy guard before suspending
+    fn finish(self) {
+        self.transition(Transition::Expire);
+        self.status_text.set(t(Text::CheckingBusy).to_string());
+        spawn_local(async move {
+            let guard = self.settings.with_untracked(|s| s.busy_guard.clone());
+            let report = call::<BusyReport>("check_busy", &())
+                .await
+                .unwrap_or_default();
+            if !report.busy {
+                self.suspend();
+                return;
+            }
+
+            match guard.behaviour {
+                BusyBehaviour::Postpone
+                    if self.postpone_count.get_untracked() < guard.max_postpones =>
+                {
+                    self.postpone(guard.postpone_minutes);
+                }
+                BusyBehaviour::Postpone => self.suspend(),
+                BusyBehaviour::Ask => {
+                    self.set_status(t(Text::SystemBusySuspend).to_string(), "warning");
+                    self.busy_prompt.set(Some(report));
+                }
+            }
+        });
+    }
+
+    /// Hand the end over to the backend's pipeline, which ends with the end
+    /// action (sleep by default)
+    pub fn suspend(self) {
+        self.ticker.stop();
+        self.transition(Transition::Finish);
+        self.busy_prompt.set(None);
+        self.set_status(t_status(Text::ClosingApps), "");
+
+        let action = self.action.get_untracked();
+        spawn_local(async move {
+            if action != EndAction::Nothing {
+                let results = call::<Vec<QuitResult>>("quit_apps_before_suspend", &())
+                    .await
+                    .unwrap_or_default();
+                self.quit_report.set(results);
+            }
+            self.status_text.set(t_status(Text::SweetDreams));
+            if let Err(e) = call::<()>("run_end_pipeline", &EndActionArgs { action }).await {
+                self.set_status(e.to_string().to_uppercase(), "warning");
+                self.end_error.set(Some((action, e)));
+            }
+            let _ = call::<()>("restore_dnd", &()).await;
+        });
+    }
+
+    /// Run the end action that failed once more
+    pub fn retry_end_action(self, action: EndAction) {
+        self.end_error.set(None);
+        spawn_local(async move {
+            if let Err(e) = call::<()>("run_end_action", &EndActionArgs { action }).await {
+                self.end_error.set(Some((action, e)));
+            }
+        });
+    }
+
+    /// Stop the session without running the end action
+    pub fn cancel(self) {
+        self.transition(Transition::Cancel);
+        self.remaining.set(0);
+        self.total.set(0);
+        self.busy_prompt.set(None);
+        self.set_status(t_status(Text::TimerCancelled), "");
+        haptics::buzz(Haptic::Impact);
+        self.ticker.stop();
+        self.dim.set(0.0);
+        self.fade_level.set(100);
+
+        // Give notifications back and let integrations undo their changes
+        spawn_local(async move {
+            let _ = call::<()>("restore_dnd", &()).await;
+            let _ = call::<()>("report_cancelled", &()).await;
+        });
+    }
+
+    /// Cancel, once strict mode lets it
+    pub fn cancel_unless_locked(self) {
+        self.unless_locked(Callback::new(move |_| self.cancel()));
+    }
+
+    /// Strict mode: cancelling or extending a session waits for the PIN
+    pub fn unless_locked(self, then: Callback<()>) {
+        spawn_local(async move {
+            match call::<StrictStatus>("strict_status", &()).await {
+                Ok(status) if status.locked => self.strict_prompt.set(Some((status, then))),
+                _ => then.run(()),
+            }
+        });
+    }
+
+    /// Hold the countdown where it is, or pick it back up. Holding it keeps
+    /// the end from coming, so strict mode asks for the PIN like extending.
+    pub fn toggle_pause(self) {
+        match self.phase.get_untracked() {
+            SessionPhase::Running { .. } => self.unless_locked(Callback::new(move |_| {
+                self.transition(Transition::Pause);
+                self.set_status(t_status(Text::TimerPaused), "");
+            })),
+            SessionPhase::Paused { .. } => {
+                self.transition(Transition::Resume);
+                self.set_status(t_status(Text::TimerRunning), "running");
+            }
+            _ => {}
+        }
+    }
+
+    /// TEST: the end of a session squeezed into about 10 seconds, with no
+    /// session to lose and nothing going to sleep. The screen dims, then the
+    /// backend walks the window through the fade and pause.
+    pub fn run_test(self) {
+        if self.is_running() || self.testing.get_untracked() {
+            return;
+        }
+        self.testing.set(true);
+        self.status_text.set(t(Text::TestRunning).to_string());
+        for step in 1..=TEST_DIM_SECONDS {
+            set_timeout(
+                move || {
+                    self.dim
+                        .set(dim_level(TEST_DIM_SECONDS, TEST_DIM_SECONDS - step))
+                },
+                Duration::from_secs(step as u64),
+            );
+        }
+        let action = self.settings.with_untracked(|s| s.end_action);
+        let notice = t_with(
+            Text::TestNotice,
+            &[("action", &t(action_label(action)).to_lowercase())],
+        );
+        set_timeout(
+            move || {
+                spawn_local(async move {
+                    let args = TestPipelineArgs { action, notice };
+                    let ran = call::<()>("run_test_pipeline", &args).await;
+                    self.dim.set(0.0);
+                    self.fade_level.set(100);
+                    self.testing.set(false);
+                    match ran {
+                        Ok(()) => self.status_text.set(t(Text::TestOver).to_string()),
+                        Err(e) => self.status_text.set(e.explained()),
+                    }
+                });
+            },
+            Duration::from_secs(TEST_DIM_SECONDS as u64),
+        );
+    }
+
+    /// The end-of-session pipeline runs in the backend, apart from what it
+    /// does to the player, which `pause` stops
+    pub fn follow_stages(self, pause: impl Fn() + 'static) {
+        listen_to::<StageReport>(STAGE_EVENT, move |report| match report.outcome {
+            StageOutcome::Started => {
+                self.status_text
+                    .set(t(stage_doing(report.stage)).to_string());
+                let stop = self.settings.with_untracked(|s| s.playback.stop);
+                match report.stage {
+                    Stage::FadeAudio if stop.fades() => self.fade_out(),
+                    Stage::PauseMedia if stop.pauses() => pause(),
+                    // Silent without a pause, in case the fade stage is off
+                    Stage::PauseMedia => self.fade_level.set(0),
+                    _ => {}
+                }
+            }
+            StageOutcome::Done => {}
+            StageOutcome::Failed(e) => {
+                leptos::logging::warn!("{} failed: {}", report.stage.label(), e);
+            }
+        });
+    }
+
+    /// Bring the fade the rest of the way down to silence
+    fn fade_out(self) {
+        let from = self.fade_level.get_untracked();
+        for step in 1..=FADE_OUT_STEPS {
+            set_timeout(
+                move || {
+                    let level = from * (FADE_OUT_STEPS - step) / FADE_OUT_STEPS;
+                    self.fade_level.update(|l| *l = (*l).min(level));
+                },
+                Duration::from_millis((FADE_OUT_SECONDS * 1000 / FADE_OUT_STEPS * step) as u64),
+            );
+        }
+    }
+
+    /// Keep the running session on disk, so it survives a crash or a quit.
+    /// Only written while one runs and once when it stops. `video` is the
+    /// link playing along, if any.
+    pub fn keep_saved(self, video: Signal<Option<String>>) {
+        Effect::new(move |was_running: Option<bool>| {
+            let video = video.get();
+            let current = self.phase.get();
+            let running = current.is_active();
+            let total = self.total.get();
+            let action = self.action.get();
+            let fade = self.fade.get();
+            if !running && was_running != Some(true) {
+                return running;
+            }
+            let session = running.then(|| {
+                let ends_at = current.ends_at(js_sys::Date::now()).unwrap_or_default() as u64;
+                SavedSession {
+                    started_at: ends_at.saturating_sub(total as u64 * 1000),
+                    ends_at,
+                    total_seconds: total,
+                    video,
+                    action,
+                    fade,
+                }
+            });
+            spawn_local(async move {
+                if let Err(e) = call::<()>("save_session", &SaveSessionArgs { session }).await {
+                    leptos::logging::warn!("Could not save the session: {}", e);
+                }
+            });
+            running
+        });
+    }
+
+    /// Offer to pick up a session the app went away in the middle of
+    pub async fn check_recovered(self) {
+        if let Ok(Some(saved)) = call::<Option<SavedSession>>("take_recovered_session", &()).await {
+            if !self.is_running() && saved.ends_at > js_sys::Date::now() as u64 {
+                self.recovered.set(Some(saved));
+            } else if !self.is_running() {
+                let _ = call::<()>("save_session", &SaveSessionArgs { session: None }).await;
+            }
+        }
+    }
+
+    /// Take the recovered session up, with the seconds it has left by the
+    /// clock. None if it ran out while on offer.
+    pub fn take_recovered(self) -> Option<(SavedSession, u32)> {
+        let saved = self.recovered.get_untracked()?;
+        self.recovered.set(None);
+        let left = saved.ends_at.saturating_sub(js_sys::Date::now() as u64) / 1000;
+        (left > 0).then_some((saved, left as u32))
+    }
+
+    pub fn discard_recovered(self) {
+        self.recovered.set(None);
+        spawn_local(async move {
+            let _ = call::<()>("save_session", &SaveSessionArgs { session: None }).await;
+        });
+    }
+
+    /// Mirror the countdown to the backend for the remote API and
+    /// integrations
+    pub fn mirror_status(self, video_id: Memo<Option<String>>) {
+        Effect::new(move |_| {
+            let status = SessionStatus {
+                running: self.is_running(),
+                remaining_seconds: self.remaining.get(),
+                total_seconds: self.total.get(),
+                video_id: video_id.get(),
+                action: self.action.get(),
+            };
+            spawn_local(async move {
+                let _ = call::<()>("report_status", &StatusArgs { status }).await;
+            });
+        });
+    }
+}
@@ -0,0 +1,3584 @@
+use std::time::Duration;
+
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use serde::Serialize;
+use sleepy_video_shared::apps::QuitAppRule;
+use sleepy_video_shared::busy::BusyBehaviour;
+use sleepy_video_shared::chime::ChimeTone;
+use sleepy_video_shared::display::{
+    format_clock, BreathingPattern, CustomCss, Theme, MAX_SCALE, MIN_SCALE,
+};
+use sleepy_video_shared::equalizer::{EqPreset, BANDS_HZ, MAX_GAIN_DB};
+use sleepy_video_shared::error::EepyError;
+use sleepy_video_shared::history::{HistoryFormat, SessionOutcome, SessionRecord};
+use sleepy_video_shared::i18n::Locale;
+use sleepy_video_shared::library::LibraryEntry;
+use sleepy_video_shared::lights::{FoundLight, HueSettings};
+use sleepy_video_shared::mascot::{Mascot, StatusMessage};
+use sleepy_video_shared::media::{MediaServer, MediaServerKind};
+use sleepy_video_shared::offline::OfflineCache;
+use sleepy_video_shared::playback::{EmbedHost, StopStyle};
+use sleepy_video_shared::plugins::PluginList;
+use sleepy_video_shared::power::{CheckStatus, EndAction, PowerCapabilities, PowerDiagnostics};
+use sleepy_video_shared::profiles::{ImportPreview, ProfileList, DEFAULT_PROFILE};
+use sleepy_video_shared::push::{PushService, PushSettings};
+use sleepy_video_shared::remote::Pairing;
+use sleepy_video_shared::settings::Settings;
+use sleepy_video_shared::sonos::SonosGroup;
+use sleepy_video_shared::sponsorblock::CATEGORIES;
+use sleepy_video_shared::templates::SessionTemplate;
+use sleepy_video_shared::timer::{preset_label, CloseBehaviour, MAX_MINUTES_LIMIT};
+use sleepy_video_shared::widget::Corner;
+use sleepy_video_shared::windows_integration::PowerPlan;
+use wasm_bindgen::JsValue;
+
+use crate::chime;
+use crate::custom_css;
+use crate::dialog::{focus_on_open, trap_focus};
+use crate::i18n::{
+    action_label, breathing_label, category_label, chime_label, close_label, corner_label,
+    eq_preset_label, mascot_label, message_label, stage_label, status_text, stop_style_label, t,
+    t_with, theme_label, Text,
+};
+use crate::ipc::call;
+use crate::sfx::{self, Sfx};
+
+#[derive(Serialize)]
+struct PathArgs {
+    path: String,
+}
+
+#[derive(Serialize)]
+struct HuePairArgs {
+    bridge: String,
+}
+
+#[derive(Serialize)]
+struct HueLightsArgs {
+    hue: HueSettings,
+}
+
+#[derive(Serialize)]
+struct SpotifyConnectArgs {
+    client: String,
+}
+
+#[derive(Serialize)]
+struct PushTestArgs {
+    push: PushSettings,
+}
+
+#[derive(Serialize)]
+struct PinArgs {
+    pin: String,
+}
+
+#[derive(Serialize)]
+struct PasswordArgs {
+    password: String,
+}
+
+#[derive(Serialize)]
+struct NameArgs {
+    name: String,
+}
+
+#[derive(Serialize)]
+struct IdArgs {
+    id: String,
+}
+
+#[derive(Serialize)]
+struct VideoArgs {
+    video: String,
+}
+
+#[derive(Serialize)]
+struct HistoryExportArgs {
+    format: HistoryFormat,
+}
+
+#[derive(Serialize)]
+struct SaveSettingsArgs {
+    settings: Settings,
+}
+
+/// The deep sea theme's accent, what the accent picker starts from
+const DEFAULT_ACCENT: &str = "#00f5d4";
+
+/// Parse a comma separated list, dropping empty entries
+fn parse_list(raw: &str) -> Vec<String> {
+    raw.split(',')
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Persist settings through the backend, returning what was actually saved
+pub async fn persist(settings: Settings) -> Result<Settings, EepyError> {
+    call::<Settings>("save_settings", &SaveSettingsArgs { settings }).await
+}
+
+/// Modal settings panel. Edits a draft copy and only writes it back to the
+/// shared settings signal once the backend has persisted it.
+#[component]
+pub fn SettingsPanel(set_open: WriteSignal<bool>) -> impl IntoView {
+    let settings = expect_context::<RwSignal<Settings>>();
+    let draft = RwSignal::new(settings.get_untracked());
+    let (error, set_error) = signal(String::new());
+
+    let save = move |_| {
+        let next = draft.get_untracked();
+        spawn_local(async move {
+            match persist(next).await {
+                Ok(saved) => {
+                    settings.set(saved);
+                    set_open.set(false);
+                }
+                Err(e) => set_error.set(e.explained()),
+            }
+        });
+    };
+
+    view! {
+        <div
+            class="settings-overlay"
+            role="dialog"
+            aria-modal="true"
+            node_ref=focus_on_open()
+            on:keydown=trap_focus
+        >
+            <div class="settings-panel pixel-border">
+                <h2 class="settings-title">{move || t(Text::Settings)}</h2>
+
+                <EndActionSection draft=draft/>
+                <PipelineSection draft=draft/>
+                <PlaybackSection draft=draft/>
+                <OfflineSection draft=draft/>
+                <SponsorBlockSection draft=draft/>
+                <SearchSection draft=draft/>
+                <ProfilesSection/>
+                <PresetsSection draft=draft/>
+                <TemplatesSection draft=draft/>
+                <BusyGuardSection draft=draft/>
+                <AppQuitSection draft=draft/>
+                <DndSection draft=draft/>
+                <WindowsSection draft=draft/>
+                <MilestonesSection draft=draft/>
+                <ChimeSection draft=draft/>
+                <SoundsSection draft=draft/>
+                <StartupSection draft=draft/>
+                <WakeAlarmSection draft=draft/>
+                <StrictSection draft=draft/>
+                <ParentalSection draft=draft/>
+                <WidgetSection draft=draft/>
+                <DisplaySection draft=draft/>
+                <MascotSection draft=draft/>
+                <RemoteSection draft=draft/>
+                <WebhooksSection draft=draft/>
+                <PluginsSection draft=draft/>
+                <MqttSection draft=draft/>
+                <LightsSection draft=draft/>
+                <DiscordSection draft=draft/>
+                <MediaServersSection draft=draft/>
+                <SpotifySection draft=draft/>
+                <SonosSection draft=draft/>
+                <PushSection draft=draft/>
+                <HistorySection/>
+                <JournalSection draft=draft/>
+                <BackupSection/>
+                <LogsSection/>
+                <HelpSection/>
+
+                <p class="settings-error">{move || error.get()}</p>
+
+                <div class="action-buttons">
+                    <button class="btn btn-primary pixel-border" on:click=save>{move || t(Text::Save)}</button>
+                    <button
+                        class="btn btn-load pixel-border"
+                        data-back=true
+                        on:click=move |_| set_open.set(false)
+                    >{move || t(Text::Close)}</button>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn EndActionSection(draft: RwSignal<Settings>) -> impl IntoView {
+    let (capabilities, set_capabilities) = signal(Option::<PowerCapabilities>::None);
+    spawn_local(async move {
+        if let Ok(loaded) = call::<PowerCapabilities>("power_capabilities", &()).await {
+            set_capabilities.set(Some(loaded));
+        }
+    });
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::WhenTimerEnds)}</h3>
+            <label class="settings-row">
+                {move || t(Text::ComputerShould)}
+                <select
+                    class="settings-input"
+                    on:change=move |ev| {
+                        if let Some(action) = EndAction::parse(&event_target_value(&ev)) {
+                            draft.update(|s| s.end_action = action);
+                        }
+                    }
+                >
+                    {EndAction::ALL.into_iter().map(|action| view! {
+                        <option
+                            value=action.as_str()
+                            selected=move || draft.with(|s| s.end_action == action)
+                            disabled=move || capabilities.get().is_some_and(|c| !c.supports(action))
+                        >{t(action_label(action))}</option>
+                    }).collect_view()}
+                </select>
+            </label>
+        </section>
+    }
+}
+
+#[component]
+fn PipelineSection(draft: RwSignal<Settings>) -> impl IntoView {
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::EndOfSession)}</h3>
+            <ul class="settings-list">
+                {move || draft.with(|s| {
+                    s.pipeline.stages.iter().enumerate().map(|(i, step)| view! {
+                        <li class="settings-list-item">
+                            <label class="settings-row">
+                                <input
+                                    type="checkbox"
+                                    prop:checked=step.enabled
+                                    on:change=move |ev| draft.update(|s| {
+                                        s.pipeline.stages[i].enabled = event_target_checked(&ev);
+                                    })
+                                />
+                                {t(stage_label(step.stage))}
+                            </label>
+                            <label class="settings-row">
+                                {move || t(Text::WaitSeconds)}
+                                <input
+                                    type="number"
+                                    class="settings-input"
+                                    min="0"
+                                    prop:value=step.delay_seconds.to_string()
+                                    on:change=move |ev| {
+                                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                                            draft.update(|s| s.pipeline.stages[i].delay_seconds = val);
+                                        }
+                                    }
+                                />
+                            </label>
+                            <button
+                                class="settings-remove"
+                                title=move || t(Text::MoveUp)
+                                aria-label=move || t(Text::MoveUp)
+                                disabled=i == 0
+                                on:click=move |_| draft.update(|s| s.pipeline.move_up(i))
+                            >"↑"</button>
+                        </li>
+                    }).collect_view()
+                })}
+            </ul>
+            <p class="settings-hint">
+                {move || t(Text::PipelineHint)}
+            </p>
+        </section>
+    }
+}
+
+#[component]
+fn PlaybackSection(draft: RwSignal<Settings>) -> impl IntoView {
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::Playback)}</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.playback.audio_only)
+                    on:change=move |ev| draft.update(|s| s.playback.audio_only = event_target_checked(&ev))
+                />
+                {move || t(Text::AudioOnly)}
+            </label>
+            <p class="settings-hint">
+                {move || t(Text::AudioOnlyHint)}
+            </p>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.playback.low_data)
+                    on:change=move |ev| draft.update(|s| s.playback.low_data = event_target_checked(&ev))
+                />
+                {move || t(Text::SaveData)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.playback.low_data_when_metered)
+                    on:change=move |ev| draft.update(|s| s.playback.low_data_when_metered = event_target_checked(&ev))
+                />
+                {move || t(Text::SaveDataMetered)}
+            </label>
+            <p class="settings-hint">
+                {move || t(Text::SaveDataHint)}
+            </p>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.playback.extract_audio)
+                    on:change=move |ev| draft.update(|s| s.playback.extract_audio = event_target_checked(&ev))
+                />
+                {move || t(Text::ExtractAudio)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.playback.muffle)
+                    on:change=move |ev| draft.update(|s| s.playback.muffle = event_target_checked(&ev))
+                />
+                {move || t(Text::Muffle)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.playback.normalize)
+                    on:change=move |ev| draft.update(|s| s.playback.normalize = event_target_checked(&ev))
+                />
+                {move || t(Text::Normalize)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.playback.duck_spikes)
+                    on:change=move |ev| draft.update(|s| s.playback.duck_spikes = event_target_checked(&ev))
+                />
+                {move || t(Text::DuckBursts)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.playback.eq.enabled)
+                    on:change=move |ev| draft.update(|s| s.playback.eq.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::Equalizer)}
+            </label>
+            <label class="settings-row">
+                {move || t(Text::EqPresetLabel)}
+                <select
+                    class="settings-input"
+                    disabled=move || draft.with(|s| !s.playback.eq.enabled)
+                    on:change=move |ev| {
+                        if let Some(preset) = EqPreset::parse(&event_target_value(&ev)) {
+                            draft.update(|s| s.playback.eq.gains = preset.gains());
+                        }
+                    }
+                >
+                    {EqPreset::ALL.into_iter().map(|preset| view! {
+                        <option
+                            value=preset.as_str()
+                            selected=move || draft.with(|s| EqPreset::matching(&s.playback.eq.gains) == Some(preset))
+                        >{t(eq_preset_label(preset))}</option>
+                    }).collect_view()}
+                    <option
+                        value=""
+                        selected=move || draft.with(|s| EqPreset::matching(&s.playback.eq.gains).is_none())
+                    >{move || t(Text::EqCustom)}</option>
+                </select>
+            </label>
+            {BANDS_HZ.into_iter().enumerate().map(|(band, hz)| view! {
+                <label class="settings-row">
+                    {if hz >= 1000.0 { format!("{} KHZ", hz / 1000.0) } else { format!("{} HZ", hz) }}
+                    <input
+                        type="range"
+                        class="settings-input"
+                        min=-MAX_GAIN_DB
+                        max=MAX_GAIN_DB
+                        disabled=move || draft.with(|s| !s.playback.eq.enabled)
+                        prop:value=move || draft.with(|s| s.playback.eq.gains[band].to_string())
+                        on:input=move |ev| {
+                            if let Ok(gain) = event_target_value(&ev).parse::<i32>() {
+                                draft.update(|s| s.playback.eq.gains[band] = gain.clamp(-MAX_GAIN_DB, MAX_GAIN_DB));
+                            }
+                        }
+                    />
+                    {move || format!("{:+} DB", draft.with(|s| s.playback.eq.gains[band]))}
+                </label>
+            }).collect_view()}
+            <p class="settings-hint">
+                {move || t(Text::FiltersHint)}
+            </p>
+            <label class="settings-row">
+                {move || t(Text::StoppingPlayer)}
+                <select
+                    class="settings-input"
+                    on:change=move |ev| {
+                        if let Some(style) = StopStyle::parse(&event_target_value(&ev)) {
+                            draft.update(|s| s.playback.stop = style);
+                        }
+                    }
+                >
+                    {StopStyle::ALL.into_iter().map(|style| view! {
+                        <option
+                            value=style.as_str()
+                            selected=move || draft.with(|s| s.playback.stop == style)
+                        >{t(stop_style_label(style))}</option>
+                    }).collect_view()}
+                </select>
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.playback.media_keys)
+                    on:change=move |ev| draft.update(|s| s.playback.media_keys = event_target_checked(&ev))
+                />
+                {move || t(Text::MediaKeys)}
+            </label>
+            <p class="settings-hint">
+                {move || t(Text::MediaKeysHint)}
+            </p>
+            <label class="settings-row">
+                "YT-DLP"
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    placeholder=move || t(Text::YtdlpPlaceholder)
+                    prop:value=move || draft.with(|s| s.playback.ytdlp_path.clone())
+                    on:input=move |ev| draft.update(|s| s.playback.ytdlp_path = event_target_value(&ev))
+                />
+            </label>
+            <p class="settings-hint">
+                {move || t(Text::YtdlpHint)}
+            </p>
+            <label class="settings-row">
+                {move || t(Text::PlayerFrom)}
+                <select
+                    class="settings-input"
+                    on:change=move |ev| {
+                        if let Some(host) = EmbedHost::parse(&event_target_value(&ev)) {
+                            draft.update(|s| s.playback.embed_host = host);
+                        }
+                    }
+                >
+                    {EmbedHost::ALL.into_iter().map(|host| view! {
+                        <option
+                            value=host.as_str()
+                            selected=move || draft.with(|s| s.playback.embed_host == host)
+                        >{host.label()}</option>
+                    }).collect_view()}
+                </select>
+            </label>
+            {move || (draft.with(|s| s.playback.embed_host) != EmbedHost::YouTube).then(|| view! {
+                <div class="settings-row">
+                    <input
+                        type="text"
+                        class="settings-input wide"
+                        placeholder="https://yewtu.be"
+                        prop:value=move || draft.with(|s| s.playback.instance_url.clone())
+                        on:input=move |ev| draft.update(|s| s.playback.instance_url = event_target_value(&ev))
+                    />
+                </div>
+                <p class="settings-hint">
+                    {move || t(Text::InstanceHint)}
+                </p>
+            })}
+        </section>
+    }
+}
+
+/// How often the offline cache is looked at again while downloads run
+const OFFLINE_REFRESH: Duration = Duration::from_secs(3);
+
+/// Look at the offline cache, and again every few seconds while downloads
+/// run in the background. Stops once settings are closed.
+fn refresh_offline(set_cache: WriteSignal<OfflineCache>) {
+    spawn_local(async move {
+        if let Ok(report) = call::<OfflineCache>("offline_list", &()).await {
+            let pending = !report.downloading.is_empty();
+            if set_cache.try_set(report).is_none() && pending {
+                set_timeout(move || refresh_offline(set_cache), OFFLINE_REFRESH);
+            }
+        }
+    });
+}
+
+#[component]
+fn OfflineSection(draft: RwSignal<Settings>) -> impl IntoView {
+    let (cache, set_cache) = signal(OfflineCache::default());
+    let (titles, set_titles) = signal(Vec::<LibraryEntry>::new());
+    let (message, set_message) = signal(String::new());
+
+    refresh_offline(set_cache);
+    spawn_local(async move {
+        if let Ok(entries) = call::<Vec<LibraryEntry>>("library_list", &()).await {
+            set_titles.set(entries);
+        }
+    });
+
+    let title = move |video: &str| {
+        titles.with(|entries| {
+            entries
+                .iter()
+                .find(|entry| entry.video_id == video)
+                .and_then(|entry| entry.title.clone())
+                .unwrap_or_else(|| video.to_string())
+        })
+    };
+    let run = move |command: &'static str, video: Option<String>| {
+        spawn_local(async move {
+            let result = match video {
+                Some(video) => call::<OfflineCache>(command, &VideoArgs { video }).await,
+                None => call::<OfflineCache>(command, &()).await,
+            };
+            match result {
+                Ok(report) => {
+                    set_cache.set(report);
+                    refresh_offline(set_cache);
+                }
+                Err(e) => set_message.set(e.explained()),
+            }
+        });
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::OfflineAudio)}</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.offline.enabled)
+                    on:change=move |ev| draft.update(|s| s.offline.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::DownloadFavorites)}
+            </label>
+            <label class="settings-row">
+                {move || t(Text::KeepAtMost)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="100"
+                    prop:value=move || draft.with(|s| s.offline.max_mb.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            draft.update(|s| s.offline.max_mb = val.max(100));
+                        }
+                    }
+                />
+            </label>
+            <p class="settings-hint">
+                {move || cache.with(|c| {
+                    let mut summary = t_with(Text::OfflineSummary, &[
+                        ("count", &c.videos.len().to_string()),
+                        ("mb", &(c.total_bytes() / 1024 / 1024).to_string()),
+                    ]);
+                    if !c.downloading.is_empty() {
+                        summary += &t_with(Text::OfflineDownloading, &[("count", &c.downloading.len().to_string())]);
+                    }
+                    summary
+                })}
+            </p>
+            <ul class="settings-list">
+                {move || cache.get().videos.into_iter().map(|video| {
+                    let id = video.video_id.clone();
+                    view! {
+                        <li class="settings-list-item">
+                            <span class="settings-list-name">
+                                {format!("{} · {} MB", title(&video.video_id), video.bytes / 1024 / 1024)}
+                            </span>
+                            <button
+                                class="settings-remove"
+                                title=move || t(Text::DeleteTip)
+                                aria-label=move || t(Text::DeleteTip)
+                                on:click=move |_| run("offline_remove", Some(id.clone()))
+                            >"✕"</button>
+                        </li>
+                    }
+                }).collect_view()}
+            </ul>
+            <div class="settings-row">
+                <button
+                    class="btn btn-load pixel-border"
+                    on:click=move |_| run("offline_download_favorites", None)
+                >{move || t(Text::DownloadAllFavorites)}</button>
+                <button
+                    class="btn btn-load pixel-border"
+                    on:click=move |_| run("offline_clear", None)
+                >{move || t(Text::Clear)}</button>
+            </div>
+            <p class="settings-hint">
+                {move || t(Text::OfflineHint)}
+            </p>
+            <p class="settings-hint">{move || message.get()}</p>
+        </section>
+    }
+}
+
+#[component]
+fn SponsorBlockSection(draft: RwSignal<Settings>) -> impl IntoView {
+    view! {
+        <section class="settings-section">
+            <h3>"SPONSORBLOCK"</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.sponsorblock.enabled)
+                    on:change=move |ev| draft.update(|s| s.sponsorblock.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::SkipSegments)}
+            </label>
+            {CATEGORIES.into_iter().map(|(category, label)| view! {
+                <label class="settings-row">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || draft.with(|s| s.sponsorblock.categories.iter().any(|c| c == category))
+                        on:change=move |ev| {
+                            let checked = event_target_checked(&ev);
+                            draft.update(|s| {
+                                s.sponsorblock.categories.retain(|c| c != category);
+                                if checked {
+                                    s.sponsorblock.categories.push(category.to_string());
+                                }
+                            });
+                        }
+                    />
+                    {move || category_label(category).map_or(label, t)}
+                </label>
+            }).collect_view()}
+            <p class="settings-hint">
+                {move || t(Text::SponsorBlockHint)}
+            </p>
+        </section>
+    }
+}
+
+#[component]
+fn SearchSection(draft: RwSignal<Settings>) -> impl IntoView {
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::SearchHeading)}</h3>
+            <label class="settings-row">
+                {move || t(Text::ApiKey)}
+                <input
+                    type="password"
+                    class="settings-input wide"
+                    placeholder=move || t(Text::ApiKeyPlaceholder)
+                    prop:value=move || draft.with(|s| s.search.api_key.clone())
+                    on:input=move |ev| draft.update(|s| s.search.api_key = event_target_value(&ev))
+                />
+            </label>
+            <label class="settings-row">
+                {move || t(Text::OrInvidious)}
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    placeholder="https://yewtu.be"
+                    prop:value=move || draft.with(|s| s.search.invidious_url.clone())
+                    on:input=move |ev| draft.update(|s| s.search.invidious_url = event_target_value(&ev))
+                />
+            </label>
+            <p class="settings-hint">
+                {move || t(Text::SearchHint)}
+            </p>
+        </section>
+    }
+}
+
+/// Profiles are saved as soon as they're added or deleted, apart from the
+/// rest of the settings
+#[component]
+fn ProfilesSection() -> impl IntoView {
+    let profiles = expect_context::<RwSignal<ProfileList>>();
+    let (name, set_name) = signal(String::new());
+    let (error, set_error) = signal(String::new());
+
+    let update = move |result: Result<ProfileList, EepyError>| match result {
+        Ok(list) => {
+            profiles.set(list);
+            set_error.set(String::new());
+        }
+        Err(e) => set_error.set(e.to_string().to_uppercase()),
+    };
+
+    let add_profile = move |_| {
+        let name = name.get_untracked();
+        spawn_local(async move {
+            let result = call::<ProfileList>("profile_create", &NameArgs { name }).await;
+            if result.is_ok() {
+                set_name.set(String::new());
+            }
+            update(result);
+        });
+    };
+
+    let delete_profile = move |id: String| {
+        spawn_local(async move {
+            update(call::<ProfileList>("profile_delete", &IdArgs { id }).await);
+        });
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::Profiles)}</h3>
+            <ul class="settings-list">
+                {move || profiles.with(|list| {
+                    list.profiles.iter().map(|profile| {
+                        let id = profile.id.clone();
+                        let removable = id != list.active && id != DEFAULT_PROFILE;
+                        let name = if id == list.active {
+                            t_with(Text::InUse, &[("name", &profile.name)])
+                        } else {
+                            profile.name.clone()
+                        };
+                        view! {
+                            <li class="settings-list-item">
+                                <span class="settings-list-name">{name}</span>
+                                {removable.then(|| view! {
+                                    <button
+                                        class="settings-remove"
+                                        title=move || t(Text::DeleteTip)
+                                        aria-label=move || t(Text::DeleteTip)
+                                        on:click=move |_| delete_profile(id.clone())
+                                    >"✕"</button>
+                                })}
+                            </li>
+                        }
+                    }).collect_view()
+                })}
+            </ul>
+            <div class="settings-row">
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    placeholder=move || t(Text::ProfilePlaceholder)
+                    prop:value=move || name.get()
+                    on:input=move |ev| set_name.set(event_target_value(&ev))
+                />
+                <button class="btn btn-load pixel-border" on:click=add_profile>{move || t(Text::AddNew)}</button>
+            </div>
+            <p class="settings-error">{move || error.get()}</p>
+            <p class="settings-hint">
+                {move || t(Text::ProfilesHint)}
+            </p>
+        </section>
+    }
+}
+
+#[component]
+fn PresetsSection(draft: RwSignal<Settings>) -> impl IntoView {
+    let (new_minutes, set_new_minutes) = signal(20u32);
+    let max_minutes = move || draft.with(|s| s.timer.max_minutes());
+
+    // Swap a preset with its neighbour; out of range moves are ignored
+    let move_preset = move |from: usize, to: usize| {
+        draft.update(|s| {
+            if from < s.timer.presets.len() && to < s.timer.presets.len() {
+                s.timer.presets.swap(from, to);
+            }
+        })
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::Presets)}</h3>
+            <label class="settings-row">
+                {move || t(Text::LongestSession)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="1"
+                    max=MAX_MINUTES_LIMIT.to_string()
+                    prop:value=move || max_minutes().to_string()
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            draft.update(|s| s.timer.max_minutes = val.clamp(1, MAX_MINUTES_LIMIT));
+                        }
+                    }
+                />
+            </label>
+            <label class="settings-row">
+                {move || t(Text::PickedAtStart)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="1"
+                    max=move || max_minutes().to_string()
+                    prop:value=move || draft.with(|s| s.timer.default_minutes.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            let val = val.clamp(1, max_minutes());
+                            draft.update(|s| s.timer.default_minutes = val);
+                        }
+                    }
+                />
+            </label>
+            <ul class="settings-list">
+                {move || draft.with(|s| {
+                    let count = s.timer.presets.len();
+                    s.timer.presets.iter().enumerate().map(|(i, minutes)| view! {
+                        <li class="settings-list-item">
+                            <span class="settings-list-name">{format!("{} · {}M", preset_label(*minutes), minutes)}</span>
+                            <button
+                                class="settings-remove"
+                                title=move || t(Text::MoveLeft)
+                                aria-label=move || t(Text::MoveLeft)
+                                disabled={i == 0}
+                                on:click=move |_| move_preset(i, i.saturating_sub(1))
+                            >"◀"</button>
+                            <button
+                                class="settings-remove"
+                                title=move || t(Text::MoveRight)
+                                aria-label=move || t(Text::MoveRight)
+                                disabled={i + 1 == count}
+                                on:click=move |_| move_preset(i, i + 1)
+                            >"▶"</button>
+                            <button
+                                class="settings-remove"
+                                title=move || t(Text::RemoveTip)
+                                aria-label=move || t(Text::RemoveTip)
+                                on:click=move |_| draft.update(|s| {
+                                    s.timer.presets.remove(i);
+                                })
+                            >"✕"</button>
+                        </li>
+                    }).collect_view()
+                })}
+            </ul>
+            <div class="settings-row">
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="1"
+                    max=move || max_minutes().to_string()
+                    prop:value=move || new_minutes.get().to_string()
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            set_new_minutes.set(val.clamp(1, max_minutes()));
+                        }
+                    }
+                />
+                {move || t(Text::Minutes)}
+                <button
+                    class="btn btn-load pixel-border"
+                    on:click=move |_| draft.update(|s| s.timer.presets.push(new_minutes.get_untracked()))
+                >{move || t(Text::AddNew)}</button>
+            </div>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.timer.fade)
+                    on:change=move |ev| draft.update(|s| s.timer.fade = event_target_checked(&ev))
+                />
+                {move || t(Text::FadeVolumeAtEnd)}
+            </label>
+            <p class="settings-hint">{move || t(Text::PresetsHint)}</p>
+        </section>
+    }
+}
+
+#[component]
+fn TemplatesSection(draft: RwSignal<Settings>) -> impl IntoView {
+    let new_template = RwSignal::new(SessionTemplate::default());
+
+    let add_template = move |_| {
+        let template = new_template.get_untracked();
+        if template.name.trim().is_empty() {
+            return;
+        }
+        draft.update(|s| s.templates.push(template));
+        new_template.set(SessionTemplate::default());
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::SessionTemplates)}</h3>
+            <ul class="settings-list">
+                {move || draft.with(|s| {
+                    s.templates.iter().enumerate().map(|(i, template)| {
+                        let summary = format!(
+                            "{} · {}M · {}{}",
+                            template.name,
+                            template.minutes,
+                            t(action_label(template.action)),
+                            if template.fade { "" } else { t(Text::NoFade) },
+                        );
+                        view! {
+                            <li class="settings-list-item">
+                                <span class="settings-list-name" title=template.video.clone()>{summary}</span>
+                                <button
+                                    class="settings-remove"
+                                    title=move || t(Text::RemoveTip)
+                                    aria-label=move || t(Text::RemoveTip)
+                                    on:click=move |_| draft.update(|s| {
+                                        s.templates.remove(i);
+                                    })
+                                >"✕"</button>
+                            </li>
+                        }
+                    }).collect_view()
+                })}
+            </ul>
+            <div class="settings-row">
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    placeholder=move || t(Text::TemplateNamePlaceholder)
+                    prop:value=move || new_template.with(|t| t.name.clone())
+                    on:input=move |ev| new_template.update(|t| t.name = event_target_value(&ev))
+                />
+            </div>
+            <div class="settings-row">
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    placeholder=move || t(Text::YoutubeLinkOptional)
+                    prop:value=move || new_template.with(|t| t.video.clone())
+                    on:input=move |ev| new_template.update(|t| t.video = event_target_value(&ev))
+                />
+            </div>
+            <div class="settings-row">
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="1"
+                    max=move || draft.with(|s| s.timer.max_minutes()).to_string()
+                    prop:value=move || new_template.with(|t| t.minutes.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            let max = draft.with_untracked(|s| s.timer.max_minutes());
+                            new_template.update(|t| t.minutes = val.clamp(1, max));
+                        }
+                    }
+                />
+                {move || t(Text::MinThen)}
+                <select
+                    class="settings-input"
+                    on:change=move |ev| {
+                        if let Some(action) = EndAction::parse(&event_target_value(&ev)) {
+                            new_template.update(|t| t.action = action);
+                        }
+                    }
+                >
+                    {EndAction::ALL.into_iter().map(|action| view! {
+                        <option
+                            value=action.as_str()
+                            selected=move || new_template.with(|t| t.action == action)
+                        >{t(action_label(action))}</option>
+                    }).collect_view()}
+                </select>
+            </div>
+            <div class="settings-row">
+                <label>
+                    <input
+                        type="checkbox"
+                        prop:checked=move || new_template.with(|t| t.fade)
+                        on:change=move |ev| new_template.update(|t| t.fade = event_target_checked(&ev))
+                    />
+                    {move || t(Text::FadeTheVolume)}
+                </label>
+                <button class="btn btn-load pixel-border" on:click=add_template>{move || t(Text::Add)}</button>
+            </div>
+            <p class="settings-hint">
+                {move || t(Text::TemplatesHint)}
+            </p>
+        </section>
+    }
+}
+
+#[component]
+fn BusyGuardSection(draft: RwSignal<Settings>) -> impl IntoView {
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::BusyGuard)}</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.busy_guard.enabled)
+                    on:change=move |ev| draft.update(|s| s.busy_guard.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::CheckBeforeSuspending)}
+            </label>
+            <label class="settings-row">
+                {move || t(Text::WhenBusy)}
+                <select
+                    class="settings-input"
+                    on:change=move |ev| {
+                        let behaviour = match event_target_value(&ev).as_str() {
+                            "postpone" => BusyBehaviour::Postpone,
+                            _ => BusyBehaviour::Ask,
+                        };
+                        draft.update(|s| s.busy_guard.behaviour = behaviour);
+                    }
+                >
+                    <option
+                        value="ask"
+                        selected=move || draft.with(|s| s.busy_guard.behaviour == BusyBehaviour::Ask)
+                    >{move || t(Text::AskMe)}</option>
+                    <option
+                        value="postpone"
+                        selected=move || draft.with(|s| s.busy_guard.behaviour == BusyBehaviour::Postpone)
+                    >{move || t(Text::Postpone)}</option>
+                </select>
+            </label>
+            <label class="settings-row">
+                {move || t(Text::PostponeBy)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="1"
+                    prop:value=move || draft.with(|s| s.busy_guard.postpone_minutes.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            draft.update(|s| s.busy_guard.postpone_minutes = val.max(1));
+                        }
+                    }
+                />
+            </label>
+            <label class="settings-row">
+                {move || t(Text::MaxPostpones)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="0"
+                    prop:value=move || draft.with(|s| s.busy_guard.max_postpones.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            draft.update(|s| s.busy_guard.max_postpones = val);
+                        }
+                    }
+                />
+            </label>
+            <label class="settings-row">
+                {move || t(Text::CpuAbove)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="0"
+                    max="100"
+                    prop:value=move || draft.with(|s| s.busy_guard.cpu_threshold_percent.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            draft.update(|s| s.busy_guard.cpu_threshold_percent = val.min(100));
+                        }
+                    }
+                />
+            </label>
+            <label class="settings-row">
+                {move || t(Text::NetworkAbove)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="0"
+                    prop:value=move || draft.with(|s| s.busy_guard.network_threshold_kbps.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            draft.update(|s| s.busy_guard.network_threshold_kbps = val);
+                        }
+                    }
+                />
+            </label>
+            <label class="settings-column">
+                {move || t(Text::BlockingProcesses)}
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    placeholder="rsync, backupd, steam"
+                    prop:value=move || draft.with(|s| s.busy_guard.processes.join(", "))
+                    on:change=move |ev| {
+                        let processes = parse_list(&event_target_value(&ev));
+                        draft.update(|s| s.busy_guard.processes = processes);
+                    }
+                />
+            </label>
+        </section>
+    }
+}
+
+#[component]
+fn AppQuitSection(draft: RwSignal<Settings>) -> impl IntoView {
+    let (new_app, set_new_app) = signal(String::new());
+
+    let add_app = move |_| {
+        let name = new_app.get_untracked().trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        draft.update(|s| {
+            s.quit_apps.apps.push(QuitAppRule {
+                name,
+                ..Default::default()
+            })
+        });
+        set_new_app.set(String::new());
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::CloseAppsHeading)}</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.quit_apps.enabled)
+                    on:change=move |ev| draft.update(|s| s.quit_apps.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::QuitListedApps)}
+            </label>
+            <label class="settings-row">
+                {move || t(Text::GracePeriod)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="1"
+                    prop:value=move || draft.with(|s| s.quit_apps.grace_secs.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            draft.update(|s| s.quit_apps.grace_secs = val.max(1));
+                        }
+                    }
+                />
+            </label>
+            <ul class="settings-list">
+                {move || draft.with(|s| {
+                    s.quit_apps.apps.iter().enumerate().map(|(i, rule)| {
+                        let name = rule.name.clone();
+                        let enabled = rule.enabled;
+                        let force_kill = rule.force_kill;
+                        view! {
+                            <li class="settings-list-item">
+                                <input
+                                    type="checkbox"
+                                    title=move || t(Text::EnabledTip)
+                                    prop:checked=enabled
+                                    on:change=move |ev| draft.update(|s| {
+                                        if let Some(rule) = s.quit_apps.apps.get_mut(i) {
+                                            rule.enabled = event_target_checked(&ev);
+                                        }
+                                    })
+                                />
+                                <span class="settings-list-name">{name}</span>
+                                <label class="settings-list-flag">
+                                    <input
+                                        type="checkbox"
+                                        prop:checked=force_kill
+                                        on:change=move |ev| draft.update(|s| {
+                                            if let Some(rule) = s.quit_apps.apps.get_mut(i) {
+                                                rule.force_kill = event_target_checked(&ev);
+                                            }
+                                        })
+                                    />
+                                    {move || t(Text::Force)}
+                                </label>
+                                <button
+                                    class="settings-remove"
+                                    title=move || t(Text::RemoveTip)
+                                    aria-label=move || t(Text::RemoveTip)
+                                    on:click=move |_| draft.update(|s| {
+                                        s.quit_apps.apps.remove(i);
+                                    })
+                                >"✕"</button>
+                            </li>
+                        }
+                    }).collect_view()
+                })}
+            </ul>
+            <div class="settings-row">
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    placeholder=move || t(Text::AppNamePlaceholder)
+                    prop:value=move || new_app.get()
+                    on:input=move |ev| set_new_app.set(event_target_value(&ev))
+                />
+                <button class="btn btn-load pixel-border" on:click=add_app>{move || t(Text::Add)}</button>
+            </div>
+        </section>
+    }
+}
+
+#[component]
+fn DndSection(draft: RwSignal<Settings>) -> impl IntoView {
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::DoNotDisturb)}</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.dnd.enabled)
+                    on:change=move |ev| draft.update(|s| s.dnd.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::SilenceNotifications)}
+            </label>
+            <label class="settings-column">
+                {move || t(Text::MacFocusOn)}
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    prop:value=move || draft.with(|s| s.dnd.mac_shortcut_on.clone())
+                    on:change=move |ev| draft.update(|s| s.dnd.mac_shortcut_on = event_target_value(&ev))
+                />
+            </label>
+            <label class="settings-column">
+                {move || t(Text::MacFocusOff)}
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    prop:value=move || draft.with(|s| s.dnd.mac_shortcut_off.clone())
+                    on:change=move |ev| draft.update(|s| s.dnd.mac_shortcut_off = event_target_value(&ev))
+                />
+            </label>
+        </section>
+    }
+}
+
+#[component]
+fn WindowsSection(draft: RwSignal<Settings>) -> impl IntoView {
+    let plans = RwSignal::new(Vec::<PowerPlan>::new());
+    let (error, set_error) = signal(String::new());
+    // Only shown on Windows
+    let (shown, set_shown) = signal(false);
+    spawn_local(async move {
+        match call::<Vec<PowerPlan>>("power_plans", &()).await {
+            Ok(loaded) => plans.set(loaded),
+            Err(EepyError::UnsupportedPlatform(_)) => return,
+            Err(e) => set_error.set(e.explained()),
+        }
+        set_shown.set(true);
+    });
+
+    view! {
+        <section class="settings-section" class:hidden=move || !shown.get()>
+            <h3>"WINDOWS"</h3>
+            <label class="settings-row">
+                {move || t(Text::PowerPlan)}
+                <select
+                    class="settings-input"
+                    disabled=move || plans.with(Vec::is_empty)
+                    on:change=move |ev| {
+                        let guid = event_target_value(&ev);
+                        draft.update(|s| s.windows.power_plan = (!guid.is_empty()).then_some(guid));
+                    }
+                >
+                    <option value="" selected=move || draft.with(|s| s.windows.power_plan.is_none())>
+                        {move || t(Text::DontChange)}
+                    </option>
+                    {move || plans.get().into_iter().map(|plan| {
+                        let guid = plan.guid.clone();
+                        view! {
+                            <option
+                                value=plan.guid
+                                selected=move || draft.with(|s| s.windows.power_plan.as_ref() == Some(&guid))
+                            >{plan.name}</option>
+                        }
+                    }).collect_view()}
+                </select>
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.windows.focus_assist)
+                    on:change=move |ev| draft.update(|s| s.windows.focus_assist = event_target_checked(&ev))
+                />
+                {move || t(Text::FocusAssist)}
+            </label>
+            <p class="settings-hint">{move || t(Text::WindowsHint)}</p>
+            <p class="settings-error">{move || error.get()}</p>
+        </section>
+    }
+}
+
+#[component]
+fn MilestonesSection(draft: RwSignal<Settings>) -> impl IntoView {
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::Milestones)}</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.notifications.enabled)
+                    on:change=move |ev| draft.update(|s| s.notifications.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::DesktopNotifications)}
+            </label>
+            <label class="settings-column">
+                {move || t(Text::MinutesLeftToAnnounce)}
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    placeholder="30, 10"
+                    prop:value=move || draft.with(|s| {
+                        s.milestones
+                            .minutes_left
+                            .iter()
+                            .map(|m| m.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    })
+                    on:change=move |ev| {
+                        let minutes = parse_list(&event_target_value(&ev))
+                            .iter()
+                            .filter_map(|m| m.parse::<u32>().ok())
+                            .filter(|m| *m > 0)
+                            .collect();
+                        draft.update(|s| s.milestones.minutes_left = minutes);
+                    }
+                />
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.milestones.fade_started)
+                    on:change=move |ev| draft.update(|s| s.milestones.fade_started = event_target_checked(&ev))
+                />
+                {move || t(Text::AnnounceFade)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.milestones.speak)
+                    on:change=move |ev| draft.update(|s| s.milestones.speak = event_target_checked(&ev))
+                />
+                {move || t(Text::SpeakMilestones)}
+            </label>
+            <label class="settings-row">
+                {move || t(Text::WarnBeforeSuspend)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="0"
+                    prop:value=move || draft.with(|s| s.milestones.suspend_warning_secs.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            draft.update(|s| s.milestones.suspend_warning_secs = val);
+                        }
+                    }
+                />
+            </label>
+        </section>
+    }
+}
+
+#[component]
+fn ChimeSection(draft: RwSignal<Settings>) -> impl IntoView {
+    let test = move |_| {
+        let (tone, volume) = draft.with_untracked(|s| (s.chime.tone, s.chime.volume));
+        if let Err(e) = chime::play(tone, volume as f32 / 100.0) {
+            leptos::logging::warn!("Could not play the chime: {:?}", e);
+        }
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::Chime)}</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.chime.enabled)
+                    on:change=move |ev| draft.update(|s| s.chime.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::ChimeBeforeEnd)}
+            </label>
+            <label class="settings-row">
+                {move || t(Text::Sound)}
+                <select
+                    class="settings-input"
+                    on:change=move |ev| {
+                        if let Some(tone) = ChimeTone::parse(&event_target_value(&ev)) {
+                            draft.update(|s| s.chime.tone = tone);
+                        }
+                    }
+                >
+                    {ChimeTone::ALL.into_iter().map(|tone| view! {
+                        <option
+                            value=tone.as_str()
+                            selected=move || draft.with(|s| s.chime.tone == tone)
+                        >{t(chime_label(tone))}</option>
+                    }).collect_view()}
+                </select>
+                <button class="btn btn-load pixel-border" on:click=test>{move || t(Text::Test)}</button>
+            </label>
+            <label class="settings-row">
+                {move || t(Text::VolumePercent)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="1"
+                    max="100"
+                    prop:value=move || draft.with(|s| s.chime.volume.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            draft.update(|s| s.chime.volume = val.clamp(1, 100));
+                        }
+                    }
+                />
+            </label>
+            <label class="settings-row">
+                {move || t(Text::SecondsBeforeEnd)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="5"
+                    prop:value=move || draft.with(|s| s.chime.seconds_before.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            draft.update(|s| s.chime.seconds_before = val.max(5));
+                        }
+                    }
+                />
+            </label>
+        </section>
+    }
+}
+
+#[component]
+fn SoundsSection(draft: RwSignal<Settings>) -> impl IntoView {
+    let test = move |_| {
+        let volume = draft.with_untracked(|s| s.sounds.volume);
+        sfx::play_with_volume(Sfx::Start, volume as f32 / 100.0);
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::SoundsVibration)}</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.sounds.enabled)
+                    on:change=move |ev| draft.update(|s| s.sounds.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::Blips)}
+            </label>
+            <label class="settings-row">
+                {move || t(Text::Volume)}
+                <input
+                    type="range"
+                    class="settings-input"
+                    min="1"
+                    max="100"
+                    disabled=move || draft.with(|s| !s.sounds.enabled)
+                    prop:value=move || draft.with(|s| s.sounds.volume.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            draft.update(|s| s.sounds.volume = val.clamp(1, 100));
+                        }
+                    }
+                />
+                {move || format!("{}%", draft.with(|s| s.sounds.volume))}
+                <button class="btn btn-load pixel-border" on:click=test>{move || t(Text::Test)}</button>
+            </label>
+            <p class="settings-hint">
+                {move || t(Text::SoundsHint)}
+            </p>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.haptics.enabled)
+                    on:change=move |ev| draft.update(|s| s.haptics.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::Vibrate)}
+            </label>
+            <p class="settings-hint">{move || t(Text::PhonesOnly)}</p>
+        </section>
+    }
+}
+
+const WEEKDAYS: [Text; 7] = [
+    Text::DayMo,
+    Text::DayTu,
+    Text::DayWe,
+    Text::DayTh,
+    Text::DayFr,
+    Text::DaySa,
+    Text::DaySu,
+];
+
+#[component]
+fn DisplaySection(draft: RwSignal<Settings>) -> impl IntoView {
+    let skin = RwSignal::new(None::<CustomCss>);
+    let reload_css = move || {
+        spawn_local(async move {
+            skin.set(custom_css::load().await);
+        });
+    };
+    reload_css();
+    let system_dim = RwSignal::new(None::<Option<String>>);
+    spawn_local(async move {
+        if let Ok(backend) = call::<Option<String>>("system_dim_backend", &()).await {
+            system_dim.set(Some(backend));
+        }
+    });
+    let monitors = RwSignal::new(Vec::<String>::new());
+    spawn_local(async move {
+        if let Ok(mut names) = call::<Vec<String>>("monitor_list", &()).await {
+            // Keep a monitor that's unplugged right now selectable
+            if let Some(saved) = draft.with_untracked(|s| s.display.monitor.clone()) {
+                if !names.contains(&saved) {
+                    names.push(saved);
+                }
+            }
+            monitors.set(names);
+        }
+    });
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::Screen)}</h3>
+            <label class="settings-row">
+                {move || t(Text::Language)}
+                <select
+                    class="settings-input"
+                    on:change=move |ev| {
+                        let language = Locale::parse(&event_target_value(&ev));
+                        draft.update(|s| s.display.language = language);
+                    }
+                >
+                    <option value="" selected=move || draft.with(|s| s.display.language.is_none())>
+                        {move || t(Text::FollowSystem)}
+                    </option>
+                    {Locale::ALL.into_iter().map(|locale| view! {
+                        <option
+                            value=locale.as_str()
+                            selected=move || draft.with(|s| s.display.language == Some(locale))
+                        >{locale.label()}</option>
+                    }).collect_view()}
+                </select>
+            </label>
+            <label class="settings-row">
+                {move || t(Text::Theme)}
+                <select
+                    class="settings-input"
+                    on:change=move |ev| {
+                        if let Some(theme) = Theme::parse(&event_target_value(&ev)) {
+                            draft.update(|s| s.display.theme = theme);
+                        }
+                    }
+                >
+                    {Theme::ALL.into_iter().map(|theme| view! {
+                        <option
+                            value=theme.as_str()
+                            selected=move || draft.with(|s| s.display.theme == theme)
+                        >{t(theme_label(theme))}</option>
+                    }).collect_view()}
+                </select>
+            </label>
+            <div class="settings-row">
+                {move || t(Text::Accent)}
+                <input
+                    type="color"
+                    class="settings-color"
+                    prop:value=move || draft.with(|s| {
+                        s.display.accent.clone().unwrap_or_else(|| DEFAULT_ACCENT.to_string())
+                    })
+                    on:input=move |ev| {
+                        let color = event_target_value(&ev);
+                        draft.update(|s| s.display.accent = Some(color));
+                    }
+                />
+                <button
+                    class="btn btn-load pixel-border"
+                    disabled=move || draft.with(|s| s.display.accent.is_none())
+                    on:click=move |_| draft.update(|s| s.display.accent = None)
+                >{move || t(Text::ThemesOwn)}</button>
+            </div>
+            <div class="settings-row">
+                {move || t(Text::CustomCss)}
+                <button class="btn btn-load pixel-border" on:click=move |_| reload_css()>
+                    {move || t(Text::Reload)}
+                </button>
+            </div>
+            <p class="settings-hint">
+                {move || skin.with(|c| match c {
+                    Some(CustomCss { path, css: Some(_) }) => t_with(Text::LoadedFrom, &[("path", path)]),
+                    Some(CustomCss { path, css: None }) => t_with(Text::PutSkinIn, &[("path", path)]),
+                    None => String::new(),
+                })}
+            </p>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.display.scale.is_none())
+                    on:change=move |ev| {
+                        let follow = event_target_checked(&ev);
+                        draft.update(|s| s.display.scale = (!follow).then_some(100));
+                    }
+                />
+                {move || t(Text::ScaleWithSystem)}
+            </label>
+            <label class="settings-row">
+                {move || t(Text::UiScale)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min=MIN_SCALE
+                    max=MAX_SCALE
+                    step="25"
+                    disabled=move || draft.with(|s| s.display.scale.is_none())
+                    prop:value=move || draft.with(|s| s.display.scale.unwrap_or(100).to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            let val = val.clamp(MIN_SCALE, MAX_SCALE);
+                            draft.update(|s| s.display.scale = Some(val));
+                        }
+                    }
+                />
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.display.bedside)
+                    on:change=move |ev| draft.update(|s| s.display.bedside = event_target_checked(&ev))
+                />
+                {move || t(Text::Bedside)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.display.leanback)
+                    on:change=move |ev| draft.update(|s| s.display.leanback = event_target_checked(&ev))
+                />
+                {move || t(Text::Leanback)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.display.system_dim)
+                    on:change=move |ev| draft.update(|s| s.display.system_dim = event_target_checked(&ev))
+                />
+                {move || t(Text::SystemDim)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    disabled=move || draft.with(|s| !s.display.system_dim)
+                    prop:checked=move || draft.with(|s| s.display.night_light)
+                    on:change=move |ev| draft.update(|s| s.display.night_light = event_target_checked(&ev))
+                />
+                {move || t(Text::NightLight)}
+            </label>
+            <p class="settings-hint">
+                {move || match system_dim.get() {
+                    Some(Some(backend)) => t_with(Text::DimThrough, &[("backend", &backend.to_uppercase())]),
+                    Some(None) => t(Text::DimNotPossible).to_string(),
+                    None => String::new(),
+                }}
+            </p>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.display.dim_all_monitors)
+                    on:change=move |ev| draft.update(|s| s.display.dim_all_monitors = event_target_checked(&ev))
+                />
+                {move || t(Text::DimOtherMonitors)}
+            </label>
+            <label class="settings-row">
+                {move || t(Text::TheaterOn)}
+                <select
+                    class="settings-input"
+                    on:change=move |ev| {
+                        let name = event_target_value(&ev);
+                        draft.update(|s| s.display.monitor = (!name.is_empty()).then_some(name));
+                    }
+                >
+                    <option value="" selected=move || draft.with(|s| s.display.monitor.is_none())>
+                        {move || t(Text::SameMonitor)}
+                    </option>
+                    {move || monitors.get().into_iter().map(|name| {
+                        let picked = name.clone();
+                        let value = name.clone();
+                        view! {
+                            <option
+                                value=value
+                                selected=move || draft.with(|s| s.display.monitor.as_ref() == Some(&picked))
+                            >{name}</option>
+                        }
+                    }).collect_view()}
+                </select>
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.display.clock_12h)
+                    on:change=move |ev| draft.update(|s| s.display.clock_12h = event_target_checked(&ev))
+                />
+                {move || t(Text::Clock12h)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.display.zen_clock)
+                    on:change=move |ev| draft.update(|s| s.display.zen_clock = event_target_checked(&ev))
+                />
+                {move || t(Text::ZenClock)}
+            </label>
+            <label class="settings-row">
+                {move || t(Text::ZenDim)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="10"
+                    max="90"
+                    prop:value=move || draft.with(|s| s.display.zen_dim.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            draft.update(|s| s.display.zen_dim = val.clamp(10, 90));
+                        }
+                    }
+                />
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.display.breathing)
+                    on:change=move |ev| draft.update(|s| s.display.breathing = event_target_checked(&ev))
+                />
+                {move || t(Text::BreathingGuide)}
+            </label>
+            <label class="settings-row">
+                {move || t(Text::Pattern)}
+                <select
+                    class="settings-input"
+                    on:change=move |ev| {
+                        if let Some(pattern) = BreathingPattern::parse(&event_target_value(&ev)) {
+                            draft.update(|s| s.display.breathing_pattern = pattern);
+                        }
+                    }
+                >
+                    {BreathingPattern::ALL.into_iter().map(|pattern| view! {
+                        <option
+                            value=pattern.as_str()
+                            selected=move || draft.with(|s| s.display.breathing_pattern == pattern)
+                        >{t(breathing_label(pattern))}</option>
+                    }).collect_view()}
+                </select>
+            </label>
+            <label class="settings-row">
+                {move || t(Text::ForMinutes)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="1"
+                    max="60"
+                    prop:value=move || draft.with(|s| s.display.breathing_minutes.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            draft.update(|s| s.display.breathing_minutes = val.clamp(1, 60));
+                        }
+                    }
+                />
+            </label>
+        </section>
+    }
+}
+
+#[component]
+fn StrictSection(draft: RwSignal<Settings>) -> impl IntoView {
+    let settings = expect_context::<RwSignal<Settings>>();
+    let (pin, set_pin) = signal(String::new());
+    let (pin_status, set_pin_status) = signal(String::new());
+
+    // The PIN is saved right away and on its own; only its hash is kept
+    let save_pin = move |_| {
+        let pin = pin.get_untracked();
+        spawn_local(async move {
+            match call::<Settings>("strict_set_pin", &PinArgs { pin }).await {
+                Ok(saved) => {
+                    draft.update(|s| s.strict.pin_hash = saved.strict.pin_hash.clone());
+                    settings.set(saved);
+                    set_pin.set(String::new());
+                    set_pin_status.set(t(Text::PinSaved).to_string());
+                }
+                Err(e) => set_pin_status.set(e.to_string().to_uppercase()),
+            }
+        });
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::StrictMode)}</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.strict.enabled)
+                    on:change=move |ev| draft.update(|s| s.strict.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::LockSessions)}
+            </label>
+            <label class="settings-row">
+                {move || t(Text::UnlocksAfter)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="1"
+                    max="240"
+                    prop:value=move || draft.with(|s| s.strict.cooldown_minutes.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            draft.update(|s| s.strict.cooldown_minutes = val.clamp(1, 240));
+                        }
+                    }
+                />
+            </label>
+            <div class="settings-row">
+                <input
+                    type="password"
+                    inputmode="numeric"
+                    class="settings-input"
+                    placeholder=move || if draft.with(|s| s.strict.pin_hash.is_empty()) { t(Text::NewPin) } else { t(Text::ChangePin) }
+                    prop:value=move || pin.get()
+                    on:input=move |ev| set_pin.set(event_target_value(&ev))
+                />
+                <button class="btn btn-load pixel-border" on:click=save_pin>{move || t(Text::SetPin)}</button>
+            </div>
+            <p class="settings-hint">
+                {move || {
+                    let status = pin_status.get();
+                    if !status.is_empty() {
+                        status
+                    } else if draft.with(|s| s.strict.pin_hash.is_empty()) {
+                        t(Text::NoPinSet).to_string()
+                    } else {
+                        t(Text::PinIsSet).to_string()
+                    }
+                }}
+            </p>
+        </section>
+    }
+}
+
+#[component]
+fn ParentalSection(draft: RwSignal<Settings>) -> impl IntoView {
+    let settings = expect_context::<RwSignal<Settings>>();
+    let (password, set_password) = signal(String::new());
+    let (password_status, set_password_status) = signal(String::new());
+    // Favorites are what the parent picks the allowed videos from
+    let (favorites, set_favorites) = signal(Vec::<LibraryEntry>::new());
+    spawn_local(async move {
+        if let Ok(entries) = call::<Vec<LibraryEntry>>("library_list", &()).await {
+            set_favorites.set(entries.into_iter().filter(|entry| entry.favorite).collect());
+        }
+    });
+
+    // The password is saved right away and on its own; only its hash is kept
+    let save_password = move |_| {
+        let password = password.get_untracked();
+        spawn_local(async move {
+            match call::<Settings>("parental_set_password", &PasswordArgs { password }).await {
+                Ok(saved) => {
+                    draft.update(|s| {
+                        s.parental.password_hash = saved.parental.password_hash.clone()
+                    });
+                    settings.set(saved);
+                    set_password.set(String::new());
+                    set_password_status.set(t(Text::PasswordSaved).to_string());
+                }
+                Err(e) => set_password_status.set(e.to_string().to_uppercase()),
+            }
+        });
+    };
+
+    let set_allowed = move |video: String, allowed: bool| {
+        draft.update(|s| {
+            s.parental.videos.retain(|v| *v != video);
+            if allowed {
+                s.parental.videos.push(video);
+            }
+        })
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::ParentalProfile)}</h3>
+            <div class="settings-row">
+                <input
+                    type="password"
+                    class="settings-input"
+                    placeholder=move || if draft.with(|s| s.parental.password_hash.is_empty()) { t(Text::ParentPassword) } else { t(Text::ChangePassword) }
+                    prop:value=move || password.get()
+                    on:input=move |ev| set_password.set(event_target_value(&ev))
+                />
+                <button class="btn btn-load pixel-border" on:click=save_password>{move || t(Text::SetPassword)}</button>
+            </div>
+            <p class="settings-hint">{move || password_status.get()}</p>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.parental.enabled)
+                    on:change=move |ev| draft.update(|s| s.parental.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::StartAsChild)}
+            </label>
+            <label class="settings-row">
+                {move || t(Text::SessionMinutes)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="1"
+                    max=move || draft.with(|s| s.timer.max_minutes()).to_string()
+                    prop:value=move || draft.with(|s| s.parental.minutes.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            draft.update(|s| s.parental.minutes = val.clamp(1, s.timer.max_minutes()));
+                        }
+                    }
+                />
+                {move || t(Text::Then)}
+                <select
+                    class="settings-input"
+                    on:change=move |ev| {
+                        if let Some(action) = EndAction::parse(&event_target_value(&ev)) {
+                            draft.update(|s| s.parental.action = action);
+                        }
+                    }
+                >
+                    {EndAction::ALL.into_iter().map(|action| view! {
+                        <option
+                            value=action.as_str()
+                            selected=move || draft.with(|s| s.parental.action == action)
+                        >{t(action_label(action))}</option>
+                    }).collect_view()}
+                </select>
+            </label>
+            <p class="settings-hint">{move || t(Text::AllowedVideos)}</p>
+            <ul class="settings-list">
+                {move || favorites.with(|entries| {
+                    entries.iter().map(|entry| {
+                        let video = entry.video_id.clone();
+                        let checked_id = video.clone();
+                        let name = entry.title.clone().unwrap_or_else(|| video.clone());
+                        view! {
+                            <li class="settings-list-item">
+                                <input
+                                    type="checkbox"
+                                    title=move || t(Text::AllowedTip)
+                                    prop:checked=move || draft.with(|s| s.parental.allows(&checked_id))
+                                    on:change=move |ev| set_allowed(video.clone(), event_target_checked(&ev))
+                                />
+                                <span class="settings-list-name">{name}</span>
+                            </li>
+                        }
+                    }).collect_view()
+                })}
+            </ul>
+        </section>
+    }
+}
+
+#[component]
+fn WidgetSection(draft: RwSignal<Settings>) -> impl IntoView {
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::CountdownWidget)}</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.widget.enabled)
+                    on:change=move |ev| draft.update(|s| s.widget.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::FloatTimeLeft)}
+            </label>
+            <label class="settings-row">
+                {move || t(Text::CornerLabel)}
+                <select
+                    class="settings-input"
+                    on:change=move |ev| {
+                        if let Some(corner) = Corner::parse(&event_target_value(&ev)) {
+                            draft.update(|s| s.widget.corner = corner);
+                        }
+                    }
+                >
+                    {Corner::ALL.into_iter().map(|corner| view! {
+                        <option
+                            value=corner.as_str()
+                            selected=move || draft.with(|s| s.widget.corner == corner)
+                        >{t(corner_label(corner))}</option>
+                    }).collect_view()}
+                </select>
+            </label>
+        </section>
+    }
+}
+
+#[component]
+fn StartupSection(draft: RwSignal<Settings>) -> impl IntoView {
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::StartupBedtime)}</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.autostart.enabled)
+                    on:change=move |ev| draft.update(|s| s.autostart.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::LaunchAtLogin)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.autostart.start_minimized)
+                    on:change=move |ev| draft.update(|s| s.autostart.start_minimized = event_target_checked(&ev))
+                />
+                {move || t(Text::StartInTray)}
+            </label>
+            <label class="settings-row">
+                {move || t(Text::ClosingWhileRunning)}
+                <select
+                    class="settings-input"
+                    on:change=move |ev| {
+                        if let Some(behaviour) = CloseBehaviour::parse(&event_target_value(&ev)) {
+                            draft.update(|s| s.timer.on_close = behaviour);
+                        }
+                    }
+                >
+                    {CloseBehaviour::ALL.into_iter().map(|behaviour| view! {
+                        <option
+                            value=behaviour.as_str()
+                            selected=move || draft.with(|s| s.timer.on_close == behaviour)
+                        >{t(close_label(behaviour))}</option>
+                    }).collect_view()}
+                </select>
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.handoff.auto_start)
+                    on:change=move |ev| draft.update(|s| s.handoff.auto_start = event_target_checked(&ev))
+                />
+                {move || t(Text::AutoStartHandoff)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.clipboard.enabled)
+                    on:change=move |ev| draft.update(|s| s.clipboard.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::OfferClipboard)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.schedule.enabled)
+                    on:change=move |ev| draft.update(|s| s.schedule.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::StartAtBedtime)}
+            </label>
+            <label class="settings-row">
+                {move || t(Text::Bedtime)}
+                <input
+                    type="time"
+                    class="settings-input"
+                    prop:value=move || draft.with(|s| s.schedule.time.clone())
+                    on:change=move |ev| draft.update(|s| s.schedule.time = event_target_value(&ev))
+                />
+            </label>
+            <label class="settings-row">
+                {move || t(Text::DurationMinutes)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="1"
+                    max=move || draft.with(|s| s.timer.max_minutes()).to_string()
+                    prop:value=move || draft.with(|s| s.schedule.minutes.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            draft.update(|s| s.schedule.minutes = val.clamp(1, s.timer.max_minutes()));
+                        }
+                    }
+                />
+            </label>
+            <div class="settings-days">
+                {WEEKDAYS.iter().enumerate().map(|(i, label)| {
+                    let day = i as u32;
+                    view! {
+                        <label class="settings-list-flag">
+                            <input
+                                type="checkbox"
+                                prop:checked=move || draft.with(|s| s.schedule.days.contains(&day))
+                                on:change=move |ev| {
+                                    let checked = event_target_checked(&ev);
+                                    draft.update(|s| {
+                                        s.schedule.days.retain(|d| *d != day);
+                                        if checked {
+                                            s.schedule.days.push(day);
+                                            s.schedule.days.sort_unstable();
+                                        }
+                                    });
+                                }
+                            />
+                            {move || t(*label)}
+                        </label>
+                    }
+                }).collect_view()}
+            </div>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.reminders.enabled)
+                    on:change=move |ev| draft.update(|s| s.reminders.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::RemindBeforeBedtime)}
+            </label>
+            <label class="settings-column">
+                {move || t(Text::MinutesBeforeBedtime)}
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    placeholder="30, 10"
+                    prop:value=move || draft.with(|s| {
+                        s.reminders
+                            .lead_minutes
+                            .iter()
+                            .map(|m| m.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    })
+                    on:change=move |ev| {
+                        let minutes = parse_list(&event_target_value(&ev))
+                            .iter()
+                            .filter_map(|m| m.parse::<u32>().ok())
+                            .collect();
+                        draft.update(|s| s.reminders.lead_minutes = minutes);
+                    }
+                />
+            </label>
+            <p class="settings-hint">
+                {move || t(Text::RemindersHint)}
+            </p>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.streaks.enabled)
+                    on:change=move |ev| draft.update(|s| s.streaks.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::ShowStreak)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.streaks.weekly_summary)
+                    on:change=move |ev| draft.update(|s| s.streaks.weekly_summary = event_target_checked(&ev))
+                />
+                {move || t(Text::WeeklySummary)}
+            </label>
+        </section>
+    }
+}
+
+#[component]
+fn WakeAlarmSection(draft: RwSignal<Settings>) -> impl IntoView {
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::WakeAlarm)}</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.wake.enabled)
+                    on:change=move |ev| draft.update(|s| s.wake.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::WakeWithSunrise)}
+            </label>
+            <label class="settings-row">
+                {move || t(Text::Alarm)}
+                <input
+                    type="time"
+                    class="settings-input"
+                    prop:value=move || draft.with(|s| s.wake.time.clone())
+                    on:change=move |ev| draft.update(|s| s.wake.time = event_target_value(&ev))
+                />
+            </label>
+            <div class="settings-days">
+                {WEEKDAYS.iter().enumerate().map(|(i, label)| {
+                    let day = i as u32;
+                    view! {
+                        <label class="settings-list-flag">
+                            <input
+                                type="checkbox"
+                                prop:checked=move || draft.with(|s| s.wake.days.contains(&day))
+                                on:change=move |ev| {
+                                    let checked = event_target_checked(&ev);
+                                    draft.update(|s| {
+                                        s.wake.days.retain(|d| *d != day);
+                                        if checked {
+                                            s.wake.days.push(day);
+                                            s.wake.days.sort_unstable();
+                                        }
+                                    });
+                                }
+                            />
+                            {move || t(*label)}
+                        </label>
+                    }
+                }).collect_view()}
+            </div>
+            <label class="settings-row">
+                {move || t(Text::SunriseMinutes)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="1"
+                    max="60"
+                    prop:value=move || draft.with(|s| s.wake.sunrise_minutes.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            draft.update(|s| s.wake.sunrise_minutes = val.clamp(1, 60));
+                        }
+                    }
+                />
+            </label>
+            <label class="settings-row">
+                {move || t(Text::SnoozeMinutes)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="1"
+                    max="60"
+                    prop:value=move || draft.with(|s| s.wake.snooze_minutes.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            draft.update(|s| s.wake.snooze_minutes = val.clamp(1, 60));
+                        }
+                    }
+                />
+            </label>
+            <label class="settings-row">
+                {move || t(Text::Sound)}
+                <select
+                    class="settings-input"
+                    on:change=move |ev| {
+                        if let Some(tone) = ChimeTone::parse(&event_target_value(&ev)) {
+                            draft.update(|s| s.wake.tone = tone);
+                        }
+                    }
+                >
+                    {ChimeTone::ALL.into_iter().map(|tone| view! {
+                        <option
+                            value=tone.as_str()
+                            selected=move || draft.with(|s| s.wake.tone == tone)
+                        >{t(chime_label(tone))}</option>
+                    }).collect_view()}
+                </select>
+            </label>
+            <label class="settings-row">
+                {move || t(Text::VolumePercent)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="1"
+                    max="100"
+                    prop:value=move || draft.with(|s| s.wake.volume.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                            draft.update(|s| s.wake.volume = val.clamp(1, 100));
+                        }
+                    }
+                />
+            </label>
+        </section>
+    }
+}
+
+#[component]
+fn MascotSection(draft: RwSignal<Settings>) -> impl IntoView {
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::MascotMessages)}</h3>
+            <label class="settings-row">
+                {move || t(Text::MascotLabel)}
+                <select
+                    class="settings-input"
+                    on:change=move |ev| {
+                        if let Some(mascot) = Mascot::parse(&event_target_value(&ev)) {
+                            draft.update(|s| s.mascot.mascot = mascot);
+                        }
+                    }
+                >
+                    {Mascot::ALL.into_iter().map(|mascot| view! {
+                        <option
+                            value=mascot.as_str()
+                            selected=move || draft.with(|s| s.mascot.mascot == mascot)
+                        >{t(mascot_label(mascot))}</option>
+                    }).collect_view()}
+                </select>
+            </label>
+            {move || draft.with(|s| s.mascot.mascot == Mascot::Custom).then(|| view! {
+                <label class="settings-column">
+                    {move || t(Text::ImageFile)}
+                    <input
+                        type="text"
+                        class="settings-input wide"
+                        placeholder="mascot.png"
+                        prop:value=move || draft.with(|s| s.mascot.sprite_path.clone())
+                        on:change=move |ev| draft.update(|s| s.mascot.sprite_path = event_target_value(&ev))
+                    />
+                </label>
+                <p class="settings-hint">
+                    {move || t(Text::ImageFileHint)}
+                </p>
+            })}
+            <p class="settings-hint">{move || t(Text::MessagesHint)}</p>
+            {StatusMessage::ALL.into_iter().map(|message| view! {
+                <label class="settings-column">
+                    {t(message_label(message))}
+                    <input
+                        type="text"
+                        class="settings-input wide"
+                        placeholder=move || t(status_text(message))
+                        prop:value=move || draft.with(|s| {
+                            s.mascot.messages.get(message.as_str()).cloned().unwrap_or_default()
+                        })
+                        on:change=move |ev| {
+                            let text = event_target_value(&ev);
+                            draft.update(|s| {
+                                if text.trim().is_empty() {
+                                    s.mascot.messages.remove(message.as_str());
+                                } else {
+                                    s.mascot.messages.insert(message.as_str().to_string(), text);
+                                }
+                            });
+                        }
+                    />
+                </label>
+            }).collect_view()}
+        </section>
+    }
+}
+
+#[component]
+fn RemoteSection(draft: RwSignal<Settings>) -> impl IntoView {
+    let settings = expect_context::<RwSignal<Settings>>();
+    let (pairing, set_pairing) = signal(Option::<Result<Pairing, EepyError>>::None);
+
+    // Pairing uses the saved settings, since that's what the server runs with
+    let pair = move |_| {
+        spawn_local(async move {
+            set_pairing.set(Some(call::<Pairing>("remote_pairing", &()).await));
+        });
+    };
+
+    let example_url = move || {
+        draft.with(|s| {
+            let host = if s.remote.bind_lan {
+                t(Text::ThisComputer)
+            } else {
+                "127.0.0.1"
+            };
+            format!("http://{}:{}/api/status", host, s.remote.port)
+        })
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::RemoteApi)}</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.remote.enabled)
+                    on:change=move |ev| draft.update(|s| s.remote.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::EnableLocalApi)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.remote.bind_lan)
+                    on:change=move |ev| draft.update(|s| s.remote.bind_lan = event_target_checked(&ev))
+                />
+                {move || t(Text::AllowLan)}
+            </label>
+            <label class="settings-row">
+                {move || t(Text::Port)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="1024"
+                    max="65535"
+                    prop:value=move || draft.with(|s| s.remote.port.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u16>() {
+                            draft.update(|s| s.remote.port = val);
+                        }
+                    }
+                />
+            </label>
+            <div class="settings-row">
+                <span class="settings-token">
+                    {move || draft.with(|s| {
+                        if s.remote.token.is_empty() {
+                            t(Text::TokenOnSave).to_string()
+                        } else {
+                            t_with(Text::Token, &[("token", &s.remote.token)])
+                        }
+                    })}
+                </span>
+                <button
+                    class="btn btn-load pixel-border"
+                    title=move || t(Text::NewTokenTip)
+                    on:click=move |_| draft.update(|s| s.remote.token.clear())
+                >{move || t(Text::NewToken)}</button>
+            </div>
+            <p class="settings-hint">{example_url}</p>
+            <button
+                class="btn btn-load pixel-border"
+                disabled=move || !settings.with(|s| s.remote.enabled)
+                on:click=pair
+            >{move || t(Text::PairPhone)}</button>
+            {move || pairing.get().map(|result| match result {
+                Ok(pairing) => view! {
+                    <div class="pairing">
+                        <div class="pairing-qr" inner_html=pairing.qr_svg></div>
+                        <p class="settings-hint">{pairing.url}</p>
+                    </div>
+                }.into_any(),
+                Err(e) => view! { <p class="settings-error">{e.explained()}</p> }.into_any(),
+            })}
+        </section>
+    }
+}
+
+#[component]
+fn WebhooksSection(draft: RwSignal<Settings>) -> impl IntoView {
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::Webhooks)}</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.webhooks.enabled)
+                    on:change=move |ev| draft.update(|s| s.webhooks.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::PostEvents)}
+            </label>
+            <label class="settings-column">
+                {move || t(Text::UrlsCommaSeparated)}
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    placeholder="http://homeassistant.local:8123/api/webhook/eepy"
+                    prop:value=move || draft.with(|s| s.webhooks.urls.join(", "))
+                    on:change=move |ev| {
+                        let urls = parse_list(&event_target_value(&ev));
+                        draft.update(|s| s.webhooks.urls = urls);
+                    }
+                />
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.webhooks.on_start)
+                    on:change=move |ev| draft.update(|s| s.webhooks.on_start = event_target_checked(&ev))
+                />
+                {move || t(Text::OnStart)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.webhooks.on_milestone)
+                    on:change=move |ev| draft.update(|s| s.webhooks.on_milestone = event_target_checked(&ev))
+                />
+                {move || t(Text::OnEachMilestone)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.webhooks.on_suspend)
+                    on:change=move |ev| draft.update(|s| s.webhooks.on_suspend = event_target_checked(&ev))
+                />
+                {move || t(Text::BeforeSuspend)}
+            </label>
+            <p class="settings-hint">
+                {move || t(Text::WebhooksHint)}
+            </p>
+        </section>
+    }
+}
+
+#[component]
+fn PluginsSection(draft: RwSignal<Settings>) -> impl IntoView {
+    let (plugins, set_plugins) = signal(PluginList::default());
+    spawn_local(async move {
+        if let Ok(loaded) = call::<PluginList>("plugin_list", &()).await {
+            set_plugins.set(loaded);
+        }
+    });
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::Plugins)}</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.plugins.enabled)
+                    on:change=move |ev| draft.update(|s| s.plugins.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::RunRhai)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.plugins.allow_commands)
+                    on:change=move |ev| draft.update(|s| s.plugins.allow_commands = event_target_checked(&ev))
+                />
+                {move || t(Text::LetScriptsRun)}
+            </label>
+            <p class="settings-hint">{move || plugins.with(|p| p.dir.clone())}</p>
+            <p class="settings-hint">
+                {move || plugins.with(|p| {
+                    if p.names.is_empty() {
+                        t(Text::NoScripts).to_string()
+                    } else {
+                        p.names.join(", ")
+                    }
+                })}
+            </p>
+        </section>
+    }
+}
+
+#[component]
+fn MqttSection(draft: RwSignal<Settings>) -> impl IntoView {
+    view! {
+        <section class="settings-section">
+            <h3>"MQTT"</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.mqtt.enabled)
+                    on:change=move |ev| draft.update(|s| s.mqtt.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::PublishMqtt)}
+            </label>
+            <label class="settings-column">
+                {move || t(Text::BrokerHost)}
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    placeholder="localhost"
+                    prop:value=move || draft.with(|s| s.mqtt.host.clone())
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev).trim().to_string();
+                        draft.update(|s| s.mqtt.host = value);
+                    }
+                />
+            </label>
+            <label class="settings-row">
+                {move || t(Text::Port)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="1"
+                    max="65535"
+                    prop:value=move || draft.with(|s| s.mqtt.port.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u16>() {
+                            draft.update(|s| s.mqtt.port = val);
+                        }
+                    }
+                />
+            </label>
+            <label class="settings-column">
+                {move || t(Text::Username)}
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    placeholder=move || t(Text::Optional)
+                    prop:value=move || draft.with(|s| s.mqtt.username.clone())
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev).trim().to_string();
+                        draft.update(|s| s.mqtt.username = value);
+                    }
+                />
+            </label>
+            <label class="settings-column">
+                {move || t(Text::Password)}
+                <input
+                    type="password"
+                    class="settings-input wide"
+                    placeholder=move || t(Text::Optional)
+                    prop:value=move || draft.with(|s| s.mqtt.password.clone())
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev).trim().to_string();
+                        draft.update(|s| s.mqtt.password = value);
+                    }
+                />
+            </label>
+            <label class="settings-column">
+                {move || t(Text::ClientId)}
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    placeholder="eepy"
+                    prop:value=move || draft.with(|s| s.mqtt.client_id.clone())
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev).trim().to_string();
+                        draft.update(|s| s.mqtt.client_id = value);
+                    }
+                />
+            </label>
+            <label class="settings-column">
+                {move || t(Text::StateTopic)}
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    placeholder="eepy/state"
+                    prop:value=move || draft.with(|s| s.mqtt.state_topic.clone())
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev).trim().to_string();
+                        draft.update(|s| s.mqtt.state_topic = value);
+                    }
+                />
+            </label>
+            <label class="settings-column">
+                {move || t(Text::RemainingTopic)}
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    placeholder="eepy/remaining"
+                    prop:value=move || draft.with(|s| s.mqtt.remaining_topic.clone())
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev).trim().to_string();
+                        draft.update(|s| s.mqtt.remaining_topic = value);
+                    }
+                />
+            </label>
+            <label class="settings-column">
+                {move || t(Text::EventsTopic)}
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    placeholder="eepy/events"
+                    prop:value=move || draft.with(|s| s.mqtt.event_topic.clone())
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev).trim().to_string();
+                        draft.update(|s| s.mqtt.event_topic = value);
+                    }
+                />
+            </label>
+            <p class="settings-hint">
+                {move || t(Text::MqttHint)}
+            </p>
+        </section>
+    }
+}
+
+#[component]
+fn LightsSection(draft: RwSignal<Settings>) -> impl IntoView {
+    let (message, set_message) = signal(String::new());
+    let (hue_found, set_hue_found) = signal(Vec::<FoundLight>::new());
+    let (lifx_found, set_lifx_found) = signal(Vec::<FoundLight>::new());
+
+    let pair_hue = move |_| {
+        let bridge = draft.with_untracked(|s| s.lights.hue.bridge_ip.clone());
+        set_message.set(t(Text::PressLinkButton).to_string());
+        spawn_local(async move {
+            match call::<String>("hue_pair", &HuePairArgs { bridge }).await {
+                Ok(username) => {
+                    draft.update(|s| s.lights.hue.username = username);
+                    set_message.set(t(Text::PairedHue).to_string());
+                }
+                Err(e) => set_message.set(e.explained()),
+            }
+        });
+    };
+
+    let find_hue = move |_| {
+        let hue = draft.with_untracked(|s| s.lights.hue.clone());
+        spawn_local(async move {
+            match call::<Vec<FoundLight>>("hue_lights", &HueLightsArgs { hue }).await {
+                Ok(found) => set_hue_found.set(found),
+                Err(e) => set_message.set(e.explained()),
+            }
+        });
+    };
+
+    let find_lifx = move |_| {
+        set_message.set(t(Text::SearchingLifx).to_string());
+        spawn_local(async move {
+            match call::<Vec<FoundLight>>("lifx_discover", &()).await {
+                Ok(found) => {
+                    set_message.set(t_with(
+                        Text::FoundLifx,
+                        &[("count", &found.len().to_string())],
+                    ));
+                    set_lifx_found.set(found);
+                }
+                Err(e) => set_message.set(e.explained()),
+            }
+        });
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::SmartLights)}</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.lights.enabled)
+                    on:change=move |ev| draft.update(|s| s.lights.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::FadeLights)}
+            </label>
+            <label class="settings-row">
+                {move || t(Text::StartBrightness)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="1"
+                    max="100"
+                    prop:value=move || draft.with(|s| s.lights.start_brightness.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u8>() {
+                            draft.update(|s| s.lights.start_brightness = val.clamp(1, 100));
+                        }
+                    }
+                />
+            </label>
+            <label class="settings-row">
+                {move || t(Text::WarmthFrom)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="1500"
+                    max="6500"
+                    prop:value=move || draft.with(|s| s.lights.start_kelvin.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u16>() {
+                            draft.update(|s| s.lights.start_kelvin = val);
+                        }
+                    }
+                />
+                {move || t(Text::To)}
+                <input
+                    type="number"
+                    class="settings-input"
+                    min="1500"
+                    max="6500"
+                    prop:value=move || draft.with(|s| s.lights.end_kelvin.to_string())
+                    on:input=move |ev| {
+                        if let Ok(val) = event_target_value(&ev).parse::<u16>() {
+                            draft.update(|s| s.lights.end_kelvin = val);
+                        }
+                    }
+                />
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.lights.off_at_end)
+                    on:change=move |ev| draft.update(|s| s.lights.off_at_end = event_target_checked(&ev))
+                />
+                {move || t(Text::OffAtEnd)}
+            </label>
+            <div class="settings-row">
+                <input
+                    type="text"
+                    class="settings-input"
+                    placeholder=move || t(Text::HueBridgeIp)
+                    prop:value=move || draft.with(|s| s.lights.hue.bridge_ip.clone())
+                    on:change=move |ev| {
+                        let ip = event_target_value(&ev).trim().to_string();
+                        draft.update(|s| s.lights.hue.bridge_ip = ip);
+                    }
+                />
+                <button class="btn btn-load pixel-border" on:click=pair_hue>{move || t(Text::Pair)}</button>
+                <button
+                    class="btn btn-load pixel-border"
+                    disabled=move || draft.with(|s| s.lights.hue.username.is_empty())
+                    on:click=find_hue
+                >{move || t(Text::FindHue)}</button>
+                <button class="btn btn-load pixel-border" on:click=find_lifx>{move || t(Text::FindLifx)}</button>
+            </div>
+            <p class="settings-hint">{move || message.get()}</p>
+            <FoundLights
+                draft=draft
+                found=hue_found
+                selected=|s| &s.lights.hue.lights
+                selected_mut=|s| &mut s.lights.hue.lights
+            />
+            <FoundLights
+                draft=draft
+                found=lifx_found
+                selected=|s| &s.lights.lifx.lights
+                selected_mut=|s| &mut s.lights.lifx.lights
+            />
+        </section>
+    }
+}
+
+/// Checkboxes for lights found on the network, plus any already selected
+/// ones that didn't answer this time
+#[component]
+fn FoundLights(
+    draft: RwSignal<Settings>,
+    found: ReadSignal<Vec<FoundLight>>,
+    selected: fn(&Settings) -> &Vec<String>,
+    selected_mut: fn(&mut Settings) -> &mut Vec<String>,
+) -> impl IntoView {
+    let lights = move || {
+        let mut lights = found.get();
+        let saved = draft.with(|s| selected(s).clone());
+        for id in saved {
+            if !lights.iter().any(|light| light.id == id) {
+                lights.push(FoundLight {
+                    name: id.clone(),
+                    id,
+                });
+            }
+        }
+        lights
+    };
+
+    view! {
+        <ul class="settings-list">
+            {move || lights().into_iter().map(|light| {
+                let id = light.id.clone();
+                let checked_id = light.id.clone();
+                view! {
+                    <li class="settings-list-item">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || draft.with(|s| selected(s).contains(&checked_id))
+                            on:change=move |ev| {
+                                let checked = event_target_checked(&ev);
+                                draft.update(|s| {
+                                    let list = selected_mut(s);
+                                    list.retain(|l| *l != id);
+                                    if checked {
+                                        list.push(id.clone());
+                                    }
+                                });
+                            }
+                        />
+                        <span class="settings-list-name">{light.name}</span>
+                    </li>
+                }
+            }).collect_view()}
+        </ul>
+    }
+}
+
+#[component]
+fn DiscordSection(draft: RwSignal<Settings>) -> impl IntoView {
+    view! {
+        <section class="settings-section">
+            <h3>"DISCORD"</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.discord.enabled)
+                    on:change=move |ev| draft.update(|s| s.discord.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::RichPresence)}
+            </label>
+            <label class="settings-column">
+                {move || t(Text::ApplicationId)}
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    placeholder=move || t(Text::DiscordPlaceholder)
+                    prop:value=move || draft.with(|s| s.discord.application_id.clone())
+                    on:change=move |ev| {
+                        let id = event_target_value(&ev).trim().to_string();
+                        draft.update(|s| s.discord.application_id = id);
+                    }
+                />
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.discord.show_title)
+                    on:change=move |ev| draft.update(|s| s.discord.show_title = event_target_checked(&ev))
+                />
+                {move || t(Text::ShareTitle)}
+            </label>
+        </section>
+    }
+}
+
+#[component]
+fn MediaServersSection(draft: RwSignal<Settings>) -> impl IntoView {
+    let new_server = RwSignal::new(MediaServer::default());
+
+    let add_server = move |_| {
+        let server = new_server.get_untracked();
+        if server.url.trim().is_empty() {
+            return;
+        }
+        draft.update(|s| s.media_servers.servers.push(server));
+        new_server.set(MediaServer::default());
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::MediaServers)}</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.media_servers.enabled)
+                    on:change=move |ev| draft.update(|s| s.media_servers.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::PausePlaybackAtEnd)}
+            </label>
+            <ul class="settings-list">
+                {move || draft.with(|s| {
+                    s.media_servers.servers.iter().enumerate().map(|(i, server)| {
+                        let name = format!("{} {}", server.kind.label(), server.url);
+                        let enabled = server.enabled;
+                        view! {
+                            <li class="settings-list-item">
+                                <input
+                                    type="checkbox"
+                                    title=move || t(Text::EnabledTip)
+                                    prop:checked=enabled
+                                    on:change=move |ev| draft.update(|s| {
+                                        if let Some(server) = s.media_servers.servers.get_mut(i) {
+                                            server.enabled = event_target_checked(&ev);
+                                        }
+                                    })
+                                />
+                                <span class="settings-list-name">{name}</span>
+                                <button
+                                    class="settings-remove"
+                                    title=move || t(Text::RemoveTip)
+                                    aria-label=move || t(Text::RemoveTip)
+                                    on:click=move |_| draft.update(|s| {
+                                        s.media_servers.servers.remove(i);
+                                    })
+                                >"✕"</button>
+                            </li>
+                        }
+                    }).collect_view()
+                })}
+            </ul>
+            <div class="settings-row">
+                <select
+                    class="settings-input"
+                    on:change=move |ev| {
+                        if let Some(kind) = MediaServerKind::parse(&event_target_value(&ev)) {
+                            new_server.update(|s| s.kind = kind);
+                        }
+                    }
+                >
+                    {MediaServerKind::ALL.into_iter().map(|kind| view! {
+                        <option
+                            value=kind.as_str()
+                            selected=move || new_server.with(|s| s.kind == kind)
+                        >{kind.label()}</option>
+                    }).collect_view()}
+                </select>
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    placeholder="http://192.168.1.20:32400"
+                    prop:value=move || new_server.with(|s| s.url.clone())
+                    on:input=move |ev| new_server.update(|s| s.url = event_target_value(&ev))
+                />
+            </div>
+            <div class="settings-row">
+                <input
+                    type="password"
+                    class="settings-input wide"
+                    placeholder=move || t(Text::ServerTokenPlaceholder)
+                    prop:value=move || new_server.with(|s| s.token.clone())
+                    on:input=move |ev| new_server.update(|s| s.token = event_target_value(&ev))
+                />
+                <button class="btn btn-load pixel-border" on:click=add_server>{move || t(Text::Add)}</button>
+            </div>
+            <p class="settings-hint">
+                {move || t(Text::KodiHint)}
+            </p>
+        </section>
+    }
+}
+
+#[component]
+fn SpotifySection(draft: RwSignal<Settings>) -> impl IntoView {
+    let (connected, set_connected) = signal(false);
+    let (message, set_message) = signal(String::new());
+
+    spawn_local(async move {
+        if let Ok(is_connected) = call::<bool>("spotify_connected", &()).await {
+            set_connected.set(is_connected);
+        }
+    });
+
+    let connect = move |_| {
+        let client = draft.with_untracked(|s| s.spotify.client_id.clone());
+        set_message.set(t(Text::FinishLogin).to_string());
+        spawn_local(async move {
+            match call::<()>("spotify_connect", &SpotifyConnectArgs { client }).await {
+                Ok(()) => {
+                    set_connected.set(true);
+                    set_message.set(String::new());
+                }
+                Err(e) => set_message.set(e.explained()),
+            }
+        });
+    };
+
+    let disconnect = move |_| {
+        spawn_local(async move {
+            match call::<()>("spotify_disconnect", &()).await {
+                Ok(()) => set_connected.set(false),
+                Err(e) => set_message.set(e.explained()),
+            }
+        });
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>"SPOTIFY"</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.spotify.enabled)
+                    on:change=move |ev| draft.update(|s| s.spotify.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::PauseSpotify)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.spotify.fade)
+                    on:change=move |ev| draft.update(|s| s.spotify.fade = event_target_checked(&ev))
+                />
+                {move || t(Text::FadeWithVideo)}
+            </label>
+            <label class="settings-column">
+                {move || t(Text::ClientId)}
+                <input
+                    type="text"
+                    class="settings-input wide"
+                    placeholder=move || t(Text::SpotifyPlaceholder)
+                    prop:value=move || draft.with(|s| s.spotify.client_id.clone())
+                    on:change=move |ev| {
+                        let id = event_target_value(&ev).trim().to_string();
+                        draft.update(|s| s.spotify.client_id = id);
+                    }
+                />
+            </label>
+            <div class="settings-row">
+                <span class="settings-token">
+                    {move || if connected.get() { t(Text::Connected) } else { t(Text::NotConnected) }}
+                </span>
+                {move || if connected.get() {
+                    view! {
+                        <button class="btn btn-danger pixel-border" on:click=disconnect>{move || t(Text::Disconnect)}</button>
+                    }.into_any()
+                } else {
+                    view! {
+                        <button class="btn btn-load pixel-border" on:click=connect>{move || t(Text::Connect)}</button>
+                    }.into_any()
+                }}
+            </div>
+            <p class="settings-hint">{move || message.get()}</p>
+            <p class="settings-hint">{move || t(Text::RedirectUri)}</p>
+        </section>
+    }
+}
+
+#[component]
+fn SonosSection(draft: RwSignal<Settings>) -> impl IntoView {
+    let (found, set_found) = signal(Vec::<SonosGroup>::new());
+    let (message, set_message) = signal(String::new());
+
+    let discover = move |_| {
+        set_message.set(t(Text::SearchingSonos).to_string());
+        spawn_local(async move {
+            match call::<Vec<SonosGroup>>("sonos_discover", &()).await {
+                Ok(groups) => {
+                    set_message.set(t_with(
+                        Text::FoundGroups,
+                        &[("count", &groups.len().to_string())],
+                    ));
+                    set_found.set(groups);
+                }
+                Err(e) => set_message.set(e.explained()),
+            }
+        });
+    };
+
+    // Found groups plus saved ones that didn't answer this time
+    let groups = move || {
+        let mut groups = found.get();
+        for saved in draft.with(|s| s.sonos.groups.clone()) {
+            if !groups.iter().any(|group| group.id == saved.id) {
+                groups.push(saved);
+            }
+        }
+        groups
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>"SONOS"</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.sonos.enabled)
+                    on:change=move |ev| draft.update(|s| s.sonos.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::FadeSonos)}
+            </label>
+            <div class="settings-row">
+                <button class="btn btn-load pixel-border" on:click=discover>{move || t(Text::FindSonos)}</button>
+                <span class="settings-hint">{move || message.get()}</span>
+            </div>
+            <ul class="settings-list">
+                {move || groups().into_iter().map(|group| {
+                    let id = group.id.clone();
+                    let name = group.name.clone();
+                    view! {
+                        <li class="settings-list-item">
+                            <input
+                                type="checkbox"
+                                prop:checked=move || draft.with(|s| s.sonos.groups.iter().any(|g| g.id == id))
+                                on:change=move |ev| {
+                                    let checked = event_target_checked(&ev);
+                                    let group = group.clone();
+                                    draft.update(|s| {
+                                        s.sonos.groups.retain(|g| g.id != group.id);
+                                        if checked {
+                                            s.sonos.groups.push(group);
+                                        }
+                                    });
+                                }
+                            />
+                            <span class="settings-list-name">{name}</span>
+                        </li>
+                    }
+                }).collect_view()}
+            </ul>
+        </section>
+    }
+}
+
+#[component]
+fn PushSection(draft: RwSignal<Settings>) -> impl IntoView {
+    let (message, set_message) = signal(String::new());
+    let service = move || draft.with(|s| s.push.service);
+
+    let test = move |_| {
+        let push = draft.with_untracked(|s| s.push.clone());
+        set_message.set(t(Text::Sending).to_string());
+        spawn_local(async move {
+            match call::<()>("push_test", &PushTestArgs { push }).await {
+                Ok(()) => set_message.set(t(Text::Sent).to_string()),
+                Err(e) => set_message.set(e.explained()),
+            }
+        });
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::PushNotifications)}</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.push.enabled)
+                    on:change=move |ev| draft.update(|s| s.push.enabled = event_target_checked(&ev))
+                />
+                {move || t(Text::SendToPhone)}
+            </label>
+            <label class="settings-row">
+                {move || t(Text::Service)}
+                <select
+                    class="settings-input"
+                    on:change=move |ev| {
+                        if let Some(service) = PushService::parse(&event_target_value(&ev)) {
+                            draft.update(|s| s.push.service = service);
+                        }
+                    }
+                >
+                    {PushService::ALL.into_iter().map(|option| view! {
+                        <option
+                            value=option.as_str()
+                            selected=move || service() == option
+                        >{option.label()}</option>
+                    }).collect_view()}
+                </select>
+            </label>
+            {move || (service() != PushService::Telegram).then(|| view! {
+                <label class="settings-column">
+                    {move || t(Text::Server)}
+                    <input
+                        type="text"
+                        class="settings-input wide"
+                        placeholder="https://ntfy.sh"
+                        prop:value=move || draft.with(|s| s.push.server.clone())
+                        on:change=move |ev| {
+                            let server = event_target_value(&ev).trim().to_string();
+                            draft.update(|s| s.push.server = server);
+                        }
+                    />
+                </label>
+            })}
+            {move || (service() != PushService::Gotify).then(|| view! {
+                <label class="settings-column">
+                    {move || if service() == PushService::Ntfy { t(Text::Topic) } else { t(Text::ChatId) }}
+                    <input
+                        type="text"
+                        class="settings-input wide"
+                        prop:value=move || draft.with(|s| s.push.target.clone())
+                        on:change=move |ev| {
+                            let target = event_target_value(&ev).trim().to_string();
+                            draft.update(|s| s.push.target = target);
+                        }
+                    />
+                </label>
+            })}
+            {move || (service() != PushService::Ntfy).then(|| view! {
+                <label class="settings-column">
+                    {move || if service() == PushService::Gotify { t(Text::AppToken) } else { t(Text::BotToken) }}
+                    <input
+                        type="password"
+                        class="settings-input wide"
+                        prop:value=move || draft.with(|s| s.push.token.clone())
+                        on:change=move |ev| {
+                            let token = event_target_value(&ev).trim().to_string();
+                            draft.update(|s| s.push.token = token);
+                        }
+                    />
+                </label>
+            })}
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.push.on_start)
+                    on:change=move |ev| draft.update(|s| s.push.on_start = event_target_checked(&ev))
+                />
+                {move || t(Text::WhenSessionStarts)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.push.on_end)
+                    on:change=move |ev| draft.update(|s| s.push.on_end = event_target_checked(&ev))
+                />
+                {move || t(Text::WhenComputerSleeps)}
+            </label>
+            <div class="settings-row">
+                <button class="btn btn-load pixel-border" on:click=test>{move || t(Text::SendTest)}</button>
+                <span class="settings-hint">{move || message.get()}</span>
+            </div>
+        </section>
+    }
+}
+
+/// Sessions shown in settings; the export has all of them
+const HISTORY_SHOWN: usize = 5;
+
+/// Date and time a session started, as in "10/14/2026 23:42"
+fn started_at(record: &SessionRecord, twelve_hour: bool) -> String {
+    let at = js_sys::Date::new(&JsValue::from_f64(record.started_at as f64));
+    let date: String = at
+        .to_locale_date_string("default", &JsValue::UNDEFINED)
+        .into();
+    format!(
+        "{} {}",
+        date,
+        format_clock(at.get_hours(), at.get_minutes(), twelve_hour)
+    )
+}
+
+#[component]
+fn HistorySection() -> impl IntoView {
+    let settings = expect_context::<RwSignal<Settings>>();
+    let (records, set_records) = signal(Vec::<SessionRecord>::new());
+    let (message, set_message) = signal(String::new());
+    spawn_local(async move {
+        if let Ok(loaded) = call::<Vec<SessionRecord>>("history_list", &()).await {
+            set_records.set(loaded);
+        }
+    });
+
+    let export = move |format: HistoryFormat| {
+        spawn_local(async move {
+            match call::<Option<String>>("history_export", &HistoryExportArgs { format }).await {
+                Ok(Some(path)) => set_message.set(t_with(Text::SavedTo, &[("path", &path)])),
+                Ok(None) => {}
+                Err(e) => set_message.set(e.explained()),
+            }
+        });
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::History)}</h3>
+            <p class="settings-hint">
+                {move || records.with(|r| t_with(Text::SessionsRecorded, &[("count", &r.len().to_string())]))}
+            </p>
+            <ul class="settings-list">
+                {move || {
+                    let twelve_hour = settings.with(|s| s.display.clock_12h);
+                    records.with(|r| {
+                        r.iter().rev().take(HISTORY_SHOWN).map(|record| {
+                            let line = format!(
+                                "{} · {} {}{}",
+                                started_at(record, twelve_hour),
+                                record.planned_seconds / 60,
+                                t(Text::Minutes),
+                                if record.outcome == SessionOutcome::Cancelled {
+                                    t(Text::HistoryCancelled)
+                                } else {
+                                    ""
+                                },
+                            );
+                            view! {
+                                <li class="settings-list-item">
+                                    <span class="settings-list-name">{line}</span>
+                                </li>
+                            }
+                        }).collect_view()
+                    })
+                }}
+            </ul>
+            <div class="settings-row">
+                <button
+                    class="btn btn-load pixel-border"
+                    on:click=move |_| export(HistoryFormat::Csv)
+                >{move || t(Text::ExportCsv)}</button>
+                <button
+                    class="btn btn-load pixel-border"
+                    on:click=move |_| export(HistoryFormat::Json)
+                >{move || t(Text::ExportJson)}</button>
+            </div>
+            <div class="settings-row">
+                <button
+                    class="btn btn-load pixel-border"
+                    on:click=move |_| export(HistoryFormat::SleepCsv)
+                >{move || t(Text::SleepTrackerCsv)}</button>
+                <button
+                    class="btn btn-load pixel-border"
+                    on:click=move |_| export(HistoryFormat::OpenMHealth)
+                >"OPEN MHEALTH"</button>
+            </div>
+            <p class="settings-hint">
+                {move || t(Text::HealthHint)}
+            </p>
+            <p class="settings-hint">{move || message.get()}</p>
+        </section>
+    }
+}
+
+/// Sessions with notes shown on the journal timeline
+const JOURNAL_SHOWN: usize = 14;
+
+#[component]
+fn JournalSection(draft: RwSignal<Settings>) -> impl IntoView {
+    let (records, set_records) = signal(Vec::<SessionRecord>::new());
+    spawn_local(async move {
+        if let Ok(loaded) = call::<Vec<SessionRecord>>("history_list", &()).await {
+            set_records.set(loaded);
+        }
+    });
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::Journal)}</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.journal.at_start)
+                    on:change=move |ev| draft.update(|s| s.journal.at_start = event_target_checked(&ev))
+                />
+                {move || t(Text::AskFeeling)}
+            </label>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || draft.with(|s| s.journal.on_wake)
+                    on:change=move |ev| draft.update(|s| s.journal.on_wake = event_target_checked(&ev))
+                />
+                {move || t(Text::AskSlept)}
+            </label>
+            <p class="settings-hint">
+                {move || t(Text::JournalHint)}
+            </p>
+            <ol class="journal-timeline">
+                {move || {
+                    let twelve_hour = draft.with(|s| s.display.clock_12h);
+                    records.with(|r| {
+                        r.iter()
+                            .rev()
+                            .filter(|record| {
+                                record.bedtime_note.is_some()
+                                    || record.wake_note.as_deref().is_some_and(|note| !note.is_empty())
+                            })
+                            .take(JOURNAL_SHOWN)
+                            .map(|record| {
+                                let bedtime = record.bedtime_note.clone();
+                                let wake = record.wake_note.clone().filter(|note| !note.is_empty());
+                                view! {
+                                    <li class="journal-entry">
+                                        <span class="journal-when">{started_at(record, twelve_hour)}</span>
+                                        {bedtime.map(|note| view! { <span class="journal-note">"🌙 " {note}</span> })}
+                                        {wake.map(|note| view! { <span class="journal-note">"☀ " {note}</span> })}
+                                    </li>
+                                }
+                            })
+                            .collect_view()
+                    })
+                }}
+            </ol>
+        </section>
+    }
+}
+
+#[component]
+fn BackupSection() -> impl IntoView {
+    let (message, set_message) = signal(String::new());
+
+    let export = move |_| {
+        spawn_local(async move {
+            match call::<Option<String>>("settings_export", &()).await {
+                Ok(Some(path)) => set_message.set(t_with(Text::SavedTo, &[("path", &path)])),
+                Ok(None) => {}
+                Err(e) => set_message.set(e.explained()),
+            }
+        });
+    };
+
+    // Export waiting on a yes, as it would remove profiles
+    let (pending, set_pending) = signal(Option::<ImportPreview>::None);
+
+    // Profiles and libraries change along with the settings, so start over
+    // from what the backend has now
+    let apply = move |path: String| {
+        set_pending.set(None);
+        spawn_local(async move {
+            match call::<Settings>("settings_import", &PathArgs { path }).await {
+                Ok(_) => {
+                    let _ = window().location().reload();
+                }
+                Err(e) => set_message.set(e.explained()),
+            }
+        });
+    };
+
+    let import = move |_| {
+        spawn_local(async move {
+            match call::<Option<ImportPreview>>("settings_import_pick", &()).await {
+                Ok(Some(preview)) if preview.dropped.is_empty() => apply(preview.path),
+                Ok(Some(preview)) => set_pending.set(Some(preview)),
+                Ok(None) => {}
+                Err(e) => set_message.set(e.explained()),
+            }
+        });
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::Backup)}</h3>
+            <div class="settings-row">
+                <button class="btn btn-load pixel-border" on:click=export>{move || t(Text::Export)}</button>
+                <button class="btn btn-load pixel-border" on:click=import>{move || t(Text::Import)}</button>
+            </div>
+            <p class="settings-hint">
+                {move || t(Text::BackupHint)}
+            </p>
+            {move || pending.get().map(|preview| {
+                let names = preview.dropped.join(", ");
+                view! {
+                    <p class="settings-hint">{t_with(Text::ImportDrops, &[("names", &names)])}</p>
+                    <div class="settings-row">
+                        <button
+                            class="btn btn-danger pixel-border"
+                            on:click=move |_| apply(preview.path.clone())
+                        >{move || t(Text::ImportAnyway)}</button>
+                        <button
+                            class="btn btn-load pixel-border"
+                            on:click=move |_| set_pending.set(None)
+                        >{move || t(Text::Cancel)}</button>
+                    </div>
+                }
+            })}
+            <p class="settings-hint">{move || message.get()}</p>
+        </section>
+    }
+}
+
+#[component]
+fn LogsSection() -> impl IntoView {
+    let (lines, set_lines) = signal(Option::<Vec<String>>::None);
+    let (message, set_message) = signal(String::new());
+
+    let toggle = move |_| {
+        if lines.with_untracked(Option::is_some) {
+            set_lines.set(None);
+            return;
+        }
+        spawn_local(async move {
+            match call::<Vec<String>>("log_tail", &()).await {
+                Ok(loaded) => set_lines.set(Some(loaded)),
+                Err(e) => set_message.set(e.explained()),
+            }
+        });
+    };
+
+    let copy = move |_| {
+        spawn_local(async move {
+            match call::<()>("copy_diagnostics", &()).await {
+                Ok(()) => set_message.set(t(Text::Copied).to_string()),
+                Err(e) => set_message.set(e.explained()),
+            }
+        });
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::Logs)}</h3>
+            <div class="settings-row">
+                <button class="btn btn-load pixel-border" on:click=toggle>
+                    {move || if lines.with(Option::is_some) { t(Text::HideLogs) } else { t(Text::ViewLogs) }}
+                </button>
+                <button class="btn btn-load pixel-border" on:click=copy>{move || t(Text::CopyDiagnostics)}</button>
+            </div>
+            {move || lines.get().map(|lines| view! {
+                <pre class="settings-log">{lines.join("\n")}</pre>
+            })}
+            <p class="settings-hint">{move || message.get()}</p>
+        </section>
+    }
+}
+
+/// Troubleshooting for when the computer doesn't go to sleep
+#[component]
+fn HelpSection() -> impl IntoView {
+    let (report, set_report) = signal(Option::<Result<PowerDiagnostics, EepyError>>::None);
+
+    let check = move |_| {
+        spawn_local(async move {
+            set_report.set(Some(call::<PowerDiagnostics>("run_diagnostics", &()).await));
+        });
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>{move || t(Text::HelpDidntSleep)}</h3>
+            <button class="btn btn-load pixel-border" on:click=check>{move || t(Text::RunChecks)}</button>
+            {move || report.get().map(|result| match result {
+                Ok(report) => view! {
+                    <p class="settings-hint">{report.platform}</p>
+                    <ul class="settings-list">
+                        {report.checks.into_iter().map(|check| {
+                            let (mark, class) = match check.status {
+                                CheckStatus::Pass => ("OK", "check-pass"),
+                                CheckStatus::Warn => ("!!", "check-warn"),
+                                CheckStatus::Fail => ("XX", "check-fail"),
+                            };
+                            view! {
+                                <li class="settings-list-item">
+                                    <span class=class>{mark}</span>
+                                    <span class="settings-list-name">{check.label}</span>
+                                </li>
+                                <li class="settings-hint">{check.detail}</li>
+                            }
+                        }).collect_view()}
+                    </ul>
+                }.into_any(),
+                Err(e) => view! { <p class="settings-error">{e.explained()}</p> }.into_any(),
+            })}
+        </section>
+    }
+}
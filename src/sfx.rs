@@ -0,0 +1,88 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use leptos::task::spawn_local;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AudioBuffer, AudioContext, Response};
+
+use crate::chime;
+
+/// Bundled UI sounds, copied along with `public/`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sfx {
+    Click,
+    Start,
+    Milestone,
+}
+
+impl Sfx {
+    fn url(self) -> &'static str {
+        match self {
+            Sfx::Click => "public/sfx/click.wav",
+            Sfx::Start => "public/sfx/start.wav",
+            Sfx::Milestone => "public/sfx/milestone.wav",
+        }
+    }
+}
+
+thread_local! {
+    // Decoded once, they're played over and over
+    static BUFFERS: RefCell<HashMap<Sfx, AudioBuffer>> = RefCell::new(HashMap::new());
+    static VOLUME: Cell<f32> = const { Cell::new(0.0) };
+}
+
+/// Gain (0.0 to 1.0) for the sounds to come, 0.0 to mute them
+pub fn set_volume(volume: f32) {
+    VOLUME.with(|v| v.set(volume.clamp(0.0, 1.0)));
+}
+
+/// Play a UI sound, unless muted
+pub fn play(sound: Sfx) {
+    play_with_volume(sound, VOLUME.with(Cell::get));
+}
+
+/// Play a UI sound at `volume` (0.0 to 1.0), to try it out
+pub fn play_with_volume(sound: Sfx, volume: f32) {
+    if volume <= 0.0 {
+        return;
+    }
+    spawn_local(async move {
+        if let Err(e) = play_at(sound, volume).await {
+            leptos::logging::warn!("Could not play a sound effect: {:?}", e);
+        }
+    });
+}
+
+async fn play_at(sound: Sfx, volume: f32) -> Result<(), JsValue> {
+    let context = chime::context()?;
+
+    let buffer = match BUFFERS.with(|b| b.borrow().get(&sound).cloned()) {
+        Some(buffer) => buffer,
+        None => {
+            let buffer = load(&context, sound).await?;
+            BUFFERS.with(|b| b.borrow_mut().insert(sound, buffer.clone()));
+            buffer
+        }
+    };
+
+    let source = context.create_buffer_source()?;
+    source.set_buffer(Some(&buffer));
+    let gain = context.create_gain()?;
+    gain.gain().set_value(volume);
+    source.connect_with_audio_node(&gain)?;
+    gain.connect_with_audio_node(&context.destination())?;
+    source.start()?;
+    Ok(())
+}
+
+async fn load(context: &AudioContext, sound: Sfx) -> Result<AudioBuffer, JsValue> {
+    let window = web_sys::window().ok_or("no window")?;
+    let response: Response = JsFuture::from(window.fetch_with_str(sound.url()))
+        .await?
+        .dyn_into()?;
+    let data = JsFuture::from(response.array_buffer()?).await?;
+    JsFuture::from(context.decode_audio_data(&data.dyn_into()?)?)
+        .await?
+        .dyn_into()
+}
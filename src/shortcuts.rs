@@ -0,0 +1,107 @@
+use leptos::ev;
+use leptos::prelude::*;
+use web_sys::{window, KeyboardEvent};
+
+use crate::dialog::{focus_on_open, trap_focus};
+use crate::i18n::{t, Text};
+use crate::source::MediaSource;
+use crate::transport::PlayerState;
+
+/// Seconds the arrow keys seek by
+const SEEK_SECONDS: f64 = 10.0;
+/// Minutes + and - add to or take off the timer
+const TIMER_STEP: i32 = 5;
+
+/// Shown in the "?" overlay, as (keys, what they do)
+const SHORTCUTS: [(&str, Text); 7] = [
+    ("SPACE", Text::ShortcutPlayPause),
+    ("← →", Text::ShortcutSeek),
+    ("+ -", Text::ShortcutTimer),
+    ("D", Text::ShortcutDim),
+    ("F", Text::ShortcutTheater),
+    ("ESC", Text::ShortcutLeave),
+    ("?", Text::ShortcutList),
+];
+
+/// Keys typed into a text field belong to the field
+fn typing_in_field() -> bool {
+    let Some(active) = window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.active_element())
+    else {
+        return false;
+    };
+    matches!(
+        active.tag_name().to_ascii_lowercase().as_str(),
+        "input" | "textarea" | "select"
+    ) || active.has_attribute("contenteditable")
+}
+
+/// App-wide keyboard handler, with an overlay listing the shortcuts on "?"
+#[component]
+pub fn KeyboardShortcuts(
+    media: ReadSignal<Option<MediaSource>>,
+    player: PlayerState,
+    /// Minutes to add to (or, negative, take off) the timer
+    adjust_timer: Callback<i32>,
+    /// Leaves theater mode first, if it's on
+    close_video: Callback<()>,
+    toggle_dim: Callback<()>,
+    toggle_theater: Callback<()>,
+) -> impl IntoView {
+    let (show_help, set_show_help) = signal(false);
+
+    let handle_key = move |ev: KeyboardEvent| {
+        // Already taken, like the arrows moving the focus in leanback
+        if ev.ctrl_key()
+            || ev.meta_key()
+            || ev.alt_key()
+            || ev.default_prevented()
+            || typing_in_field()
+        {
+            return;
+        }
+        let loaded = media.with_untracked(Option::is_some);
+        match ev.key().as_str() {
+            " " if loaded => player.toggle_play(media),
+            "ArrowLeft" if loaded => player.seek_by(media, -SEEK_SECONDS),
+            "ArrowRight" if loaded => player.seek_by(media, SEEK_SECONDS),
+            "+" | "=" => adjust_timer.run(TIMER_STEP),
+            "-" | "_" => adjust_timer.run(-TIMER_STEP),
+            "d" | "D" => toggle_dim.run(()),
+            "f" | "F" if loaded => toggle_theater.run(()),
+            "?" => set_show_help.update(|shown| *shown = !*shown),
+            "Escape" if show_help.get_untracked() => set_show_help.set(false),
+            "Escape" if loaded => close_video.run(()),
+            _ => return,
+        }
+        ev.prevent_default();
+    };
+    let _ = window_event_listener(ev::keydown, handle_key);
+
+    view! {
+        <div class="shortcut-hint">{move || t(Text::PressForShortcuts)}</div>
+        {move || show_help.get().then(|| view! {
+            <div
+                class="settings-overlay"
+                role="dialog"
+                aria-modal="true"
+                node_ref=focus_on_open()
+                on:keydown=trap_focus
+                on:click=move |_| set_show_help.set(false)
+            >
+                <div class="settings-panel pixel-border">
+                    <h2 class="settings-title">{t(Text::KeyboardShortcuts)}</h2>
+                    <ul class="shortcut-list">
+                        {SHORTCUTS.into_iter().map(|(keys, action)| view! {
+                            <li>
+                                <kbd>{keys}</kbd>
+                                <span>{t(action)}</span>
+                            </li>
+                        }).collect_view()}
+                    </ul>
+                </div>
+            </div>
+        })}
+    }
+}
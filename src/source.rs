@@ -0,0 +1,556 @@
+use std::cell::{Cell, RefCell};
+
+use leptos::ev;
+use leptos::prelude::window_event_listener;
+use serde_json::json;
+use sleepy_video_shared::playback::PlaybackSettings;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{window, HtmlIFrameElement, HtmlMediaElement};
+
+use crate::i18n::Text;
+
+/// Element ID of the embedded player iframe
+pub const PLAYER_ID: &str = "media-player";
+
+/// Where each player's page is served from; messages only go to that origin
+const YOUTUBE_ORIGIN: &str = "https://www.youtube.com";
+const VIMEO_ORIGIN: &str = "https://player.vimeo.com";
+const SOUNDCLOUD_ORIGIN: &str = "https://w.soundcloud.com";
+
+/// Something the player can embed. Each provider knows how to recognize its
+/// links, build its embed URL and control its player.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MediaSource {
+    /// Video ID, also played through Invidious or Piped
+    YouTube(String),
+    /// Numeric video ID
+    Vimeo(String),
+    /// Numeric VOD ID
+    Twitch(String),
+    /// Track or playlist page URL
+    SoundCloud(String),
+}
+
+/// First capture group of `pattern` in `text`
+fn capture(pattern: &str, text: &str) -> Option<String> {
+    let re = regex_lite::Regex::new(pattern).ok()?;
+    Some(re.captures(text)?.get(1)?.as_str().to_string())
+}
+
+/// Extract YouTube video ID from various URL formats
+fn youtube_id(url: &str) -> Option<String> {
+    // Invidious and Piped instances use the same paths as youtube.com on
+    // their own domains
+    if let Some(id) = capture(
+        r"(?:/watch\?(?:[^#]*&)?v=|youtu\.be/|/embed/|/shorts/|/live/)([a-zA-Z0-9_-]{11})",
+        url,
+    ) {
+        return Some(id);
+    }
+
+    // Check if it's a direct video ID (11 characters)
+    if url.len() == 11
+        && url
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+    {
+        return Some(url.to_string());
+    }
+
+    None
+}
+
+/// Percent-encode `text` for a query parameter, like JavaScript's
+/// `encodeURIComponent`
+fn encode_component(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' => (byte as char).to_string(),
+            b'-' | b'_' | b'.' | b'!' | b'~' | b'*' | b'\'' | b'(' | b')' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+impl MediaSource {
+    /// Recognize a pasted link (or bare YouTube video ID)
+    pub fn parse(url: &str) -> Option<Self> {
+        let url = url.trim();
+        if url.is_empty() {
+            return None;
+        }
+
+        if let Some(id) = youtube_id(url) {
+            return Some(Self::YouTube(id));
+        }
+        if let Some(id) = capture(r"vimeo\.com/(?:video/)?(\d+)", url) {
+            return Some(Self::Vimeo(id));
+        }
+        if let Some(id) = capture(r"twitch\.tv/videos/(\d+)", url) {
+            return Some(Self::Twitch(id));
+        }
+        capture(
+            r"^(https?://(?:www\.|m\.)?soundcloud\.com/[^/?#\s]+/[^?#\s]+)",
+            url,
+        )
+        .map(Self::SoundCloud)
+    }
+
+    /// YouTube-only features (resume, SponsorBlock, yt-dlp, ...) key off this
+    pub fn youtube_id(&self) -> Option<&str> {
+        match self {
+            Self::YouTube(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    pub fn embed_url(&self, playback: &PlaybackSettings) -> String {
+        let host = window()
+            .and_then(|w| w.location().hostname().ok())
+            .unwrap_or_else(|| "localhost".to_string());
+        self.embed_url_on(playback, &host)
+    }
+
+    /// Embed URL for a page served from `host`
+    fn embed_url_on(&self, playback: &PlaybackSettings, host: &str) -> String {
+        match self {
+            Self::YouTube(id) => playback.embed_url(id),
+            Self::Vimeo(id) => format!("https://player.vimeo.com/video/{}?autoplay=1", id),
+            // Twitch refuses to play unless it's told who embeds it
+            Self::Twitch(id) => format!(
+                "https://player.twitch.tv/?video=v{}&parent={}&autoplay=true",
+                id, host
+            ),
+            Self::SoundCloud(page) => format!(
+                "https://w.soundcloud.com/player/?url={}&auto_play=true",
+                encode_component(page)
+            ),
+        }
+    }
+
+    /// Whether the player takes play, pause, seek and volume commands
+    pub fn controllable(&self, playback: &PlaybackSettings) -> bool {
+        match self {
+            // Invidious and Piped players ignore YouTube's commands
+            Self::YouTube(id) => playback
+                .embed_url(id)
+                .starts_with("https://www.youtube.com/"),
+            Self::Vimeo(_) | Self::SoundCloud(_) => true,
+            Self::Twitch(_) => false,
+        }
+    }
+
+    pub fn play(&self) {
+        match self {
+            Self::YouTube(_) => PlayerCommand::Play.send(),
+            Self::Vimeo(_) => send_method(VIMEO_ORIGIN, "play", None),
+            Self::SoundCloud(_) => send_method(SOUNDCLOUD_ORIGIN, "play", None),
+            Self::Twitch(_) => {}
+        }
+    }
+
+    pub fn pause(&self) {
+        match self {
+            Self::YouTube(_) => {
+                PlayerCommand::Pause.send();
+                // Invidious and Piped players don't take YouTube's commands
+                if !player_src().starts_with(&format!("{}/", YOUTUBE_ORIGIN)) {
+                    unload_player();
+                }
+            }
+            Self::Vimeo(_) => send_method(VIMEO_ORIGIN, "pause", None),
+            Self::SoundCloud(_) => send_method(SOUNDCLOUD_ORIGIN, "pause", None),
+            // The Twitch player has no postMessage API, so it can only be unloaded
+            Self::Twitch(_) => unload_player(),
+        }
+    }
+
+    /// Set the volume in percent; Twitch can't be controlled
+    pub fn set_volume(&self, volume: u32) {
+        let volume = volume.min(100);
+        match self {
+            Self::YouTube(_) => PlayerCommand::SetVolume(volume).send(),
+            Self::Vimeo(_) => send_method(VIMEO_ORIGIN, "setVolume", Some(volume as f64 / 100.0)),
+            Self::SoundCloud(_) => send_method(SOUNDCLOUD_ORIGIN, "setVolume", Some(volume as f64)),
+            Self::Twitch(_) => {}
+        }
+    }
+
+    pub fn seek(&self, seconds: f64) {
+        match self {
+            Self::YouTube(_) => PlayerCommand::SeekTo(seconds).send(),
+            Self::Vimeo(_) => send_method(VIMEO_ORIGIN, "setCurrentTime", Some(seconds)),
+            Self::SoundCloud(_) => send_method(SOUNDCLOUD_ORIGIN, "seekTo", Some(seconds * 1000.0)),
+            Self::Twitch(_) => {}
+        }
+    }
+}
+
+/// Element ID of the native player for streams extracted with yt-dlp
+pub const AUDIO_PLAYER_ID: &str = "audio-player";
+
+/// The native player used for streams extracted with yt-dlp, if it's showing
+pub fn audio_player() -> Option<HtmlMediaElement> {
+    window()?
+        .document()?
+        .get_element_by_id(AUDIO_PLAYER_ID)?
+        .dyn_into::<HtmlMediaElement>()
+        .ok()
+}
+
+pub fn play_video(source: Option<&MediaSource>) {
+    match (audio_player(), source) {
+        (Some(audio), _) => {
+            let _ = audio.play();
+        }
+        (None, Some(source)) => source.play(),
+        (None, None) => {}
+    }
+}
+
+pub fn pause_video(source: Option<&MediaSource>) {
+    match (audio_player(), source) {
+        (Some(audio), _) => {
+            let _ = audio.pause();
+        }
+        (None, Some(source)) => source.pause(),
+        (None, None) => {}
+    }
+}
+
+pub fn set_video_volume(source: Option<&MediaSource>, volume: u32) {
+    match (audio_player(), source) {
+        (Some(audio), _) => audio.set_volume(volume.min(100) as f64 / 100.0),
+        (None, Some(source)) => source.set_volume(volume),
+        (None, None) => {}
+    }
+}
+
+pub fn seek_video(source: Option<&MediaSource>, seconds: f64) {
+    match (audio_player(), source) {
+        (Some(audio), _) => audio.set_current_time(seconds),
+        (None, Some(source)) => source.seek(seconds),
+        (None, None) => {}
+    }
+}
+
+fn player_iframe() -> Option<HtmlIFrameElement> {
+    window()?
+        .document()?
+        .get_element_by_id(PLAYER_ID)?
+        .dyn_into::<HtmlIFrameElement>()
+        .ok()
+}
+
+fn player_src() -> String {
+    player_iframe()
+        .map(|iframe| iframe.src())
+        .unwrap_or_default()
+}
+
+/// Stop a player that can't be paused by pointing its frame at nothing
+fn unload_player() {
+    if let Some(iframe) = player_iframe() {
+        iframe.set_src("about:blank");
+    }
+}
+
+/// Post a message to the player iframe, if it's showing a page from `origin`
+fn post_to_player(message: &str, origin: &str) {
+    if let Some(content_window) = player_iframe().and_then(|iframe| iframe.content_window()) {
+        let _ = content_window.post_message(&JsValue::from_str(message), origin);
+    }
+}
+
+/// Commands the YouTube iframe API takes over postMessage
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlayerCommand {
+    Play,
+    Pause,
+    /// Volume in percent
+    SetVolume(u32),
+    /// Position in seconds
+    SeekTo(f64),
+    /// Ask for `infoDelivery` messages with the playback state
+    Listen,
+}
+
+impl PlayerCommand {
+    fn to_json(self) -> String {
+        let call = |func: &str, args: serde_json::Value| json!({ "event": "command", "func": func, "args": args });
+        match self {
+            Self::Play => call("playVideo", json!([])),
+            Self::Pause => call("pauseVideo", json!([])),
+            Self::SetVolume(volume) => call("setVolume", json!([volume])),
+            Self::SeekTo(seconds) => call("seekTo", json!([seconds, true])),
+            Self::Listen => json!({ "event": "listening", "id": PLAYER_ID }),
+        }
+        .to_string()
+    }
+
+    /// Post the command to the player; anything but YouTube's won't get it
+    pub fn send(self) {
+        post_to_player(&self.to_json(), YOUTUBE_ORIGIN);
+    }
+}
+
+/// Vimeo's and SoundCloud's players both take `{"method", "value"}` messages
+fn send_method(origin: &str, method: &str, value: Option<f64>) {
+    let mut message = json!({ "method": method });
+    if let Some(value) = value {
+        message["value"] = json!(value);
+    }
+    post_to_player(&message.to_string(), origin);
+}
+
+thread_local! {
+    /// Whether the YouTube player has answered since it was loaded
+    static READY: Cell<bool> = const { Cell::new(false) };
+    /// `player_ready` callers waiting for that answer
+    static WAITING: RefCell<Vec<js_sys::Function>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Ask a freshly loaded YouTube player to post its playback state. Callers
+/// still waiting on the previous player are dropped.
+pub fn listen_to_player() {
+    READY.set(false);
+    WAITING.take();
+    PlayerCommand::Listen.send();
+}
+
+/// Wait until the YouTube player answers, after which it takes commands.
+/// Never finishes for other players.
+pub async fn player_ready() {
+    if READY.get() {
+        return;
+    }
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        WAITING.with_borrow_mut(|waiting| waiting.push(resolve));
+    });
+    let _ = JsFuture::from(promise).await;
+}
+
+/// Call `handler` with every message the YouTube player posts
+pub fn on_player_message(handler: impl Fn(PlayerMessage) + 'static) {
+    let _ = window_event_listener(ev::message, move |ev| {
+        if ev.origin() != YOUTUBE_ORIGIN {
+            return;
+        }
+        let Some(message) = parse_player_message(&ev.data()) else {
+            return;
+        };
+        if !matches!(message, PlayerMessage::Error(_)) && !READY.get() {
+            READY.set(true);
+            for resolve in WAITING.take() {
+                let _ = resolve.call0(&JsValue::NULL);
+            }
+        }
+        handler(message);
+    });
+}
+
+/// What the YouTube player reports once we're listening
+pub enum PlayerMessage {
+    /// The player is set up and takes commands
+    Ready,
+    /// `initialDelivery` and `infoDelivery` carry whichever of these changed
+    Info {
+        current: Option<f64>,
+        duration: Option<f64>,
+        /// Playing or paused; other states such as buffering are left out
+        playing: Option<bool>,
+    },
+    /// Error code from the iframe API's `onError` event
+    Error(i64),
+}
+
+/// Parse a message from the YouTube player
+fn parse_player_message(data: &JsValue) -> Option<PlayerMessage> {
+    let message = js_sys::JSON::parse(&data.as_string()?).ok()?;
+    let field =
+        |value: &JsValue, key: &str| js_sys::Reflect::get(value, &JsValue::from_str(key)).ok();
+    let info = field(&message, "info").unwrap_or(JsValue::UNDEFINED);
+    match field(&message, "event")?.as_string()?.as_str() {
+        "onReady" => Some(PlayerMessage::Ready),
+        "initialDelivery" | "infoDelivery" if info.is_object() => Some(PlayerMessage::Info {
+            current: field(&info, "currentTime").and_then(|t| t.as_f64()),
+            duration: field(&info, "duration").and_then(|d| d.as_f64()),
+            playing: match field(&info, "playerState").and_then(|s| s.as_f64()) {
+                Some(1.0) => Some(true),
+                Some(2.0 | 0.0) => Some(false),
+                _ => None,
+            },
+        }),
+        "onError" => Some(PlayerMessage::Error(info.as_f64()? as i64)),
+        _ => None,
+    }
+}
+
+/// Explain an iframe API error code
+pub fn describe_embed_error(code: i64) -> Text {
+    match code {
+        2 => Text::EmbedBadId,
+        5 => Text::EmbedCantPlay,
+        100 => Text::EmbedRemoved,
+        101 | 150 => Text::EmbedNotAllowed,
+        _ => Text::EmbedUnavailable,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sleepy_video_shared::playback::EmbedHost;
+
+    use super::*;
+
+    const ID: &str = "dQw4w9WgXcQ";
+
+    fn youtube() -> Option<MediaSource> {
+        Some(MediaSource::YouTube(ID.to_string()))
+    }
+
+    #[test]
+    fn parse_recognizes_youtube_links() {
+        for url in [
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+            "https://youtube.com/watch?feature=share&v=dQw4w9WgXcQ&t=42",
+            "https://m.youtube.com/watch?v=dQw4w9WgXcQ",
+            "https://youtu.be/dQw4w9WgXcQ?si=abc",
+            "https://www.youtube.com/embed/dQw4w9WgXcQ",
+            "https://www.youtube.com/shorts/dQw4w9WgXcQ",
+            "https://www.youtube.com/live/dQw4w9WgXcQ",
+            "dQw4w9WgXcQ",
+            "  https://youtu.be/dQw4w9WgXcQ\n",
+        ] {
+            assert_eq!(MediaSource::parse(url), youtube(), "{url}");
+        }
+    }
+
+    #[test]
+    fn parse_recognizes_invidious_and_piped_links() {
+        for url in [
+            "https://yewtu.be/watch?v=dQw4w9WgXcQ",
+            "https://invidious.nerdvpn.de/watch?v=dQw4w9WgXcQ&listen=1",
+            "https://yewtu.be/embed/dQw4w9WgXcQ",
+            "https://piped.video/watch?v=dQw4w9WgXcQ",
+            "https://piped.video/embed/dQw4w9WgXcQ?autoplay=1",
+        ] {
+            assert_eq!(MediaSource::parse(url), youtube(), "{url}");
+        }
+    }
+
+    #[test]
+    fn parse_recognizes_other_providers() {
+        assert_eq!(
+            MediaSource::parse("https://vimeo.com/76979871"),
+            Some(MediaSource::Vimeo("76979871".to_string()))
+        );
+        assert_eq!(
+            MediaSource::parse("https://player.vimeo.com/video/76979871?h=abc"),
+            Some(MediaSource::Vimeo("76979871".to_string()))
+        );
+        assert_eq!(
+            MediaSource::parse("https://www.twitch.tv/videos/1234567890"),
+            Some(MediaSource::Twitch("1234567890".to_string()))
+        );
+        assert_eq!(
+            MediaSource::parse("https://soundcloud.com/artist/a-track?in=artist/sets/night"),
+            Some(MediaSource::SoundCloud(
+                "https://soundcloud.com/artist/a-track".to_string()
+            ))
+        );
+        assert_eq!(
+            MediaSource::parse("https://m.soundcloud.com/artist/sets/night"),
+            Some(MediaSource::SoundCloud(
+                "https://m.soundcloud.com/artist/sets/night".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_rejects_everything_else() {
+        for url in [
+            "",
+            "   ",
+            "dQw4w9WgXc",
+            "dQw4w9WgXcQQ",
+            "not a video!",
+            "https://www.youtube.com/",
+            "https://www.youtube.com/watch?v=short",
+            "https://vimeo.com/channels/staffpicks",
+            "https://www.twitch.tv/somechannel",
+            "https://soundcloud.com/artist",
+            "https://example.com/watch",
+        ] {
+            assert_eq!(MediaSource::parse(url), None, "{url}");
+        }
+    }
+
+    #[test]
+    fn only_youtube_has_a_youtube_id() {
+        assert_eq!(youtube().unwrap().youtube_id(), Some(ID));
+        assert_eq!(
+            MediaSource::Vimeo("76979871".to_string()).youtube_id(),
+            None
+        );
+    }
+
+    #[test]
+    fn embed_urls_autoplay_each_provider() {
+        let playback = PlaybackSettings::default();
+        let embed = |source: MediaSource| source.embed_url_on(&playback, "tauri.localhost");
+        assert_eq!(
+            embed(youtube().unwrap()),
+            "https://www.youtube.com/embed/dQw4w9WgXcQ?autoplay=1&enablejsapi=1"
+        );
+        assert_eq!(
+            embed(MediaSource::Vimeo("76979871".to_string())),
+            "https://player.vimeo.com/video/76979871?autoplay=1"
+        );
+        assert_eq!(
+            embed(MediaSource::Twitch("1234567890".to_string())),
+            "https://player.twitch.tv/?video=v1234567890&parent=tauri.localhost&autoplay=true"
+        );
+        assert_eq!(
+            embed(MediaSource::SoundCloud(
+                "https://soundcloud.com/artist/a-track".to_string()
+            )),
+            "https://w.soundcloud.com/player/?url=https%3A%2F%2Fsoundcloud.com%2Fartist%2Fa-track&auto_play=true"
+        );
+    }
+
+    #[test]
+    fn encode_component_keeps_only_unreserved_characters() {
+        assert_eq!(encode_component("a-Z_0.!~*'()"), "a-Z_0.!~*'()");
+        assert_eq!(encode_component("a b&c=d?#/"), "a%20b%26c%3Dd%3F%23%2F");
+        assert_eq!(encode_component("é"), "%C3%A9");
+    }
+
+    #[test]
+    fn instance_players_ignore_youtube_commands() {
+        for embed_host in [EmbedHost::Invidious, EmbedHost::Piped] {
+            let playback = PlaybackSettings {
+                embed_host,
+                instance_url: "https://yewtu.be".to_string(),
+                ..PlaybackSettings::default()
+            };
+            assert!(!youtube().unwrap().controllable(&playback));
+        }
+    }
+
+    #[test]
+    fn twitch_is_the_only_player_without_controls() {
+        let playback = PlaybackSettings::default();
+        assert!(youtube().unwrap().controllable(&playback));
+        assert!(MediaSource::Vimeo("1".to_string()).controllable(&playback));
+        assert!(
+            MediaSource::SoundCloud("https://soundcloud.com/a/b".to_string())
+                .controllable(&playback)
+        );
+        assert!(!MediaSource::Twitch("1".to_string()).controllable(&playback));
+    }
+}
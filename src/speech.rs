@@ -0,0 +1,17 @@
+use wasm_bindgen::JsValue;
+use web_sys::SpeechSynthesisUtterance;
+
+/// Read `text` aloud with the system voice at `volume` (0.0 to 1.0). Does
+/// nothing where the webview has no speech synthesis.
+pub fn speak(text: &str, volume: f32) -> Result<(), JsValue> {
+    let Some(window) = web_sys::window() else {
+        return Ok(());
+    };
+    let synthesis = window.speech_synthesis()?;
+    let utterance = SpeechSynthesisUtterance::new_with_text(text)?;
+    utterance.set_volume(volume.clamp(0.0, 1.0));
+    // Slower than the default, for someone drifting off
+    utterance.set_rate(0.85);
+    synthesis.speak(&utterance);
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use leptos::prelude::*;
+use sleepy_video_shared::apps::{QuitOutcome, QuitResult};
+
+use crate::i18n::{t, t_with, Text};
+use crate::session::SessionState;
+
+/// Describe what happened to an app that was closed before suspending
+fn describe_quit(result: &QuitResult) -> String {
+    let outcome = match &result.outcome {
+        QuitOutcome::NotRunning => t(Text::NotRunning).to_string(),
+        QuitOutcome::Closed => t(Text::Closed).to_string(),
+        QuitOutcome::ForceKilled => t(Text::ForceKilled).to_string(),
+        QuitOutcome::StillRunning => t(Text::StillRunning).to_string(),
+        QuitOutcome::Failed(e) => t_with(Text::Failed, &[("error", e)]),
+    };
+    format!("{}: {}", result.name.to_uppercase(), outcome)
+}
+
+/// Status line under the timer, with the apps closed before the end action
+#[component]
+pub fn StatusBar() -> impl IntoView {
+    let session = expect_context::<SessionState>();
+
+    view! {
+        // Read out by screen readers whenever it changes
+        <p
+            class=move || format!("status {}", session.status_class.get())
+            role="status"
+            aria-live="polite"
+        >
+            {move || session.status_text.get()}
+        </p>
+        <ul class="quit-report" aria-live="polite">
+            {move || session.quit_report.with(|results| {
+                results
+                    .iter()
+                    .filter(|r| r.outcome != QuitOutcome::NotRunning)
+                    .map(|r| view! { <li>{describe_quit(r)}</li> })
+                    .collect_view()
+            })}
+        </ul>
+    }
+}
@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use sleepy_video_shared::settings::Settings;
+use sleepy_video_shared::streaks::{StreakReport, WhaleMood};
+
+use crate::i18n::{mood_label, t, t_with, Text};
+use crate::ipc::call;
+use crate::mascot::Mascot;
+use crate::session::SessionState;
+
+/// The backend records a session as it ends, a moment after the frontend
+/// hears about it
+const RECORD_DELAY: Duration = Duration::from_secs(1);
+
+fn refresh(report: RwSignal<StreakReport>) {
+    spawn_local(async move {
+        match call::<StreakReport>("history_streak", &()).await {
+            Ok(loaded) => {
+                report.try_set(loaded);
+            }
+            Err(e) => leptos::logging::warn!("Could not load the streak: {}", e),
+        }
+    });
+}
+
+/// Nights in a row the timer was started by bedtime, kept by the mascot,
+/// which gets sleepier and happier the longer the streak runs
+#[component]
+pub fn StreakWhale() -> impl IntoView {
+    let settings = expect_context::<RwSignal<Settings>>();
+    let session = expect_context::<SessionState>();
+    let running = Memo::new(move |_| session.is_running());
+    let report = RwSignal::new(StreakReport::default());
+
+    // Again whenever a session ends, and once the settings are in
+    Effect::new(move |_| {
+        settings.with(|s| (s.streaks.enabled, s.schedule.clone()));
+        if !running.get() {
+            set_timeout(move || refresh(report), RECORD_DELAY);
+        }
+    });
+
+    move || {
+        if !settings.with(|s| s.streaks.enabled) || running.get() {
+            return None;
+        }
+        let StreakReport {
+            current,
+            best,
+            week_kept,
+            week_nights,
+        } = report.get();
+        let mood = WhaleMood::from_streak(current);
+        Some(view! {
+            <div class=format!("streak-whale mood-{}", mood.as_str())>
+                <div class="streak-sprite" aria-hidden="true">
+                    <span class="streak-bubble">{mood.bubble()}</span>
+                    <Mascot class="streak-body"/>
+                </div>
+                <div class="streak-text">
+                    <span class="streak-count">
+                        {if current == 1 {
+                            t(Text::StreakOneNight).to_string()
+                        } else {
+                            t_with(Text::StreakNights, &[("count", &current.to_string())])
+                        }}
+                    </span>
+                    <span class="streak-mood">{t(mood_label(mood))}</span>
+                    <span class="streak-detail">
+                        {t_with(Text::StreakDetail, &[
+                            ("best", &best.to_string()),
+                            ("kept", &week_kept.to_string()),
+                            ("nights", &week_nights.to_string()),
+                        ])}
+                    </span>
+                </div>
+            </div>
+        })
+    }
+}
@@ -0,0 +1,110 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use serde::Serialize;
+use sleepy_video_shared::strict::StrictStatus;
+
+use crate::dialog::{focus_on_open, trap_focus};
+use crate::i18n::{t, t_with, Text};
+use crate::ipc::call;
+
+#[derive(Serialize)]
+struct PinArgs {
+    pin: String,
+}
+
+/// Asks for the PIN before a locked session can be cancelled or extended,
+/// or starts the cooldown that unlocks it without one
+#[component]
+pub fn StrictPrompt(
+    status: StrictStatus,
+    on_unlocked: Callback<()>,
+    on_close: Callback<()>,
+) -> impl IntoView {
+    let (pin, set_pin) = signal(String::new());
+    let (error, set_error) = signal(String::new());
+    let (unlocks_in, set_unlocks_in) = signal(status.unlocks_in);
+
+    let unlock = move || {
+        let pin = pin.get_untracked();
+        spawn_local(async move {
+            match call::<StrictStatus>("strict_unlock", &PinArgs { pin }).await {
+                Ok(status) if !status.locked => on_unlocked.run(()),
+                Ok(_) => {}
+                Err(e) => {
+                    set_error.set(e.to_string().to_uppercase());
+                    set_pin.set(String::new());
+                }
+            }
+        });
+    };
+
+    let request_unlock = move |_| {
+        spawn_local(async move {
+            if let Ok(status) = call::<StrictStatus>("strict_request_unlock", &()).await {
+                if status.locked {
+                    set_unlocks_in.set(status.unlocks_in);
+                } else {
+                    on_unlocked.run(());
+                }
+            }
+        });
+    };
+
+    view! {
+        <div
+            class="settings-overlay"
+            role="dialog"
+            aria-modal="true"
+            node_ref=focus_on_open()
+            on:keydown=trap_focus
+        >
+            <div class="settings-panel pixel-border">
+                <h2 class="settings-title">{move || t(Text::StrictMode)}</h2>
+                <p class="busy-reasons">{move || t(Text::SessionLocked)}</p>
+                <div class="settings-row">
+                    <input
+                        type="password"
+                        inputmode="numeric"
+                        class="settings-input wide"
+                        placeholder=move || t(Text::Pin)
+                        autofocus=true
+                        prop:value=move || pin.get()
+                        on:input=move |ev| set_pin.set(event_target_value(&ev))
+                        on:keydown=move |ev| {
+                            if ev.key() == "Enter" {
+                                unlock();
+                            }
+                        }
+                    />
+                </div>
+                <p class="settings-error">{move || error.get()}</p>
+                <p class="settings-hint">
+                    {move || match unlocks_in.get() {
+                        Some(seconds) => {
+                            let minutes = seconds.div_ceil(60).to_string();
+                            t_with(Text::UnlocksIn, &[("minutes", &minutes)])
+                        }
+                        None => String::new(),
+                    }}
+                </p>
+                <div class="action-buttons">
+                    <button class="btn btn-load pixel-border" on:click=move |_| unlock()>
+                        {move || t(Text::Unlock)}
+                    </button>
+                    <button
+                        class="btn btn-load pixel-border"
+                        disabled=move || unlocks_in.get().is_some()
+                        on:click=request_unlock
+                    >{move || t(Text::WaitItOut)}</button>
+                    <button
+                        class="btn btn-danger pixel-border"
+                        data-back=true
+                        on:click=move |_| on_close.run(())
+                    >
+                        {move || t(Text::NeverMind)}
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use leptos::prelude::*;
+use sleepy_video_shared::schedule::WakeAlarm;
+use sleepy_video_shared::settings::Settings;
+
+use crate::app::clock_in;
+use crate::chime;
+use crate::i18n::{t, t_with, Text};
+
+/// Seconds between rings of the alarm
+const RING_SECONDS: u32 = 15;
+/// Volume of the first ring, as a share of the alarm volume
+const FIRST_RING: f32 = 0.05;
+
+/// Full-screen sunrise that brightens over the alarm's sunrise time while
+/// its chime rings louder, the morning counterpart of the evening dim
+#[component]
+pub fn Sunrise(
+    alarm: WakeAlarm,
+    on_snooze: Callback<()>,
+    on_dismiss: Callback<()>,
+) -> impl IntoView {
+    let settings = expect_context::<RwSignal<Settings>>();
+    let length = alarm.sunrise_minutes.max(1) * 60;
+    let (tone, volume, snooze) = (alarm.tone, alarm.volume, alarm.snooze_minutes);
+    let (elapsed, set_elapsed) = signal(0u32);
+    let progress = move || (elapsed.get() as f64 / length as f64).min(1.0);
+
+    let ring = move |seconds: u32| {
+        let share = (seconds as f32 / length as f32).clamp(FIRST_RING, 1.0);
+        if let Err(e) = chime::play(tone, share * volume as f32 / 100.0) {
+            leptos::logging::warn!("Could not play the alarm: {:?}", e);
+        }
+    };
+    ring(0);
+
+    let handle = set_interval_with_handle(
+        move || {
+            let seconds = elapsed.get_untracked() + 1;
+            set_elapsed.set(seconds);
+            if seconds % RING_SECONDS == 0 {
+                ring(seconds);
+            }
+        },
+        Duration::from_secs(1),
+    )
+    .ok();
+    on_cleanup(move || {
+        if let Some(handle) = handle {
+            handle.clear();
+        }
+    });
+
+    view! {
+        <div class="sunrise">
+            // Night fading away uncovers the sunrise underneath
+            <div class="sunrise-night" style:opacity=move || (1.0 - progress()).to_string()></div>
+            <div class="sunrise-time">
+                {move || { elapsed.track(); clock_in(0, settings.with(|s| s.display.clock_12h)) }}
+            </div>
+            <div class="action-buttons">
+                <button class="btn btn-load pixel-border" on:click=move |_| on_snooze.run(())>
+                    {move || t_with(Text::Snooze, &[("minutes", &snooze.to_string())])}
+                </button>
+                <button class="btn btn-danger pixel-border" on:click=move |_| on_dismiss.run(())>
+                    {move || t(Text::GoodMorning)}
+                </button>
+            </div>
+        </div>
+    }
+}
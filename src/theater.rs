@@ -0,0 +1,101 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use serde::Serialize;
+
+use crate::app::{format_time, toggle_body_class};
+use crate::i18n::{t, Text};
+use crate::ipc::call;
+use crate::session::SessionState;
+
+#[derive(Serialize)]
+struct EnabledArgs {
+    enabled: bool,
+}
+
+/// Always on top and theater mode: a fullscreen window with the video under
+/// the dim overlay and a small countdown
+#[derive(Clone, Copy)]
+pub struct Theater {
+    pub active: RwSignal<bool>,
+    /// The window stays on top of the others
+    pub pinned: RwSignal<bool>,
+}
+
+impl Theater {
+    pub fn new() -> Self {
+        Self {
+            active: RwSignal::new(false),
+            pinned: RwSignal::new(false),
+        }
+    }
+
+    /// Go fullscreen with the body dressed for it, or back
+    pub fn set(&self, enabled: bool) {
+        self.active.set(enabled);
+        toggle_body_class("theater", enabled);
+        spawn_local(async move {
+            if let Err(e) = call::<()>("set_fullscreen", &EnabledArgs { enabled }).await {
+                leptos::logging::warn!("{}", e);
+            }
+        });
+    }
+
+    pub fn toggle(&self) {
+        self.set(!self.active.get_untracked());
+    }
+
+    /// Leave theater mode, if it's on
+    pub fn leave(&self) {
+        if self.active.get_untracked() {
+            self.set(false);
+        }
+    }
+
+    pub fn toggle_pinned(&self) {
+        let enabled = !self.pinned.get_untracked();
+        let pinned = self.pinned;
+        spawn_local(async move {
+            match call::<()>("set_always_on_top", &EnabledArgs { enabled }).await {
+                Ok(()) => pinned.set(enabled),
+                Err(e) => leptos::logging::warn!("{}", e),
+            }
+        });
+    }
+}
+
+/// Header button keeping the window on top
+#[component]
+pub fn PinButton(theater: Theater) -> impl IntoView {
+    let tip = move || {
+        if theater.pinned.get() {
+            t(Text::StopKeepingOnTop)
+        } else {
+            t(Text::KeepOnTop)
+        }
+    };
+
+    view! {
+        <button
+            class=move || if theater.pinned.get() { "header-btn active" } else { "header-btn" }
+            title=tip
+            aria-label=tip
+            on:click=move |_| theater.toggle_pinned()
+        >"📌"</button>
+    }
+}
+
+/// Small countdown left over the video in theater mode
+#[component]
+pub fn TheaterCountdown(theater: Theater) -> impl IntoView {
+    let session = expect_context::<SessionState>();
+
+    move || {
+        theater.active.get().then(|| {
+            view! {
+                <div class="theater-countdown">
+                    {move || if session.is_running() { format_time(session.remaining.get()) } else { String::new() }}
+                </div>
+            }
+        })
+    }
+}
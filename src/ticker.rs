@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use leptos::prelude::*;
+
+/// A running interval, cleared when dropped
+struct Interval(IntervalHandle);
+
+impl Drop for Interval {
+    fn drop(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// The one repeating timer behind the countdown. Starting it replaces the
+/// interval that was running, and it stops for good along with its owner.
+#[derive(Clone, Copy)]
+pub struct Ticker(StoredValue<Option<Interval>, LocalStorage>);
+
+impl Ticker {
+    pub fn new() -> Self {
+        Self(StoredValue::new_local(None))
+    }
+
+    /// Call `tick` every `period`, stopping whatever ticked before
+    pub fn start(&self, period: Duration, tick: impl Fn() + 'static) {
+        self.stop();
+        match set_interval_with_handle(tick, period) {
+            Ok(handle) => self.0.set_value(Some(Interval(handle))),
+            Err(e) => leptos::logging::warn!("Could not start the timer: {:?}", e),
+        }
+    }
+
+    pub fn stop(&self) {
+        self.0.set_value(None);
+    }
+}
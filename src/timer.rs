@@ -0,0 +1,174 @@
+use leptos::prelude::*;
+use sleepy_video_shared::parental::Profile;
+use sleepy_video_shared::session::SessionPhase;
+use sleepy_video_shared::settings::Settings;
+use sleepy_video_shared::templates::SessionTemplate;
+
+use crate::app::{clock_in, format_time};
+use crate::i18n::{action_label, action_verb, t, t_with, Text};
+use crate::presets::PresetGrid;
+use crate::session::SessionState;
+
+/// Countdown, progress and the controls for picking and starting a session
+#[component]
+pub fn TimerPanel(
+    on_start: Callback<()>,
+    on_cancel: Callback<()>,
+    /// Pause a running countdown, or resume a paused one
+    on_pause: Callback<()>,
+    on_template: Callback<SessionTemplate>,
+    /// Dry run of the end of a session
+    on_test: Callback<()>,
+    /// Whether a dry run is under way
+    testing: Signal<bool>,
+) -> impl IntoView {
+    let settings = expect_context::<RwSignal<Settings>>();
+    let profile = expect_context::<RwSignal<Profile>>();
+    let session = expect_context::<SessionState>();
+    let is_child = move || profile.get() == Profile::Child;
+    let running = Signal::derive(move || session.is_running());
+    let paused = move || matches!(session.phase.get(), SessionPhase::Paused { .. });
+    // Template dropdown, reset after every pick so it always reads as a menu
+    let (template_choice, set_template_choice) = signal(String::new());
+
+    view! {
+        <div class="timer-display pixel-border" role="timer" aria-label=move || t(Text::Remaining)>
+            <div class="timer-value">{move || format_time(session.remaining.get())}</div>
+            <div class="timer-label">{move || t(Text::Remaining)}</div>
+            {move || running.get().then(|| view! {
+                <div class="timer-end">
+                    {move || {
+                        let time = clock_in(
+                            session.remaining.get(),
+                            settings.with(|s| s.display.clock_12h),
+                        );
+                        t_with(Text::EndsAt, &[
+                            ("action", t(action_verb(session.action.get()))),
+                            ("time", &time),
+                        ])
+                    }}
+                </div>
+            })}
+            {move || if running.get() {
+                view! {
+                    <span class="zzz" aria-hidden="true">"z"</span>
+                    <span class="zzz" aria-hidden="true">"z"</span>
+                    <span class="zzz" aria-hidden="true">"z"</span>
+                }.into_any()
+            } else {
+                view! { <span></span> }.into_any()
+            }}
+        </div>
+
+        <div
+            class="progress-container pixel-border"
+            role="progressbar"
+            aria-valuemin="0"
+            aria-valuemax="100"
+            aria-valuenow=move || session.progress_percent().to_string()
+        >
+            <div
+                class=move || if running.get() { "progress-bar active" } else { "progress-bar" }
+                style=move || format!("width: {}%", session.progress_percent())
+            ></div>
+        </div>
+
+        {move || if is_child() {
+            let (minutes, action) = settings.with(|s| (s.parental.minutes, s.parental.action));
+            view! {
+                <p class="child-session">
+                    {t_with(Text::ChildSession, &[
+                        ("minutes", &minutes.to_string()),
+                        ("action", t(action_label(action))),
+                    ])}
+                </p>
+            }.into_any()
+        } else {
+            view! {
+                <PresetGrid
+                    selected=session.selected_minutes.read_only()
+                    set_selected=session.selected_minutes.write_only()
+                    running=running
+                />
+
+                <div class="custom-input-group">
+                    <label for="custom-minutes">{move || t(Text::Custom)}</label>
+                    <input
+                        type="number"
+                        id="custom-minutes"
+                        class="custom-input pixel-border"
+                        min="1"
+                        max=move || settings.with(|s| s.timer.max_minutes()).to_string()
+                        placeholder="60"
+                        disabled=move || running.get()
+                        on:input=move |ev| {
+                            if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                                session.selected_minutes.set(val);
+                            }
+                        }
+                    />
+                    <span style="font-size: 8px; color: var(--text-dim);">{move || t(Text::Minutes)}</span>
+                </div>
+            }.into_any()
+        }}
+
+        {move || {
+            let templates = settings.with(|s| s.templates.clone());
+            (!running.get() && !is_child() && !templates.is_empty()).then(|| view! {
+                <div class="template-group">
+                    <select
+                        class="template-select pixel-border"
+                        prop:value=move || template_choice.get()
+                        on:change=move |ev| {
+                            let choice = event_target_value(&ev);
+                            set_template_choice.set(String::new());
+                            let template = choice
+                                .parse::<usize>()
+                                .ok()
+                                .and_then(|i| settings.with_untracked(|s| s.templates.get(i).cloned()));
+                            if let Some(template) = template {
+                                on_template.run(template);
+                            }
+                        }
+                    >
+                        <option value="">{move || t(Text::StartTemplate)}</option>
+                        {templates.into_iter().enumerate().map(|(i, template)| view! {
+                            <option value=i.to_string()>{template.name}</option>
+                        }).collect_view()}
+                    </select>
+                </div>
+            })
+        }}
+
+        <div class="action-buttons">
+            {move || if !running.get() {
+                view! {
+                    <button
+                        class="btn btn-primary pixel-border"
+                        disabled=move || testing.get()
+                        on:click=move |_| on_start.run(())
+                    >{move || t(Text::Start)}</button>
+                    <button
+                        class="btn btn-load pixel-border"
+                        title=move || t(Text::TestTip)
+                        disabled=move || testing.get()
+                        on:click=move |_| on_test.run(())
+                    >{move || t(Text::Test)}</button>
+                }.into_any()
+            } else {
+                view! {
+                    {move || (!is_child()).then(|| view! {
+                        <button
+                            class="btn btn-load pixel-border"
+                            on:click=move |_| on_pause.run(())
+                        >{move || t(if paused() { Text::Resume } else { Text::Pause })}</button>
+                    })}
+                    <button
+                        class="btn btn-danger pixel-border"
+                        on:click=move |_| on_cancel.run(())
+                    >{move || t(Text::CancelSession)}</button>
+                }.into_any()
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,209 @@
+use leptos::prelude::*;
+
+use crate::i18n::{t, t_with, Text};
+use crate::source::{pause_video, play_video, seek_video, MediaSource};
+
+/// Seconds skipped by the back and forward buttons
+const SKIP_SECONDS: f64 = 30.0;
+
+/// What the transport bar shows and controls, kept up to date from player
+/// events
+#[derive(Clone, Copy)]
+pub struct PlayerState {
+    pub position: RwSignal<f64>,
+    pub duration: RwSignal<Option<f64>>,
+    pub playing: RwSignal<bool>,
+    pub muted: RwSignal<bool>,
+    /// Manual volume in percent; the end-of-session fade scales down from it
+    pub volume: RwSignal<u32>,
+    /// A–B loop points in seconds; playback jumps back to A on reaching B
+    pub loop_start: RwSignal<Option<f64>>,
+    pub loop_end: RwSignal<Option<f64>>,
+}
+
+impl PlayerState {
+    pub fn new() -> Self {
+        Self {
+            position: RwSignal::new(0.0),
+            duration: RwSignal::new(None),
+            playing: RwSignal::new(true),
+            muted: RwSignal::new(false),
+            volume: RwSignal::new(100),
+            loop_start: RwSignal::new(None),
+            loop_end: RwSignal::new(None),
+        }
+    }
+
+    /// A new video autoplays from the start
+    pub fn reset(&self) {
+        self.position.set(0.0);
+        self.duration.set(None);
+        self.playing.set(true);
+        self.clear_loop();
+    }
+
+    pub fn clear_loop(&self) {
+        self.loop_start.set(None);
+        self.loop_end.set(None);
+    }
+
+    /// Mark where the loop starts, dropping an end that's no longer after it
+    fn mark_loop_start(&self) {
+        let start = self.position.get_untracked();
+        self.loop_start.set(Some(start));
+        if self
+            .loop_end
+            .get_untracked()
+            .is_some_and(|end| end <= start)
+        {
+            self.loop_end.set(None);
+        }
+    }
+
+    /// Mark where the loop ends; without a start it loops from the beginning
+    fn mark_loop_end(&self) {
+        let end = self.position.get_untracked();
+        let start = self.loop_start.get_untracked().unwrap_or(0.0);
+        if end > start {
+            self.loop_start.set(Some(start));
+            self.loop_end.set(Some(end));
+        }
+    }
+
+    /// Jump back to A once playback reaches B
+    pub fn hold_loop(&self, media: ReadSignal<Option<MediaSource>>) {
+        if let (Some(start), Some(end)) = (
+            self.loop_start.get_untracked(),
+            self.loop_end.get_untracked(),
+        ) {
+            if self.position.get_untracked() >= end {
+                self.seek_to(media, start);
+            }
+        }
+    }
+
+    pub fn toggle_play(&self, media: ReadSignal<Option<MediaSource>>) {
+        if self.playing.get_untracked() {
+            media.with_untracked(|m| pause_video(m.as_ref()));
+        } else {
+            media.with_untracked(|m| play_video(m.as_ref()));
+        }
+        self.playing.update(|playing| *playing = !*playing);
+    }
+
+    /// Jump to a position, kept within the video
+    pub fn seek_to(&self, media: ReadSignal<Option<MediaSource>>, target: f64) {
+        let target = target.max(0.0);
+        let target = self
+            .duration
+            .get_untracked()
+            .map_or(target, |d| target.min(d));
+        media.with_untracked(|m| seek_video(m.as_ref(), target));
+        self.position.set(target);
+    }
+
+    pub fn seek_by(&self, media: ReadSignal<Option<MediaSource>>, delta: f64) {
+        self.seek_to(media, self.position.get_untracked() + delta);
+    }
+}
+
+/// M:SS, or H:MM:SS past an hour
+fn clock(seconds: f64) -> String {
+    let seconds = seconds.max(0.0) as u32;
+    let (h, m, s) = (seconds / 3600, (seconds % 3600) / 60, seconds % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    }
+}
+
+/// Play/pause, ±30s, a progress scrubber, mute and a volume slider
+#[component]
+pub fn TransportBar(media: ReadSignal<Option<MediaSource>>, player: PlayerState) -> impl IntoView {
+    view! {
+        <div class="transport pixel-border">
+            <button
+                class="transport-btn"
+                title=move || t(Text::Back30)
+                aria-label=move || t(Text::Back30)
+                on:click=move |_| player.seek_by(media, -SKIP_SECONDS)
+            >"⏪"</button>
+            <button
+                class="transport-btn"
+                title=move || if player.playing.get() { t(Text::PauseTip) } else { t(Text::PlayTip) }
+                aria-label=move || if player.playing.get() { t(Text::PauseTip) } else { t(Text::PlayTip) }
+                on:click=move |_| player.toggle_play(media)
+            >{move || if player.playing.get() { "⏸" } else { "▶" }}</button>
+            <button
+                class="transport-btn"
+                title=move || t(Text::Forward30)
+                aria-label=move || t(Text::Forward30)
+                on:click=move |_| player.seek_by(media, SKIP_SECONDS)
+            >"⏩"</button>
+            {move || player.duration.get().map(|duration| view! {
+                <input
+                    type="range"
+                    class="transport-scrubber"
+                    min="0"
+                    max=duration.to_string()
+                    step="1"
+                    prop:value=move || player.position.get().to_string()
+                    on:change=move |ev| {
+                        if let Ok(target) = event_target_value(&ev).parse::<f64>() {
+                            player.seek_to(media, target);
+                        }
+                    }
+                />
+                <span class="transport-time">
+                    {move || format!("{} / {}", clock(player.position.get()), clock(duration))}
+                </span>
+                <button
+                    class=move || if player.loop_start.get().is_some() { "transport-btn active" } else { "transport-btn" }
+                    title=move || match player.loop_start.get() {
+                        Some(start) => t_with(Text::LoopFrom, &[("time", &clock(start))]),
+                        None => t(Text::MarkLoopStart).to_string(),
+                    }
+                    aria-label=move || t(Text::LoopStart)
+                    on:click=move |_| player.mark_loop_start()
+                >"A"</button>
+                <button
+                    class=move || if player.loop_end.get().is_some() { "transport-btn active" } else { "transport-btn" }
+                    title=move || match player.loop_end.get() {
+                        Some(end) => t_with(Text::LoopTo, &[("time", &clock(end))]),
+                        None => t(Text::MarkLoopEnd).to_string(),
+                    }
+                    aria-label=move || t(Text::LoopEnd)
+                    on:click=move |_| player.mark_loop_end()
+                >"B"</button>
+                {move || player.loop_start.get().is_some().then(|| view! {
+                    <button
+                        class="transport-btn"
+                        title=move || t(Text::StopLooping)
+                        aria-label=move || t(Text::StopLooping)
+                        on:click=move |_| player.clear_loop()
+                    >"✕"</button>
+                })}
+            })}
+            <button
+                class="transport-btn"
+                title=move || if player.muted.get() { t(Text::Unmute) } else { t(Text::Mute) }
+                aria-label=move || if player.muted.get() { t(Text::Unmute) } else { t(Text::Mute) }
+                on:click=move |_| player.muted.update(|muted| *muted = !*muted)
+            >{move || if player.muted.get() { "🔇" } else { "🔊" }}</button>
+            <input
+                type="range"
+                class="transport-volume"
+                title=move || t(Text::VolumeTip)
+                min="0"
+                max="100"
+                prop:value=move || player.volume.get().to_string()
+                on:input=move |ev| {
+                    if let Ok(volume) = event_target_value(&ev).parse::<u32>() {
+                        player.volume.set(volume.min(100));
+                    }
+                }
+            />
+        </div>
+    }
+}
@@ -0,0 +1,544 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use serde::Serialize;
+use sleepy_video_shared::library::LibraryEntry;
+use sleepy_video_shared::offline::OFFLINE_AUDIO_SCHEME;
+use sleepy_video_shared::parental::Profile;
+use sleepy_video_shared::playback::normalized_level;
+use sleepy_video_shared::settings::Settings;
+use sleepy_video_shared::sponsorblock::SkipSegment;
+use web_sys::HtmlMediaElement;
+
+use crate::ad_break::AdBreak;
+use crate::app::toggle_body_class;
+use crate::clipboard::{ClipboardPrompt, CopiedLink};
+use crate::i18n::{t, t_with, Text};
+use crate::ipc::{call, convert_file_src};
+use crate::library::RecentVideos;
+use crate::metered::Metered;
+use crate::muffle;
+use crate::queue::{Follow, Queue};
+use crate::resume::ResumePosition;
+use crate::search::SearchBox;
+use crate::session::SessionState;
+use crate::settings::persist;
+use crate::source::{
+    describe_embed_error, on_player_message, play_video, seek_video, set_video_volume, MediaSource,
+    PlayerMessage,
+};
+use crate::theater::Theater;
+use crate::transport::PlayerState;
+use crate::volume_memory;
+
+#[derive(Serialize)]
+struct VideoArgs {
+    video: String,
+}
+
+#[derive(Serialize)]
+struct OpenUrlArgs {
+    url: String,
+}
+
+/// The loaded video and everything that goes with it, shared by `App` with
+/// the player, the link field and the commands that load or close videos
+#[derive(Clone, Copy)]
+pub struct VideoState {
+    settings: RwSignal<Settings>,
+    profile: RwSignal<Profile>,
+    session: SessionState,
+    /// Recently played and favorite videos
+    pub library: RwSignal<Vec<LibraryEntry>>,
+    /// What's typed in the link field
+    pub url: RwSignal<String>,
+    pub media: RwSignal<Option<MediaSource>>,
+    /// YouTube ID of the loaded video, for the features that only work there
+    pub video_id: Memo<Option<String>>,
+    /// Line under the link field, and its class
+    pub hint: RwSignal<String>,
+    pub hint_class: RwSignal<String>,
+    /// Direct audio stream from yt-dlp replacing the embed, and whether it's
+    /// still being looked up
+    pub audio_stream: RwSignal<Option<String>>,
+    pub resolving_audio: RwSignal<bool>,
+    /// Video the embed refused to play, offered to open on YouTube instead
+    pub blocked: RwSignal<Option<String>>,
+    /// SponsorBlock segments of the loaded video
+    skip_segments: RwSignal<Vec<SkipSegment>>,
+    /// Loudness normalization of the loaded video, in percent; the fades
+    /// scale down from it
+    loudness: RwSignal<u32>,
+    /// Transport bar state
+    pub player: PlayerState,
+    /// Links to play next
+    pub queue: Queue,
+    /// Saved position offered when a video is loaded again
+    pub resume: ResumePosition,
+    /// Ads the embed plays in the middle of the video
+    ad_break: AdBreak,
+    /// Bandwidth saver on metered connections
+    pub metered: Metered,
+    /// Always on top and theater mode
+    pub theater: Theater,
+}
+
+impl VideoState {
+    pub fn new(
+        settings: RwSignal<Settings>,
+        profile: RwSignal<Profile>,
+        session: SessionState,
+    ) -> Self {
+        let media = RwSignal::new(None::<MediaSource>);
+        let video = Self {
+            settings,
+            profile,
+            session,
+            library: RwSignal::new(Vec::new()),
+            url: RwSignal::new(String::new()),
+            media,
+            video_id: Memo::new(move |_| {
+                media.with(|m| {
+                    m.as_ref()
+                        .and_then(MediaSource::youtube_id)
+                        .map(str::to_string)
+                })
+            }),
+            hint: RwSignal::new(String::new()),
+            hint_class: RwSignal::new(String::new()),
+            audio_stream: RwSignal::new(None),
+            resolving_audio: RwSignal::new(false),
+            blocked: RwSignal::new(None),
+            skip_segments: RwSignal::new(Vec::new()),
+            loudness: RwSignal::new(100),
+            player: PlayerState::new(),
+            queue: Queue::new(),
+            resume: ResumePosition::new(),
+            ad_break: AdBreak::new(settings),
+            metered: Metered::new(settings),
+            theater: Theater::new(),
+        };
+
+        let fade_level = session.fade_level;
+        Effect::new(move |_| {
+            video.player.muted.track();
+            video.player.volume.track();
+            video.loudness.track();
+            fade_level.track();
+            video.queue.level.track();
+            video.ad_break.active.track();
+            video.apply_volume();
+        });
+        Effect::new(move |_| {
+            fade_level.track();
+            settings.track();
+            video.apply_muffle();
+        });
+        Effect::new(move |_| settings.with(|s| muffle::set_eq(&s.playback.eq)));
+        Effect::new(move |_| {
+            toggle_body_class("audio-only", settings.with(|s| s.playback.audio_only));
+        });
+        // Remember the volume of a video in the library once the slider rests
+        volume_memory::remember(video.player, video.video_id, video.library);
+        video
+    }
+
+    /// Whether a video is loaded
+    pub fn loaded(self) -> Signal<bool> {
+        Signal::derive(move || self.media.with(Option::is_some))
+    }
+
+    fn set_hint(&self, hint: String, class: &str) {
+        self.hint.set(hint);
+        self.hint_class.set(class.to_string());
+    }
+
+    /// Send the manual volume, scaled by the fades, to whichever player is up
+    pub fn apply_volume(&self) {
+        let level = if self.player.muted.get_untracked() {
+            0
+        } else {
+            self.player.volume.get_untracked() * self.loudness.get_untracked() / 100
+                * self.session.fade_level.get_untracked()
+                / 100
+                * self.queue.level.get_untracked()
+                / 100
+                * self.ad_break.level()
+                / 100
+        };
+        self.media
+            .with_untracked(|m| set_video_volume(m.as_ref(), level));
+    }
+
+    /// Muffle the audio stream along with the end-of-session fade
+    fn apply_muffle(&self) {
+        let amount = if self.settings.with_untracked(|s| s.playback.muffle) {
+            1.0 - self.session.fade_level.get_untracked() as f64 / 100.0
+        } else {
+            0.0
+        };
+        muffle::set_amount(amount);
+    }
+
+    /// Put `url` in the link field and load it
+    pub fn open(self, url: String) {
+        self.url.set(url.clone());
+        self.load(url);
+    }
+
+    /// Load a YouTube, Vimeo, Twitch or SoundCloud link into the player
+    pub fn load(self, url: String) {
+        if url.is_empty() {
+            self.set_hint(t(Text::EnterVideoLink).to_string(), "error");
+            return;
+        }
+
+        let allowed = |source: &MediaSource| {
+            self.profile.get_untracked() == Profile::Parent
+                || source
+                    .youtube_id()
+                    .is_some_and(|id| self.settings.with_untracked(|s| s.parental.allows(id)))
+        };
+        match MediaSource::parse(&url) {
+            Some(source) if !allowed(&source) => {
+                self.set_hint(t(Text::VideoNotAllowed).to_string(), "error");
+            }
+            Some(source) => {
+                self.resume.clear();
+                self.audio_stream.set(None);
+                self.resolving_audio.set(false);
+                self.blocked.set(None);
+                self.skip_segments.set(Vec::new());
+                self.queue.reset_fade();
+                self.ad_break.reset();
+                muffle::detach();
+                // Until the real loudness is known, assume the reference
+                self.loudness
+                    .set(if self.settings.with_untracked(|s| s.playback.normalize) {
+                        normalized_level(None)
+                    } else {
+                        100
+                    });
+                if let Some(id) = source.youtube_id() {
+                    self.prepare_youtube(id.to_string());
+                }
+                self.player.reset();
+                self.media.set(Some(source));
+                toggle_body_class("video-active", true);
+                self.set_hint(t(Text::VideoLoaded).to_string(), "success");
+            }
+            None => self.set_hint(t(Text::LinkNotRecognized).to_string(), "error"),
+        }
+    }
+
+    /// Library, resume, SponsorBlock and yt-dlp lookups for a YouTube video
+    fn prepare_youtube(self, id: String) {
+        // The connection may have changed since the last video
+        self.metered.check();
+        let video = id.clone();
+        spawn_local(async move {
+            let args = VideoArgs {
+                video: video.clone(),
+            };
+            match call::<Vec<LibraryEntry>>("library_record", &args).await {
+                Ok(entries) => {
+                    volume_memory::recall(self.player, &video, &entries);
+                    self.library.set(entries);
+                }
+                Err(e) => leptos::logging::warn!("Could not remember video: {}", e),
+            }
+        });
+        self.resume.look_up(id.clone());
+        let video = id.clone();
+        spawn_local(async move {
+            match call::<Vec<SkipSegment>>("sponsor_segments", &VideoArgs { video }).await {
+                Ok(segments) => self.skip_segments.set(segments),
+                Err(e) => leptos::logging::warn!("Could not load SponsorBlock segments: {}", e),
+            }
+        });
+        if self.settings.with_untracked(|s| s.playback.normalize) {
+            let video = id.clone();
+            spawn_local(async move {
+                match call::<Option<f64>>(
+                    "video_loudness",
+                    &VideoArgs {
+                        video: video.clone(),
+                    },
+                )
+                .await
+                {
+                    Ok(loudness) if self.video_id.get_untracked().as_ref() == Some(&video) => {
+                        self.loudness.set(normalized_level(loudness));
+                    }
+                    Ok(_) => {}
+                    Err(e) => leptos::logging::warn!("Could not look up loudness: {}", e),
+                }
+            });
+        }
+        // A downloaded copy plays even without a connection, then the
+        // stream yt-dlp finds
+        let extract = self.settings.with_untracked(|s| s.playback.extract_audio);
+        self.resolving_audio.set(true);
+        spawn_local(async move {
+            let args = VideoArgs { video: id.clone() };
+            let downloaded = call::<()>("offline_audio", &args)
+                .await
+                .ok()
+                .map(|()| convert_file_src(&id, OFFLINE_AUDIO_SCHEME));
+            let result = match downloaded {
+                Some(url) => Some(Ok(url)),
+                None if extract => Some(call::<String>("resolve_audio", &args).await),
+                None => None,
+            };
+            // Another video may have been loaded in the meantime
+            if self.video_id.get_untracked().as_ref() != Some(&id) {
+                return;
+            }
+            self.resolving_audio.set(false);
+            match result {
+                Some(Ok(url)) => self.audio_stream.set(Some(url)),
+                Some(Err(e)) => self.set_hint(
+                    t_with(Text::UsingYoutubePlayer, &[("error", &e.explained())]),
+                    "error",
+                ),
+                None => {}
+            }
+        });
+    }
+
+    /// Unload the video and bring the link field back
+    pub fn close(self) {
+        self.resume.dismiss();
+        self.blocked.set(None);
+        self.audio_stream.set(None);
+        self.resolving_audio.set(false);
+        self.skip_segments.set(Vec::new());
+        self.queue.reset_fade();
+        muffle::detach();
+        self.media.set(None);
+        self.url.set(String::new());
+        self.theater.leave();
+        toggle_body_class("video-active", false);
+        toggle_body_class("dim-mode", false);
+        self.set_hint(String::new(), "");
+    }
+
+    /// Move on to the next queued link, fading it in
+    pub fn advance_queue(self) {
+        if let Some(url) = self.queue.take_next(self.url.get_untracked()) {
+            self.open(url);
+            self.queue.fade_in();
+        }
+    }
+
+    /// Crossfade around the end of an item and advance once it's over
+    fn follow_queue(self) {
+        let media = self.media.read_only();
+        let position = self.player.position.get_untracked();
+        match self
+            .queue
+            .follow(position, self.player.duration.get_untracked())
+        {
+            Follow::Play => {}
+            Follow::Restart => {
+                self.player.seek_to(media, 0.0);
+                media.with_untracked(|m| play_video(m.as_ref()));
+                self.player.playing.set(true);
+            }
+            Follow::Advance => self.advance_queue(),
+        }
+    }
+
+    /// Seek past SponsorBlock segments as playback enters them
+    fn skip_sponsors(&self, time: f64) {
+        let end = self.skip_segments.with_untracked(|segments| {
+            segments
+                .iter()
+                .find(|s| time >= s.start && time < s.end - 1.0)
+                .map(|s| s.end)
+        });
+        if let Some(end) = end {
+            self.media.with_untracked(|m| seek_video(m.as_ref(), end));
+        }
+    }
+
+    /// Keep track of how far the video got, so it can pick up there next time
+    fn record_position(&self, time: f64, duration: Option<f64>) {
+        if let Some(video) = self.video_id.get_untracked() {
+            self.resume.record(video, time, duration);
+        }
+    }
+
+    /// The embed refused to play: drop the black box and say why
+    fn embed_failed(self, code: i64) {
+        let Some(id) = self.video_id.get_untracked() else {
+            return;
+        };
+        let url = self.url.get_untracked();
+        self.close();
+        self.url.set(url);
+
+        let hint = if self.settings.with_untracked(|s| s.playback.extract_audio) {
+            Text::EmbedFailed
+        } else {
+            Text::EmbedFailedTryAudio
+        };
+        self.set_hint(
+            t_with(hint, &[("reason", t(describe_embed_error(code)))]),
+            "error",
+        );
+        self.blocked.set(Some(id));
+        if self.session.is_running() {
+            self.session
+                .status_text
+                .set(t(Text::VideoUnavailable).to_string());
+            self.session.status_class.set("warning".to_string());
+        }
+    }
+
+    /// Open the video the embed refused on YouTube
+    pub fn open_blocked(&self) {
+        if let Some(id) = self.blocked.get_untracked() {
+            let url = format!("https://www.youtube.com/watch?v={}", id);
+            spawn_local(async move {
+                if let Err(e) = call::<()>("plugin:opener|open_url", &OpenUrlArgs { url }).await {
+                    leptos::logging::warn!("Could not open YouTube: {}", e);
+                }
+            });
+        }
+    }
+
+    /// Follow what the embed reports as it plays
+    pub fn follow_player(self) {
+        let media = self.media.read_only();
+        on_player_message(move |message| match message {
+            // Commands sent before this were dropped, so catch the volume up
+            PlayerMessage::Ready => self.apply_volume(),
+            PlayerMessage::Info {
+                current,
+                duration,
+                playing,
+            } => {
+                if let Some(length) = duration.filter(|d| *d > 0.0) {
+                    self.ad_break
+                        .watch(length, self.player.duration.get_untracked());
+                    if !self.ad_break.playing() {
+                        self.player.duration.set(duration);
+                    }
+                }
+                // The ad's own time isn't the video's
+                if let Some(current) = current.filter(|_| !self.ad_break.playing()) {
+                    self.skip_sponsors(current);
+                    self.record_position(current, duration);
+                    self.player.position.set(current);
+                    self.player.hold_loop(media);
+                }
+                if current.is_some() {
+                    self.follow_queue();
+                }
+                if let Some(playing) = playing {
+                    self.player.playing.set(playing);
+                    // The player only takes commands once it's going
+                    if playing {
+                        self.apply_volume();
+                    }
+                }
+            }
+            PlayerMessage::Error(code) => self.embed_failed(code),
+        });
+    }
+
+    /// Take the resume offer up
+    pub fn resume_playback(&self) {
+        self.resume.resume(
+            self.media.read_only(),
+            self.audio_stream.with_untracked(Option::is_none),
+        );
+    }
+
+    /// The extracted stream stopped working, e.g. its URL expired
+    pub fn audio_failed(&self) {
+        muffle::detach();
+        self.audio_stream.set(None);
+        self.set_hint(t(Text::AudioStreamFailed).to_string(), "error");
+    }
+
+    /// Quick switch on the player; remembered like any other setting
+    pub fn toggle_audio_only(&self) {
+        let settings = self.settings;
+        let mut next = settings.get_untracked();
+        next.playback.audio_only = !next.playback.audio_only;
+        spawn_local(async move {
+            match persist(next).await {
+                Ok(saved) => settings.set(saved),
+                Err(e) => leptos::logging::warn!("Could not save audio only mode: {}", e),
+            }
+        });
+    }
+
+    /// The audio stream is ready: bring it to the current volume and muffle
+    pub fn stream_loaded(&self, audio: HtmlMediaElement) {
+        self.apply_volume();
+        let playback = self.settings.with_untracked(|s| s.playback.clone());
+        if playback.filters_stream() {
+            match muffle::attach(&audio, &playback) {
+                Ok(()) => self.apply_muffle(),
+                Err(e) => leptos::logging::warn!("Could not muffle the stream: {:?}", e),
+            }
+        }
+    }
+
+    /// The audio stream played on: skip sponsors, save the spot, follow the queue
+    pub fn stream_progress(self, time: f64, duration: Option<f64>) {
+        self.skip_sponsors(time);
+        self.record_position(time, duration);
+        self.follow_queue();
+    }
+}
+
+/// Link field with the clipboard chip, search and recent videos, shown while
+/// nothing is loaded
+#[component]
+pub fn LinkField(
+    video: VideoState,
+    clipboard: ClipboardPrompt,
+    /// A search result only loads, so the duration can still be picked
+    on_pick: Callback<String>,
+    /// One click from the recent list: load the video and start right away
+    on_play: Callback<String>,
+) -> impl IntoView {
+    let session = expect_context::<SessionState>();
+
+    view! {
+        <div class="video-input-group">
+            <CopiedLink prompt=clipboard on_load=Callback::new(move |url| video.open(url))/>
+            <label for="video-url">{move || t(Text::VideoUrl)}</label>
+            <div class="video-input-row">
+                <input
+                    type="url"
+                    id="video-url"
+                    class="video-input pixel-border"
+                    placeholder=move || t(Text::VideoPlaceholder)
+                    prop:value=move || video.url.get()
+                    on:input=move |ev| video.url.set(event_target_value(&ev))
+                />
+                <button
+                    class="btn btn-load pixel-border"
+                    on:click=move |_| video.load(video.url.get_untracked())
+                >{move || t(Text::Load)}</button>
+            </div>
+            <p class=move || format!("video-hint {}", video.hint_class.get())>
+                {move || video.hint.get()}
+            </p>
+            {move || video.blocked.get().map(|_| view! {
+                <button
+                    class="btn btn-load pixel-border"
+                    on:click=move |_| video.open_blocked()
+                >{move || t(Text::OpenOnYoutube)}</button>
+            })}
+            <SearchBox on_pick=on_pick/>
+            {move || (!session.is_running()).then(|| view! {
+                <RecentVideos library=video.library on_play=on_play/>
+            })}
+        </div>
+    }
+}
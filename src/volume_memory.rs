@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use serde::Serialize;
+use sleepy_video_shared::library::LibraryEntry;
+
+use crate::ipc::call;
+use crate::transport::PlayerState;
+
+/// How long the volume slider rests before the video's volume is saved
+const VOLUME_SAVE_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Serialize)]
+struct VolumeArgs {
+    video: String,
+    volume: u32,
+}
+
+/// Remember the volume of `video_id` in the library once the slider rests.
+/// Only videos already in the library, and only changes from the volume it
+/// has there.
+pub fn remember(
+    player: PlayerState,
+    video_id: Memo<Option<String>>,
+    library: RwSignal<Vec<LibraryEntry>>,
+) {
+    let timer = RwSignal::new(Option::<TimeoutHandle>::None);
+    Effect::new(move |_| {
+        let volume = player.volume.get();
+        let Some(video) = video_id.get_untracked() else {
+            return;
+        };
+        let remembered = library.with_untracked(|entries| {
+            entries
+                .iter()
+                .find(|entry| entry.video_id == video)
+                .map(|entry| entry.volume.unwrap_or(100))
+        });
+        if remembered.is_none_or(|remembered| remembered == volume) {
+            return;
+        }
+        if let Some(handle) = timer.get_untracked() {
+            handle.clear();
+        }
+        let save = move || {
+            spawn_local(async move {
+                match call::<Vec<LibraryEntry>>("library_volume", &VolumeArgs { video, volume })
+                    .await
+                {
+                    Ok(entries) => library.set(entries),
+                    Err(e) => leptos::logging::warn!("Could not remember the volume: {}", e),
+                }
+            });
+        };
+        timer.set(set_timeout_with_handle(save, VOLUME_SAVE_DELAY).ok());
+    });
+}
+
+/// Back to the volume `video` last played at, if the library has one
+pub fn recall(player: PlayerState, video: &str, entries: &[LibraryEntry]) {
+    let volume = entries
+        .iter()
+        .find(|entry| entry.video_id == video)
+        .and_then(|entry| entry.volume);
+    if let Some(volume) = volume {
+        player.volume.set(volume);
+    }
+}
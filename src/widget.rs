@@ -0,0 +1,37 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use sleepy_video_shared::session::{SessionStatus, TICK_EVENT};
+use web_sys::window;
+
+use crate::app::format_time;
+use crate::ipc::{call, listen_to};
+
+/// The time left and nothing else, on a transparent page; empty between
+/// sessions so the widget disappears
+#[component]
+pub fn CountdownWidget() -> impl IntoView {
+    if let Some(root) = window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.document_element())
+    {
+        let _ = root.class_list().add_1("widget");
+    }
+
+    let status = RwSignal::new(SessionStatus::default());
+    listen_to::<SessionStatus>(TICK_EVENT, move |update| status.set(update));
+    spawn_local(async move {
+        if let Ok(current) = call::<SessionStatus>("session_status", &()).await {
+            status.set(current);
+        }
+    });
+
+    move || {
+        status.with(|s| s.running).then(|| {
+            view! {
+                <div class="countdown-widget">
+                    "💤 "{move || format_time(status.with(|s| s.remaining_seconds))}
+                </div>
+            }
+        })
+    }
+}
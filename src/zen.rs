@@ -0,0 +1,23 @@
+use leptos::prelude::*;
+use sleepy_video_shared::settings::Settings;
+
+use crate::app::{clock_in, format_time};
+
+/// Screensaver-style clock and time left in dim red over a black screen. Any
+/// tap or key brings the controls back.
+#[component]
+pub fn ZenClock(remaining: ReadSignal<u32>, on_wake: Callback<()>) -> impl IntoView {
+    let settings = expect_context::<RwSignal<Settings>>();
+
+    view! {
+        <div
+            class="zen-clock"
+            on:click=move |_| on_wake.run(())
+            on:mousemove=move |_| on_wake.run(())
+        >
+            // Re-read every tick of the countdown
+            <div class="zen-time">{move || { remaining.track(); clock_in(0, settings.with(|s| s.display.clock_12h)) }}</div>
+            <div class="zen-remaining">{move || format_time(remaining.get())}</div>
+        </div>
+    }
+}